@@ -8,6 +8,8 @@ use std::{
 };
 use uuid::Uuid;
 
+use crate::io::AssetPath;
+
 pub trait Asset: AssetDependencies + Send + Sync + 'static {}
 
 pub trait AssetDependencies {
@@ -56,13 +58,14 @@ impl<A: Asset> Into<Uuid> for AssetId<A> {
 
 impl<A: Asset> Debug for AssetId<A> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&self.0.to_string())
+        let name = ecs::ext::short_type_name::<A>();
+        write!(f, "AssetId<{}>({:032x})", name, self.0.as_u128())
     }
 }
 
 impl<A: Asset> Display for AssetId<A> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&self.0.to_string())
+        Display::fmt(&ErasedId::from(*self), f)
     }
 }
 
@@ -132,17 +135,37 @@ impl ErasedId {
     pub fn with_namespace(&self, name: &[u8]) -> Self {
         Self(Uuid::new_v5(&self.0, name))
     }
+
+    pub fn uuid(&self) -> Uuid {
+        self.0
+    }
+
+    /// Formats the id followed by `path`, e.g. for logging an asset that was
+    /// looked up through the [`AssetLibrary`](crate::database::library::AssetLibrary).
+    pub fn with_path<'a>(&'a self, path: &'a AssetPath<'a>) -> impl Display + 'a {
+        struct WithPath<'a>(&'a ErasedId, &'a AssetPath<'a>);
+
+        impl Display for WithPath<'_> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{} ({})", self.0, self.1)
+            }
+        }
+
+        WithPath(self, path)
+    }
 }
 
 impl std::fmt::Debug for ErasedId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&self.0.to_string())
+        write!(f, "ErasedId({:032x})", self.0.as_u128())
     }
 }
 
+/// Formats the id as 32 hex digits. Use [`ErasedId::with_path`] to include
+/// the asset's path.
 impl std::fmt::Display for ErasedId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        std::fmt::Debug::fmt(&self, f)
+        write!(f, "{:032x}", self.0.as_u128())
     }
 }
 
@@ -344,3 +367,37 @@ impl Drop for ErasedAsset {
         (self.drop)(self.ptr);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Asset, AssetDependencies, AssetId, ErasedId};
+    use crate::io::AssetPath;
+
+    struct Texture;
+    impl Asset for Texture {}
+    impl AssetDependencies for Texture {}
+
+    #[test]
+    fn id_format() {
+        let id = AssetId::<Texture>::from_u128(0x1a2b3c4d5e6f708192a0b1c2d3e4f506);
+        let erased = ErasedId::from(id);
+
+        assert_eq!(id.to_string(), "1a2b3c4d5e6f708192a0b1c2d3e4f506");
+        assert_eq!(id.to_string(), erased.to_string());
+        assert_eq!(id.to_string(), AssetId::<Texture>::from(erased).to_string());
+        assert_eq!(
+            format!("{:?}", id),
+            "AssetId<Texture>(1a2b3c4d5e6f708192a0b1c2d3e4f506)"
+        );
+        assert_eq!(
+            format!("{:?}", erased),
+            "ErasedId(1a2b3c4d5e6f708192a0b1c2d3e4f506)"
+        );
+
+        let path = AssetPath::from("textures/wall.png");
+        assert_eq!(
+            erased.with_path(&path).to_string(),
+            "1a2b3c4d5e6f708192a0b1c2d3e4f506 (textures/wall.png)"
+        );
+    }
+}
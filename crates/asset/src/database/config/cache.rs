@@ -2,7 +2,7 @@ use crate::{
     asset::{Asset, AssetId, AssetType, ErasedId},
     database::{config::importer::AssetProcessorId, library::AssetLibrary},
     ext::{DeserializeExt, SerializeExt},
-    io::{AsyncIoError, AsyncReader, ErasedFileSystem, FileSystem, path::AssetPath},
+    io::{AsyncIoError, AsyncReader, AsyncWriter, ErasedFileSystem, FileSystem, path::AssetPath},
 };
 use futures::{AsyncReadExt, AsyncWriteExt};
 use serde::{Deserialize, Serialize, ser::SerializeStruct};
@@ -25,6 +25,14 @@ impl Checksum {
 
         Self(hasher.finalize())
     }
+
+    pub fn with_sibling(self, sibling: &[u8]) -> Self {
+        let mut hasher = crc32fast::Hasher::new();
+        self.hash(&mut hasher);
+        sibling.hash(&mut hasher);
+
+        Self(hasher.finalize())
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
@@ -45,6 +53,14 @@ pub struct ImportInfo {
     pub checksum: Checksum,
     pub full_checksum: Checksum,
     pub dependencies: Vec<ImportDependency>,
+    /// Files read alongside the asset during import, included in the checksum.
+    pub siblings: Vec<PathBuf>,
+    /// [`AssetImporter::VERSION`](super::AssetImporter::VERSION) of the
+    /// importer that produced the artifact.
+    pub importer_version: u32,
+    /// [`AssetProcessor::VERSION`](super::AssetProcessor::VERSION) of the
+    /// processor that produced the artifact.
+    pub processor_version: u32,
 }
 
 impl ImportInfo {
@@ -110,6 +126,16 @@ impl AssetMetadata {
         self
     }
 
+    pub fn with_siblings(mut self, siblings: Vec<PathBuf>) -> Self {
+        self.import.siblings = siblings;
+        self
+    }
+
+    pub fn with_importer_version(mut self, version: u32) -> Self {
+        self.import.importer_version = version;
+        self
+    }
+
     pub fn all_dependencies(&self) -> impl Iterator<Item = &ErasedId> {
         self.dependencies.iter().chain(self.parent.iter())
     }
@@ -265,6 +291,14 @@ pub struct AssetCache {
 impl AssetCache {
     pub const LIBRARY: &'static str = "assets.lib";
 
+    /// Holds the [`AssetCache::VERSION`] the cache was written with.
+    pub const VERSION_FILE: &'static str = "cache.version";
+
+    /// Bump this when [`AssetMetadata`], [`Artifact`] or [`AssetLibrary`]
+    /// change shape. Bincode can't skip or default missing fields, so a cache
+    /// written with another version is cleared and every asset re-imported.
+    pub const VERSION: u32 = 1;
+
     pub fn new<F: FileSystem>(fs: F) -> Self {
         Self { fs: Box::new(fs) }
     }
@@ -274,11 +308,18 @@ impl AssetCache {
     }
 
     pub fn artifact_path(&self, path: ArtifactPath, id: &ErasedId) -> PathBuf {
-        self.fs.root().join(path.as_str()).join(id.to_string())
+        self.fs
+            .root()
+            .join(path.as_str())
+            .join(id.uuid().to_string())
     }
 
     pub async fn exists(&self, path: ArtifactPath, id: &ErasedId) -> Result<bool, AsyncIoError> {
-        let path = self.fs.root().join(path.as_str()).join(id.to_string());
+        let path = self
+            .fs
+            .root()
+            .join(path.as_str())
+            .join(id.uuid().to_string());
         self.fs.exists(&path).await
     }
 
@@ -334,7 +375,7 @@ impl AssetCache {
         let mut writer = self.fs.writer(&path).await?;
 
         writer.write_all(&data).await.map_err(AsyncIoError::from)?;
-        writer.flush().await.map_err(AsyncIoError::from)
+        AsyncWriter::flush(&mut writer).await
     }
 
     pub async fn remove_artifact(
@@ -354,11 +395,39 @@ impl AssetCache {
         let mut writer = self.fs.writer(Self::LIBRARY.as_ref()).await?;
         let data = library.to_bytes().map_err(AsyncIoError::from)?;
         writer.write_all(&data).await.map_err(AsyncIoError::from)?;
-        writer.flush().await.map_err(AsyncIoError::from)?;
+        AsyncWriter::flush(&mut writer).await?;
 
         Ok(data)
     }
 
+    /// Whether the cache was written with the current [`AssetCache::VERSION`].
+    pub async fn is_current(&self) -> bool {
+        let Ok(mut reader) = self.fs.reader(Self::VERSION_FILE.as_ref()).await else {
+            return false;
+        };
+
+        let mut data = Vec::new();
+        match AsyncReader::read_to_end(&mut reader, &mut data).await {
+            Ok(_) => data == Self::VERSION.to_le_bytes(),
+            Err(_) => false,
+        }
+    }
+
+    /// Removes the library and every artifact, then marks the cache as
+    /// written with the current [`AssetCache::VERSION`].
+    pub async fn clear(&self) -> Result<(), AsyncIoError> {
+        let _ = self.fs.remove(Self::LIBRARY.as_ref()).await;
+        let _ = self.remove_artifacts(ArtifactPath::Cache).await;
+        let _ = self.remove_artifacts(ArtifactPath::Source).await;
+
+        let mut writer = self.fs.writer(Self::VERSION_FILE.as_ref()).await?;
+        writer
+            .write_all(&Self::VERSION.to_le_bytes())
+            .await
+            .map_err(AsyncIoError::from)?;
+        AsyncWriter::flush(&mut writer).await
+    }
+
     pub async fn load_library(&self) -> Result<AssetLibrary, AsyncIoError> {
         let mut reader = self.fs.reader(Self::LIBRARY.as_ref()).await?;
         let mut data = Vec::new();
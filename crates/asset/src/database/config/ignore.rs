@@ -0,0 +1,118 @@
+use std::path::Path;
+
+/// A `.gitignore` style pattern for paths the scanner should skip.
+///
+/// `*` matches any run of characters and `?` matches a single character, but
+/// neither matches `/`. A trailing `/` only matches directories, so `temp/`
+/// skips every directory named `temp` along with its contents. Patterns with
+/// no other `/` match a file or directory name at any depth, like `*.blend`.
+/// Otherwise the pattern is matched against the whole path from the root of
+/// the source, like `models/raw/*.fbx`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IgnorePattern {
+    pattern: Vec<char>,
+    dir_only: bool,
+    anchored: bool,
+}
+
+impl IgnorePattern {
+    pub fn new(pattern: &str) -> Self {
+        let pattern = pattern.trim();
+        let dir_only = pattern.ends_with('/');
+        let pattern = pattern.trim_end_matches('/');
+        let anchored = pattern.contains('/');
+        let pattern = pattern.trim_start_matches("./").trim_start_matches('/');
+
+        Self {
+            pattern: pattern.chars().collect(),
+            dir_only,
+            anchored,
+        }
+    }
+
+    pub fn matches(&self, path: &Path, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+
+        let text = match self.anchored {
+            true => path.to_string_lossy().replace('\\', "/"),
+            false => match path.file_name() {
+                Some(name) => name.to_string_lossy().into_owned(),
+                None => return false,
+            },
+        };
+
+        Self::wildcard(&self.pattern, &text.chars().collect::<Vec<_>>())
+    }
+
+    fn wildcard(pattern: &[char], text: &[char]) -> bool {
+        match (pattern.first(), text.first()) {
+            (None, None) => true,
+            (Some('*'), _) => {
+                Self::wildcard(&pattern[1..], text)
+                    || (text.first().is_some_and(|c| *c != '/')
+                        && Self::wildcard(pattern, &text[1..]))
+            }
+            (Some('?'), Some(c)) if *c != '/' => Self::wildcard(&pattern[1..], &text[1..]),
+            (Some(p), Some(c)) if p == c => Self::wildcard(&pattern[1..], &text[1..]),
+            _ => false,
+        }
+    }
+}
+
+impl From<&str> for IgnorePattern {
+    fn from(value: &str) -> Self {
+        Self::new(value)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct IgnorePatterns(Vec<IgnorePattern>);
+
+impl IgnorePatterns {
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    pub fn add(&mut self, pattern: impl Into<IgnorePattern>) {
+        self.0.push(pattern.into());
+    }
+
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        self.0.iter().any(|pattern| pattern.matches(path, is_dir))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IgnorePattern;
+    use std::path::Path;
+
+    #[test]
+    fn extension_pattern() {
+        let pattern = IgnorePattern::new("*.blend");
+
+        assert!(pattern.matches(Path::new("scene.blend"), false));
+        assert!(pattern.matches(Path::new("models/scene.blend"), false));
+        assert!(!pattern.matches(Path::new("scene.blend1"), false));
+        assert!(!pattern.matches(Path::new("scene.png"), false));
+    }
+
+    #[test]
+    fn directory_pattern() {
+        let pattern = IgnorePattern::new("temp/");
+
+        assert!(pattern.matches(Path::new("temp"), true));
+        assert!(pattern.matches(Path::new("models/temp"), true));
+        assert!(!pattern.matches(Path::new("temp"), false));
+
+        let pattern = IgnorePattern::new("models/*/raw");
+        assert!(pattern.matches(Path::new("models/tree/raw"), true));
+        assert!(!pattern.matches(Path::new("models/tree/leaf/raw"), true));
+    }
+}
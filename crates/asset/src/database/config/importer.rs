@@ -16,7 +16,14 @@ use crate::{
     settings::{AssetSettings, ErasedAssetSettings, Settings},
 };
 use serde::{Deserialize, Serialize};
-use std::{any::TypeId, collections::HashMap, error::Error, marker::PhantomData, path::Path};
+use smol::stream::StreamExt;
+use std::{
+    any::TypeId,
+    collections::HashMap,
+    error::Error,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+};
 
 #[derive(Debug, thiserror::Error)]
 pub enum AddChildAssetError {
@@ -39,6 +46,7 @@ pub struct ImportContext<'a> {
     ty: AssetType,
     processor: Option<AssetProcessorId>,
     children: HashMap<ErasedId, Artifact>,
+    siblings: Vec<PathBuf>,
 }
 
 impl<'a> ImportContext<'a> {
@@ -57,6 +65,7 @@ impl<'a> ImportContext<'a> {
             ty,
             processor: None,
             children: HashMap::new(),
+            siblings: Vec::new(),
         }
     }
 
@@ -72,6 +81,55 @@ impl<'a> ImportContext<'a> {
         self.processor = Some(processor);
     }
 
+    /// Opens a file relative to the directory of the asset being imported,
+    /// e.g. the `.mtl` file referenced by an `.obj`. Changes to the file
+    /// cause this asset to be reimported.
+    pub async fn read_sibling(
+        &mut self,
+        path: impl AsRef<Path>,
+    ) -> Result<Box<dyn AsyncReader>, AsyncIoError> {
+        let path = match self.path.path().parent() {
+            Some(parent) => parent.join(path),
+            None => path.as_ref().to_path_buf(),
+        };
+
+        self.read_tracked(path).await
+    }
+
+    /// Lists the files in the directory of the asset being imported, sorted
+    /// by path. Open them with [`ImportContext::read_sibling`] to reimport
+    /// this asset when they change.
+    pub async fn sibling_paths(&self) -> Result<Vec<PathBuf>, AsyncIoError> {
+        let parent = self.path.path().parent().unwrap_or(Path::new(""));
+        let mut paths = self.fs.read_dir(parent).await?.collect::<Vec<_>>().await;
+        paths.sort();
+
+        Ok(paths)
+    }
+
+    /// Opens a file by its asset path, e.g. a shader `#import`. The file
+    /// must live in the same source as the asset being imported. Changes to
+    /// the file cause this asset to be reimported.
+    pub async fn read_path(
+        &mut self,
+        path: &AssetPath<'_>,
+    ) -> Result<Box<dyn AsyncReader>, AsyncIoError> {
+        if path.source() != self.path.source() {
+            return Err(AsyncIoError::NotFound(path.path().to_path_buf()));
+        }
+
+        self.read_tracked(path.path().to_path_buf()).await
+    }
+
+    async fn read_tracked(&mut self, path: PathBuf) -> Result<Box<dyn AsyncReader>, AsyncIoError> {
+        let reader = self.fs.reader(&path).await?;
+        if !self.siblings.contains(&path) {
+            self.siblings.push(path);
+        }
+
+        Ok(reader)
+    }
+
     pub fn add_child<A: Asset + Serialize + for<'de> Deserialize<'de>>(
         &mut self,
         name: &str,
@@ -108,6 +166,34 @@ impl<'a> ImportContext<'a> {
 
         Ok(id.into())
     }
+
+    /// Serializes the imported `asset` with the children added while
+    /// importing it. The asset's artifact comes first.
+    fn into_artifacts<A: Asset + SerializeExt>(
+        self,
+        asset: A,
+        metadata: AssetMetadata,
+    ) -> Result<Vec<Artifact>, Box<dyn Error + Send + Sync>> {
+        let data = asset
+            .to_bytes()
+            .map_err(|e| Box::new(AsyncIoError::from(e)) as Box<dyn Error + Send + Sync>)?;
+
+        let mut dependencies = Vec::new();
+        asset.get_dependencies(|id| dependencies.push(id));
+
+        let children = self.children.keys().cloned().collect();
+        let metadata = metadata
+            .with_processor(self.processor)
+            .with_children(children)
+            .with_dependencies(dependencies)
+            .with_siblings(self.siblings);
+
+        let artifact = unsafe { Artifact::from_raw_parts(data, metadata) };
+        let mut artifacts = vec![artifact];
+        artifacts.extend(self.children.into_values());
+
+        Ok(artifacts)
+    }
 }
 
 pub trait AssetImporter: 'static {
@@ -117,6 +203,10 @@ pub trait AssetImporter: 'static {
 
     type Error: Error + Send + Sync + 'static;
 
+    /// Stored in every artifact this importer produces. Bump it whenever the
+    /// import output changes so cached artifacts are imported again.
+    const VERSION: u32 = 0;
+
     fn import(
         ctx: &mut ImportContext,
         reader: &mut dyn AsyncReader,
@@ -190,6 +280,10 @@ pub trait AssetProcessor: 'static {
 
     type Error: Error + Send + Sync + 'static;
 
+    /// Stored in every artifact this processor produces. Bump it whenever the
+    /// processed output changes so cached artifacts are processed again.
+    const VERSION: u32 = 0;
+
     fn process(
         ctx: &mut ProcessContext,
         asset: Self::Input,
@@ -217,11 +311,17 @@ pub struct ErasedImporter {
         &'a mut dyn AsyncReader,
         &'a dyn ErasedAssetSettings,
     ) -> BoxFuture<'a, Result<Vec<Artifact>, Box<dyn Error + Send + Sync>>>,
+    import_inline: for<'a> fn(
+        ImportContext<'a>,
+        &'a mut dyn AsyncReader,
+    )
+        -> BoxFuture<'a, Result<Vec<Artifact>, Box<dyn Error + Send + Sync>>>,
     deserialize_settings: fn(&[u8]) -> Result<Box<dyn ErasedAssetSettings>, AsyncIoError>,
     default_settings: fn() -> Box<dyn ErasedAssetSettings>,
     type_id: fn() -> TypeId,
     asset_type_id: fn() -> TypeId,
     extensions: fn() -> &'static [&'static str],
+    version: u32,
 }
 
 impl ErasedImporter {
@@ -251,34 +351,45 @@ impl ErasedImporter {
                             .await
                             .map_err(|e| Box::new(e) as Box<dyn Error + Send + Sync>)?;
 
-                        Checksum::from(&asset, &settings)
+                        let mut checksum = Checksum::from(&asset, &settings);
+                        for sibling in &ctx.siblings {
+                            let sibling = ctx
+                                .fs
+                                .read(sibling)
+                                .await
+                                .map_err(|e| Box::new(e) as Box<dyn Error + Send + Sync>)?;
+                            checksum = checksum.with_sibling(&sibling);
+                        }
+
+                        checksum
                     };
 
-                    let data = asset.to_bytes().map_err(|e| {
-                        Box::new(AsyncIoError::from(e)) as Box<dyn Error + Send + Sync>
-                    })?;
-
-                    let mut dependencies = Vec::new();
-                    asset.get_dependencies(|id| dependencies.push(id));
-
-                    let children = ctx.children.keys().cloned().collect();
                     let metadata = AssetMetadata::new(settings.id, ctx.ty, ctx.path.to_owned())
-                        .with_processor(ctx.processor)
                         .with_checksum(checksum)
-                        .with_children(children)
-                        .with_dependencies(dependencies);
+                        .with_importer_version(I::VERSION);
+
+                    ctx.into_artifacts(asset, metadata)
+                };
+
+                Box::pin(f)
+            },
+            import_inline: |mut ctx, reader| {
+                let f = async move {
+                    let settings = AssetSettings::<I::Settings>::new(ctx.id, Default::default());
+                    let asset = I::import(&mut ctx, reader, &settings)
+                        .await
+                        .map_err(|e| Box::new(e) as Box<dyn Error + Send + Sync>)?;
 
-                    let artifact = unsafe { Artifact::from_raw_parts(data, metadata) };
-                    let mut artifacts = vec![artifact];
-                    artifacts.extend(ctx.children.into_values());
+                    let metadata = AssetMetadata::new(ctx.id, ctx.ty, ctx.path.to_owned())
+                        .with_importer_version(I::VERSION);
 
-                    Ok(artifacts)
+                    ctx.into_artifacts(asset, metadata)
                 };
 
                 Box::pin(f)
             },
             deserialize_settings: |data| {
-                AssetSettings::<I::Settings>::from_bytes(data)
+                AssetSettings::<I::Settings>::decode(data)
                     .map(|metadata| Box::new(metadata) as Box<dyn ErasedAssetSettings>)
                     .map_err(AsyncIoError::from)
             },
@@ -286,6 +397,7 @@ impl ErasedImporter {
             type_id: || TypeId::of::<I>(),
             asset_type_id: || TypeId::of::<I::Asset>(),
             extensions: <I as AssetImporter>::extensions,
+            version: I::VERSION,
         }
     }
 
@@ -298,6 +410,16 @@ impl ErasedImporter {
         (self.import)(ctx, reader, settings)
     }
 
+    /// Imports with default settings. Unlike [`ErasedImporter::import`],
+    /// nothing is read from or written to the asset's source.
+    pub fn import_inline<'a>(
+        &'a self,
+        ctx: ImportContext<'a>,
+        reader: &'a mut dyn AsyncReader,
+    ) -> BoxFuture<'a, Result<Vec<Artifact>, Box<dyn Error + Send + Sync>>> {
+        (self.import_inline)(ctx, reader)
+    }
+
     pub fn deserialize_settings(
         &self,
         data: &[u8],
@@ -323,6 +445,10 @@ impl ErasedImporter {
     pub fn extensions(&self) -> &'static [&'static str] {
         (self.extensions)()
     }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
 }
 
 pub struct ErasedProcesser {
@@ -331,6 +457,7 @@ pub struct ErasedProcesser {
         &'a [u8],
     ) -> BoxFuture<'a, Result<Vec<u8>, Box<dyn Error + Send + Sync>>>,
     output_asset: fn() -> TypeId,
+    version: u32,
 }
 
 impl ErasedProcesser {
@@ -352,6 +479,7 @@ impl ErasedProcesser {
                 Box::pin(f)
             },
             output_asset: || TypeId::of::<P::Output>(),
+            version: P::VERSION,
         }
     }
 
@@ -366,6 +494,10 @@ impl ErasedProcesser {
     pub fn output_asset(&self) -> TypeId {
         (self.output_asset)()
     }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
 }
 
 pub struct AssetImporters {
@@ -455,3 +587,42 @@ impl AssetProcessors {
         id
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::ImportContext;
+    use crate::{
+        asset::{AssetType, ErasedId},
+        database::config::{registry::AssetRegistry, sources::AssetFileSystem},
+        io::{AsyncWriter, FileSystem, path::AssetPath, vfs::VirtualFs},
+    };
+    use smol::io::{AsyncReadExt, AsyncWriteExt};
+    use std::path::PathBuf;
+
+    #[test]
+    fn read_sibling() {
+        let source = VirtualFs::new();
+        smol::block_on(async {
+            source.create_dir("models".as_ref()).await.unwrap();
+            let mut writer = source.writer("models/cube.mtl".as_ref()).await.unwrap();
+            writer.write_all(b"newmtl red").await.unwrap();
+            AsyncWriter::flush(&mut writer).await.unwrap();
+        });
+
+        let fs = AssetFileSystem::new(source);
+        let registry = AssetRegistry::new();
+        let path = AssetPath::from("models/cube.obj");
+        let mut ctx =
+            ImportContext::new(&fs, &registry, &path, ErasedId::new(), AssetType::UNKNOWN);
+
+        let content = smol::block_on(async {
+            let mut reader = ctx.read_sibling("cube.mtl").await.unwrap();
+            let mut content = String::new();
+            reader.read_to_string(&mut content).await.unwrap();
+            content
+        });
+
+        assert_eq!(content, "newmtl red");
+        assert_eq!(ctx.siblings, vec![PathBuf::from("models/cube.mtl")]);
+    }
+}
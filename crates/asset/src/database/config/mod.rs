@@ -1,22 +1,25 @@
 use crate::{
     AssetId,
-    asset::{Asset, AssetType},
-    database::{AddAsset, LoadAsset, state::LoadDependencies},
-    ext::DeserializeExt,
+    asset::{Asset, AssetType, ErasedId},
+    database::{AddAsset, ImportError, LoadAsset, library::AssetLibrary, state::LoadDependencies},
+    ext::{DeserializeExt, PathExt},
     io::{
-        FileSystem,
+        FileReader, FileSystem, VirtualFs,
         local::LocalFs,
-        path::{AssetSource, LoadPath},
+        path::{AssetPath, AssetSource, LoadPath},
     },
 };
 use ecs::{CommandBuffer, Resource};
+use std::error::Error;
 
 pub mod cache;
+pub mod ignore;
 pub mod importer;
 pub mod registry;
 pub mod sources;
 
 pub use cache::*;
+pub use ignore::*;
 pub use importer::*;
 pub use registry::*;
 pub use sources::*;
@@ -27,6 +30,7 @@ pub struct AssetDatabaseConfig {
     processors: AssetProcessors,
     sources: AssetSources,
     cache: AssetCache,
+    ignore: IgnorePatterns,
 }
 
 impl AssetDatabaseConfig {
@@ -37,6 +41,7 @@ impl AssetDatabaseConfig {
             processors: AssetProcessors::new(),
             sources: AssetSources::new(),
             cache: AssetCache::new(LocalFs::new(".cache")),
+            ignore: IgnorePatterns::new(),
         }
     }
 
@@ -59,6 +64,123 @@ impl AssetDatabaseConfig {
     pub fn cache(&self) -> &AssetCache {
         &self.cache
     }
+
+    pub fn ignore_patterns(&self) -> &IgnorePatterns {
+        &self.ignore
+    }
+
+    /// Imports an asset from `bytes` using the importer registered for `ext`
+    /// and its default settings, then processes it and its children like a
+    /// file with that extension. Nothing is read from or written to disk.
+    /// The asset's artifact comes first, followed by its children.
+    pub async fn import_inline(
+        &self,
+        library: &AssetLibrary,
+        bytes: &[u8],
+        ext: &str,
+    ) -> Result<Vec<Artifact>, ImportError> {
+        let path = AssetPath::from(format!("inline.{ext}"));
+        let error = |error: Box<dyn Error + Send + Sync>| ImportError::ImportAsset {
+            path: path.clone(),
+            error,
+        };
+
+        let importer = self
+            .importers
+            .get_by_ext(ext)
+            .ok_or_else(|| error(format!("No importer for extension: {ext}").into()))?;
+        let ty = self
+            .registry
+            .get_ty(importer.asset_type())
+            .ok_or_else(|| error(format!("Unregistered asset for extension: {ext}").into()))?;
+
+        let fs = AssetFileSystem::new(VirtualFs::new());
+        let ctx = ImportContext::new(&fs, &self.registry, &path, ErasedId::new(), ty);
+        let mut reader = FileReader::new(bytes.to_vec());
+
+        let mut artifacts = importer
+            .import_inline(ctx, &mut reader)
+            .await
+            .map_err(error)?;
+
+        for artifact in &mut artifacts {
+            self.process(library, artifact).await?;
+        }
+
+        Ok(artifacts)
+    }
+
+    /// Runs `artifact` through the processor selected by its importer, or
+    /// the default processor for its type.
+    pub async fn process(
+        &self,
+        library: &AssetLibrary,
+        artifact: &mut Artifact,
+    ) -> Result<(), ImportError> {
+        let processor = match artifact.meta.import.processor {
+            Some(processor) => Some(self.processors.get(processor)),
+            None => self.processors.get_default(artifact.ty()),
+        };
+
+        let version = processor.map(|p| p.version()).unwrap_or_default();
+        let process = async {
+            let processor = processor?;
+            let output_ty = self.registry.get_ty(processor.output_asset())?;
+            let mut ctx = ProcessContext::new(library, &self.cache, artifact.path(), artifact.ty());
+            let data = match processor.process(&mut ctx, artifact.data()).await {
+                Ok(data) => data,
+                Err(error) => {
+                    return Some(Err(ImportError::ProcessAsset {
+                        id: artifact.id(),
+                        path: Some(artifact.path().clone()),
+                        error,
+                    }));
+                }
+            };
+
+            let deps = ctx.dependencies;
+            artifact.meta.ty = output_ty;
+            artifact.data = data;
+            Some(Ok(deps))
+        };
+
+        let deps = match process.await {
+            Some(deps) => deps?,
+            None => vec![],
+        };
+
+        artifact.meta.import.set_dependencies(deps);
+        artifact.meta.import.processor_version = version;
+
+        Ok(())
+    }
+
+    /// Returns true if `meta` was produced by a different version of its
+    /// importer or processor than the ones currently registered.
+    pub fn is_outdated(&self, meta: &AssetMetadata) -> bool {
+        let importer = meta
+            .path
+            .ext()
+            .and_then(|ext| self.importers.get_by_ext(ext));
+        let Some(importer) = importer else {
+            return true;
+        };
+
+        if importer.version() != meta.import.importer_version {
+            return true;
+        }
+
+        let processor = match meta.import.processor {
+            Some(processor) => Some(self.processors.get(processor)),
+            None => self
+                .registry
+                .get_ty(importer.asset_type())
+                .and_then(|ty| self.processors.get_default(ty)),
+        };
+
+        let version = processor.map(|p| p.version()).unwrap_or_default();
+        version != meta.import.processor_version
+    }
 }
 
 #[derive(Resource)]
@@ -75,6 +197,15 @@ impl AssetDatabaseBuilder {
         }
     }
 
+    /// Builder backed by [`VirtualFs`] for both the default source and the
+    /// cache, for tests and tools that shouldn't touch the disk.
+    pub fn in_memory() -> Self {
+        let mut builder = Self::new();
+        builder.add_source(AssetSource::Default, VirtualFs::new());
+        builder.set_cache(VirtualFs::new());
+        builder
+    }
+
     pub fn config(&self) -> &AssetDatabaseConfig {
         &self.config
     }
@@ -128,6 +259,11 @@ impl AssetDatabaseBuilder {
         let ty = self.register::<P::Input>();
         self.config.processors.set_default::<P>(ty);
     }
+
+    /// Excludes paths matching `pattern` from scanning. See [`IgnorePattern`].
+    pub fn ignore(&mut self, pattern: &str) {
+        self.config.ignore.add(pattern);
+    }
 }
 
 impl Default for AssetDatabaseBuilder {
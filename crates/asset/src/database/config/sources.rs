@@ -82,7 +82,7 @@ impl AssetFileSystem {
         let mut reader = self.reader(&path.append_ext("meta")).await?;
         let mut bytes = Vec::new();
         AsyncReader::read_to_end(&mut reader, &mut bytes).await?;
-        AssetSettings::<S>::from_bytes(&bytes).map_err(AsyncIoError::from)
+        AssetSettings::<S>::decode(&bytes).map_err(AsyncIoError::from)
     }
 
     pub async fn save_settings<S: Settings + SerializeExt>(
@@ -1,5 +1,5 @@
 use crate::{
-    asset::{Asset, AssetType, ErasedAsset, ErasedId},
+    asset::{Asset, AssetId, AssetType, ErasedAsset, ErasedId},
     database::{
         library::AssetLibrary,
         scanner::{AssetScanError, Blacklist, ScanInfo},
@@ -24,6 +24,7 @@ use smol::{
     lock::RwLock,
 };
 use std::{
+    any::TypeId,
     collections::{HashMap, HashSet},
     error::Error,
     sync::OnceLock,
@@ -145,6 +146,10 @@ impl AssetDatabase {
         }
 
         let _ = self.cache().fs().create_dir_all("".as_ref()).await;
+        if !self.cache().is_current().await {
+            let _ = self.cache().clear().await;
+        }
+
         let _ = self
             .cache()
             .fs()
@@ -259,11 +264,18 @@ impl AssetDatabase {
             }
         };
 
-        let settings = fs
-            .read(&path.append_ext("meta"))
-            .await
-            .and_then(|b| importer.deserialize_settings(&b))
-            .unwrap_or(importer.default_settings());
+        // A meta that exists but can't be read or migrated is an error rather
+        // than a reason to silently replace the user's settings with defaults.
+        let settings = match fs.read(&path.append_ext("meta")).await {
+            Ok(bytes) => match importer.deserialize_settings(&bytes) {
+                Ok(settings) => settings,
+                Err(error) => {
+                    let error = Box::new(error);
+                    return Some(Err(ImportError::ImportAsset { path, error }));
+                }
+            },
+            Err(_) => importer.default_settings(),
+        };
 
         let ctx = ImportContext::new(fs, self.registry(), &path, settings.id(), ty);
         let artifacts = match importer.import(ctx, &mut reader, settings.as_ref()).await {
@@ -317,40 +329,9 @@ impl AssetDatabase {
             }
         };
 
-        let processor = match artifact.meta.import.processor {
-            Some(processor) => Some(self.config.processors().get(processor)),
-            None => self.config.processors().get_default(artifact.ty()),
-        };
-
-        let process = async {
-            let processor = processor?;
-            let output_ty = self.registry().get_ty(processor.output_asset())?;
-            let library = self.library.read().await;
-            let mut ctx =
-                ProcessContext::new(&library, self.cache(), artifact.path(), artifact.ty());
-            let data = match processor.process(&mut ctx, artifact.data()).await {
-                Ok(data) => data,
-                Err(error) => {
-                    return Some(Err(ImportError::ProcessAsset {
-                        id: *id,
-                        path: Some(artifact.path().clone()),
-                        error,
-                    }));
-                }
-            };
-
-            let deps = ctx.dependencies;
-            artifact.meta.ty = output_ty;
-            artifact.data = data;
-            Some(Ok(deps))
-        };
-
-        let deps = match process.await {
-            Some(deps) => deps?,
-            None => vec![],
-        };
-
-        artifact.meta.import.set_dependencies(deps);
+        let library = self.library.read().await;
+        self.config.process(&library, &mut artifact).await?;
+        drop(library);
 
         if let Err(error) = self
             .cache()
@@ -458,6 +439,62 @@ impl AssetDatabase {
         task
     }
 
+    /// Imports and processes an asset from `bytes` without a source or
+    /// cache. The asset and its children are added to the world on the next
+    /// update.
+    pub async fn load_inline<A: Asset>(
+        &self,
+        bytes: &[u8],
+        ext: &str,
+    ) -> Result<AssetId<A>, ImportError> {
+        let library = self.library.read().await;
+        let artifacts = self.config.import_inline(&library, bytes, ext).await?;
+        drop(library);
+
+        let path = artifacts[0].path().clone();
+        let error = |error: Box<dyn Error + Send + Sync>| ImportError::ImportAsset {
+            path: path.clone(),
+            error,
+        };
+
+        let id = artifacts[0].id();
+        if self.registry().get_ty(TypeId::of::<A>()) != Some(artifacts[0].ty()) {
+            let name = std::any::type_name::<A>();
+            return Err(error(format!("Inline asset is not a {name}").into()));
+        }
+
+        let mut loaded = Vec::new();
+        for artifact in artifacts {
+            let meta = self.registry().get(artifact.ty());
+            let asset = meta
+                .deserialize(artifact.data())
+                .ok_or_else(|| error(format!("Missing deserializer: {}", meta.name).into()))?
+                .map_err(|e| error(Box::new(AsyncIoError::from(e))))?;
+
+            loaded.push((asset, artifact.meta));
+        }
+
+        for (asset, metadata) in loaded {
+            let loaded = self.states.write().await.loaded(
+                metadata.id,
+                metadata.ty,
+                &metadata.dependencies,
+                metadata.parent,
+            );
+
+            let event = AssetDatabaseEvent::LoadedAsset {
+                id: metadata.id,
+                asset,
+                metadata,
+                loaded,
+            };
+
+            self.send_event(event).await;
+        }
+
+        Ok(id.into())
+    }
+
     async fn load_asset(
         &self,
         id: ErasedId,
@@ -722,14 +759,18 @@ mod tests {
     use crate::{
         asset::{Asset, AssetDependencies, ErasedAsset},
         database::{
-            AssetDatabase, AssetDatabaseEvent,
+            AssetDatabase, AssetDatabaseEvent, DB,
             config::{
-                AssetDatabaseBuilder,
-                importer::{AssetImporter, AssetProcessor},
+                AssetDatabaseBuilder, AssetDatabaseConfig, AssetFileSystem,
+                cache::{ArtifactPath, AssetCache},
+                importer::{AssetImporter, AssetProcessor, ImportContext},
             },
+            library::AssetLibrary,
+            scanner::Blacklist,
         },
+        ext::DeserializeExt,
         io::{
-            FileSystem,
+            AsyncWriter, FileSystem,
             embedded::EmbeddedFs,
             path::{AssetPath, AssetSource},
             vfs::VirtualFs,
@@ -739,6 +780,10 @@ mod tests {
     use ecs::core::task::{IoTaskPool, TaskPoolBuilder};
     use serde::{Deserialize, Serialize};
     use smol::io::{AsyncReadExt, AsyncWriteExt};
+    use std::{
+        any::TypeId,
+        sync::{Mutex, MutexGuard, PoisonError},
+    };
 
     #[derive(Debug, Serialize, Deserialize)]
     pub struct TextFile(String);
@@ -752,6 +797,8 @@ mod tests {
 
         type Error = std::io::Error;
 
+        const VERSION: u32 = 1;
+
         async fn import(
             _: &mut super::config::importer::ImportContext<'_>,
             reader: &mut dyn crate::io::AsyncReader,
@@ -764,7 +811,7 @@ mod tests {
         }
 
         fn extensions() -> &'static [&'static str] {
-            &["txt"]
+            &["txt", "log"]
         }
     }
 
@@ -784,12 +831,54 @@ mod tests {
         }
     }
 
-    fn setup() -> &'static AssetDatabase {
+    pub struct TextFileV2;
+    impl AssetImporter for TextFileV2 {
+        type Asset = TextFile;
+
+        type Settings = DefaultSettings;
+
+        type Error = std::io::Error;
+
+        const VERSION: u32 = 2;
+
+        async fn import(
+            ctx: &mut super::config::importer::ImportContext<'_>,
+            reader: &mut dyn crate::io::AsyncReader,
+            settings: &crate::settings::AssetSettings<Self::Settings>,
+        ) -> Result<Self::Asset, Self::Error> {
+            TextFile::import(ctx, reader, settings).await
+        }
+
+        fn extensions() -> &'static [&'static str] {
+            &["txt"]
+        }
+    }
+
+    /// Tests that use the global database hold the returned guard, so one
+    /// test can't read the events sent for another.
+    fn setup() -> (MutexGuard<'static, ()>, &'static AssetDatabase) {
+        static LOCK: Mutex<()> = Mutex::new(());
+        let guard = LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if let Some(database) = DB.get() {
+            return (guard, database);
+        }
+
         IoTaskPool::init(TaskPoolBuilder::new().build());
         let source = smol::block_on(async {
             let source = VirtualFs::new();
-            let mut writer = source.writer("test.txt".as_ref()).await.unwrap();
-            writer.write_all(b"test").await.unwrap();
+            let files: [(&str, &[u8]); 3] = [
+                ("test.txt", b"test"),
+                ("test.log", b"ignored"),
+                ("temp/test.txt", b"ignored"),
+            ];
+
+            source.create_dir("temp".as_ref()).await.unwrap();
+            for (path, content) in files {
+                let mut writer = source.writer(path.as_ref()).await.unwrap();
+                writer.write_all(content).await.unwrap();
+                AsyncWriter::flush(&mut writer).await.unwrap();
+            }
 
             source
         });
@@ -799,13 +888,15 @@ mod tests {
         builder.set_cache(VirtualFs::new());
         builder.add_importer::<TextFile>();
         builder.set_default_processor::<TextFile>();
+        builder.ignore("*.log");
+        builder.ignore("temp/");
 
-        AssetDatabase::init(builder)
+        (guard, AssetDatabase::init(builder))
     }
 
     #[test]
     fn test_import() {
-        let database = setup();
+        let (_guard, database) = setup();
         smol::block_on(database.import());
 
         let library = database.library.try_read().unwrap();
@@ -822,9 +913,124 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_ignore_patterns() {
+        let (_guard, database) = setup();
+        smol::block_on(database.import());
+
+        let library = database.library.try_read().unwrap();
+        assert!(library.get(&AssetPath::from("test.txt")).is_some());
+        assert!(library.get(&AssetPath::from("test.log")).is_none());
+        assert!(library.get(&AssetPath::from("temp/test.txt")).is_none());
+    }
+
+    #[test]
+    fn test_load_inline() {
+        let mut builder = AssetDatabaseBuilder::in_memory();
+        builder.add_importer::<TextFile>();
+
+        let library = AssetLibrary::new();
+        let import = |config: &AssetDatabaseConfig, ext| {
+            smol::block_on(config.import_inline(&library, b"inline", ext))
+        };
+
+        let artifacts = import(builder.config(), "txt").unwrap();
+        assert_eq!(artifacts.len(), 1);
+        let file = TextFile::from_bytes(artifacts[0].data()).unwrap();
+        assert_eq!(file.0, "inline");
+
+        builder.set_default_processor::<TextFile>();
+        let artifacts = import(builder.config(), "txt").unwrap();
+        let file = TextFile::from_bytes(artifacts[0].data()).unwrap();
+        assert_eq!(file.0, "processed");
+
+        assert!(import(builder.config(), "obj").is_err());
+    }
+
+    #[test]
+    fn test_importer_version() {
+        fn database<I: AssetImporter<Asset = TextFile>>(
+            source: &VirtualFs,
+            cache: &VirtualFs,
+        ) -> AssetDatabase {
+            let mut builder = AssetDatabaseBuilder::new();
+            builder.add_source(AssetSource::Default, source.clone());
+            builder.set_cache(cache.clone());
+            builder.add_importer::<I>();
+            builder.set_default_processor::<TextFile>();
+            AssetDatabase::new(builder)
+        }
+
+        async fn import(database: &AssetDatabase) -> Vec<AssetPath<'static>> {
+            database.setup().await;
+
+            let mut blacklist = Blacklist::new();
+            let scan = database.scan(&blacklist).await.unwrap();
+            let paths = scan.paths.clone();
+
+            let assets = database.import_assets(scan.paths, &mut blacklist).await;
+            database.process_assets(assets, &mut blacklist).await;
+
+            let library = database.library.read().await;
+            database.cache().save_library(&library).await.unwrap();
+
+            paths
+        }
+
+        async fn importer_version(database: &AssetDatabase, path: &AssetPath<'static>) -> u32 {
+            let id = database.library.read().await.get(path).copied().unwrap();
+            let artifact = database
+                .cache()
+                .read_artifact(ArtifactPath::Cache, &id)
+                .await
+                .unwrap();
+
+            artifact.meta().import.importer_version
+        }
+
+        let source = VirtualFs::new();
+        let cache = VirtualFs::new();
+        let path = AssetPath::from("test.txt");
+
+        smol::block_on(async {
+            let mut writer = source.writer("test.txt".as_ref()).await.unwrap();
+            writer.write_all(b"test").await.unwrap();
+            AsyncWriter::flush(&mut writer).await.unwrap();
+
+            let v1 = database::<TextFile>(&source, &cache);
+            assert_eq!(import(&v1).await, vec![path.clone()]);
+            assert_eq!(importer_version(&v1, &path).await, 1);
+
+            // An up to date artifact is loaded from the cache.
+            let v1 = database::<TextFile>(&source, &cache);
+            assert!(import(&v1).await.is_empty());
+
+            // Bumping the importer version makes the scanner re-import it.
+            let v2 = database::<TextFileV2>(&source, &cache);
+            assert_eq!(import(&v2).await, vec![path.clone()]);
+            assert_eq!(importer_version(&v2, &path).await, 2);
+            assert!(import(&v2).await.is_empty());
+        });
+    }
+
+    #[test]
+    fn test_cache_version() {
+        let cache = AssetCache::new(VirtualFs::new());
+
+        smol::block_on(async {
+            assert!(!cache.is_current().await);
+
+            cache.save_library(&AssetLibrary::new()).await.unwrap();
+            cache.clear().await.unwrap();
+
+            assert!(cache.is_current().await);
+            assert!(cache.load_library().await.is_err());
+        });
+    }
+
     #[test]
     fn test_load() {
-        let database = setup();
+        let (_guard, database) = setup();
         smol::block_on(database.import());
 
         let loaded = smol::block_on(database.load::<TextFile>("test.txt")).unwrap();
@@ -93,14 +93,17 @@ impl AssetDatabase {
         let mut info = self.scan_dir(path, &source).await?;
         let mut imports = Vec::new();
 
+        let ignore = self.config.ignore_patterns();
+
         while let Some(path) = info.paths.pop() {
             match source.is_dir(&path).await {
+                Ok(true) if ignore.is_ignored(path.path(), true) => continue,
                 Ok(true) => match self.scan_dir(path, &source).await {
                     Ok(other) => info.extend(other),
                     Err(error) => self.send_event(error).await,
                 },
                 Ok(false) => {
-                    if blacklist.contains(&path) {
+                    if blacklist.contains(&path) || ignore.is_ignored(path.path(), false) {
                         continue;
                     }
 
@@ -190,7 +193,14 @@ impl AssetDatabase {
             return Ok(Some(path));
         };
 
-        let checksum = Checksum::from(&asset, &settings);
+        let mut checksum = Checksum::from(&asset, &settings);
+        for sibling in &meta.import.siblings {
+            let Ok(sibling) = fs.read(sibling).await else {
+                return Ok(Some(path));
+            };
+
+            checksum = checksum.with_sibling(&sibling);
+        }
 
         if checksum != meta.import.checksum {
             return Ok(Some(path));
@@ -200,7 +210,7 @@ impl AssetDatabase {
             return Ok(Some(path));
         };
 
-        if full_checksum != meta.import.full_checksum {
+        if full_checksum != meta.import.full_checksum || self.config.is_outdated(&meta) {
             return Ok(Some(path));
         }
 
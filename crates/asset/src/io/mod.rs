@@ -1,8 +1,10 @@
+use crate::settings::SettingsError;
 use smol::{
-    io::{AsyncRead, AsyncSeek, AsyncWrite},
+    io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt, AsyncWrite},
     stream::Stream,
 };
 use std::{
+    io::SeekFrom,
     path::{Path, PathBuf},
     pin::Pin,
     sync::Arc,
@@ -76,6 +78,13 @@ impl From<bincode::error::DecodeError> for AsyncIoError {
     }
 }
 
+impl From<SettingsError> for AsyncIoError {
+    fn from(value: SettingsError) -> Self {
+        let error = std::io::Error::new(std::io::ErrorKind::InvalidData, value);
+        Self::Io(Arc::new(error))
+    }
+}
+
 pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
 
 pub trait AsyncReader: AsyncRead + AsyncSeek + Send + Sync + Unpin {
@@ -83,6 +92,30 @@ pub trait AsyncReader: AsyncRead + AsyncSeek + Send + Sync + Unpin {
         &'a mut self,
         buf: &'a mut Vec<u8>,
     ) -> BoxFuture<'a, Result<usize, AsyncIoError>>;
+
+    /// Seeks to `start` and reads exactly `len` bytes, leaving the reader
+    /// positioned at the end of the range. Fails with
+    /// [`std::io::ErrorKind::UnexpectedEof`] before allocating anything if the
+    /// range ends past the end of the stream.
+    fn read_range<'a>(
+        &'a mut self,
+        start: u64,
+        len: usize,
+    ) -> BoxFuture<'a, Result<Vec<u8>, AsyncIoError>> {
+        Box::pin(async move {
+            let size = self.seek(SeekFrom::End(0)).await?;
+            let end = start.checked_add(len as u64);
+            if end.is_none_or(|end| end > size) {
+                return Err(std::io::ErrorKind::UnexpectedEof.into());
+            }
+
+            self.seek(SeekFrom::Start(start)).await?;
+
+            let mut buf = vec![0; len];
+            self.read_exact(&mut buf).await?;
+            Ok(buf)
+        })
+    }
 }
 
 impl AsyncReader for Box<dyn AsyncReader> {
@@ -92,6 +125,14 @@ impl AsyncReader for Box<dyn AsyncReader> {
     ) -> BoxFuture<'a, Result<usize, AsyncIoError>> {
         self.as_mut().read_to_end(buf)
     }
+
+    fn read_range<'a>(
+        &'a mut self,
+        start: u64,
+        len: usize,
+    ) -> BoxFuture<'a, Result<Vec<u8>, AsyncIoError>> {
+        self.as_mut().read_range(start, len)
+    }
 }
 
 pub trait AsyncWriter: AsyncWrite + Send + Sync + Unpin {
@@ -172,6 +172,22 @@ impl From<&AssetPath<'_>> for PathBuf {
     }
 }
 
+/// Formats the path in the same `source://path@name` form it is parsed from.
+impl std::fmt::Display for AssetPath<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let AssetSource::Name(name) = &self.source {
+            write!(f, "{}://", name)?;
+        }
+
+        write!(f, "{}", self.path.display())?;
+
+        match &self.name {
+            Some(name) => write!(f, "@{}", name),
+            None => Ok(()),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum LoadPath<'a> {
     Path(AssetPath<'a>),
@@ -238,4 +254,17 @@ mod tests {
         assert_eq!(PathBuf::from(&path), PathBuf::from("remote://models/test.obj@cube"));
         assert_eq!(Some("cube"), path.name());
     }
+
+    #[test]
+    fn asset_path_display() {
+        assert_eq!(AssetPath::from("test.txt").to_string(), "test.txt");
+        assert_eq!(
+            AssetPath::from("models/test.obj@cube").to_string(),
+            "models/test.obj@cube"
+        );
+        assert_eq!(
+            AssetPath::from("remote://models/test.obj@cube").to_string(),
+            "remote://models/test.obj@cube"
+        );
+    }
 }
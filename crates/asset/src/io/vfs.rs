@@ -50,6 +50,14 @@ pub struct FileReader {
 }
 
 impl FileReader {
+    /// Reads from `data` without it living in a [`VirtualFs`].
+    pub fn new(data: Vec<u8>) -> Self {
+        Self {
+            data: Arc::new(data),
+            position: 0,
+        }
+    }
+
     pub fn size(&self) -> u64 {
         self.data.len() as u64
     }
@@ -57,7 +65,7 @@ impl FileReader {
 
 impl Read for FileReader {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-        let len = buf.len().min((self.size() - self.position) as usize);
+        let len = buf.len().min(self.size().saturating_sub(self.position) as usize);
         if len > 0 {
             buf[..len]
                 .copy_from_slice(&self.data[self.position as usize..self.position as usize + len]);
@@ -73,10 +81,10 @@ impl Seek for FileReader {
         match pos {
             std::io::SeekFrom::Start(offset) => self.position = offset,
             std::io::SeekFrom::End(offset) => {
-                self.position = (self.position as i64 + offset) as u64
+                self.position = (self.data.len() as i64 + offset) as u64
             }
             std::io::SeekFrom::Current(offset) => {
-                self.position = (self.data.len() as i64 + offset) as u64
+                self.position = (self.position as i64 + offset) as u64
             }
         }
 
@@ -90,7 +98,7 @@ impl AsyncRead for FileReader {
         _: &mut std::task::Context<'_>,
         buf: &mut [u8],
     ) -> std::task::Poll<std::io::Result<usize>> {
-        let len = buf.len().min((self.size() - self.position) as usize);
+        let len = buf.len().min(self.size().saturating_sub(self.position) as usize);
         if len > 0 {
             buf[..len]
                 .copy_from_slice(&self.data[self.position as usize..self.position as usize + len]);
@@ -128,10 +136,10 @@ impl AsyncSeek for FileReader {
         match pos {
             std::io::SeekFrom::Start(offset) => self.position = offset,
             std::io::SeekFrom::End(offset) => {
-                self.position = (self.position as i64 + offset) as u64
+                self.position = (self.data.len() as i64 + offset) as u64
             }
             std::io::SeekFrom::Current(offset) => {
-                self.position = (self.data.len() as i64 + offset) as u64
+                self.position = (self.position as i64 + offset) as u64
             }
         }
 
@@ -466,3 +474,35 @@ impl FileSystem for VirtualFs {
         Ok(fs.entries.contains_key(path))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::VirtualFs;
+    use crate::io::{AsyncIoError, AsyncReader, AsyncWriter, FileSystem};
+    use smol::{block_on, io::AsyncWriteExt};
+    use std::{io::ErrorKind, path::Path};
+
+    #[test]
+    fn read_range() {
+        let fs = VirtualFs::new();
+        let path = Path::new("data.bin");
+
+        block_on(async {
+            let mut writer = fs.writer(path).await.unwrap();
+            writer.write_all(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]).await.unwrap();
+            AsyncWriter::flush(&mut writer).await.unwrap();
+
+            let mut reader = fs.reader(path).await.unwrap();
+            let bytes = reader.read_range(4, 3).await.unwrap();
+
+            assert_eq!(bytes, vec![4, 5, 6]);
+            assert_eq!(reader.position, 7);
+
+            let mut boxed: Box<dyn AsyncReader> = Box::new(reader);
+            assert_eq!(boxed.read_range(8, 2).await.unwrap(), vec![8, 9]);
+
+            let error = boxed.read_range(8, usize::MAX).await.unwrap_err();
+            assert!(matches!(error, AsyncIoError::Io(e) if e.kind() == ErrorKind::UnexpectedEof));
+        });
+    }
+}
@@ -64,6 +64,7 @@ pub trait AssetAppExt {
     ) -> &mut Self;
     fn set_default_processor<P: AssetProcessor>(&mut self) -> &mut Self;
     fn set_cache<F: FileSystem>(&mut self, fs: F) -> &mut Self;
+    fn ignore_assets(&mut self, pattern: &str) -> &mut Self;
 }
 
 impl AssetAppExt for ecs::AppBuilder {
@@ -118,6 +119,11 @@ impl AssetAppExt for ecs::AppBuilder {
         self.world_mut().set_cache(fs);
         self
     }
+
+    fn ignore_assets(&mut self, pattern: &str) -> &mut Self {
+        self.world_mut().ignore_assets(pattern);
+        self
+    }
 }
 
 impl AssetAppExt for ecs::World {
@@ -211,4 +217,11 @@ impl AssetAppExt for ecs::World {
 
         self
     }
+
+    fn ignore_assets(&mut self, pattern: &str) -> &mut Self {
+        let builder = self.get_or_insert_resource(AssetDatabaseBuilder::new);
+        builder.ignore(pattern);
+
+        self
+    }
 }
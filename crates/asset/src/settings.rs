@@ -1,9 +1,33 @@
-use crate::asset::ErasedId;
-use serde::{Deserialize, Serialize};
+use crate::{
+    asset::ErasedId,
+    ext::{DeserializeExt, SerializeExt},
+};
+use serde::{Deserialize, Serialize, ser::SerializeTuple};
 
 pub trait Settings:
     Default + Send + Sync + Serialize + for<'de> Deserialize<'de> + 'static
 {
+    /// Written to `.meta` files alongside the settings. Bump this when the
+    /// settings change shape and upgrade older data in [`Settings::migrate`].
+    const VERSION: u32 = 0;
+
+    /// Upgrades settings that were serialized with an older `version`.
+    fn migrate(version: u32, data: &[u8]) -> Result<Self, SettingsError> {
+        let _ = data;
+        Err(SettingsError::Unmigratable {
+            version,
+            current: Self::VERSION,
+        })
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum SettingsError {
+    #[error("Cannot migrate settings from version {version} to version {current}")]
+    Unmigratable { version: u32, current: u32 },
+
+    #[error("Failed to decode settings: {0}")]
+    Decode(#[from] bincode::error::DecodeError),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -24,18 +48,52 @@ impl Default for DefaultSettings {
 
 impl Settings for DefaultSettings {}
 
-#[derive(Serialize)]
+#[derive(Debug)]
 pub struct AssetSettings<S: Settings> {
     pub id: ErasedId,
     settings: S,
 }
 impl<S: Settings> AssetSettings<S> {
+    /// Starts every versioned `.meta` file. Files written before settings
+    /// were versioned start with the id, which bincode always prefixes with
+    /// its length of 16, so they can never start with this header.
+    const HEADER: [u8; 4] = *b"meta";
+
     pub fn new(id: impl Into<ErasedId>, settings: S) -> Self {
         Self {
             id: id.into(),
             settings,
         }
     }
+
+    /// Decodes settings from a `.meta` file, treating files without a
+    /// version as version 0.
+    pub fn decode(bytes: &[u8]) -> Result<Self, SettingsError> {
+        if bytes.starts_with(&Self::HEADER) {
+            return Ok(Self::from_bytes(bytes)?);
+        }
+
+        let (id, read) =
+            bincode::serde::decode_from_slice::<ErasedId, _>(bytes, bincode::config::standard())?;
+
+        Ok(Self {
+            id,
+            settings: Self::decode_settings(0, &bytes[read..])?,
+        })
+    }
+
+    fn decode_settings(version: u32, data: &[u8]) -> Result<S, SettingsError> {
+        if version == S::VERSION {
+            Ok(S::from_bytes(data)?)
+        } else if version < S::VERSION {
+            S::migrate(version, data)
+        } else {
+            Err(SettingsError::Unmigratable {
+                version,
+                current: S::VERSION,
+            })
+        }
+    }
 }
 
 impl<S: Settings> Default for AssetSettings<S> {
@@ -61,16 +119,42 @@ impl<S: Settings> std::ops::DerefMut for AssetSettings<S> {
     }
 }
 
+impl<S: Settings> Serialize for AssetSettings<S> {
+    fn serialize<Ser>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+    where
+        Ser: serde::Serializer,
+    {
+        let settings = self
+            .settings
+            .to_bytes()
+            .map_err(serde::ser::Error::custom)?;
+
+        let mut tuple = serializer.serialize_tuple(4)?;
+        tuple.serialize_element(&Self::HEADER)?;
+        tuple.serialize_element(&self.id)?;
+        tuple.serialize_element(&S::VERSION)?;
+        tuple.serialize_element(&settings)?;
+        tuple.end()
+    }
+}
+
 impl<'de, S: Settings> Deserialize<'de> for AssetSettings<S> {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
-        let (id, settings): (ErasedId, S) = Deserialize::deserialize(deserializer)?;
-        Ok(Self {
-            id: ErasedId::from(id),
-            settings,
-        })
+        let (header, id, version, data): ([u8; 4], ErasedId, u32, Vec<u8>) =
+            Deserialize::deserialize(deserializer)?;
+
+        if header != Self::HEADER {
+            return Err(serde::de::Error::custom(
+                "Settings are missing a version, use AssetSettings::decode",
+            ));
+        }
+
+        let settings = Self::decode_settings(version, &data).map_err(serde::de::Error::custom)?;
+
+        Ok(Self { id, settings })
     }
 }
 
@@ -88,8 +172,9 @@ impl<S: Settings> ErasedAssetSettings for AssetSettings<S> {
 #[cfg(test)]
 mod tests {
     use crate::{
+        asset::ErasedId,
         ext::{DeserializeExt, SerializeExt},
-        settings::Settings,
+        settings::{AssetSettings, Settings, SettingsError},
     };
     use serde::{Deserialize, Serialize};
 
@@ -110,4 +195,119 @@ mod tests {
 
         assert_eq!(settings, deserialized);
     }
+
+    #[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+    struct TestSettingsV1 {
+        value: u32,
+    }
+
+    impl Settings for TestSettingsV1 {
+        const VERSION: u32 = 1;
+    }
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct TestSettingsV2 {
+        value: u32,
+        scale: f32,
+    }
+
+    impl Default for TestSettingsV2 {
+        fn default() -> Self {
+            Self {
+                value: 0,
+                scale: 1.0,
+            }
+        }
+    }
+
+    impl Settings for TestSettingsV2 {
+        const VERSION: u32 = 2;
+
+        fn migrate(version: u32, data: &[u8]) -> Result<Self, SettingsError> {
+            match version {
+                0 | 1 => {
+                    let v1 = TestSettingsV1::from_bytes(data)?;
+                    Ok(Self {
+                        value: v1.value,
+                        ..Default::default()
+                    })
+                }
+                _ => Err(SettingsError::Unmigratable {
+                    version,
+                    current: Self::VERSION,
+                }),
+            }
+        }
+    }
+
+    #[test]
+    fn test_migrate_settings() {
+        let id = ErasedId::new();
+        let v1 = AssetSettings::new(id, TestSettingsV1 { value: 5 });
+        let bytes = v1.to_bytes().unwrap();
+
+        let v2 = AssetSettings::<TestSettingsV2>::from_bytes(&bytes).unwrap();
+        assert_eq!(v2.id, id);
+        assert_eq!(v2.value, 5);
+        assert_eq!(v2.scale, 1.0);
+    }
+
+    #[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+    struct LegacySettings {
+        value: u32,
+    }
+
+    #[test]
+    fn test_decode_legacy_settings() {
+        let id = ErasedId::new();
+        let bytes = (id, LegacySettings { value: 5 }).to_bytes().unwrap();
+
+        let v2 = AssetSettings::<TestSettingsV2>::decode(&bytes).unwrap();
+        assert_eq!(v2.id, id);
+        assert_eq!(v2.value, 5);
+        assert_eq!(v2.scale, 1.0);
+
+        let v0 = AssetSettings::<TestSettings>::decode(&bytes).unwrap();
+        assert_eq!(v0.id, id);
+        assert_eq!(v0.value, 5);
+
+        let bytes = v0.to_bytes().unwrap();
+        let v0 = AssetSettings::<TestSettings>::decode(&bytes).unwrap();
+        assert_eq!(v0.id, id);
+        assert_eq!(v0.value, 5);
+    }
+
+    #[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+    struct TagSettings {
+        tag: [u8; 4],
+    }
+
+    impl Settings for TagSettings {}
+
+    #[test]
+    fn test_decode_legacy_settings_with_header_bytes() {
+        let id = ErasedId::new();
+        let legacy = TagSettings { tag: *b"ver:" };
+        let bytes = (id, &legacy).to_bytes().unwrap();
+
+        let decoded = AssetSettings::<TagSettings>::decode(&bytes).unwrap();
+        assert_eq!(decoded.id, id);
+        assert_eq!(*decoded, legacy);
+
+        let legacy = TagSettings { tag: *b"meta" };
+        let bytes = (id, &legacy).to_bytes().unwrap();
+
+        let decoded = AssetSettings::<TagSettings>::decode(&bytes).unwrap();
+        assert_eq!(decoded.id, id);
+        assert_eq!(*decoded, legacy);
+    }
+
+    #[test]
+    fn test_unmigratable_settings() {
+        let v2 = AssetSettings::new(ErasedId::new(), TestSettingsV2::default());
+        let bytes = v2.to_bytes().unwrap();
+
+        let error = AssetSettings::<TestSettingsV1>::from_bytes(&bytes).unwrap_err();
+        assert!(error.to_string().contains("version 2 to version 1"));
+    }
 }
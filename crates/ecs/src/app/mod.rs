@@ -108,11 +108,13 @@ impl App {
         self.world.update();
     }
 
-    pub fn run_sub(&mut self, phase: impl Phase, main: MainWorld) {
+    pub fn run_sub(&mut self, phase: impl Phase, mut main: MainWorld) {
+        self.world.sync_change_tick(main.change_tick());
         self.world.add_resource(main);
         self.run(phase);
         self.world.remove_resource::<MainWorld>();
         self.world.frame = main.frame;
+        main.sync_change_tick(self.world.change_tick());
     }
 
     fn run_once(mut self, phase: impl Phase) -> Self {
@@ -211,7 +213,7 @@ pub struct AppConfig {
     world: World,
     schedule: Schedule,
     plugins: Vec<Box<dyn Plugin>>,
-    registered: HashSet<&'static str>,
+    registered: HashSet<PluginId>,
 }
 
 impl Default for AppConfig {
@@ -276,6 +278,11 @@ impl AppConfig {
         self
     }
 
+    pub fn register_exclusive<A: Component, B: Component>(&mut self) -> &mut Self {
+        self.world_mut().register_exclusive::<A, B>();
+        self
+    }
+
     pub fn register_resource<R: Resource + Send>(&mut self) -> &mut Self {
         self.world_mut().register_resource::<R>();
         self
@@ -291,6 +298,11 @@ impl AppConfig {
         self
     }
 
+    pub fn register_event_capacity<E: Event>(&mut self, capacity: usize) -> &mut Self {
+        self.world_mut().register_event_capacity::<E>(capacity);
+        self
+    }
+
     pub fn add_mode<M: WorldMode>(&mut self) -> &mut Self {
         self.world_mut().add_mode::<M>();
         self
@@ -389,6 +401,55 @@ impl AppConfig {
     pub fn build(mut self, main: Option<MainWorld>) -> App {
         AppBuilder::from_sub_app(&mut self).build_app(main)
     }
+
+    /// Orders `plugins` so that each plugin comes after the plugins it depends on.
+    /// Dependencies outside of `plugins` must already be registered.
+    fn sort_plugins(
+        &self,
+        plugins: Vec<Box<dyn Plugin>>,
+    ) -> Result<Vec<Box<dyn Plugin>>, PluginBuildError> {
+        for plugin in &plugins {
+            for dependency in plugin.dependencies() {
+                if !self.registered.contains(&dependency) {
+                    return Err(PluginBuildError::MissingDependency {
+                        plugin: plugin.name(),
+                        dependency: dependency.name(),
+                    });
+                }
+            }
+        }
+
+        let mut pending = plugins.into_iter().map(Some).collect::<Vec<_>>();
+        let mut sorted = Vec::with_capacity(pending.len());
+
+        while sorted.len() < pending.len() {
+            let count = sorted.len();
+
+            for index in 0..pending.len() {
+                let Some(plugin) = &pending[index] else {
+                    continue;
+                };
+
+                let ready = plugin.dependencies().iter().all(|dependency| {
+                    pending
+                        .iter()
+                        .flatten()
+                        .all(|plugin| plugin.id() != *dependency)
+                });
+
+                if ready {
+                    sorted.extend(pending[index].take());
+                }
+            }
+
+            if sorted.len() == count {
+                let names = pending.iter().flatten().map(|p| p.name()).collect();
+                return Err(PluginBuildError::CyclicDependency(names));
+            }
+        }
+
+        Ok(sorted)
+    }
 }
 
 impl PluginCollection for AppConfig {
@@ -472,6 +533,11 @@ impl AppBuilder {
         self
     }
 
+    pub fn register_exclusive<A: Component, B: Component>(&mut self) -> &mut Self {
+        self.world_mut().register_exclusive::<A, B>();
+        self
+    }
+
     pub fn register_resource<R: Resource + Send>(&mut self) -> &mut Self {
         self.world_mut().register_resource::<R>();
         self
@@ -487,6 +553,11 @@ impl AppBuilder {
         self
     }
 
+    pub fn register_event_capacity<E: Event>(&mut self, capacity: usize) -> &mut Self {
+        self.world_mut().register_event_capacity::<E>(capacity);
+        self
+    }
+
     pub fn add_mode<M: WorldMode>(&mut self) -> &mut Self {
         self.world_mut().add_mode::<M>();
         self
@@ -638,6 +709,11 @@ impl AppBuilder {
         let mut queue: Vec<Box<dyn Plugin>> = vec![];
 
         while !plugins.is_empty() {
+            plugins = match self.main.sort_plugins(plugins) {
+                Ok(plugins) => plugins,
+                Err(error) => panic!("{}", error),
+            };
+
             for plugin in &mut plugins {
                 plugin.build(self);
             }
@@ -687,9 +763,7 @@ impl AppBuilder {
 
 impl PluginCollection for AppBuilder {
     fn add_plugin<P: Plugin>(&mut self, mut plugin: P) -> &mut Self {
-        if !self.main.registered.contains(plugin.name()) {
-            self.main.registered.insert(plugin.name());
-
+        if self.main.registered.insert(PluginId::of::<P>()) {
             plugin.setup(self);
             self.main.plugins.push(Box::new(plugin));
         }
@@ -700,7 +774,7 @@ impl PluginCollection for AppBuilder {
 
 #[allow(unused_imports, dead_code)]
 mod tests {
-    use super::{App, Plugin, PluginCollection};
+    use super::{App, Plugin, PluginBuildError, PluginCollection, PluginId};
     use crate::{AppTag, Resource, app::AppConfig};
 
     #[derive(Debug, Clone, Copy, PartialEq, Eq, Resource)]
@@ -745,6 +819,39 @@ mod tests {
         }
     }
 
+    #[derive(Debug, Default, PartialEq, Eq, Resource)]
+    pub struct BuildOrder(Vec<&'static str>);
+
+    pub struct Dependent;
+    impl Plugin for Dependent {
+        fn name(&self) -> &'static str {
+            "Dependent"
+        }
+
+        fn dependencies(&self) -> Vec<PluginId> {
+            vec![PluginId::of::<Dependency>()]
+        }
+
+        fn setup(&mut self, _: &mut super::AppBuilder) {}
+
+        fn build(&mut self, app: &mut super::AppBuilder) {
+            app.resource_mut::<BuildOrder>().0.push(self.name());
+        }
+    }
+
+    pub struct Dependency;
+    impl Plugin for Dependency {
+        fn name(&self) -> &'static str {
+            "Dependency"
+        }
+
+        fn setup(&mut self, _: &mut super::AppBuilder) {}
+
+        fn build(&mut self, app: &mut super::AppBuilder) {
+            app.resource_mut::<BuildOrder>().0.push(self.name());
+        }
+    }
+
     #[derive(AppTag)]
     pub struct TestApp;
 
@@ -758,6 +865,78 @@ mod tests {
         assert_eq!(app.world.resource::<Value>().0, 4);
     }
 
+    #[test]
+    fn build_dependencies_first() {
+        let mut config = AppConfig::new();
+        config.add_resource(BuildOrder::default());
+        config.add_plugin(Dependent);
+        config.add_plugin(Dependency);
+        let app = config.build(None);
+
+        let order = app.world.resource::<BuildOrder>();
+        assert_eq!(order.0, vec!["Dependency", "Dependent"]);
+    }
+
+    #[test]
+    fn missing_dependency() {
+        let mut config = AppConfig::new();
+        config.add_resource(BuildOrder::default());
+        config.add_plugin(Dependent);
+
+        let plugins = std::mem::take(&mut config.plugins);
+        let error = config.sort_plugins(plugins).err();
+
+        assert_eq!(
+            error,
+            Some(PluginBuildError::MissingDependency {
+                plugin: "Dependent",
+                dependency: std::any::type_name::<Dependency>(),
+            })
+        );
+    }
+
+    pub struct CycleA;
+    impl Plugin for CycleA {
+        fn name(&self) -> &'static str {
+            "CycleA"
+        }
+
+        fn dependencies(&self) -> Vec<PluginId> {
+            vec![PluginId::of::<CycleB>()]
+        }
+
+        fn setup(&mut self, _: &mut super::AppBuilder) {}
+    }
+
+    pub struct CycleB;
+    impl Plugin for CycleB {
+        fn name(&self) -> &'static str {
+            "CycleB"
+        }
+
+        fn dependencies(&self) -> Vec<PluginId> {
+            vec![PluginId::of::<CycleA>()]
+        }
+
+        fn setup(&mut self, _: &mut super::AppBuilder) {}
+    }
+
+    #[test]
+    fn cyclic_dependency() {
+        let mut config = AppConfig::new();
+        config.add_plugin(Dependency);
+        config.add_plugin(CycleA);
+        config.add_plugin(CycleB);
+
+        let plugins = std::mem::take(&mut config.plugins);
+        let error = config.sort_plugins(plugins).err();
+
+        assert_eq!(
+            error,
+            Some(PluginBuildError::CyclicDependency(vec!["CycleA", "CycleB"]))
+        );
+    }
+
     #[test]
     fn build_multi_app() {
         let mut builder = App::new();
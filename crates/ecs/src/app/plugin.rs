@@ -1,11 +1,18 @@
 use crate::AppBuilder;
+use std::any::{Any, TypeId};
 
 #[allow(unused_variables)]
-pub trait Plugin: 'static {
+pub trait Plugin: Any {
     fn name(&self) -> &'static str {
         std::any::type_name::<Self>()
     }
 
+    /// Plugins that must be built before this plugin. Each one must be added
+    /// to the app before this plugin is built.
+    fn dependencies(&self) -> Vec<PluginId> {
+        vec![]
+    }
+
     /// Setup is called when the plugin is added to the app.
     /// It is used to register systems, resources, and other app components.
     fn setup(&mut self, app: &mut AppBuilder);
@@ -17,6 +24,71 @@ pub trait Plugin: 'static {
     fn finish(&mut self, app: &mut AppBuilder) {}
 }
 
+impl dyn Plugin {
+    pub fn id(&self) -> PluginId {
+        PluginId {
+            id: (self as &dyn Any).type_id(),
+            name: self.name(),
+        }
+    }
+}
+
+/// Identifies a plugin by its type.
+#[derive(Debug, Clone, Copy)]
+pub struct PluginId {
+    id: TypeId,
+    name: &'static str,
+}
+
+impl PluginId {
+    pub fn of<P: Plugin>() -> Self {
+        Self {
+            id: TypeId::of::<P>(),
+            name: std::any::type_name::<P>(),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl PartialEq for PluginId {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for PluginId {}
+
+impl std::hash::Hash for PluginId {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PluginBuildError {
+    MissingDependency {
+        plugin: &'static str,
+        dependency: &'static str,
+    },
+    CyclicDependency(Vec<&'static str>),
+}
+
+impl std::fmt::Display for PluginBuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PluginBuildError::MissingDependency { plugin, dependency } => {
+                write!(f, "Plugin {} depends on missing plugin {}", plugin, dependency)
+            }
+            PluginBuildError::CyclicDependency(names) => {
+                write!(f, "Cyclic plugin dependency detected: {:?}", names)
+            }
+        }
+    }
+}
+
 pub trait PluginCollection {
     fn add_plugin<P: Plugin>(&mut self, plugin: P) -> &mut Self;
 }
@@ -129,40 +129,44 @@ impl Command for Spawn {
 #[derive(Event)]
 pub struct Despawned(pub Entity);
 
+/// Despawns an entity and all of its descendants. See
+/// [`World::despawn_recursive`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Despawn(pub Entity);
 
 impl Command for Despawn {
     fn execute(self, world: &mut World) {
-        let id = world.components().get_id::<Children>().unwrap();
-        let parent = world.get_component::<Parent>(self.0).copied();
+        let despawned = world.despawn_recursive(self.0);
 
-        let mut stack = vec![self.0];
-        let mut despawned = Vec::new();
-        while let Some(entity) = stack.pop() {
-            let Some(row) = world.despawn(entity) else {
-                continue;
-            };
+        let mut events = world.resource_mut::<Events<Despawned>>().writer();
+        events.send_batch(despawned.into_iter().map(Despawned));
+    }
+}
 
-            despawned.push(Despawned(entity));
+/// Despawns an entity without despawning its descendants. Direct children
+/// have their [`Parent`] removed and become root entities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DespawnAndOrphan(pub Entity);
 
-            let Some(children) = row.get::<Children>(id) else {
-                continue;
-            };
+impl Command for DespawnAndOrphan {
+    fn execute(self, world: &mut World) {
+        let parent = world.get_component::<Parent>(self.0).copied();
+        let children = world.get_component::<Children>(self.0).cloned();
 
-            for child in children.as_slice() {
-                stack.push(*child);
-            }
+        if world.despawn(self.0).is_none() {
+            return;
+        }
+
+        for child in children.iter().flat_map(|children| children.as_slice()) {
+            world.remove_component::<Parent>(*child);
         }
 
-        if let Some(children) =
-            parent.and_then(|parent| world.get_component_mut::<Children>(*parent))
-        {
-            children.retain(|child| *child != self.0);
+        if let Some(parent) = parent {
+            EntityMut::remove_child(world, *parent, self.0);
         }
 
         let mut events = world.resource_mut::<Events<Despawned>>().writer();
-        events.send_batch(despawned);
+        events.send(Despawned(self.0));
     }
 }
 
@@ -501,14 +505,21 @@ impl EntityCommands<'_> {
     pub fn despawn(self) {
         self.buffer.add(Despawn(self.entity));
     }
+
+    pub fn despawn_and_orphan(self) {
+        self.buffer.add(DespawnAndOrphan(self.entity));
+    }
 }
 
 #[allow(unused_imports, dead_code)]
 mod tests {
     use super::{
-        AddChild, AddChildren, Despawn, Despawned, RemoveAllChildren, RemoveChild, RemoveChildren, Spawner,
+        AddChild, AddChildren, Despawn, DespawnAndOrphan, Despawned, RemoveAllChildren, RemoveChild,
+        RemoveChildren, Spawner,
+    };
+    use crate::{
+        Children, ClonedEntity, Command, CommandBuffer, Component, Events, Parent, SystemArg, World,
     };
-    use crate::{Children, Command, CommandBuffer, Component, Events, Parent, SystemArg, World};
 
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     struct Age(u32);
@@ -585,6 +596,62 @@ mod tests {
         assert!(world.archetypes.get_entity(child).is_none());
     }
 
+    #[test]
+    fn despawn_recursive() {
+        let mut world = World::new();
+        world.register::<Parent>();
+        world.register::<Children>();
+
+        let holder = world.spawn();
+        let root = world.spawn();
+        let sibling = world.spawn();
+        let children = (0..2).map(|_| world.spawn()).collect::<Vec<_>>();
+        let leaves = (0..2).map(|_| world.spawn()).collect::<Vec<_>>();
+
+        AddChildren::new(holder, vec![root, sibling]).execute(&mut world);
+        AddChildren::new(root, children.clone()).execute(&mut world);
+        AddChildren::new(children[0], leaves.clone()).execute(&mut world);
+
+        let despawned = world.despawn_recursive(root);
+
+        let mut hierarchy = vec![root];
+        hierarchy.extend(&children);
+        hierarchy.extend(&leaves);
+        assert_eq!(despawned.len(), hierarchy.len());
+        for entity in hierarchy {
+            assert!(despawned.contains(&entity));
+            assert!(world.archetypes.get_entity(entity).is_none());
+        }
+
+        let remaining = world.get_component::<Children>(holder).unwrap();
+        assert_eq!(remaining.as_slice(), &[sibling]);
+        assert!(world.archetypes.get_entity(sibling).is_some());
+    }
+
+    #[test]
+    fn despawn_and_orphan() {
+        let mut world = World::new();
+        world.register::<Parent>();
+        world.register::<Children>();
+        world.add_resource(Events::<Despawned>::new());
+
+        let root = world.spawn();
+        let parent = world.spawn();
+        let children = (0..2).map(|_| world.spawn()).collect::<Vec<_>>();
+
+        AddChild::new(root, parent).execute(&mut world);
+        AddChildren::new(parent, children.clone()).execute(&mut world);
+
+        DespawnAndOrphan(parent).execute(&mut world);
+
+        assert!(world.archetypes.get_entity(parent).is_none());
+        assert!(world.get_component::<Children>(root).is_none());
+        for child in children {
+            assert!(world.archetypes.get_entity(child).is_some());
+            assert!(world.get_component::<Parent>(child).is_none());
+        }
+    }
+
     #[test]
     fn add_child() {
         let mut world = World::new();
@@ -690,4 +757,85 @@ mod tests {
             assert!(parent.is_none());
         }
     }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct Name(String);
+    impl Component for Name {}
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Health(u32);
+    impl Component for Health {}
+
+    #[test]
+    fn clone_entity() {
+        let mut world = World::new();
+        world.register::<Parent>();
+        world.register::<Children>();
+        world.register_cloneable::<Age>();
+        world.register_cloneable::<Name>();
+        let health = world.register::<Health>();
+
+        let root = world.spawn();
+        let prefab = world.spawn();
+        let child = world.spawn();
+        let grandchild = world.spawn();
+
+        world.add_component(prefab, Name(String::from("prefab")));
+        world.add_component(child, Age(1));
+        world.add_component(child, Name(String::from("child")));
+        world.add_component(grandchild, Age(2));
+        world.add_component(grandchild, Health(10));
+
+        AddChild::new(root, prefab).execute(&mut world);
+        AddChild::new(prefab, child).execute(&mut world);
+        AddChild::new(child, grandchild).execute(&mut world);
+
+        let ClonedEntity {
+            entity: clone,
+            skipped,
+        } = world.clone_entity(prefab);
+        assert_ne!(clone, prefab);
+        assert_eq!(skipped, vec![health]);
+        assert_eq!(
+            world.get_component::<Name>(clone),
+            world.get_component::<Name>(prefab)
+        );
+        assert_eq!(
+            world.get_component::<Parent>(clone).copied(),
+            Some(Parent::from(root))
+        );
+        assert!(
+            world
+                .get_component::<Children>(root)
+                .unwrap()
+                .contains(clone)
+        );
+
+        let children = world.get_component::<Children>(clone).unwrap();
+        assert_eq!(children.len(), 1);
+        let cloned_child = children[0];
+        assert_ne!(cloned_child, child);
+        assert_eq!(world.get_component::<Age>(cloned_child), Some(&Age(1)));
+        assert_eq!(
+            world.get_component::<Name>(cloned_child),
+            Some(&Name(String::from("child")))
+        );
+        assert_eq!(
+            world.get_component::<Parent>(cloned_child).copied(),
+            Some(Parent::from(clone))
+        );
+
+        let children = world.get_component::<Children>(cloned_child).unwrap();
+        assert_eq!(children.len(), 1);
+        let cloned_grandchild = children[0];
+        assert_ne!(cloned_grandchild, grandchild);
+        assert_eq!(world.get_component::<Age>(cloned_grandchild), Some(&Age(2)));
+        assert_eq!(
+            world.get_component::<Parent>(cloned_grandchild).copied(),
+            Some(Parent::from(cloned_child))
+        );
+
+        let children = world.get_component::<Children>(prefab).unwrap();
+        assert_eq!(children.as_slice(), &[child]);
+    }
 }
@@ -1,6 +1,9 @@
 use std::any::TypeId;
 
-use crate::{Entity, Resource, SystemArg, World, WorldAccess, WorldMode, world::WorldCell};
+use crate::{
+    ComponentKit, Entities, Entity, Resource, SystemArg, World, WorldAccess, WorldMode,
+    world::WorldCell,
+};
 
 pub mod entity;
 
@@ -131,16 +134,13 @@ impl CommandBuffer {
 }
 
 pub struct Commands<'world, 'state> {
+    entities: &'world Entities,
     commands: &'state mut CommandBuffer,
-    _marker: std::marker::PhantomData<&'world ()>,
 }
 
 impl<'world, 'state> Commands<'world, 'state> {
-    pub fn new(commands: &'state mut CommandBuffer) -> Self {
-        Commands {
-            commands,
-            _marker: std::marker::PhantomData,
-        }
+    pub fn new(entities: &'world Entities, commands: &'state mut CommandBuffer) -> Self {
+        Commands { entities, commands }
     }
 
     pub fn add<C: Command>(&mut self, command: C) {
@@ -157,6 +157,26 @@ impl<'world, 'state> Commands<'world, 'state> {
             buffer: self.commands,
         }
     }
+
+    /// Reserves an entity that is spawned with `components` when the buffer
+    /// is executed.
+    pub fn spawn<C: ComponentKit>(&'_ mut self, components: C) -> EntityCommands<'_> {
+        let mut commands = EntityCommands::new(self.entities.reserve(), self.commands);
+        commands.add(|entity, world: &mut World| {
+            world.archetypes.add_entity(entity);
+        });
+        commands.add_components(components);
+        commands
+    }
+
+    /// Removes the resource `R` when the buffer is executed and hands it to `f`.
+    /// `f` receives `None` if the resource doesn't exist.
+    pub fn remove_resource_with<R: Resource>(
+        &mut self,
+        f: impl FnOnce(Option<R>, &mut World) + Send + 'static,
+    ) {
+        self.commands.add(RemoveResourceWith::<R, _>::new(f));
+    }
 }
 
 unsafe impl SystemArg for Commands<'_, '_> {
@@ -174,10 +194,10 @@ unsafe impl SystemArg for Commands<'_, '_> {
 
     unsafe fn get<'world, 'state>(
         state: &'state mut Self::State,
-        _: WorldCell<'world>,
+        world: WorldCell<'world>,
         _: &crate::system::SystemMeta,
     ) -> Self::Item<'world, 'state> {
-        Commands::new(state)
+        Commands::new(unsafe { world.get() }.entities(), state)
     }
 }
 
@@ -204,11 +224,34 @@ impl RemoveResource {
 impl Command for RemoveResource {
     fn execute(self, world: &mut World) {
         if let Some(id) = world.resources.get_id_dynamic(&self.0) {
-            world.resources.remove_by_id(id, world.frame);
+            world.resources.remove_by_id(id, world.change_tick());
         }
     }
 }
 
+pub struct RemoveResourceWith<R: Resource, F: FnOnce(Option<R>, &mut World) + Send + 'static> {
+    f: F,
+    _marker: std::marker::PhantomData<fn() -> R>,
+}
+
+impl<R: Resource, F: FnOnce(Option<R>, &mut World) + Send + 'static> RemoveResourceWith<R, F> {
+    pub fn new(f: F) -> Self {
+        Self {
+            f,
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<R: Resource, F: FnOnce(Option<R>, &mut World) + Send + 'static> Command
+    for RemoveResourceWith<R, F>
+{
+    fn execute(self, world: &mut World) {
+        let resource = world.remove_resource::<R>();
+        (self.f)(resource, world);
+    }
+}
+
 pub struct EnterMode<M: WorldMode>(std::marker::PhantomData<M>);
 impl<M: WorldMode> Default for EnterMode<M> {
     fn default() -> Self {
@@ -261,7 +304,7 @@ impl<'a> EntityCommands<'a> {
 
 #[allow(unused_imports, dead_code)]
 mod tests {
-    use super::{Command, CommandBuffer};
+    use super::{Command, CommandBuffer, Commands};
     use crate::{Resource, World};
 
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -286,4 +329,32 @@ mod tests {
 
         assert_eq!(world.resource::<Value>().0, count);
     }
+
+    #[test]
+    fn remove_resource_with() {
+        let mut world = World::new();
+        let mut buffer = CommandBuffer::new();
+        world.add_resource(Value(7));
+
+        let mut commands = Commands::new(world.entities(), &mut buffer);
+        commands.remove_resource_with::<Value>(|value, world| {
+            world.add_resource(Removed(value));
+        });
+        buffer.execute(&mut world);
+
+        assert!(world.try_resource::<Value>().is_none());
+        assert_eq!(world.resource::<Removed>().0, Some(Value(7)));
+
+        let mut commands = Commands::new(world.entities(), &mut buffer);
+        commands.remove_resource_with::<Value>(|value, world| {
+            world.add_resource(Removed(value));
+        });
+        buffer.execute(&mut world);
+
+        assert_eq!(world.resource::<Removed>().0, None);
+    }
+
+    #[derive(Debug)]
+    pub struct Removed(Option<Value>);
+    impl Resource for Removed {}
 }
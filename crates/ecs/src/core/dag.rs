@@ -1,5 +1,5 @@
 use fixedbitset::FixedBitSet;
-use std::collections::VecDeque;
+use std::{cmp::Reverse, collections::BinaryHeap};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CyclicDependency(pub Vec<usize>);
@@ -56,11 +56,17 @@ impl<N> IndexDag<N> {
         index
     }
 
-    pub fn add_dependency(&mut self, dependency: usize, index: usize) {
-        self.dependencies[index] += 1;
+    /// Makes `index` depend on `dependency`. Adding an edge that already
+    /// exists does nothing. Returns true if the edge is new.
+    pub fn add_dependency(&mut self, dependency: usize, index: usize) -> bool {
         self.dependents[dependency].grow(index + 1);
-        self.dependents[dependency].set(index, true);
+        if self.dependents[dependency].put(index) {
+            return false;
+        }
+
+        self.dependencies[index] += 1;
         self.is_dirty = true;
+        true
     }
 
     pub fn remove_dependency(&mut self, dependency: usize, index: usize) -> bool {
@@ -143,20 +149,22 @@ impl<N> IndexDag<N> {
         //     self.topology = order;
         // }
 
+        // Nodes without a path between them are ordered by index, so the
+        // topology follows insertion order wherever dependencies allow it.
         let mut order = Vec::new();
         let mut dependencies = self.dependencies.clone();
         let mut stack = self
             .dependencies
             .iter()
             .enumerate()
-            .filter_map(|(index, count)| (*count == 0).then_some(index))
-            .collect::<VecDeque<_>>();
+            .filter_map(|(index, count)| (*count == 0).then_some(Reverse(index)))
+            .collect::<BinaryHeap<_>>();
 
-        while let Some(index) = stack.pop_front() {
+        while let Some(Reverse(index)) = stack.pop() {
             for dependent in self.dependents[index].ones() {
                 dependencies[dependent] -= 1;
                 if dependencies[dependent] == 0 {
-                    stack.push_back(dependent);
+                    stack.push(Reverse(dependent));
                 }
             }
 
@@ -164,9 +172,15 @@ impl<N> IndexDag<N> {
         }
 
         if order.len() != self.nodes.len() {
-            if let Some(cycle) = self.find_cycle() {
-                return Err(CyclicDependency(cycle));
-            }
+            // Nodes left out of the order can only be part of, or depend on, a
+            // cycle. Fall back to reporting all of them if none is found.
+            let cycle = self.find_cycle().unwrap_or_else(|| {
+                (0..self.nodes.len())
+                    .filter(|index| !order.contains(index))
+                    .collect()
+            });
+
+            return Err(CyclicDependency(cycle));
         }
 
         self.topology = order;
@@ -332,6 +346,19 @@ mod tests {
         assert!(dag.dependents()[node1].contains(node2));
     }
 
+    #[test]
+    fn duplicate_dependency() {
+        let mut dag = super::IndexDag::new();
+        let node1 = dag.add_node("Node1");
+        let node2 = dag.add_node("Node2");
+
+        assert!(dag.add_dependency(node1, node2));
+        assert!(!dag.add_dependency(node1, node2));
+
+        assert_eq!(dag.dependencies()[node2], 1);
+        assert_eq!(dag.build().unwrap(), &[node1, node2]);
+    }
+
     #[test]
     fn remove_dependency() {
         let mut dag = super::IndexDag::new();
@@ -360,6 +387,20 @@ mod tests {
         assert_eq!(topology, &[node2, node1, node3]);
     }
 
+    #[test]
+    fn topological_sort_insertion_order() {
+        let mut dag = super::IndexDag::new();
+        let node1 = dag.add_node("Node1");
+        let node2 = dag.add_node("Node2");
+        let node3 = dag.add_node("Node3");
+        let node4 = dag.add_node("Node4");
+
+        dag.add_dependency(node3, node1); // Node1 depends on Node3
+
+        let topology = dag.build().unwrap();
+        assert_eq!(topology, &[node2, node3, node1, node4]);
+    }
+
     #[test]
     fn cycle_detection() {
         let mut dag = super::IndexDag::new();
@@ -1,3 +1,5 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Frame(pub u32);
 
@@ -108,3 +110,31 @@ impl ObjectStatus {
         self.modified.update(current);
     }
 }
+
+/// A counter that advances every time a system runs, so changes are ordered
+/// within a frame and not just across frames.
+#[derive(Debug)]
+pub struct ChangeTick(AtomicU32);
+
+impl ChangeTick {
+    pub fn new(tick: Frame) -> Self {
+        Self(AtomicU32::new(tick.0))
+    }
+
+    pub fn get(&self) -> Frame {
+        Frame(self.0.load(Ordering::Acquire))
+    }
+
+    /// Advances the tick and returns the new value.
+    pub fn increment(&self) -> Frame {
+        Frame(self.0.fetch_add(1, Ordering::AcqRel).wrapping_add(1))
+    }
+
+    /// Moves the tick forward to `tick` if it's behind.
+    pub fn sync(&mut self, tick: Frame) {
+        let current = self.0.get_mut();
+        if (tick.0.wrapping_sub(*current) as i32) > 0 {
+            *current = tick.0;
+        }
+    }
+}
@@ -335,6 +335,11 @@ macro_rules! task_pool_type {
                     .get()
                     .expect(concat!(stringify!($name), " has not been initialized."))
             }
+
+            /// Get a reference to the singleton pool if it has been initialized.
+            pub fn try_get() -> Option<&'static $name> {
+                $static_name.get()
+            }
         }
 
         impl std::ops::Deref for $name {
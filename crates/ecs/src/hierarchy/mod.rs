@@ -1,4 +1,7 @@
-use crate::{BaseFilter, BaseQuery, Component, Entity, Query};
+use crate::{
+    BaseFilter, BaseQuery, Component, Entity, Query, QueryIter, QueryState, ReadOnly, ReadQuery,
+    SystemArg, SystemMeta, World, WorldAccess, world::WorldCell,
+};
 use std::ops::Index;
 
 const EMPTY: &'static [Entity] = &[];
@@ -115,7 +118,11 @@ pub trait HierarchyExt<Q: BaseQuery, F: BaseFilter> {
     fn parent(&self, entity: Entity) -> Option<Q::Item<'_>>;
     fn children(&'_ self, entity: Entity) -> ChildIter<'_, Q, F>;
     fn ancestors(&'_ self, entity: Entity) -> AncestorIter<'_, Q, F>;
+    fn siblings(&'_ self, entity: Entity) -> SiblingIter<'_, Q, F>;
     fn descendents(&'_ self, entity: Entity) -> DescendentIter<'_, Q, F>;
+    /// Every entity below `entity` in depth-first order, whether or not it
+    /// matches the query.
+    fn descendant_entities(&'_ self, entity: Entity) -> DescendantEntities<'_>;
 }
 
 impl<'w, 's, Q: BaseQuery, F: BaseFilter> HierarchyExt<Q, F> for Query<'w, 's, Q, F> {
@@ -134,9 +141,17 @@ impl<'w, 's, Q: BaseQuery, F: BaseFilter> HierarchyExt<Q, F> for Query<'w, 's, Q
         AncestorIter::new(entity, self)
     }
 
+    fn siblings(&'_ self, entity: Entity) -> SiblingIter<'_, Q, F> {
+        SiblingIter::new(entity, self)
+    }
+
     fn descendents(&'_ self, entity: Entity) -> DescendentIter<'_, Q, F> {
         DescendentIter::new(entity, self)
     }
+
+    fn descendant_entities(&'_ self, entity: Entity) -> DescendantEntities<'_> {
+        DescendantEntities::new(entity, unsafe { self.world.get() })
+    }
 }
 
 pub struct EntityIter<'a, Q: BaseQuery, F: BaseFilter, P: Fn(&Entity, &Entity) -> bool> {
@@ -319,13 +334,207 @@ impl<'a, Q: BaseQuery, F: BaseFilter> Iterator for DescendentIter<'a, Q, F> {
     }
 }
 
+pub struct DescendantEntities<'a> {
+    world: &'a World,
+    stack: Vec<std::slice::Iter<'a, Entity>>,
+}
+
+impl<'a> DescendantEntities<'a> {
+    pub fn new(entity: Entity, world: &'a World) -> Self {
+        let stack = world
+            .get_component::<Children>(entity)
+            .map(|children| vec![children.as_slice().iter()])
+            .unwrap_or_default();
+
+        Self { world, stack }
+    }
+}
+
+impl<'a> Iterator for DescendantEntities<'a> {
+    type Item = Entity;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let Some(entity) = self.stack.last_mut()?.next().copied() else {
+                self.stack.pop();
+                continue;
+            };
+
+            if let Some(children) = self.world.get_component::<Children>(entity) {
+                self.stack.push(children.as_slice().iter());
+            }
+
+            return Some(entity);
+        }
+    }
+}
+
+/// Joins a child query with a parent query, yielding `(child, parent)` items
+/// for every entity with a [`Parent`] that matches the child query and whose
+/// parent matches the parent query.
+///
+/// Both queries are registered as separate archetype accesses, so a child query
+/// that writes a component the parent query reads is reported as a conflict.
+pub struct Relations<'w, 's, C: BaseQuery, P: BaseQuery, CF: BaseFilter = (), PF: BaseFilter = ()> {
+    children: Query<'w, 's, (C, &'static Parent), CF>,
+    parents: Query<'w, 's, P, PF>,
+}
+
+impl<'w, 's, C: BaseQuery, P: BaseQuery, CF: BaseFilter, PF: BaseFilter>
+    Relations<'w, 's, C, P, CF, PF>
+{
+    pub fn new(
+        children: Query<'w, 's, (C, &'static Parent), CF>,
+        parents: Query<'w, 's, P, PF>,
+    ) -> Self {
+        Self { children, parents }
+    }
+
+    pub fn children(&self) -> &Query<'w, 's, (C, &'static Parent), CF> {
+        &self.children
+    }
+
+    pub fn parents(&self) -> &Query<'w, 's, P, PF> {
+        &self.parents
+    }
+
+    /// Iterates the `(child, parent)` pairs. The items borrow the world, so
+    /// they outlive the borrow of `self`.
+    pub fn iter(&self) -> RelationIter<'w, 's, C, P, CF, PF>
+    where
+        C: ReadQuery,
+        P: ReadQuery,
+    {
+        RelationIter::new(self.children, self.parents)
+    }
+
+    /// Iterates the `(child, parent)` pairs with mutable child items. A
+    /// parent is yielded once for each of its children, so parent items are
+    /// read only.
+    pub fn iter_mut(&mut self) -> RelationIter<'_, 's, C, P, CF, PF>
+    where
+        P: ReadQuery,
+    {
+        RelationIter::new(self.children, self.parents)
+    }
+}
+
+impl<'w, 's, C: ReadQuery, P: ReadQuery, CF: BaseFilter, PF: BaseFilter> IntoIterator
+    for &Relations<'w, 's, C, P, CF, PF>
+{
+    type Item = (C::Item<'w>, P::Item<'w>);
+
+    type IntoIter = RelationIter<'w, 's, C, P, CF, PF>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'w, 's, C: BaseQuery, P: ReadQuery, CF: BaseFilter, PF: BaseFilter> IntoIterator
+    for Relations<'w, 's, C, P, CF, PF>
+{
+    type Item = (C::Item<'w>, P::Item<'w>);
+
+    type IntoIter = RelationIter<'w, 's, C, P, CF, PF>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        RelationIter::new(self.children, self.parents)
+    }
+}
+
+pub struct RelationIter<'w, 's, C: BaseQuery, P: BaseQuery, CF: BaseFilter, PF: BaseFilter> {
+    children: QueryIter<'w, 's, (C, &'static Parent), CF>,
+    parents: Query<'w, 's, P, PF>,
+}
+
+impl<'w, 's, C: BaseQuery, P: BaseQuery, CF: BaseFilter, PF: BaseFilter>
+    RelationIter<'w, 's, C, P, CF, PF>
+{
+    fn new(
+        children: Query<'w, 's, (C, &'static Parent), CF>,
+        parents: Query<'w, 's, P, PF>,
+    ) -> Self {
+        Self {
+            children: children.into_iter(),
+            parents,
+        }
+    }
+}
+
+impl<'w, 's, C: BaseQuery, P: BaseQuery, CF: BaseFilter, PF: BaseFilter> Iterator
+    for RelationIter<'w, 's, C, P, CF, PF>
+{
+    type Item = (C::Item<'w>, P::Item<'w>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (child, parent) = self.children.next()?;
+            if let Some(parent) = Query::entity_item(self.parents, parent.get()) {
+                return Some((child, parent));
+            }
+        }
+    }
+}
+
+unsafe impl<C, P, CF, PF> SystemArg for Relations<'_, '_, C, P, CF, PF>
+where
+    C: BaseQuery + 'static,
+    P: BaseQuery + 'static,
+    CF: BaseFilter + 'static,
+    PF: BaseFilter + 'static,
+{
+    type Item<'world, 'state> = Relations<'world, 'state, C, P, CF, PF>;
+
+    type State = (QueryState<(C, &'static Parent), CF>, QueryState<P, PF>);
+
+    fn init(world: &mut World, access: &mut WorldAccess) -> Self::State {
+        let children = Query::<(C, &'static Parent), CF>::init(world, access);
+        let parents = Query::<P, PF>::init(world, access);
+
+        (children, parents)
+    }
+
+    unsafe fn get<'world, 'state>(
+        state: &'state mut Self::State,
+        world: WorldCell<'world>,
+        system: &'world SystemMeta,
+    ) -> Self::Item<'world, 'state> {
+        let (children, parents) = state;
+
+        Relations {
+            children: Query::from_system(world, children, system),
+            parents: Query::from_system(world, parents, system),
+        }
+    }
+}
+
+unsafe impl<C, P, CF, PF> ReadOnly for Relations<'_, '_, C, P, CF, PF>
+where
+    C: ReadQuery + 'static,
+    P: ReadQuery + 'static,
+    CF: BaseFilter + 'static,
+    PF: BaseFilter + 'static,
+{
+}
+
 #[allow(unused_imports, dead_code)]
 mod tests {
-    use super::{Children, HierarchyExt, Parent};
+    use super::{Children, HierarchyExt, Parent, Relations};
     use crate::{
-        ArchetypeAccess, Command, Entity, Query, QueryState, World, commands::entity::AddChild,
+        ArchetypeAccess, Command, Component, Entity, Phase, Query, QueryState, Resource, RunMode,
+        Schedule, SystemArg, World, WorldAccess,
+        commands::entity::{AddChild, AddChildren},
     };
 
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    struct Value(u32);
+    impl Component for Value {}
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    struct Other(u32);
+    impl Component for Other {}
+
     #[test]
     fn parent_query() {
         let mut world = World::new();
@@ -383,4 +592,207 @@ mod tests {
         let descendents = query.descendents(ancestor);
         descendents.for_each(|entity| assert!(entity == parent || entity == child));
     }
+
+    #[test]
+    fn relations_query() {
+        let mut world = World::new();
+        world.register::<Parent>();
+        world.register::<Children>();
+        world.register::<Value>();
+
+        let root = world.spawn();
+        let parent = world.spawn();
+        let child = world.spawn();
+        let orphan = world.spawn();
+
+        world.add_component(root, Value(0));
+        world.add_component(parent, Value(1));
+        world.add_component(child, Value(2));
+        world.add_component(orphan, Value(3));
+
+        AddChild::new(root, parent).execute(&mut world);
+        AddChild::new(parent, child).execute(&mut world);
+
+        let mut children =
+            QueryState::<(Entity, &Parent), ()>::new(&mut world, &mut ArchetypeAccess::new());
+        let mut parents = QueryState::<&Value>::new(&mut world, &mut ArchetypeAccess::new());
+
+        let relations = Relations::<Entity, &Value>::new(
+            Query::new(unsafe { world.cell() }, &mut children),
+            Query::new(unsafe { world.cell() }, &mut parents),
+        );
+
+        let mut pairs = relations
+            .iter()
+            .map(|(entity, value)| (entity, *value))
+            .collect::<Vec<_>>();
+        pairs.sort_by_key(|(_, value)| value.0);
+
+        assert_eq!(pairs, vec![(parent, Value(0)), (child, Value(1))]);
+    }
+
+    #[test]
+    fn relations_skip_unmatched_parent() {
+        let mut world = World::new();
+        world.register::<Parent>();
+        world.register::<Children>();
+        world.register::<Value>();
+        world.register::<Other>();
+
+        let root = world.spawn();
+        let parent = world.spawn();
+        let child = world.spawn();
+
+        world.add_component(parent, Other(1));
+        world.add_component(child, Other(2));
+
+        AddChild::new(root, parent).execute(&mut world);
+        AddChild::new(parent, child).execute(&mut world);
+
+        let mut children =
+            QueryState::<(&Other, &Parent), ()>::new(&mut world, &mut ArchetypeAccess::new());
+        let mut parents = QueryState::<&Other>::new(&mut world, &mut ArchetypeAccess::new());
+
+        let relations = Relations::<&Other, &Other>::new(
+            Query::new(unsafe { world.cell() }, &mut children),
+            Query::new(unsafe { world.cell() }, &mut parents),
+        );
+
+        let pairs = relations
+            .iter()
+            .map(|(child, parent)| (*child, *parent))
+            .collect::<Vec<_>>();
+
+        assert_eq!(pairs, vec![(Other(2), Other(1))]);
+    }
+
+    #[test]
+    fn world_hierarchy() {
+        let mut world = World::new();
+        world.register::<Parent>();
+        world.register::<Children>();
+
+        let parent = world.spawn();
+        let children = (0..2).map(|_| world.spawn()).collect::<Vec<_>>();
+        let loose = world.spawn();
+        AddChildren::new(parent, children.clone()).execute(&mut world);
+
+        assert_eq!(world.children(parent), children.as_slice());
+        assert_eq!(world.parent(parent), None);
+
+        assert!(world.children(children[0]).is_empty());
+        assert_eq!(world.parent(children[0]), Some(parent));
+
+        assert!(world.children(loose).is_empty());
+        assert_eq!(world.parent(loose), None);
+    }
+
+    #[test]
+    fn sibling_query() {
+        let mut world = World::new();
+        world.register::<Parent>();
+        world.register::<Children>();
+
+        let parent = world.spawn();
+        let children = (0..3).map(|_| world.spawn()).collect::<Vec<_>>();
+        AddChildren::new(parent, children.clone()).execute(&mut world);
+
+        let mut state = QueryState::<Entity>::new(&mut world, &mut ArchetypeAccess::new());
+        let query = Query::new(unsafe { world.cell() }, &mut state);
+        let siblings = query.siblings(children[1]).collect::<Vec<_>>();
+
+        assert_eq!(siblings, vec![children[0], children[2]]);
+        assert_eq!(query.siblings(parent).count(), 0);
+    }
+
+    #[test]
+    fn descendant_entities() {
+        let mut world = World::new();
+        world.register::<Parent>();
+        world.register::<Children>();
+        world.register::<Value>();
+
+        let root = world.spawn();
+        let entities = (0..4).map(|_| world.spawn()).collect::<Vec<_>>();
+        world.add_component(entities[0], Value(0));
+        world.add_component(entities[3], Value(3));
+
+        AddChild::new(root, entities[0]).execute(&mut world);
+        AddChild::new(entities[0], entities[1]).execute(&mut world);
+        AddChild::new(root, entities[2]).execute(&mut world);
+        AddChild::new(entities[2], entities[3]).execute(&mut world);
+
+        let mut state = QueryState::<&Value>::new(&mut world, &mut ArchetypeAccess::new());
+        let query = Query::new(unsafe { world.cell() }, &mut state);
+        let visited = query.descendant_entities(root).collect::<Vec<_>>();
+
+        assert_eq!(visited, entities);
+        assert_eq!(query.descendant_entities(entities[1]).count(), 0);
+    }
+
+    #[test]
+    fn relations_conflicting_access() {
+        let mut world = World::new();
+
+        let mut access = WorldAccess::new();
+        Relations::<&mut Value, &Value>::init(&mut world, &mut access);
+        assert!(access.validate().is_err());
+
+        let mut access = WorldAccess::new();
+        Relations::<&mut Value, &Other>::init(&mut world, &mut access);
+        assert!(access.validate().is_ok());
+    }
+
+    struct Root;
+    impl Phase for Root {}
+
+    #[derive(Default)]
+    struct Pairs(Vec<(Value, Value)>);
+    impl Resource for Pairs {}
+
+    /// Only compiles if the items borrow the world rather than `relations`.
+    fn world_items<'w>(
+        relations: &Relations<'w, '_, &Value, &Value>,
+    ) -> Vec<(&'w Value, &'w Value)> {
+        relations.iter().collect()
+    }
+
+    #[test]
+    fn relations_system_arg() {
+        let mut world = World::new();
+        world.register::<Parent>();
+        world.register::<Children>();
+        world.register::<Value>();
+        world.register::<Other>();
+        world.add_resource(Pairs::default());
+
+        let parent = world.spawn();
+        let child = world.spawn();
+        world.add_component(parent, Value(1));
+        world.add_component(parent, Other(1));
+        world.add_component(child, Value(2));
+        AddChild::new(parent, child).execute(&mut world);
+
+        let mut schedule = Schedule::new(RunMode::Sequential);
+        schedule.add_systems(Root, |mut relations: Relations<&mut Value, &Other>| {
+            for (child, parent) in relations.iter_mut() {
+                child.0 += parent.0;
+            }
+        });
+        schedule.add_systems(
+            Root,
+            |relations: Relations<&Value, &Value>, pairs: &mut Pairs| {
+                let items = world_items(&relations);
+                pairs.0 = items
+                    .into_iter()
+                    .map(|(child, parent)| (*child, *parent))
+                    .collect();
+            },
+        );
+
+        let systems = schedule.build(&mut world).unwrap();
+        systems.run(Root, &mut world);
+
+        assert_eq!(world.resource::<Pairs>().0, vec![(Value(3), Value(1))]);
+    }
 }
@@ -14,18 +14,19 @@ pub use commands::{
     ExitMode, entity::*,
 };
 pub use core::{FixedBitSet, Frame, IndexDag, IndexMap, IndexSet, SparseIndex, ext};
-pub use hierarchy::{Children, HierarchyExt, Parent};
+pub use hierarchy::{Children, HierarchyExt, Parent, Relations};
 pub use system::{
-    ArgItem, BaseFilter, BaseQuery, Condition, IntoSystemConfig, IntoSystemConfigs, Phase, Query,
-    QueryIter, QueryState, ReadOnly, ReadQuery, RunMode, Schedule, System, SystemArg, SystemConfig,
-    SystemConfigs, SystemId, SystemMeta, Systems, query, schedule, unlifetime,
+    ArgItem, BaseFilter, BaseQuery, Condition, IntoSystemConfig, IntoSystemConfigs, Local, Phase,
+    Query, QueryIter, QueryState, ReadOnly, ReadQuery, RemovedComponents, RunMode, Schedule,
+    System, SystemArg, SystemConfig, SystemConfigs, SystemId, SystemMeta, Systems, query, schedule,
+    unlifetime,
 };
 pub use world::{
-    Access, AccessError, Archetype, ArchetypeAccess, ArchetypeId, ArchetypeQuery, Component,
-    ComponentId, ComponentKit, ComponentMeta, Components, Entities, Entity, EntityEvents,
-    EntityIndex, EntityMut, Event, EventId, EventMeta, EventReader, EventRegistry, EventWriter,
-    Events, ModeId, NonSend, NonSendMut, Resource, ResourceId, ResourceMeta, Resources, World,
-    WorldAccess, WorldId, WorldMode,
+    Access, AccessError, Archetype, ArchetypeAccess, ArchetypeId, ArchetypeQuery, ClonedEntity,
+    Component, ComponentId, ComponentKit, ComponentMeta, Components, Entities, Entity,
+    EntityEvents, EntityIndex, EntityMut, Event, EventCursor, EventId, EventMeta, EventReader,
+    EventRegistry, EventWriter, Events, ModeId, Mut, NonSend, NonSendMut, Res, ResMut, Resource,
+    ResourceId, ResourceMeta, Resources, World, WorldAccess, WorldId, WorldMode,
 };
 
 pub use derive_ecs::{
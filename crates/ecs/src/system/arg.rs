@@ -1,8 +1,9 @@
-use super::{IntoSystemConfig, SystemConfig, SystemMeta, SystemType, SystemSet};
+use super::{IntoSystemConfig, Removed, SystemConfig, SystemMeta, SystemType, SystemSet};
 use crate::{
-    Event, EventReader, EventWriter, Events, WorldAccess,
+    Component, Entity, Event, EventCursor, EventReader, EventWriter, Events, WorldAccess,
     world::{
-        Cloned, Entities, EventStorage, NonSend, NonSendMut, Resource, ResourceId, World, WorldCell,
+        Cloned, Entities, EventStorage, NonSend, NonSendMut, Res, ResMut, Resource, ResourceId,
+        World, WorldCell,
     },
 };
 use derive_ecs::Resource;
@@ -184,7 +185,7 @@ unsafe impl<R: Resource + Send> SystemArg for &mut R {
         let world = unsafe { world.get_mut() };
         world
             .resources
-            .get_mut::<R>(*state, world.frame)
+            .get_mut::<R>(*state, system.tick)
             .expect(&format!(
                 "Resource of type {} not found for system: {:?}",
                 std::any::type_name::<R>(),
@@ -193,6 +194,72 @@ unsafe impl<R: Resource + Send> SystemArg for &mut R {
     }
 }
 
+unsafe impl<R: Resource + Send> SystemArg for Res<'_, R> {
+    type Item<'world, 'state> = Res<'world, R>;
+
+    type State = ResourceId;
+
+    fn init(world: &mut World, access: &mut WorldAccess) -> Self::State {
+        <&R as SystemArg>::init(world, access)
+    }
+
+    unsafe fn validate(state: &Self::State, world: WorldCell, system: &SystemMeta) -> bool {
+        unsafe { <&R as SystemArg>::validate(state, world, system) }
+    }
+
+    unsafe fn get<'world, 'state>(
+        state: &'state mut Self::State,
+        world: WorldCell<'world>,
+        system: &'world SystemMeta,
+    ) -> Self::Item<'world, 'state> {
+        let world = unsafe { world.get() };
+        let (resource, status) = world
+            .resources()
+            .get_with_status::<R>(*state)
+            .expect(&format!(
+                "Resource of type {} not found for system: {:?}",
+                std::any::type_name::<R>(),
+                system.name,
+            ));
+
+        Res::new(resource, *status, system.tick, system.frame)
+    }
+}
+
+unsafe impl<R: Resource + Send> ReadOnly for Res<'_, R> {}
+
+unsafe impl<R: Resource + Send> SystemArg for ResMut<'_, R> {
+    type Item<'world, 'state> = ResMut<'world, R>;
+
+    type State = ResourceId;
+
+    fn init(world: &mut World, access: &mut WorldAccess) -> Self::State {
+        <&mut R as SystemArg>::init(world, access)
+    }
+
+    unsafe fn validate(state: &Self::State, world: WorldCell, system: &SystemMeta) -> bool {
+        unsafe { <&mut R as SystemArg>::validate(state, world, system) }
+    }
+
+    unsafe fn get<'world, 'state>(
+        state: &'state mut Self::State,
+        mut world: WorldCell<'world>,
+        system: &'world SystemMeta,
+    ) -> Self::Item<'world, 'state> {
+        let world = unsafe { world.get_mut() };
+        let (resource, status) = world
+            .resources
+            .get_mut_with_status::<R>(*state)
+            .expect(&format!(
+                "Resource of type {} not found for system: {:?}",
+                std::any::type_name::<R>(),
+                system.name,
+            ));
+
+        ResMut::new(resource, status, system.tick, system.frame)
+    }
+}
+
 unsafe impl<R: Resource> ReadOnly for NonSend<'_, R> {}
 
 unsafe impl<R: Resource> SystemArg for NonSend<'_, R> {
@@ -255,7 +322,7 @@ unsafe impl<R: Resource> SystemArg for NonSendMut<'_, R> {
         let world = unsafe { world.get_mut() };
         let resource = world
             .resources
-            .get_mut::<R>(*state, world.frame)
+            .get_mut::<R>(*state, system.tick)
             .expect(&format!(
                 "NonSendMut resource of type {} not found for system {:?}",
                 std::any::type_name::<R>(),
@@ -465,23 +532,25 @@ unsafe impl<R: Resource> SystemArg for Option<NonSendMut<'_, R>> {
 unsafe impl<R: Resource + Send> ReadOnly for Option<&R> {}
 unsafe impl<R: Resource> ReadOnly for Option<NonSend<'_, R>> {}
 
+/// Each system keeps its own cursor, so events are seen once per system even
+/// if it runs less often than the events are updated.
 unsafe impl<E: Event> SystemArg for EventReader<'_, E> {
     type Item<'world, 'state> = EventReader<'world, E>;
 
-    type State = ();
+    type State = EventCursor;
 
     fn init(world: &mut World, _: &mut crate::WorldAccess) -> Self::State {
         world.register_event::<E>();
-        ()
+        EventCursor::default()
     }
 
     unsafe fn get<'world, 'state>(
-        _: &'state mut Self::State,
+        state: &'state mut Self::State,
         world: super::WorldCell<'world>,
         _: &'world SystemMeta,
     ) -> Self::Item<'world, 'state> {
         let events = unsafe { world.get().resource::<Events<E>>() };
-        EventReader::new(events)
+        EventReader::with_cursor(events, state)
     }
 }
 
@@ -494,7 +563,7 @@ unsafe impl<E: Event> SystemArg for EventWriter<'_, E> {
 
     fn init(world: &mut World, _: &mut crate::WorldAccess) -> Self::State {
         world.register_event::<E>();
-        EventStorage::default()
+        EventStorage::new(world.resource::<Events<E>>().capacity())
     }
 
     unsafe fn get<'world, 'state>(
@@ -507,15 +576,99 @@ unsafe impl<E: Event> SystemArg for EventWriter<'_, E> {
 
     fn update(state: &mut Self::State, world: &mut super::World) {
         let events = world.resource_mut::<Events<E>>();
-        let offset = events.write.events.len();
-        events.write.events.append(&mut state.events);
-        for (entity, added) in state.entities.drain() {
-            let indices = events.write.entities.entry(entity).or_default();
-            indices.extend(added.iter().map(|i| *i + offset));
+        events.write.append(state);
+    }
+}
+
+/// State owned by a single system, created with `Default` when the system is
+/// initialized and kept across runs. Two systems with a `Local<T>` each get
+/// their own value.
+pub struct Local<'state, T: Default + Send + Sync + 'static>(&'state mut T);
+
+impl<T: Default + Send + Sync + 'static> std::ops::Deref for Local<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.0
+    }
+}
+
+impl<T: Default + Send + Sync + 'static> std::ops::DerefMut for Local<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.0
+    }
+}
+
+unsafe impl<T: Default + Send + Sync + 'static> SystemArg for Local<'_, T> {
+    type Item<'world, 'state> = Local<'state, T>;
+
+    type State = T;
+
+    fn init(_: &mut World, _: &mut WorldAccess) -> Self::State {
+        T::default()
+    }
+
+    unsafe fn get<'world, 'state>(
+        state: &'state mut Self::State,
+        _: WorldCell<'world>,
+        _: &'world SystemMeta,
+    ) -> Self::Item<'world, 'state> {
+        Local(state)
+    }
+}
+
+unsafe impl<T: Default + Send + Sync + 'static> ReadOnly for Local<'_, T> {}
+
+/// Entities whose `C` component was removed since the system last ran.
+/// Removals are kept for two updates like other [`Events`], so a system that
+/// skips a frame still sees them. Each system sees a removal once, even if it
+/// runs multiple times per frame.
+pub struct RemovedComponents<C: Component> {
+    entities: std::vec::IntoIter<Entity>,
+    _marker: std::marker::PhantomData<C>,
+}
+
+impl<C: Component> Iterator for RemovedComponents<C> {
+    type Item = Entity;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entities.next()
+    }
+}
+
+impl<C: Component> ExactSizeIterator for RemovedComponents<C> {
+    fn len(&self) -> usize {
+        self.entities.len()
+    }
+}
+
+unsafe impl<C: Component> SystemArg for RemovedComponents<C> {
+    type Item<'world, 'state> = RemovedComponents<C>;
+
+    type State = EventCursor;
+
+    fn init(world: &mut World, _: &mut WorldAccess) -> Self::State {
+        world.register_event::<Removed<C>>();
+        EventCursor::default()
+    }
+
+    unsafe fn get<'world, 'state>(
+        state: &'state mut Self::State,
+        world: WorldCell<'world>,
+        _: &'world SystemMeta,
+    ) -> Self::Item<'world, 'state> {
+        let events = unsafe { world.get().resource::<Events<Removed<C>>>() };
+        let reader = EventReader::with_cursor(events, state);
+
+        RemovedComponents {
+            entities: reader.entities().into_iter(),
+            _marker: std::marker::PhantomData,
         }
     }
 }
 
+unsafe impl<C: Component> ReadOnly for RemovedComponents<C> {}
+
 unsafe impl SystemArg for &SystemMeta {
     type Item<'world, 'state> = &'world SystemMeta;
 
@@ -802,3 +955,180 @@ pub mod unlifetime {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Local, RemovedComponents};
+    use crate::{
+        Component, Entity, IntoSystemConfigs, Phase, Res, ResMut, Resource, RunMode, Schedule,
+        World,
+    };
+    use std::sync::Mutex;
+
+    #[derive(Debug)]
+    struct Age;
+    impl Component for Age {}
+
+    #[derive(Default)]
+    struct Seen(Mutex<Vec<Entity>>);
+    impl Resource for Seen {}
+
+    struct Root;
+    impl Phase for Root {}
+
+    #[test]
+    fn removed_components() {
+        let mut world = World::new();
+        world.register::<Age>();
+        world.add_resource(Seen::default());
+
+        let entities = (0..3)
+            .map(|_| {
+                let entity = world.spawn();
+                world.add_component(entity, Age);
+                entity
+            })
+            .collect::<Vec<_>>();
+
+        let mut schedule = Schedule::new(RunMode::Sequential);
+        schedule.add_systems(Root, |removed: RemovedComponents<Age>, seen: &Seen| {
+            seen.0.lock().unwrap().extend(removed);
+        });
+        let systems = schedule.build(&mut world).unwrap();
+
+        world.remove_component::<Age>(entities[0]);
+        world.remove_component::<Age>(entities[2]);
+        world.update();
+
+        systems.run(Root, &mut world);
+        systems.run(Root, &mut world);
+        world.update();
+        systems.run(Root, &mut world);
+
+        let seen = world.resource::<Seen>().0.lock().unwrap();
+        assert_eq!(*seen, vec![entities[0], entities[2]]);
+    }
+
+    #[test]
+    fn removed_components_skipped_frame() {
+        let mut world = World::new();
+        world.register::<Age>();
+        world.add_resource(Seen::default());
+
+        let entity = world.spawn();
+        world.add_component(entity, Age);
+
+        let mut schedule = Schedule::new(RunMode::Sequential);
+        schedule.add_systems(Root, |removed: RemovedComponents<Age>, seen: &Seen| {
+            seen.0.lock().unwrap().extend(removed);
+        });
+        let systems = schedule.build(&mut world).unwrap();
+        systems.run(Root, &mut world);
+
+        // The system doesn't run in the frame the removal becomes readable.
+        world.remove_component::<Age>(entity);
+        world.update();
+        world.update();
+
+        systems.run(Root, &mut world);
+        systems.run(Root, &mut world);
+
+        let seen = world.resource::<Seen>().0.lock().unwrap();
+        assert_eq!(*seen, vec![entity]);
+    }
+
+    #[derive(Default)]
+    struct Value(u32);
+    impl Resource for Value {}
+
+    #[derive(Default)]
+    struct Changes(Mutex<Vec<bool>>);
+    impl Resource for Changes {}
+
+    fn write_once(mut written: Local<bool>, mut value: ResMut<Value>) {
+        if !*written {
+            value.0 += 1;
+            *written = true;
+        }
+    }
+
+    fn read_changes(value: Res<Value>, changes: &Changes) {
+        changes.0.lock().unwrap().push(value.is_changed());
+    }
+
+    #[test]
+    fn resource_change_detection() {
+        let mut world = World::new();
+        world.add_resource(Value::default());
+        world.add_resource(Changes::default());
+        world.update();
+
+        let mut schedule = Schedule::new(RunMode::Sequential);
+        schedule.add_systems(Root, write_once.before(read_changes));
+        let systems = schedule.build(&mut world).unwrap();
+
+        for _ in 0..2 {
+            systems.run(Root, &mut world);
+            world.update();
+        }
+
+        let changes = world.resource::<Changes>().0.lock().unwrap().clone();
+        assert_eq!(changes, vec![true, false]);
+        assert_eq!(world.resource::<Value>().0, 1);
+    }
+
+    #[test]
+    fn resource_change_detection_reader_first() {
+        let mut world = World::new();
+        world.add_resource(Value::default());
+        world.add_resource(Changes::default());
+        world.update();
+
+        let mut schedule = Schedule::new(RunMode::Sequential);
+        schedule.add_systems(Root, read_changes.before(write_once));
+        let systems = schedule.build(&mut world).unwrap();
+
+        for _ in 0..3 {
+            systems.run(Root, &mut world);
+            world.update();
+        }
+
+        let changes = world.resource::<Changes>().0.lock().unwrap().clone();
+        assert_eq!(changes, vec![true, true, false]);
+        assert_eq!(world.resource::<Value>().0, 1);
+    }
+
+    #[derive(Default)]
+    struct Counts(Mutex<Vec<u32>>);
+    impl Resource for Counts {}
+
+    fn count_ones(mut count: Local<u32>, counts: &Counts) {
+        *count += 1;
+        counts.0.lock().unwrap().push(*count);
+    }
+
+    fn count_tens(mut count: Local<u32>, counts: &Counts) {
+        *count += 10;
+        counts.0.lock().unwrap().push(*count);
+    }
+
+    #[test]
+    fn local_state() {
+        let mut world = World::new();
+        world.add_resource(Counts::default());
+
+        let mut schedule = Schedule::new(RunMode::Sequential);
+        schedule.add_systems(Root, count_ones);
+        schedule.add_systems(Root, count_tens);
+        let systems = schedule.build(&mut world).unwrap();
+
+        for _ in 0..3 {
+            systems.run(Root, &mut world);
+            world.update();
+        }
+
+        let mut counts = world.resource::<Counts>().0.lock().unwrap().clone();
+        counts.sort();
+        assert_eq!(counts, vec![1, 2, 3, 10, 20, 30]);
+    }
+}
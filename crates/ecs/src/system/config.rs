@@ -3,7 +3,7 @@ use super::{
     SystemState, SystemUpdate,
 };
 use crate::{
-    AccessError, Frame, Resource, SystemArg, World, WorldAccess, WorldMode,
+    AccessError, Component, Frame, Resource, SystemArg, World, WorldAccess, WorldMode,
     system::{SystemId, SystemSet, SystemType},
 };
 use std::{cell::UnsafeCell, collections::HashSet};
@@ -190,6 +190,15 @@ pub trait IntoSystemConfig<M>: Sized {
         config
     }
 
+    /// Same as [`IntoSystemConfig::when`], but takes the condition by value so
+    /// composed conditions like `a.and(b)` can be passed without naming them.
+    /// Conditions are evaluated from their type alone, so `C` must be zero
+    /// sized and anything else fails to compile.
+    fn run_if<C: Condition>(self, _: C) -> SystemConfig {
+        const { stateless::<C>() };
+        self.when::<C>()
+    }
+
     fn id(&self) -> SystemId
     where
         Self: Sized + 'static,
@@ -236,6 +245,15 @@ pub trait IntoSystemConfigs<M> {
         }
     }
 
+    /// See [`IntoSystemConfig::run_if`]. `C` must be zero sized.
+    fn run_if<C: Condition>(self, _: C) -> SystemConfigs
+    where
+        Self: Sized,
+    {
+        const { stateless::<C>() };
+        IntoSystemConfigs::when::<C>(self)
+    }
+
     fn non_send(self) -> SystemConfigs
     where
         Self: Sized,
@@ -382,6 +400,7 @@ impl IntoSystemConfigs<()> for Vec<SystemConfig> {
 
 impl From<SystemNode> for System {
     fn from(value: SystemNode) -> Self {
+        let resource_writes = value.access.current.resources.get_write().clone();
         let resources = value.access.current.resources.collect();
         let mut components = value.access.current.components.collect();
         for archetype in value.access.current.archetypes {
@@ -394,9 +413,11 @@ impl From<SystemNode> for System {
             name: value.config.name,
             components,
             resources,
+            resource_writes,
             send: value.config.send,
             exclusive: value.config.exclusive,
             frame: Frame::ZERO,
+            tick: Frame::ZERO,
         };
 
         System::new(
@@ -417,8 +438,13 @@ impl From<SystemNode> for SystemCell {
 
 pub struct Not<T>(T);
 pub struct Or<T>(T);
+/// Matches when exactly one of the inner filters matches. Entities matching
+/// none or more than one of the filters are excluded.
+pub struct Xor<T>(T);
 pub struct Modified<T>(std::marker::PhantomData<T>);
 pub struct Added<T>(std::marker::PhantomData<T>);
+/// Matches components that were added or modified since the system last ran.
+pub struct Changed<T>(std::marker::PhantomData<T>);
 pub struct Removed<T>(T);
 
 impl<T> Removed<T> {
@@ -480,20 +506,41 @@ unsafe impl<T: Resource + Send> SystemArg for Option<Removed<T>> {
 pub trait Condition: Sized + 'static {
     fn evaluate(world: &World, system: &SystemMeta) -> bool;
 
-    fn and(self, condition: impl Condition) -> impl Condition {
+    fn and<C: Condition>(self, condition: C) -> (Self, C) {
         (self, condition)
     }
 
-    fn or(self, condition: impl Condition) -> impl Condition {
+    fn or<C: Condition>(self, condition: C) -> Or<(Self, C)> {
         Or((self, condition))
     }
 
-    fn not(self) -> impl Condition {
+    fn not(self) -> Not<Self> {
         Not(self)
     }
 }
 
-pub struct Always<const VALUE: bool>(pub bool);
+/// A condition value can't carry state since only its type is kept, so
+/// `run_if` rejects conditions that aren't zero sized.
+const fn stateless<C: Condition>() {
+    assert!(
+        std::mem::size_of::<C>() == 0,
+        "Conditions passed to run_if must be zero sized"
+    );
+}
+
+pub fn not<C: Condition>(condition: C) -> Not<C> {
+    Not(condition)
+}
+
+pub fn resource_exists<R: Resource>() -> Exists<R> {
+    Exists::new()
+}
+
+pub fn any_with_component<C: Component>() -> AnyWith<C> {
+    AnyWith::new()
+}
+
+pub struct Always<const VALUE: bool>;
 
 impl<const VALUE: bool> Condition for Always<VALUE> {
     fn evaluate(_: &World, _: &SystemMeta) -> bool {
@@ -527,7 +574,7 @@ impl<M: WorldMode> Condition for Entered<M> {
 
         modes.id::<M>().is_some_and(|id| {
             let entered = modes[id].frame();
-            modes.current() == Some(id) && entered.is_newer(world.frame, system.frame)
+            modes.current() == Some(id) && entered.is_newer(world.change_tick(), system.frame)
         })
     }
 }
@@ -541,7 +588,7 @@ impl<M: WorldMode> Condition for Exited<M> {
 
         modes.id::<M>().is_some_and(|id| {
             let exited = modes[id].frame();
-            modes.current() != Some(id) && exited.is_newer(world.frame, system.frame)
+            modes.current() != Some(id) && exited.is_newer(world.change_tick(), system.frame)
         })
     }
 }
@@ -561,6 +608,24 @@ impl<R: Resource> Condition for Exists<R> {
     }
 }
 
+/// Passes while at least one entity has a `C` component.
+pub struct AnyWith<C: Component>(std::marker::PhantomData<C>);
+impl<C: Component> Condition for AnyWith<C> {
+    fn evaluate(world: &World, _: &SystemMeta) -> bool {
+        let Some(id) = world.components().get_id::<C>() else {
+            return false;
+        };
+
+        // Columns measure their length in bytes, so a tag component's column
+        // is always empty. Count the archetype's entities instead.
+        world
+            .archetypes()
+            .archetypes()
+            .iter()
+            .any(|archetype| archetype.has_component_id(id) && !archetype.table().is_empty())
+    }
+}
+
 impl<R: Resource> Condition for Modified<R> {
     fn evaluate(world: &World, system: &SystemMeta) -> bool {
         let id = match world.resources.get_id::<R>() {
@@ -569,7 +634,7 @@ impl<R: Resource> Condition for Modified<R> {
         };
 
         world.resources.get_meta(id).is_some_and(|meta| {
-            meta.exists() && meta.modified().is_newer(world.frame, system.frame)
+            meta.exists() && meta.modified().is_newer(world.change_tick(), system.frame)
         })
     }
 }
@@ -581,10 +646,9 @@ impl<R: Resource> Condition for Added<R> {
             None => return false,
         };
 
-        world
-            .resources
-            .get_meta(id)
-            .is_some_and(|meta| meta.exists() && meta.added().is_newer(world.frame, system.frame))
+        world.resources.get_meta(id).is_some_and(|meta| {
+            meta.exists() && meta.added().is_newer(world.change_tick(), system.frame)
+        })
     }
 }
 
@@ -596,11 +660,39 @@ impl<R: Resource> Condition for Removed<R> {
         };
 
         world.resources.get_meta(id).is_some_and(|meta| {
-            !meta.exists() && meta.removed().is_newer(world.frame, system.frame)
+            !meta.exists() && meta.removed().is_newer(world.change_tick(), system.frame)
         })
     }
 }
 
+macro_rules! impl_condition_new {
+    ($($name:ident<$ty:ident: $bound:path>),*) => {
+        $(
+            impl<$ty: $bound> $name<$ty> {
+                pub fn new() -> Self {
+                    Self(std::marker::PhantomData)
+                }
+            }
+
+            impl<$ty: $bound> Default for $name<$ty> {
+                fn default() -> Self {
+                    Self::new()
+                }
+            }
+        )*
+    };
+}
+
+impl_condition_new!(
+    CurrentMode<M: WorldMode>,
+    Entered<M: WorldMode>,
+    Exited<M: WorldMode>,
+    Exists<R: Resource>,
+    AnyWith<C: Component>,
+    Modified<R: Resource>,
+    Added<R: Resource>
+);
+
 macro_rules! impl_tuple_condition {
     ($($name:ident),*) => {
         #[allow(non_snake_case)]
@@ -631,9 +723,9 @@ variadics::variable_impl!(impl_tuple_condition, P, 2, 16);
 
 #[allow(unused_imports, dead_code)]
 mod tests {
-    use super::{Condition, CurrentMode, Exists};
+    use super::{Always, Condition, CurrentMode, Exists, any_with_component, not, resource_exists};
     use crate::{
-        IntoSystemConfig, Resource, System, SystemConfigs, SystemMeta, World, WorldMode,
+        Component, IntoSystemConfig, Resource, System, SystemConfigs, SystemMeta, World, WorldMode,
         system::{Added, Entered, Exited, Modified, Not, Or, Removed},
         world,
     };
@@ -727,6 +819,94 @@ mod tests {
         assert_eq!(world.resource::<Value>().0, 1);
     }
 
+    #[test]
+    fn test_composed_conditions() {
+        fn run(world: &mut World, config: crate::SystemConfig) {
+            let mut system = System::from(config.into_node(world));
+            system.run(unsafe { world.cell() });
+        }
+
+        fn increment(value: &mut Value) {
+            value.0 += 1;
+        }
+
+        let mut world = World::new();
+        world.add_mode::<TestMode>();
+        world.add_resource(Value(0));
+
+        let in_mode = || CurrentMode::<TestMode>::new().and(Exists::<Value>::new());
+        run(&mut world, increment.run_if(in_mode()));
+        assert_eq!(world.resource::<Value>().0, 0);
+
+        world.enter::<TestMode>();
+        run(&mut world, increment.run_if(in_mode()));
+        assert_eq!(world.resource::<Value>().0, 1);
+
+        let either = CurrentMode::<TestMode>::new().or(Exists::<Ghost>::new());
+        run(&mut world, increment.run_if(either));
+        assert_eq!(world.resource::<Value>().0, 2);
+
+        run(&mut world, increment.run_if(not(Exists::<Value>::new())));
+        assert_eq!(world.resource::<Value>().0, 2);
+
+        run(&mut world, increment.run_if(not(Exists::<Ghost>::new())));
+        assert_eq!(world.resource::<Value>().0, 3);
+
+        run(
+            &mut world,
+            increment.run_if(Always::<false>.or(Always::<true>)),
+        );
+        assert_eq!(world.resource::<Value>().0, 4);
+    }
+
+    #[test]
+    fn test_resource_exists_condition() {
+        let mut world = World::new();
+        world.add_resource(Value(0));
+
+        let mut system = System::from(
+            (|_: &Ghost, value: &mut Value| value.0 += 1)
+                .run_if(resource_exists::<Ghost>())
+                .into_node(&mut world),
+        );
+
+        system.run(unsafe { world.cell() });
+        assert_eq!(world.resource::<Value>().0, 0);
+
+        world.add_resource(Ghost);
+        system.run(unsafe { world.cell() });
+        assert_eq!(world.resource::<Value>().0, 1);
+    }
+
+    #[test]
+    fn test_any_with_component_condition() {
+        #[derive(Debug)]
+        struct Bar;
+        impl Component for Bar {}
+
+        let mut world = World::new();
+        world.register::<Bar>();
+        world.add_resource(Value(0));
+
+        let mut system = System::from(
+            (|value: &mut Value| value.0 += 1)
+                .run_if(any_with_component::<Bar>())
+                .into_node(&mut world),
+        );
+
+        system.run(unsafe { world.cell() });
+        assert_eq!(world.resource::<Value>().0, 0);
+
+        let entity = world.spawn();
+        world.add_component(entity, Bar);
+        system.run(unsafe { world.cell() });
+        assert_eq!(world.resource::<Value>().0, 1);
+
+        world.remove_component::<Bar>(entity);
+        system.run(unsafe { world.cell() });
+        assert_eq!(world.resource::<Value>().0, 1);
+    }
+
     #[test]
     fn test_current_mode() {
         let mut world = World::new();
@@ -103,6 +103,38 @@ mod tests {
         assert_eq!(world.resource::<Value>().0, 2);
     }
 
+    #[derive(Debug, Default)]
+    pub struct RunOrder(std::sync::Mutex<Vec<u32>>);
+    impl Resource for RunOrder {}
+
+    impl RunOrder {
+        fn push(&self, value: u32) {
+            self.0.lock().unwrap().push(value);
+        }
+
+        fn take(&self) -> Vec<u32> {
+            std::mem::take(&mut *self.0.lock().unwrap())
+        }
+    }
+
+    #[test]
+    fn test_sequential_insertion_order() {
+        init_task_pool();
+        let mut world = World::new();
+        world.add_resource(RunOrder::default());
+
+        let mut schedule = Schedule::new(RunMode::Sequential);
+        schedule.add_systems(Root, |order: &RunOrder| order.push(0));
+        schedule.add_systems(Root, |order: &RunOrder| order.push(1));
+        schedule.add_systems(Root, |order: &RunOrder| order.push(2));
+
+        let systems = schedule.build(&mut world).unwrap();
+        for _ in 0..3 {
+            systems.run(Root, &mut world);
+            assert_eq!(world.resource::<RunOrder>().take(), vec![0, 1, 2]);
+        }
+    }
+
     #[test]
     fn test_parallel() {
         init_task_pool();
@@ -0,0 +1,247 @@
+use super::{SystemName, SystemNode};
+use crate::{AccessError, ComponentId, ResourceId, SparseIndex, world::World};
+use std::fmt::Write;
+
+/// Why one system runs before another.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrderReason {
+    /// Ordered by `before`/`after` on the systems or their sets.
+    Explicit,
+    /// One of the systems is exclusive.
+    Exclusive,
+    /// Both systems access the named component or resource and at least
+    /// one of them writes to it.
+    Conflict(String),
+}
+
+impl OrderReason {
+    pub(crate) fn conflict(world: &World, error: AccessError, main: bool) -> Self {
+        let name = match error {
+            AccessError::Component(id) if !main => {
+                world.components().get_by_id(id).map(|c| c.name())
+            }
+            AccessError::Resource(id) if !main => world.resources().get_meta(id).map(|r| r.name()),
+            _ => None,
+        };
+
+        match name {
+            Some(name) => Self::Conflict(name.to_string()),
+            None => Self::Conflict(format!("{:?}", error)),
+        }
+    }
+}
+
+impl std::fmt::Display for OrderReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Explicit => write!(f, "explicit"),
+            Self::Exclusive => write!(f, "exclusive"),
+            Self::Conflict(name) => write!(f, "conflict: {}", name),
+        }
+    }
+}
+
+/// An ordering edge between two systems in a phase. `before` and `after`
+/// are indices into [`PhaseGraph::systems`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemEdge {
+    pub before: usize,
+    pub after: usize,
+    pub reason: OrderReason,
+}
+
+/// The names of the components and resources a system reads and writes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SystemAccessInfo {
+    pub name: SystemName,
+    pub exclusive: bool,
+    pub send: bool,
+    pub component_reads: Vec<&'static str>,
+    pub component_writes: Vec<&'static str>,
+    pub resource_reads: Vec<&'static str>,
+    pub resource_writes: Vec<&'static str>,
+}
+
+impl SystemAccessInfo {
+    pub(crate) fn new(world: &World, node: &SystemNode) -> Self {
+        let access = &node.access.current;
+        let mut reads = access.components.get_read().clone();
+        let mut writes = access.components.get_write().clone();
+        for archetype in &access.archetypes {
+            reads.union_with(archetype.reads());
+            writes.union_with(archetype.writes());
+        }
+
+        let component = |index: usize| {
+            world
+                .components()
+                .get_by_id(ComponentId::from_usize(index))
+                .map(|c| c.name())
+        };
+        let resource = |index: usize| {
+            world
+                .resources()
+                .get_meta(ResourceId::from_usize(index))
+                .map(|r| r.name())
+        };
+
+        Self {
+            name: node.config.name,
+            exclusive: node.config.exclusive,
+            send: node.config.send,
+            component_reads: reads.ones().filter_map(component).collect(),
+            component_writes: writes.ones().filter_map(component).collect(),
+            resource_reads: access
+                .resources
+                .get_read()
+                .ones()
+                .filter_map(resource)
+                .collect(),
+            resource_writes: access
+                .resources
+                .get_write()
+                .ones()
+                .filter_map(resource)
+                .collect(),
+        }
+    }
+}
+
+/// The systems in a phase and the edges that order them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PhaseGraph {
+    pub name: &'static str,
+    /// Phases that run before this one.
+    pub after: Vec<&'static str>,
+    /// Phases run from inside this one.
+    pub children: Vec<&'static str>,
+    pub systems: Vec<SystemAccessInfo>,
+    pub edges: Vec<SystemEdge>,
+}
+
+impl PhaseGraph {
+    pub fn system(&self, name: SystemName) -> Option<&SystemAccessInfo> {
+        self.systems.iter().find(|system| system.name == name)
+    }
+
+    /// The edges from `before` to `after`, by system name.
+    pub fn edge(&self, before: SystemName, after: SystemName) -> Option<&SystemEdge> {
+        self.edges.iter().find(|edge| {
+            self.systems[edge.before].name == before && self.systems[edge.after].name == after
+        })
+    }
+}
+
+/// A description of every phase in a schedule, built with
+/// [`Systems::dump`](super::Systems::dump).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScheduleGraph {
+    pub phases: Vec<PhaseGraph>,
+}
+
+impl ScheduleGraph {
+    pub fn phase(&self, name: &str) -> Option<&PhaseGraph> {
+        self.phases.iter().find(|phase| phase.name == name)
+    }
+
+    /// Renders the graph in the graphviz DOT format with one cluster per phase.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph schedule {\n    compound=true;\n");
+
+        for (index, phase) in self.phases.iter().enumerate() {
+            let _ = writeln!(dot, "    subgraph cluster_{} {{", index);
+            let _ = writeln!(dot, "        label={:?};", phase.name);
+            let _ = writeln!(dot, "        p{}_anchor [shape=point, style=invis];", index);
+
+            for (system_index, system) in phase.systems.iter().enumerate() {
+                let mut label = system.name.to_string();
+                let access = [
+                    ("reads", &system.component_reads),
+                    ("writes", &system.component_writes),
+                    ("res reads", &system.resource_reads),
+                    ("res writes", &system.resource_writes),
+                ];
+
+                for (kind, names) in access {
+                    if !names.is_empty() {
+                        let _ = write!(label, "\n{}: {}", kind, names.join(", "));
+                    }
+                }
+
+                let shape = if system.exclusive { "box3d" } else { "box" };
+                let _ = writeln!(
+                    dot,
+                    "        p{}_s{} [shape={}, label={:?}];",
+                    index, system_index, shape, label
+                );
+            }
+
+            for edge in &phase.edges {
+                let _ = writeln!(
+                    dot,
+                    "        p{}_s{} -> p{}_s{} [label={:?}];",
+                    index,
+                    edge.before,
+                    index,
+                    edge.after,
+                    edge.reason.to_string()
+                );
+            }
+
+            dot.push_str("    }\n");
+        }
+
+        for (index, phase) in self.phases.iter().enumerate() {
+            let anchor = |name: &str| self.phases.iter().position(|p| p.name == name);
+
+            for before in phase.after.iter().filter_map(|name| anchor(name)) {
+                let _ = writeln!(
+                    dot,
+                    "    p{}_anchor -> p{}_anchor [ltail=cluster_{}, lhead=cluster_{}];",
+                    before, index, before, index
+                );
+            }
+
+            for child in phase.children.iter().filter_map(|name| anchor(name)) {
+                let _ = writeln!(
+                    dot,
+                    "    p{}_anchor -> p{}_anchor [style=dashed, ltail=cluster_{}, lhead=cluster_{}];",
+                    index, child, index, child
+                );
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+impl std::fmt::Display for ScheduleGraph {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for phase in &self.phases {
+            writeln!(f, "{}", phase.name)?;
+            if !phase.after.is_empty() {
+                writeln!(f, "  after: {}", phase.after.join(", "))?;
+            }
+
+            for system in &phase.systems {
+                let exclusive = if system.exclusive { " (exclusive)" } else { "" };
+                writeln!(f, "  {}{}", system.name, exclusive)?;
+                writeln!(f, "    reads: {:?}", system.component_reads)?;
+                writeln!(f, "    writes: {:?}", system.component_writes)?;
+                writeln!(f, "    resource reads: {:?}", system.resource_reads)?;
+                writeln!(f, "    resource writes: {:?}", system.resource_writes)?;
+            }
+
+            for edge in &phase.edges {
+                writeln!(
+                    f,
+                    "  {} -> {} ({})",
+                    phase.systems[edge.before].name, phase.systems[edge.after].name, edge.reason
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -3,12 +3,19 @@ use crate::{
     core::Frame,
     world::{World, cell::WorldCell},
 };
+
+#[cfg(debug_assertions)]
+use crate::{
+    ResourceId, SparseIndex,
+    world::{BorrowKey, WorldBorrow},
+};
 use fixedbitset::FixedBitSet;
 use std::{any::Any, cell::UnsafeCell};
 
 pub mod arg;
 pub mod config;
 pub mod executor;
+pub mod graph;
 pub mod query;
 pub mod schedule;
 pub mod set;
@@ -16,6 +23,7 @@ pub mod set;
 pub use arg::*;
 pub use config::*;
 pub use executor::*;
+pub use graph::*;
 pub use query::*;
 pub use schedule::*;
 pub use set::*;
@@ -29,12 +37,17 @@ pub struct SystemMeta {
     pub components: FixedBitSet,
     /// Resources that the system accesses.
     pub resources: FixedBitSet,
+    /// Resources that the system writes to.
+    pub resource_writes: FixedBitSet,
     /// The system contains only send resources.
     pub send: bool,
     /// The system should be ran exclusively in the given frame.
     pub exclusive: bool,
-    /// The frame in which the system was last executed.
+    /// The change tick the system last ran at.
     pub frame: Frame,
+    /// The change tick of the system's current run. Changes made by the
+    /// system are stamped with it.
+    pub tick: Frame,
 }
 
 impl Default for SystemMeta {
@@ -44,13 +57,40 @@ impl Default for SystemMeta {
             name: "default",
             components: FixedBitSet::new(),
             resources: FixedBitSet::new(),
+            resource_writes: FixedBitSet::new(),
             send: true,
             exclusive: false,
             frame: Frame::ZERO,
+            tick: Frame::ZERO,
         }
     }
 }
 
+impl SystemMeta {
+    /// Borrows the system's resources for the duration of a run so
+    /// overlapping access from another system panics. Exclusive systems
+    /// get the whole world through [`WorldCell::get_mut`], so they borrow
+    /// [`BorrowKey::World`] instead.
+    #[cfg(debug_assertions)]
+    fn borrow_resources<'w>(&self, world: WorldCell<'w>) -> Vec<WorldBorrow<'w>> {
+        if self.exclusive {
+            return vec![world.borrow_mut(BorrowKey::World)];
+        }
+
+        self.resources
+            .ones()
+            .map(|index| {
+                let id = ResourceId::from_usize(index);
+                if self.resource_writes.contains(index) {
+                    world.borrow_mut(id)
+                } else {
+                    world.borrow(id)
+                }
+            })
+            .collect()
+    }
+}
+
 pub type SystemState = Box<dyn Any + Send + Sync>;
 pub type SystemInit = fn(&mut World, &mut WorldAccess) -> Box<dyn Any + Send + Sync>;
 pub type SystemRun =
@@ -85,6 +125,10 @@ impl System {
 
     pub fn run(&mut self, world: WorldCell) -> bool {
         if self.should_run(unsafe { world.get() }) {
+            #[cfg(debug_assertions)]
+            let _borrows = self.meta.borrow_resources(world);
+
+            self.meta.tick = unsafe { world.get() }.increment_change_tick();
             (self.run)(&mut self.state, world, &self.meta);
             true
         } else {
@@ -93,7 +137,7 @@ impl System {
     }
 
     pub fn update(&mut self, world: &mut World) {
-        self.meta.frame = world.frame();
+        self.meta.frame = self.meta.tick;
         (self.update)(&mut self.state, world);
     }
 
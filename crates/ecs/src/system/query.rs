@@ -1,10 +1,15 @@
-use super::{Added, Modified, Not, Or, ReadOnly, Removed, SystemArg};
+use super::{Added, Changed, Modified, Not, Or, ReadOnly, Removed, SystemArg, SystemMeta, Xor};
 use crate::{
     Archetype, ArchetypeAccess, ArchetypeId, ArchetypeQuery, Component, ComponentId, Entity,
     EntityEvents, Event, Frame, SparseIndex, World,
-    core::{ObjectStatus, Ptr},
-    world::{Column, Events, RowIndex, WorldCell},
+    core::{
+        ObjectStatus, Ptr,
+        task::{CpuTaskPool, TaskPool},
+    },
+    world::{Column, Events, Mut, RowIndex, WorldBorrow, WorldCell},
 };
+use fixedbitset::FixedBitSet;
+use std::ops::Range;
 
 pub trait BaseQuery {
     type Item<'w>;
@@ -182,6 +187,7 @@ pub struct WritePtr<'w, C: Component> {
     components: Ptr<'w, C>,
     frames: Ptr<'w, ObjectStatus>,
     current_frame: Frame,
+    system_frame: Frame,
 }
 
 impl<'w, C: Component> WritePtr<'w, C> {
@@ -189,15 +195,19 @@ impl<'w, C: Component> WritePtr<'w, C> {
         components: Ptr<'w, C>,
         frames: Ptr<'w, ObjectStatus>,
         current_frame: Frame,
+        system_frame: Frame,
     ) -> Self {
         Self {
             components,
             frames,
             current_frame,
+            system_frame,
         }
     }
 }
 
+/// Marks every fetched row as modified. Use [`Mut`] to only mark rows that
+/// are written to.
 impl<C: Component> BaseQuery for &mut C {
     type Item<'w> = &'w mut C;
 
@@ -214,12 +224,12 @@ impl<C: Component> BaseQuery for &mut C {
         _: WorldCell<'w>,
         archetype: &'w Archetype,
         current_frame: Frame,
-        _: Frame,
+        system_frame: Frame,
     ) -> Self::State<'w> {
         match archetype.table().get_column(data) {
             Some(column) => {
                 let (components, frames) = unsafe { column.get_ptr::<C>() };
-                WritePtr::new(components, frames, current_frame)
+                WritePtr::new(components, frames, current_frame, system_frame)
             }
             None => {
                 let component = std::any::type_name::<C>();
@@ -229,9 +239,46 @@ impl<C: Component> BaseQuery for &mut C {
     }
 
     fn get<'w>(state: &mut Self::State<'w>, _: Entity, row: RowIndex) -> Self::Item<'w> {
+        let row = row.to_usize();
+        unsafe {
+            state.frames.get_mut(row).modified = state.current_frame;
+            state.components.get_mut(row)
+        }
+    }
+}
+
+/// Fetches a component mutably like `&mut C`, but only marks the row as
+/// modified when the [`Mut`] is mutably dereferenced.
+impl<C: Component> BaseQuery for Mut<'_, C> {
+    type Item<'w> = Mut<'w, C>;
+
+    type State<'w> = WritePtr<'w, C>;
+
+    type Data = ComponentId;
+
+    fn init(world: &mut World, access: &mut ArchetypeAccess) -> Self::Data {
+        <&mut C as BaseQuery>::init(world, access)
+    }
+
+    fn state<'w>(
+        data: Self::Data,
+        world: WorldCell<'w>,
+        archetype: &'w Archetype,
+        current_frame: Frame,
+        system_frame: Frame,
+    ) -> Self::State<'w> {
+        <&mut C as BaseQuery>::state(data, world, archetype, current_frame, system_frame)
+    }
+
+    fn get<'w>(state: &mut Self::State<'w>, _: Entity, row: RowIndex) -> Self::Item<'w> {
+        let row = row.to_usize();
         unsafe {
-            state.frames.get_mut(row.to_usize()).added = state.current_frame + Frame(1);
-            state.components.get_mut(row.to_usize())
+            Mut::new(
+                state.components.get_mut(row),
+                state.frames.get_mut(row),
+                state.current_frame,
+                state.system_frame,
+            )
         }
     }
 }
@@ -422,6 +469,37 @@ impl<C: Component> BaseFilter for Modified<C> {
     }
 }
 
+impl<C: Component> BaseFilter for Changed<C> {
+    type State<'w> = ReadPtr<'w, C>;
+
+    type Data = ComponentId;
+
+    fn init(world: &mut World, access: &mut ArchetypeAccess) -> Self::Data {
+        let id = world.register::<C>();
+        access.include(id)
+    }
+
+    fn state<'w>(
+        data: Self::Data,
+        world: WorldCell<'w>,
+        archetype: &'w Archetype,
+        current_frame: Frame,
+        system_frame: Frame,
+    ) -> Self::State<'w> {
+        <&C as BaseQuery>::state(data, world, archetype, current_frame, system_frame)
+    }
+
+    fn filter<'w>(state: &Self::State<'w>, _: Entity, row: RowIndex) -> bool {
+        let status = state.components.frames().get(row.to_usize()).unwrap();
+        status
+            .added
+            .is_newer(state.current_frame, state.system_frame)
+            || status
+                .modified
+                .is_newer(state.current_frame, state.system_frame)
+    }
+}
+
 impl<C: Component> BaseFilter for Not<Added<C>> {
     type State<'w> = <Added<C> as BaseFilter>::State<'w>;
 
@@ -470,6 +548,30 @@ impl<C: Component> BaseFilter for Not<Modified<C>> {
     }
 }
 
+impl<C: Component> BaseFilter for Not<Changed<C>> {
+    type State<'w> = <Changed<C> as BaseFilter>::State<'w>;
+
+    type Data = <Changed<C> as BaseFilter>::Data;
+
+    fn init(world: &mut World, access: &mut ArchetypeAccess) -> Self::Data {
+        <Changed<C> as BaseFilter>::init(world, access)
+    }
+
+    fn state<'w>(
+        data: Self::Data,
+        world: WorldCell<'w>,
+        archetype: &'w Archetype,
+        current_frame: Frame,
+        system_frame: Frame,
+    ) -> Self::State<'w> {
+        <Changed<C> as BaseFilter>::state(data, world, archetype, current_frame, system_frame)
+    }
+
+    fn filter<'w>(state: &Self::State<'w>, entity: Entity, row: RowIndex) -> bool {
+        !<Changed<C> as BaseFilter>::filter(state, entity, row)
+    }
+}
+
 impl<E: Event> BaseFilter for E {
     type State<'w> = &'w Events<E>;
 
@@ -556,6 +658,8 @@ impl<C: Component> BaseQuery for Removed<C> {
 pub struct QueryState<Q: BaseQuery, F: BaseFilter = ()> {
     access: ArchetypeQuery,
     archetypes: Vec<ArchetypeId>,
+    /// The same archetypes as `archetypes`, for constant time lookups.
+    matched: FixedBitSet,
     last_archetype: usize,
     data: Q::Data,
     filter: F::Data,
@@ -569,6 +673,7 @@ impl<Q: BaseQuery, F: BaseFilter> QueryState<Q, F> {
         Self {
             access: ArchetypeQuery::from(access.clone()),
             archetypes: Vec::new(),
+            matched: FixedBitSet::new(),
             last_archetype: 0,
             data,
             filter,
@@ -583,6 +688,11 @@ impl<Q: BaseQuery, F: BaseFilter> QueryState<Q, F> {
         &self.archetypes
     }
 
+    /// Returns true if the query matches the archetype with `id`.
+    pub fn matches(&self, id: ArchetypeId) -> bool {
+        self.matched.contains(id.to_usize())
+    }
+
     pub fn data(&self) -> &Q::Data {
         &self.data
     }
@@ -591,11 +701,15 @@ impl<Q: BaseQuery, F: BaseFilter> QueryState<Q, F> {
         &self.filter
     }
 
+    /// Matches archetypes created since the last update. Archetypes are only
+    /// ever appended, so this also picks up archetypes containing components
+    /// registered after the query was created.
     pub fn update(&mut self, world: &mut World) {
         let archetypes = world.archetypes.archetypes();
         for archetype in archetypes.iter().skip(self.last_archetype) {
             if archetype.matches(&self.access) {
                 self.archetypes.push(archetype.id());
+                self.matched.grow_and_insert(archetype.id().to_usize());
             }
         }
 
@@ -627,18 +741,35 @@ impl<'w, 's, Q: BaseQuery, F: BaseFilter> Query<'w, 's, Q, F> {
         Self::with_frame(world, state, Frame::ZERO)
     }
 
-    pub fn with_frame(
+    /// Creates a query that yields changes made after the `frame` change tick.
+    pub fn with_frame(world: WorldCell<'w>, state: &'s mut QueryState<Q, F>, frame: Frame) -> Self {
+        let current = unsafe { world.get() }.change_tick();
+        Self::with_ticks(world, state, frame, current)
+    }
+
+    /// Creates a query for a system run. Changes are compared against the
+    /// system's last run and writes are stamped with its current tick.
+    pub fn from_system(
+        world: WorldCell<'w>,
+        state: &'s mut QueryState<Q, F>,
+        system: &SystemMeta,
+    ) -> Self {
+        Self::with_ticks(world, state, system.frame, system.tick)
+    }
+
+    fn with_ticks(
         mut world: WorldCell<'w>,
         state: &'s mut QueryState<Q, F>,
-        frame: Frame,
+        last: Frame,
+        current: Frame,
     ) -> Self {
         state.update(unsafe { world.get_mut() });
 
         Self {
             world,
             state,
-            current_frame: unsafe { world.get().frame },
-            system_frame: frame,
+            current_frame: current,
+            system_frame: last,
         }
     }
 
@@ -646,6 +777,18 @@ impl<'w, 's, Q: BaseQuery, F: BaseFilter> Query<'w, 's, Q, F> {
         QueryIter::new(*self)
     }
 
+    /// Splits the matched tables into batches that run in parallel. Each
+    /// entity is visited by exactly one task, so mutable components are
+    /// never aliased.
+    pub fn par_iter(&self) -> QueryParIter<'w, 's, Q, F> {
+        QueryParIter::new(*self)
+    }
+
+    /// Runs `f` for every matched entity across the [`CpuTaskPool`].
+    pub fn par_for_each(&self, f: impl Fn(Q::Item<'w>) + Send + Sync) {
+        self.par_iter().for_each(f)
+    }
+
     pub fn contains(&self, entity: Entity) -> bool {
         let world = unsafe { self.world.get() };
         let Some(archetype) = world.archetypes().entity_archetype(entity) else {
@@ -656,7 +799,7 @@ impl<'w, 's, Q: BaseQuery, F: BaseFilter> Query<'w, 's, Q, F> {
             return false;
         };
 
-        if !self.state.archetypes.contains(&archetype.id()) {
+        if !self.state.matches(archetype.id()) {
             return false;
         }
 
@@ -671,8 +814,55 @@ impl<'w, 's, Q: BaseQuery, F: BaseFilter> Query<'w, 's, Q, F> {
         F::filter(&filter, entity, row)
     }
 
+    /// Returns the item for `entity`, or `None` if the entity doesn't exist
+    /// or doesn't match the query.
+    pub fn get(&self, entity: Entity) -> Option<Q::Item<'_>>
+    where
+        Q: ReadQuery,
+    {
+        self.get_item(entity)
+    }
+
+    /// Returns the item for `entity`, or `None` if the entity doesn't exist
+    /// or doesn't match the query.
+    pub fn get_mut(&mut self, entity: Entity) -> Option<Q::Item<'_>> {
+        self.get_item(entity)
+    }
+
+    /// Returns the only item matched by the query.
+    pub fn single(&self) -> Result<Q::Item<'_>, QuerySingleError>
+    where
+        Q: ReadQuery,
+    {
+        Self::single_item(*self)
+    }
+
+    /// Returns the only item matched by the query.
+    pub fn single_mut(&mut self) -> Result<Q::Item<'_>, QuerySingleError> {
+        Self::single_item(*self)
+    }
+
+    fn single_item<'a>(query: Query<'a, 's, Q, F>) -> Result<Q::Item<'a>, QuerySingleError> {
+        let mut iter = QueryIter::new(query);
+        let item = iter.next().ok_or(QuerySingleError::Empty)?;
+
+        match iter.count() {
+            0 => Ok(item),
+            rest => Err(QuerySingleError::Multiple(rest + 1)),
+        }
+    }
+
     pub fn get_item<'a>(&'a self, entity: Entity) -> Option<Q::Item<'a>> {
-        let archetype = match unsafe { self.world.get() }
+        Self::entity_item(*self, entity)
+    }
+
+    /// Like [`Query::get_item`], but the item borrows the world instead of
+    /// the query.
+    pub(crate) fn entity_item<'a>(
+        query: Query<'a, 's, Q, F>,
+        entity: Entity,
+    ) -> Option<Q::Item<'a>> {
+        let archetype = match unsafe { query.world.get() }
             .archetypes
             .entity_archetype(entity)
         {
@@ -680,7 +870,11 @@ impl<'w, 's, Q: BaseQuery, F: BaseFilter> Query<'w, 's, Q, F> {
             None => return None,
         };
 
-        let mut state = QueryIterState::new(*self, archetype);
+        if !query.state.matches(archetype.id()) {
+            return None;
+        }
+
+        let mut state = QueryIterState::new(query, archetype);
 
         let row = archetype.table().get_entity_row(entity).unwrap();
 
@@ -720,7 +914,7 @@ unsafe impl<Q: BaseQuery + 'static, F: BaseFilter + 'static> SystemArg for Query
         world: WorldCell<'world>,
         system: &super::SystemMeta,
     ) -> Self::Item<'world, 'state> {
-        Query::with_frame(world, state, system.frame)
+        Query::from_system(world, state, system)
     }
 }
 
@@ -784,6 +978,7 @@ pub struct QueryIter<'w, 's, Q: BaseQuery, F: BaseFilter = ()> {
     state: Option<QueryIterState<'w, 's, Q, F>>,
     entities: Option<indexmap::set::Iter<'w, Entity>>,
     archetype: usize,
+    borrow: Option<WorldBorrow<'w>>,
 }
 
 impl<'w, 's, Q: BaseQuery, F: BaseFilter> QueryIter<'w, 's, Q, F> {
@@ -806,12 +1001,15 @@ impl<'w, 's, Q: BaseQuery, F: BaseFilter> QueryIter<'w, 's, Q, F> {
             None => (None, None),
         };
 
+        let borrow = archetypes.first().map(|a| query.world.borrow(a.id()));
+
         Self {
             query,
             archetypes,
             state,
             entities,
             archetype: 0,
+            borrow,
         }
     }
 }
@@ -820,37 +1018,153 @@ impl<'w, 's, Q: BaseQuery, F: BaseFilter> Iterator for QueryIter<'w, 's, Q, F> {
     type Item = Q::Item<'w>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.archetype >= self.archetypes.len() {
-            None
-        } else if let Some(entity) = self.entities.as_mut().and_then(|e| e.next()).copied() {
-            let Some(index) = self.archetypes[self.archetype.to_usize()].get_entity(entity) else {
-                return self.next();
-            };
-
-            let Some(state) = self.state.as_mut() else {
-                return self.next();
-            };
-
-            match F::filter(&state.filter, entity, index.row) {
-                true => Some(Q::get(&mut state.data, entity, index.row)),
-                false => None,
+        loop {
+            if self.archetype >= self.archetypes.len() {
+                return None;
+            } else if let Some(entity) = self.entities.as_mut().and_then(|e| e.next()).copied() {
+                let Some(index) = self.archetypes[self.archetype.to_usize()].get_entity(entity)
+                else {
+                    continue;
+                };
+
+                let Some(state) = self.state.as_mut() else {
+                    continue;
+                };
+
+                if F::filter(&state.filter, entity, index.row) {
+                    return Some(Q::get(&mut state.data, entity, index.row));
+                }
+            } else {
+                self.archetype += 1;
+
+                let (state, entities) = match self.archetypes.get(self.archetype) {
+                    Some(archetype) => {
+                        let state = QueryIterState::new(self.query, archetype);
+                        let entities = archetype.table().entities();
+                        (Some(state), Some(entities))
+                    }
+                    None => (None, None),
+                };
+
+                self.entities = entities;
+                self.state = state;
+                self.borrow = self
+                    .archetypes
+                    .get(self.archetype)
+                    .map(|a| self.query.world.borrow(a.id()));
             }
-        } else {
-            self.archetype += 1;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuerySingleError {
+    /// No entities matched the query.
+    Empty,
+    /// More than one entity matched the query.
+    Multiple(usize),
+}
+
+impl std::fmt::Display for QuerySingleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QuerySingleError::Empty => write!(f, "Expected a single item but the query was empty"),
+            QuerySingleError::Multiple(count) => {
+                write!(f, "Expected a single item but the query matched {}", count)
+            }
+        }
+    }
+}
+
+pub struct QueryParIter<'w, 's, Q: BaseQuery, F: BaseFilter = ()> {
+    query: Query<'w, 's, Q, F>,
+    batch_size: Option<usize>,
+}
+
+impl<'w, 's, Q: BaseQuery, F: BaseFilter> QueryParIter<'w, 's, Q, F> {
+    /// Smallest batch used when splitting tables across threads.
+    pub const MIN_BATCH_SIZE: usize = 64;
+
+    pub fn new(query: Query<'w, 's, Q, F>) -> Self {
+        Self {
+            query,
+            batch_size: None,
+        }
+    }
+
+    /// Sets how many entities each task visits. By default each table is
+    /// split evenly across the pool's threads.
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = Some(batch_size.max(1));
+        self
+    }
+
+    /// Runs `f` on the [`CpuTaskPool`], or on the calling thread if the pool
+    /// hasn't been initialized.
+    pub fn for_each(self, f: impl Fn(Q::Item<'w>) + Send + Sync) {
+        match CpuTaskPool::try_get() {
+            Some(pool) => self.for_each_in(pool, f),
+            None => {
+                let _borrows = self.borrow_archetypes();
+                for archetype in &self.query.state.archetypes {
+                    Self::run(self.query, *archetype, 0..usize::MAX, &f);
+                }
+            }
+        }
+    }
 
-            let (state, entities) = match self.archetypes.get(self.archetype) {
-                Some(archetype) => {
-                    let state = QueryIterState::new(self.query, archetype);
-                    let entities = archetype.table().entities();
-                    (Some(state), Some(entities))
+    /// Runs `f` on `pool`, blocking until every batch has finished.
+    pub fn for_each_in(self, pool: &TaskPool, f: impl Fn(Q::Item<'w>) + Send + Sync) {
+        let world = unsafe { self.query.world.get() };
+        let threads = pool.size().max(1);
+        let query = self.query;
+        let f = &f;
+        let _borrows = self.borrow_archetypes();
+
+        pool.scope(|scope| {
+            for id in &query.state.archetypes {
+                let archetype = &world.archetypes().archetypes()[id.to_usize()];
+                let len = archetype.table().entities().len();
+                let batch_size = self
+                    .batch_size
+                    .unwrap_or(len.div_ceil(threads).max(Self::MIN_BATCH_SIZE));
+
+                let mut start = 0;
+                while start < len {
+                    let rows = start..(start + batch_size).min(len);
+                    let id = *id;
+                    start = rows.end;
+
+                    scope.spawn(async move { Self::run(query, id, rows, f) });
                 }
-                None => (None, None),
-            };
+            }
+        });
+    }
 
-            self.entities = entities;
-            self.state = state;
+    /// Borrows every matched archetype like [`QueryIter`] does for the one
+    /// it's visiting, so entities can't be moved while tasks read them.
+    fn borrow_archetypes(&self) -> Vec<WorldBorrow<'w>> {
+        let world = self.query.world;
+        let archetypes = self.query.state.archetypes.iter();
+        archetypes.map(|id| world.borrow(*id)).collect()
+    }
 
-            return self.next();
+    fn run(
+        query: Query<'w, 's, Q, F>,
+        archetype: ArchetypeId,
+        rows: Range<usize>,
+        f: &impl Fn(Q::Item<'w>),
+    ) {
+        let world = unsafe { query.world.get() };
+        let archetype = &world.archetypes().archetypes()[archetype.to_usize()];
+        let mut state = QueryIterState::new(query, archetype);
+
+        let entities = archetype.table().entities().enumerate();
+        for (row, entity) in entities.skip(rows.start).take(rows.len()) {
+            let row = RowIndex(row as u32);
+            if F::filter(&state.filter, *entity, row) {
+                f(Q::get(&mut state.data, *entity, row));
+            }
         }
     }
 }
@@ -888,7 +1202,7 @@ unsafe impl<Q: BaseQuery + 'static, F: BaseFilter + 'static> SystemArg for Singl
         world: WorldCell<'world>,
         system: &'world super::SystemMeta,
     ) -> Self::Item<'world, 'state> {
-        let item = Query::<Q, F>::with_frame(world, state, system.frame)
+        let item = Query::<Q, F>::from_system(world, state, system)
             .into_single()
             .expect("Expected a single item in the query, but found none or multiple items.");
         Single {
@@ -914,7 +1228,7 @@ unsafe impl<Q: BaseQuery + 'static, F: BaseFilter + 'static> SystemArg
         world: WorldCell<'world>,
         system: &'world super::SystemMeta,
     ) -> Self::Item<'world, 'state> {
-        let item = Query::<Q, F>::with_frame(world, state, system.frame).into_single();
+        let item = Query::<Q, F>::from_system(world, state, system).into_single();
         item.map(|item| Single {
             item,
             _filter: std::marker::PhantomData,
@@ -997,6 +1311,9 @@ macro_rules! impl_base_filter_for_tuples {
                 // but NOT add them to includes, since we want ANY condition, not ALL.
                 // Each condition is initialized with a temporary access, then we copy
                 // only the read/write tracking to the main access using read_optional/write_optional.
+                // The temporary accesses become alternative terms, so archetypes that
+                // can't match any condition are never visited.
+                let mut terms = Vec::new();
                 $(
                     let mut temp_access = ArchetypeAccess::new();
                     let $name = $name::init(world, &mut temp_access);
@@ -1007,7 +1324,9 @@ macro_rules! impl_base_filter_for_tuples {
                     for component_idx in temp_access.writes().ones() {
                         access.write_optional(ComponentId::from_usize(component_idx));
                     }
+                    terms.push(temp_access);
                 )*
+                access.any(terms);
                 ($($name,)*)
             }
 
@@ -1027,6 +1346,49 @@ macro_rules! impl_base_filter_for_tuples {
                 filter
             }
         }
+
+        #[allow(non_snake_case)]
+        impl<$($name: BaseFilter),*> BaseFilter for Xor<($($name),*)>
+        {
+
+            type State<'w> = ($($name::State<'w>), *);
+            type Data = ($($name::Data), *);
+
+            fn init(world: &mut World, access: &mut ArchetypeAccess) -> Self::Data {
+                // Same as Or, none of the conditions are required on their own,
+                // but at least one of them has to be able to match.
+                let mut terms = Vec::new();
+                $(
+                    let mut temp_access = ArchetypeAccess::new();
+                    let $name = $name::init(world, &mut temp_access);
+                    for component_idx in temp_access.reads().ones() {
+                        access.read_optional(ComponentId::from_usize(component_idx));
+                    }
+                    for component_idx in temp_access.writes().ones() {
+                        access.write_optional(ComponentId::from_usize(component_idx));
+                    }
+                    terms.push(temp_access);
+                )*
+                access.any(terms);
+                ($($name,)*)
+            }
+
+            fn state<'w>(data: Self::Data, world: WorldCell<'w>, archetype: &'w Archetype, current_frame: Frame, system_frame: Frame) -> Self::State<'w> {
+                let ($($name,)*) = data;
+                ($($name::state($name, world, archetype, current_frame, system_frame),)*)
+            }
+
+            fn filter<'w>(state: &Self::State<'w>, entity: Entity, row: RowIndex) -> bool {
+                let ($($name,)*) = state;
+                let mut matched = 0;
+
+                ($(
+                    matched += $name::filter($name, entity, row) as usize,
+                )*);
+
+                matched == 1
+            }
+        }
     };
 }
 
@@ -1186,6 +1548,78 @@ mod tests {
         assert!(!query.contains(entity_a));
     }
 
+    #[test]
+    fn optional_components() {
+        let mut world = World::new();
+        world.register::<Age>();
+        world.register::<Name>();
+
+        let with_name = world.spawn();
+        let without_name = world.spawn();
+        world.add_components(
+            with_name,
+            Person {
+                age: Age(20),
+                name: Name("Alice"),
+            },
+        );
+        world.add_component(without_name, Age(32));
+
+        let mut state = QueryState::<(Entity, &Age, Option<&Name>)>::new(
+            &mut world,
+            &mut ArchetypeAccess::new(),
+        );
+        let query = Query::new(unsafe { world.cell() }, &mut state);
+        let mut items = query
+            .iter()
+            .map(|(entity, age, name)| (entity, age.0, name.map(|name| name.0)))
+            .collect::<Vec<_>>();
+        items.sort_by_key(|(_, age, _)| *age);
+
+        assert_eq!(
+            items,
+            vec![(with_name, 20, Some("Alice")), (without_name, 32, None)]
+        );
+
+        let mut state =
+            QueryState::<(&Age, Option<&mut Name>)>::new(&mut world, &mut ArchetypeAccess::new());
+        let query = Query::new(unsafe { world.cell() }, &mut state);
+        for (_, name) in query.iter() {
+            if let Some(name) = name {
+                name.0 = "Bob";
+            }
+        }
+
+        assert_eq!(world.get_component::<Name>(with_name), Some(&Name("Bob")));
+    }
+
+    #[test]
+    fn changed_query() {
+        let mut world = World::new();
+        let entities = [world.spawn(), world.spawn(), world.spawn()];
+        for (index, entity) in entities.iter().enumerate() {
+            world.add_component(*entity, Age(index as u32));
+        }
+
+        let system_frame = world.increment_change_tick();
+        world.increment_change_tick();
+
+        let mut state =
+            QueryState::<(Entity, Mut<Age>)>::new(&mut world, &mut ArchetypeAccess::new());
+        let query = Query::with_frame(unsafe { world.cell() }, &mut state, system_frame);
+        for (entity, mut age) in query.iter() {
+            if entity == entities[1] {
+                age.0 += 10;
+            }
+        }
+
+        let mut state =
+            QueryState::<Entity, Changed<Age>>::new(&mut world, &mut ArchetypeAccess::new());
+        let query = Query::with_frame(unsafe { world.cell() }, &mut state, system_frame);
+
+        assert_eq!(query.iter().collect::<Vec<_>>(), [entities[1]]);
+    }
+
     #[test]
     fn test_or_query() {
         let mut world = World::new();
@@ -1210,6 +1644,98 @@ mod tests {
         assert!(!query.contains(entity_c));
     }
 
+    #[test]
+    fn or_query_archetypes() {
+        let mut world = World::new();
+        world.register::<Age>();
+        world.register::<Name>();
+
+        let neither = world.spawn();
+        let age = world.spawn();
+        let name = world.spawn();
+        let both = world.spawn();
+        world.add_component(age, Age(32));
+        world.add_component(name, Name("Bob"));
+        world.add_components(
+            both,
+            Person {
+                age: Age(20),
+                name: Name("Alice"),
+            },
+        );
+
+        let mut state = QueryState::<Entity, Or<(With<Age>, With<Name>)>>::new(
+            &mut world,
+            &mut ArchetypeAccess::new(),
+        );
+        state.update(&mut world);
+
+        let archetypes = world.archetypes();
+        let matched = |entity: Entity| {
+            let archetype = archetypes.entity_archetype(entity).unwrap();
+            state.matches(archetype.id())
+        };
+
+        assert!(!matched(neither));
+        assert!(matched(age));
+        assert!(matched(name));
+        assert!(matched(both));
+    }
+
+    #[test]
+    fn test_xor_query() {
+        let mut world = World::new();
+        world.register::<Age>();
+        world.register::<Name>();
+
+        let neither = world.spawn();
+        let age = world.spawn();
+        let name = world.spawn();
+        let both = world.spawn();
+        world.add_component(age, Age(32));
+        world.add_component(name, Name("Bob"));
+        world.add_components(
+            both,
+            Person {
+                age: Age(20),
+                name: Name("Alice"),
+            },
+        );
+
+        let mut state = QueryState::<Entity, Xor<(With<Age>, With<Name>)>>::new(
+            &mut world,
+            &mut ArchetypeAccess::new(),
+        );
+        state.update(&mut world);
+        let query = Query::new(unsafe { world.cell() }, &mut state);
+
+        assert!(!query.contains(neither));
+        assert!(query.contains(age));
+        assert!(query.contains(name));
+        assert!(!query.contains(both));
+    }
+
+    #[test]
+    fn test_query_late_registration() {
+        #[derive(Debug, Default, PartialEq, Eq)]
+        struct Late(u32);
+        impl Component for Late {}
+
+        let mut world = World::new();
+        let mut state = QueryState::<&Age>::new(&mut world, &mut ArchetypeAccess::new());
+        let query = Query::new(unsafe { world.cell() }, &mut state);
+        assert_eq!(query.iter().count(), 0);
+
+        world.register::<Late>();
+        let entity = world.spawn();
+        world.add_component(entity, Late(1));
+        world.add_component(entity, Age(10));
+
+        let query = Query::new(unsafe { world.cell() }, &mut state);
+        assert!(query.contains(entity));
+        assert_eq!(query.iter().collect::<Vec<_>>(), vec![&Age(10)]);
+    }
+
     #[test]
     fn test_disjoint_query() {
         let mut world = World::new();
@@ -1236,6 +1762,112 @@ mod tests {
         assert!(!access.is_disjoint(&other))
     }
 
+    #[test]
+    fn get_by_entity() {
+        let mut world = World::new();
+        world.register::<Age>();
+        world.register::<Name>();
+
+        let bob = world.spawn();
+        world.add_component(bob, Age(30));
+        world.add_component(bob, Name("Bob"));
+
+        let unnamed = world.spawn();
+        world.add_component(unnamed, Age(5));
+
+        let despawned = world.spawn();
+        world.add_component(despawned, Age(1));
+        world.add_component(despawned, Name("Gone"));
+        world.despawn(despawned);
+
+        let mut state =
+            QueryState::<&Age, With<Name>>::new(&mut world, &mut ArchetypeAccess::new());
+        let query = Query::new(unsafe { world.cell() }, &mut state);
+
+        assert_eq!(query.get(bob), Some(&Age(30)));
+        assert_eq!(query.get(unnamed), None);
+        assert_eq!(query.get(despawned), None);
+
+        let mut state = QueryState::<&mut Name>::new(&mut world, &mut ArchetypeAccess::new());
+        let mut query = Query::new(unsafe { world.cell() }, &mut state);
+
+        query.get_mut(bob).unwrap().0 = "Robert";
+        assert!(query.get_mut(unnamed).is_none());
+        assert_eq!(world.get_component::<Name>(bob), Some(&Name("Robert")));
+    }
+
+    #[test]
+    fn single() {
+        let mut world = World::new();
+        world.register::<Age>();
+        world.register::<Name>();
+
+        let mut state = QueryState::<&Age>::new(&mut world, &mut ArchetypeAccess::new());
+        let query = Query::new(unsafe { world.cell() }, &mut state);
+        assert_eq!(query.single(), Err(QuerySingleError::Empty));
+
+        let entity = world.spawn();
+        world.add_component(entity, Age(1));
+
+        let mut state = QueryState::<&mut Age>::new(&mut world, &mut ArchetypeAccess::new());
+        let mut query = Query::new(unsafe { world.cell() }, &mut state);
+        query.single_mut().unwrap().0 = 2;
+        assert_eq!(world.get_component::<Age>(entity), Some(&Age(2)));
+
+        let entity = world.spawn();
+        world.add_component(entity, Age(3));
+        world.add_component(entity, Name("Bob"));
+
+        let mut state = QueryState::<&Age>::new(&mut world, &mut ArchetypeAccess::new());
+        let query = Query::new(unsafe { world.cell() }, &mut state);
+        assert_eq!(query.single(), Err(QuerySingleError::Multiple(2)));
+    }
+
+    #[test]
+    fn par_for_each() {
+        use crate::core::task::TaskPool;
+        use std::sync::atomic::{AtomicU64, Ordering};
+
+        let mut world = World::new();
+        world.register::<Age>();
+        world.register::<Name>();
+
+        for index in 0..100_000 {
+            let entity = world.spawn();
+            world.add_component(entity, Age(index));
+            if index % 2 == 0 {
+                world.add_component(entity, Name("Even"));
+            }
+        }
+
+        let mut state = QueryState::<&mut Age>::new(&mut world, &mut ArchetypeAccess::new());
+        let query = Query::new(unsafe { world.cell() }, &mut state);
+        let sequential = query.iter().map(|age| age.0 as u64).sum::<u64>();
+
+        let pool = TaskPool::builder().size(4).build();
+        let parallel = AtomicU64::new(0);
+        query.par_iter().for_each_in(&pool, |age| {
+            parallel.fetch_add(age.0 as u64, Ordering::Relaxed);
+            age.0 += 1;
+        });
+
+        assert_eq!(parallel.load(Ordering::Relaxed), sequential);
+
+        let incremented = AtomicU64::new(0);
+        query.par_iter().batch_size(1000).for_each_in(&pool, |age| {
+            incremented.fetch_add(age.0 as u64, Ordering::Relaxed);
+        });
+
+        assert_eq!(incremented.load(Ordering::Relaxed), sequential + 100_000);
+    }
+
+    #[test]
+    #[should_panic]
+    fn conflicting_query_access() {
+        let mut world = World::new();
+        QueryState::<(&mut Age, &Age)>::new(&mut world, &mut ArchetypeAccess::new());
+    }
+
     // Feature: fix-ecs-query-filters, Property 1: Added filter matches newly added components
     // **Validates: Requirements 1.2, 2.1**
     #[quickcheck_macros::quickcheck]
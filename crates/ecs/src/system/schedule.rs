@@ -1,5 +1,6 @@
 use super::{
-    IntoSystemConfigs, SystemCell, SystemConfig,
+    IntoSystemConfigs, OrderReason, PhaseGraph, ScheduleGraph, SystemAccessInfo, SystemCell,
+    SystemConfig, SystemEdge,
     executor::{RunMode, SystemExecutor},
 };
 use crate::{
@@ -93,60 +94,79 @@ impl PhaseConfig {
         let has_edge = |node: &SystemNode, other: &SystemNode| {
             if let Some(set) = other.config.set.as_ref() {
                 if node.config.dependencies.contains(set) {
-                    return Some(true);
+                    return Some((true, OrderReason::Explicit));
                 } else if node.config.dependents.contains(set) {
-                    return Some(false);
+                    return Some((false, OrderReason::Explicit));
                 }
             }
 
             if let Some(set) = node.config.set.as_ref() {
                 if other.config.dependencies.contains(set) {
-                    return Some(false);
+                    return Some((false, OrderReason::Explicit));
                 } else if other.config.dependents.contains(set) {
-                    return Some(true);
+                    return Some((true, OrderReason::Explicit));
                 }
             }
 
             // Exclusive systems conflict with all other systems.
             // The earlier system (node) should run first, so the later system (other) depends on node.
             if node.config.exclusive || other.config.exclusive {
-                return Some(false);
+                return Some((false, OrderReason::Exclusive));
             }
 
             // When there's a conflict, the earlier system (node) should run first,
             // so the later system (other) depends on the earlier system (node).
             // Return Some(false) to indicate that other depends on node.
-            if node.access.conflicts(&other.access).is_err() {
-                Some(false)
+            if let Err(error) = node.access.current.conflicts(&other.access.current) {
+                Some((false, OrderReason::conflict(world, error, false)))
+            } else if let Err(error) = node.access.main.conflicts(&other.access.main) {
+                Some((false, OrderReason::conflict(world, error, true)))
             } else {
                 None
             }
         };
 
+        let mut edges: Vec<SystemEdge> = Vec::new();
+        let mut add_edge = |systems: &mut IndexDag<SystemNode>,
+                            before: usize,
+                            after: usize,
+                            reason: OrderReason| {
+            if systems.add_dependency(before, after) {
+                edges.push(SystemEdge {
+                    before,
+                    after,
+                    reason,
+                });
+            }
+        };
+
         for node in 0..systems.nodes().len() {
+            // Set edges are checked before this node's own ordering is taken,
+            // so `after_set` works no matter which system was added first.
+            for other in (node + 1)..systems.nodes().len() {
+                let edge = has_edge(&systems.nodes()[node], &systems.nodes()[other]).map(
+                    |(dependency, reason)| match dependency {
+                        true => (node, other, reason),
+                        false => (other, node, reason),
+                    },
+                );
+
+                if let Some((index, dependency, reason)) = edge {
+                    add_edge(&mut systems, dependency, index, reason);
+                }
+            }
+
             let dependencies = std::mem::take(&mut systems.nodes_mut()[node].config.dependencies);
             for dependency in dependencies {
                 if let Some(dep) = map.get(&dependency).copied() {
-                    systems.add_dependency(dep, node);
+                    add_edge(&mut systems, dep, node, OrderReason::Explicit);
                 }
             }
 
             let dependents = std::mem::take(&mut systems.nodes_mut()[node].config.dependents);
             for dependent in dependents {
                 if let Some(dep) = map.get(&dependent).copied() {
-                    systems.add_dependency(node, dep);
-                }
-            }
-
-            for other in (node + 1)..systems.nodes().len() {
-                let edge =
-                    has_edge(&systems.nodes()[node], &systems.nodes()[other]).map(|v| match v {
-                        true => (node, other),
-                        false => (other, node),
-                    });
-
-                if let Some((index, dependency)) = edge {
-                    systems.add_dependency(dependency, index);
+                    add_edge(&mut systems, node, dep, OrderReason::Explicit);
                 }
             }
         }
@@ -166,11 +186,23 @@ impl PhaseConfig {
             );
         }
 
+        let graph = PhaseGraph {
+            name: self.phase.name(),
+            systems: systems
+                .nodes()
+                .iter()
+                .map(|node| SystemAccessInfo::new(world, node))
+                .collect(),
+            edges,
+            ..Default::default()
+        };
+
         let executor = mode.create_executor(systems.map(SystemCell::from));
 
         PhaseNode {
             phase: self.phase,
             executor,
+            graph,
         }
     }
 }
@@ -178,6 +210,7 @@ impl PhaseConfig {
 pub struct PhaseNode {
     phase: Box<dyn Phase>,
     executor: Box<dyn SystemExecutor>,
+    graph: PhaseGraph,
 }
 
 impl PhaseNode {
@@ -185,6 +218,12 @@ impl PhaseNode {
         self.phase.name()
     }
 
+    /// The phase's systems and ordering edges. Phase ordering is only filled
+    /// in by [`Systems::dump`].
+    pub fn graph(&self) -> &PhaseGraph {
+        &self.graph
+    }
+
     pub fn run<'a>(&self, index: usize, world: WorldCell<'a>, systems: &'a Systems) {
         let ctx = PhaseContext::new(index, self.executor.as_ref(), world, systems);
         self.phase.run(ctx);
@@ -271,6 +310,10 @@ impl Schedule {
         self.run_before(target, phase);
     }
 
+    /// Adds systems to the `phase`. Within a phase, systems run in the order
+    /// they were added unless reordered by `before`/`after` constraints, sets,
+    /// or conflicting access. The parallel executor may overlap systems that
+    /// don't depend on each other.
     pub fn add_systems<M>(&mut self, phase: impl Phase, systems: impl IntoSystemConfigs<M>) {
         let index = self.add_phase(phase);
         self.phases.nodes_mut()[index].add_systems(systems);
@@ -366,6 +409,32 @@ impl Systems {
     pub fn phases(&self) -> &[PhaseNode] {
         self.phases.nodes()
     }
+
+    /// Describes every phase with its systems, their access and the edges
+    /// that order them, for debugging scheduling problems.
+    pub fn dump(&self) -> ScheduleGraph {
+        let nodes = self.phases.nodes();
+        let mut phases = nodes
+            .iter()
+            .map(|node| node.graph.clone())
+            .collect::<Vec<_>>();
+
+        for (index, dependents) in self.phases.dependents().iter().enumerate() {
+            for dependent in dependents.ones() {
+                phases[dependent].after.push(nodes[index].name());
+            }
+        }
+
+        for (index, children) in self.hierarchy.dependents().iter().enumerate() {
+            let parent = self.hierarchy.nodes()[index];
+            for child in children.ones() {
+                let child = self.hierarchy.nodes()[child];
+                phases[parent].children.push(nodes[child].name());
+            }
+        }
+
+        ScheduleGraph { phases }
+    }
 }
 
 #[allow(unused_imports, dead_code)]
@@ -481,6 +550,146 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_dump() {
+        use crate::{IntoSystemConfigs, Resource, ext::short_type_name, system::OrderReason};
+
+        struct Value;
+        impl Resource for Value {}
+
+        fn write_value(_: &mut Value) {}
+        fn read_value(_: &Value) {}
+        fn first(_: &Value) {}
+        fn second(_: &Value) {}
+
+        let update = TestPhase("Update");
+        let post_update = TestPhase("PostUpdate");
+
+        let mut schedule = Schedule::new(RunMode::Sequential);
+        schedule.run_before(update, post_update);
+        schedule.add_systems(update, write_value);
+        schedule.add_systems(update, read_value);
+        schedule.add_systems(update, first.before(second));
+
+        let mut world = World::new();
+        world.add_resource(Value);
+        let graph = schedule.build(&mut world).unwrap().dump();
+
+        let phase = graph.phase("Update").unwrap();
+        let index = |name: &str| {
+            let suffix = format!("::{}", name);
+            phase
+                .systems
+                .iter()
+                .position(|system| system.name.ends_with(&suffix))
+                .unwrap()
+        };
+
+        assert_eq!(phase.systems.len(), 4);
+        let value = short_type_name::<Value>();
+        let write = &phase.systems[index("write_value")];
+        let read = &phase.systems[index("read_value")];
+        assert_eq!(write.resource_writes, vec![value]);
+        assert!(write.resource_reads.is_empty());
+        assert_eq!(read.resource_reads, vec![value]);
+        assert!(read.resource_writes.is_empty());
+
+        let edge = |before: &str, after: &str| {
+            let (before, after) = (index(before), index(after));
+            phase
+                .edges
+                .iter()
+                .find(|edge| edge.before == before && edge.after == after)
+                .map(|edge| edge.reason.clone())
+        };
+
+        assert_eq!(
+            edge("write_value", "read_value"),
+            Some(OrderReason::Conflict(value.to_string()))
+        );
+        assert_eq!(edge("first", "second"), Some(OrderReason::Explicit));
+        assert_eq!(edge("read_value", "first"), None);
+
+        assert_eq!(graph.phase("PostUpdate").unwrap().after, vec!["Update"]);
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph schedule {"));
+        assert!(dot.contains(&format!("conflict: {}", value)));
+    }
+
+    #[test]
+    fn test_component_conflict() {
+        use crate::{Component, Query, system::OrderReason};
+
+        struct Position;
+        impl Component for Position {}
+
+        fn move_a(_: Query<&mut Position>) {}
+        fn move_b(_: Query<&mut Position>) {}
+
+        let update = TestPhase("Update");
+        let mut schedule = Schedule::new(RunMode::Sequential);
+        schedule.add_systems(update, move_a);
+        schedule.add_systems(update, move_b);
+
+        let mut world = World::new();
+        world.register::<Position>();
+        let graph = schedule.build(&mut world).unwrap().dump();
+        let phase = graph.phase("Update").unwrap();
+
+        assert_eq!(phase.edges.len(), 1);
+        let edge = &phase.edges[0];
+        assert!(phase.systems[edge.before].name.ends_with("::move_a"));
+        assert!(phase.systems[edge.after].name.ends_with("::move_b"));
+        assert_eq!(
+            edge.reason,
+            OrderReason::Conflict(std::any::type_name::<Position>().to_string())
+        );
+    }
+
+    #[test]
+    fn test_set_order() {
+        use crate::{IntoSystemConfigs, system::OrderReason};
+
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        struct TestSet;
+        impl crate::system::SystemSet for TestSet {
+            fn dyn_clone(&self) -> Box<dyn crate::system::SystemSet> {
+                Box::new(*self)
+            }
+        }
+
+        fn early() {}
+        fn late() {}
+
+        let update = TestPhase("Update");
+        let mut schedule = Schedule::new(RunMode::Sequential);
+        schedule.add_systems(update, late.after_set(TestSet));
+        schedule.add_systems(update, early.in_set(TestSet));
+
+        let mut world = World::new();
+        let graph = schedule.build(&mut world).unwrap().dump();
+        let phase = graph.phase("Update").unwrap();
+        let index = |name: &str| {
+            let suffix = format!("::{}", name);
+            phase
+                .systems
+                .iter()
+                .position(|system| system.name.ends_with(&suffix))
+                .unwrap()
+        };
+
+        let (early, late) = (index("early"), index("late"));
+        let edge = phase
+            .edges
+            .iter()
+            .find(|edge| edge.before == early && edge.after == late);
+        assert_eq!(
+            edge.map(|edge| edge.reason.clone()),
+            Some(OrderReason::Explicit)
+        );
+    }
+
     #[test]
     fn test_render_order() {
         let mut schedule = Schedule::new(RunMode::Sequential);
@@ -114,6 +114,7 @@ pub struct ArchetypeAccess {
     pub(crate) access: Access<ComponentId>,
     pub(crate) includes: FixedBitSet,
     pub(crate) excludes: FixedBitSet,
+    pub(crate) any: Vec<Vec<ArchetypeAccess>>,
 }
 
 impl ArchetypeAccess {
@@ -122,6 +123,7 @@ impl ArchetypeAccess {
             access: Access::new(),
             includes: FixedBitSet::new(),
             excludes: FixedBitSet::new(),
+            any: Vec::new(),
         }
     }
 
@@ -145,30 +147,46 @@ impl ArchetypeAccess {
         &self.excludes
     }
 
+    /// Panics if the component is already written by this access, since a
+    /// query can't hand out a shared and a mutable reference to the same
+    /// component.
     pub fn read(&mut self, component: ComponentId) -> ComponentId {
-        self.access.read(component);
+        self.read_optional(component);
         self.includes.grow(component.to_usize() + 1);
         self.includes.set(component.to_usize(), true);
         component
     }
 
+    /// Panics if the component is already read or written by this access.
     pub fn write(&mut self, component: ComponentId) -> ComponentId {
-        self.access.write(component);
+        self.write_optional(component);
         self.includes.grow(component.to_usize() + 1);
         self.includes.set(component.to_usize(), true);
         component
     }
 
     pub fn read_optional(&mut self, component: ComponentId) -> ComponentId {
-        self.access.read(component);
+        if !self.access.read(component) {
+            panic!("Component {component:?} is read and written by the same query");
+        }
+
         component
     }
 
     pub fn write_optional(&mut self, component: ComponentId) -> ComponentId {
-        self.access.write(component);
+        if !self.access.write(component) {
+            panic!("Component {component:?} is accessed more than once by the same query");
+        }
+
         component
     }
 
+    /// Requires archetypes to match the includes and excludes of at least
+    /// one of `terms`. Reads and writes of the terms are not copied.
+    pub fn any(&mut self, terms: Vec<ArchetypeAccess>) {
+        self.any.push(terms);
+    }
+
     pub fn include(&mut self, component: ComponentId) -> ComponentId {
         self.includes.grow(component.to_usize() + 1);
         self.includes.set(component.to_usize(), true);
@@ -268,10 +286,12 @@ impl AppAccess {
                 if let Err(conflict) = access.conflicts(other) {
                     return Err(AccessError::from(ComponentId::from_usize(conflict)));
                 }
+            }
+        }
 
-                if let Err(conflict) = other.access.conflicts(&self.components) {
-                    return Err(AccessError::from(ComponentId::from_usize(conflict)));
-                }
+        for access in &other.archetypes {
+            if let Err(conflict) = access.access.conflicts(&self.components) {
+                return Err(AccessError::from(ComponentId::from_usize(conflict)));
             }
         }
 
@@ -411,6 +431,21 @@ mod test {
         assert_eq!(access.conflicts(&other), Ok(()));
     }
 
+    #[test]
+    fn world_access_conflict_with_archetype() {
+        let mut archetype = ArchetypeAccess::new();
+        archetype.write(ComponentId(0));
+
+        let mut access = WorldAccess::new();
+        access.current.components.read(ComponentId(0));
+
+        let mut other = WorldAccess::new();
+        other.add_archetype(archetype);
+
+        assert_ne!(access.conflicts(&other), Ok(()));
+        assert_ne!(other.conflicts(&access), Ok(()));
+    }
+
     #[test]
     fn world_access_validate() {
         let mut valid = WorldAccess::new();
@@ -4,7 +4,7 @@ use crate::{
     impl_sparse_index_wrapper,
     world::ComponentWriter,
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, hash_map::Entry};
 
 pub mod table;
 
@@ -61,7 +61,12 @@ impl Archetype {
     }
 
     pub fn matches(&self, query: &ArchetypeQuery) -> bool {
-        self.bitset.is_superset(&query.included) && self.bitset.is_disjoint(&query.excluded)
+        self.bitset.is_superset(&query.included)
+            && self.bitset.is_disjoint(&query.excluded)
+            && query
+                .any
+                .iter()
+                .all(|terms| terms.iter().any(|term| self.matches(term)))
     }
 
     pub fn has_components(&self, components: &FixedBitSet) -> bool {
@@ -235,6 +240,56 @@ impl Archetypes {
         }
     }
 
+    /// Adds entities without components to the empty archetype. Entities
+    /// that already exist are left where they are.
+    pub fn add_entities(&mut self, entities: &[Entity]) {
+        self.entity_map.reserve(entities.len());
+        let table = &mut self.archetypes[ArchetypeId::EMPTY.to_usize()].table;
+        table.reserve(entities.len());
+
+        for entity in entities {
+            if let Entry::Vacant(entry) = self.entity_map.entry(*entity) {
+                entry.insert(ArchetypeId::EMPTY);
+                table.add_entity(*entity, Row::new());
+            }
+        }
+    }
+
+    /// Adds new entities with the components of their kits. The kit's
+    /// archetype is resolved once and its table reserved up front, so each
+    /// entity is inserted straight into it. Entities that already exist are
+    /// left where they are.
+    pub fn add_entities_with<C: ComponentKit>(
+        &mut self,
+        entities: impl IntoIterator<Item = (Entity, C)>,
+        frame: Frame,
+    ) {
+        let kit = self.components.register_kit::<C>();
+        let archetype = self.next_added_archetype(ArchetypeId::EMPTY, ArchetypeEdgeId::Kit(kit));
+        let entities = entities.into_iter();
+
+        self.entity_map.reserve(entities.size_hint().0);
+        let table = &mut self.archetypes[archetype.to_usize()].table;
+        table.reserve(entities.size_hint().0);
+
+        let bits = &self.components.kits()[kit.to_usize()];
+        for (entity, components) in entities {
+            let Entry::Vacant(entry) = self.entity_map.entry(entity) else {
+                continue;
+            };
+
+            let mut row = Row::new();
+            components.get(&mut ComponentAdder {
+                row: &mut row,
+                components: bits.ones(),
+                frame,
+            });
+
+            entry.insert(archetype);
+            table.add_entity(entity, row);
+        }
+    }
+
     pub fn remove_entity(&mut self, entity: Entity) -> Option<Row> {
         let id = self.entity_map.remove(&entity)?;
         let archetype = &mut self.archetypes[id.0 as usize];
@@ -332,19 +387,6 @@ impl Archetypes {
             let table = &mut self.archetypes[next.to_usize()].table;
             let bits = &self.components.kits()[kit.to_usize()];
 
-            pub struct ComponentAdder<'a> {
-                row: &'a mut Row,
-                components: fixedbitset::Ones<'a>,
-                frame: Frame,
-            }
-
-            impl<'a> ComponentWriter for ComponentAdder<'a> {
-                fn write<C: Component>(&mut self, component: C) {
-                    let id = self.components.next().map(ComponentId::from_usize).unwrap();
-                    self.row.insert(id, component, self.frame);
-                }
-            }
-
             let mut writer = ComponentAdder {
                 row: &mut row,
                 components: bits.ones(),
@@ -362,7 +404,7 @@ impl Archetypes {
 
     pub fn remove_component<C: Component>(&mut self, entity: Entity) -> Option<(EntityIndex, C)> {
         let id = self.components.register::<C>();
-        let archetype = self.get_or_insert_entity(entity);
+        let archetype = self.entity_map.get(&entity).copied()?;
         let next = self.next_removed_archetype(archetype, ArchetypeEdgeId::Component(id));
 
         if archetype == next {
@@ -382,7 +424,7 @@ impl Archetypes {
         entity: Entity,
     ) -> Option<(EntityIndex, Row)> {
         let kit = self.components.register_kit::<C>();
-        let archetype = self.get_or_insert_entity(entity);
+        let archetype = self.entity_map.get(&entity).copied()?;
         let next = self.next_removed_archetype(archetype, ArchetypeEdgeId::Kit(kit));
 
         if archetype == next {
@@ -404,6 +446,47 @@ impl Archetypes {
         }
     }
 
+    /// Clones every component of `entity` that has a registered cloner.
+    /// Components without one are left out of the returned row and pushed
+    /// to `skipped`.
+    pub fn clone_row(
+        &self,
+        entity: Entity,
+        frame: Frame,
+        skipped: &mut Vec<ComponentId>,
+    ) -> Option<Row> {
+        let archetype = self.entity_archetype(entity)?;
+        let index = archetype.table.get_entity_row(entity)?;
+
+        let mut row = Row::new();
+        for (id, column) in archetype.table.components() {
+            let Some(cloner) = self.components.get_by_id(*id).and_then(|c| c.cloner()) else {
+                skipped.push(*id);
+                continue;
+            };
+
+            let data = column.get_raw(index.to_usize())?;
+            row.insert_cell(*id, TableCell::from_blob(cloner(data.as_ptr()), frame));
+        }
+
+        Some(row)
+    }
+
+    /// Moves `entity` into the archetype matching the components in `row`.
+    pub fn add_row(&mut self, entity: Entity, row: Row) -> EntityIndex {
+        self.remove_entity(entity);
+
+        let mut bits = FixedBitSet::with_capacity(self.components.len());
+        row.ids()
+            .iter()
+            .for_each(|id| bits.set(id.to_usize(), true));
+
+        let id = self.get_or_insert_archetype(bits);
+
+        self.entity_map.insert(entity, id);
+        self.archetypes[id.to_usize()].add_entity(entity, row)
+    }
+
     #[inline]
     fn get_or_insert_entity(&mut self, entity: Entity) -> ArchetypeId {
         match self.entity_map.get(&entity).copied() {
@@ -418,92 +501,118 @@ impl Archetypes {
 
     #[inline]
     fn next_added_archetype(&mut self, current: ArchetypeId, edge: ArchetypeEdgeId) -> ArchetypeId {
-        match self.archetypes[current.to_usize()].edges.added(edge) {
-            Some(id) => id,
-            None => {
-                let mut bits = self.archetypes[current.to_usize()].bitset.clone();
-                match edge {
-                    ArchetypeEdgeId::Component(id) => {
-                        bits.grow(id.to_usize() + 1);
-                        bits.set(id.to_usize(), true);
-                    }
-                    ArchetypeEdgeId::Kit(id) => {
-                        bits.union_with(&self.components.kits()[id.to_usize()])
-                    }
-                }
-
-                if self.archetypes[current.to_usize()].bitset == bits {
-                    return current;
-                }
-
-                let mut table = TableBuilder::new();
-                for id in bits.ones().map(ComponentId::from_usize) {
-                    let component = self.components.get_by_id(id).unwrap();
-                    table.add_raw_column(id, *component.meta());
-                }
+        if let Some(id) = self.archetypes[current.to_usize()].edges.added(edge) {
+            return id;
+        }
 
-                let next_id = ArchetypeId::from_usize(self.archetypes.len());
-                let mut archetype = Archetype::new(next_id, table.build(), bits.clone());
+        let bits = self.apply_edge(&self.archetypes[current.to_usize()].bitset, edge, true);
+        let next = self.get_or_insert_archetype(bits);
+        self.link_archetypes(current, next, edge, Edge::Add);
+        next
+    }
 
-                archetype.edges.remove.insert(edge, current);
-                self.archetypes[current.to_usize()]
-                    .edges
-                    .add
-                    .insert(edge, next_id);
+    fn next_removed_archetype(
+        &mut self,
+        current: ArchetypeId,
+        edge: ArchetypeEdgeId,
+    ) -> ArchetypeId {
+        if let Some(id) = self.archetypes[current.to_usize()].edges.removed(edge) {
+            return id;
+        }
 
-                self.archetypes.push(archetype);
-                self.archetype_map.insert(bits, next_id);
+        let bits = self.apply_edge(&self.archetypes[current.to_usize()].bitset, edge, false);
+        let next = self.get_or_insert_archetype(bits);
+        self.link_archetypes(current, next, edge, Edge::Remove);
+        next
+    }
 
-                next_id
+    fn apply_edge(&self, bits: &FixedBitSet, edge: ArchetypeEdgeId, add: bool) -> FixedBitSet {
+        let mut bits = bits.clone();
+        match edge {
+            ArchetypeEdgeId::Component(id) => {
+                bits.grow(id.to_usize() + 1);
+                bits.set(id.to_usize(), add);
+            }
+            ArchetypeEdgeId::Kit(id) if add => {
+                bits.union_with(&self.components.kits()[id.to_usize()])
+            }
+            ArchetypeEdgeId::Kit(id) => {
+                bits.difference_with(&self.components.kits()[id.to_usize()])
             }
         }
+
+        bits
     }
 
-    #[inline]
-    fn next_removed_archetype(
+    /// Records `current -> next` for `edge`, and the opposite edge from
+    /// `next` back to `current` when it's an exact inverse. Adding a kit that
+    /// `current` partially had isn't undone by removing the kit.
+    fn link_archetypes(
         &mut self,
         current: ArchetypeId,
+        next: ArchetypeId,
         edge: ArchetypeEdgeId,
-    ) -> ArchetypeId {
-        match self.archetypes[current.to_usize()].edges.removed(edge) {
-            Some(id) => id,
-            None => {
-                let mut bits = self.archetypes[current.to_usize()].bitset.clone();
-                match edge {
-                    ArchetypeEdgeId::Component(id) => {
-                        bits.grow(id.to_usize() + 1);
-                        bits.set(id.to_usize(), false);
-                    }
-                    ArchetypeEdgeId::Kit(id) => {
-                        bits.difference_with(&self.components.kits()[id.to_usize()]);
-                    }
-                }
-
-                if self.archetypes[current.to_usize()].bitset == bits {
-                    return current;
-                }
-
-                let mut table = TableBuilder::new();
-                for id in bits.ones().map(ComponentId::from_usize) {
-                    let component = self.components.get_by_id(id).unwrap();
-                    table.add_raw_column(id, *component.meta());
-                }
+        kind: Edge,
+    ) {
+        let back = self.apply_edge(
+            &self.archetypes[next.to_usize()].bitset,
+            edge,
+            kind == Edge::Remove,
+        );
+        let inverse = back
+            .ones()
+            .eq(self.archetypes[current.to_usize()].bitset.ones());
+
+        let edges = &mut self.archetypes[current.to_usize()].edges;
+        match kind {
+            Edge::Add => edges.add.insert(edge, next),
+            Edge::Remove => edges.remove.insert(edge, next),
+        };
 
-                let next_id = ArchetypeId::from_usize(self.archetypes.len());
-                let mut archetype = Archetype::new(next_id, table.build(), bits.clone());
+        if inverse {
+            let edges = &mut self.archetypes[next.to_usize()].edges;
+            match kind {
+                Edge::Add => edges.remove.insert(edge, current),
+                Edge::Remove => edges.add.insert(edge, current),
+            };
+        }
+    }
 
-                archetype.edges.remove.insert(edge, current);
-                self.archetypes[current.to_usize()]
-                    .edges
-                    .remove
-                    .insert(edge, next_id);
+    /// Finds the archetype with exactly the components in `bits`, creating it
+    /// if it doesn't exist yet.
+    fn get_or_insert_archetype(&mut self, bits: FixedBitSet) -> ArchetypeId {
+        // Bitsets with the same components can differ in length, so trim
+        // them to the highest set bit before hashing.
+        let len = bits.ones().last().map_or(0, |last| last + 1);
+        let mut trimmed = FixedBitSet::with_capacity(len);
+        trimmed.extend(bits.ones());
+        let bits = trimmed;
 
-                self.archetypes.push(archetype);
-                self.archetype_map.insert(bits, next_id);
+        if let Some(id) = self.archetype_map.get(&bits) {
+            return *id;
+        }
 
-                next_id
+        let mut table = TableBuilder::new();
+        for id in bits.ones().map(ComponentId::from_usize) {
+            let component = self.components.get_by_id(id).unwrap();
+            if let Some(other) = component.exclusive().intersection(&bits).next() {
+                let other = self.components.get_by_id(ComponentId::from_usize(other));
+                panic!(
+                    "{} can't be on the same entity as {}",
+                    component.name(),
+                    other.unwrap().name()
+                );
             }
+
+            table.add_raw_column(id, *component.meta());
         }
+
+        let id = ArchetypeId::from_usize(self.archetypes.len());
+        self.archetypes
+            .push(Archetype::new(id, table.build(), bits.clone()));
+        self.archetype_map.insert(bits, id);
+
+        id
     }
 
     pub(crate) fn update(&mut self, frame: Frame) {
@@ -513,6 +622,20 @@ impl Archetypes {
     }
 }
 
+/// Writes a kit's components into a new [`Row`].
+struct ComponentAdder<'a> {
+    row: &'a mut Row,
+    components: fixedbitset::Ones<'a>,
+    frame: Frame,
+}
+
+impl<'a> ComponentWriter for ComponentAdder<'a> {
+    fn write<C: Component>(&mut self, component: C) {
+        let id = self.components.next().map(ComponentId::from_usize).unwrap();
+        self.row.insert(id, component, self.frame);
+    }
+}
+
 impl std::ops::Index<ArchetypeId> for Archetypes {
     type Output = Archetype;
 
@@ -542,6 +665,9 @@ impl EntityIndex {
 pub struct ArchetypeQuery {
     pub included: FixedBitSet,
     pub excluded: FixedBitSet,
+    /// Groups of alternative queries. An archetype must match at least one
+    /// query from every group.
+    pub any: Vec<Vec<ArchetypeQuery>>,
 }
 
 impl ArchetypeQuery {
@@ -549,6 +675,7 @@ impl ArchetypeQuery {
         Self {
             included: FixedBitSet::new(),
             excluded: FixedBitSet::new(),
+            any: Vec::new(),
         }
     }
 
@@ -568,6 +695,11 @@ impl From<ArchetypeAccess> for ArchetypeQuery {
         Self {
             included: value.includes,
             excluded: value.excludes,
+            any: value
+                .any
+                .into_iter()
+                .map(|terms| terms.into_iter().map(ArchetypeQuery::from).collect())
+                .collect(),
         }
     }
 }
@@ -617,6 +749,19 @@ mod tests {
         );
     }
 
+    #[test]
+    #[should_panic(expected = "can't be on the same entity as")]
+    fn add_exclusive_component() {
+        let mut archetypes = Archetypes::new();
+        let entity = Entity::root(0);
+
+        archetypes
+            .components_mut()
+            .register_exclusive::<Age, Name>();
+        archetypes.add_component(entity, Age(0), Frame::ZERO);
+        archetypes.add_component(entity, Name("Bob"), Frame::ZERO);
+    }
+
     #[test]
     fn add_component() {
         let mut archetypes = Archetypes::new();
@@ -657,6 +802,36 @@ mod tests {
         assert_eq!(age.map(|(_, a)| a), Some(Age(0)));
     }
 
+    #[test]
+    fn archetype_edges_cycle() {
+        let mut archetypes = Archetypes::new();
+        let entity = Entity::root(0);
+        let id = |archetypes: &Archetypes| archetypes.entity_archetype(entity).unwrap().id;
+
+        archetypes.register::<Age>();
+        archetypes.add_entity(entity);
+        let empty = id(&archetypes);
+
+        archetypes.add_component(entity, Age(0), Frame::ZERO);
+        let age = id(&archetypes);
+        archetypes.remove_component::<Age>(entity);
+        assert_eq!(id(&archetypes), empty);
+        archetypes.add_component(entity, Age(0), Frame::ZERO);
+        assert_eq!(id(&archetypes), age);
+
+        archetypes.remove_component::<Age>(entity);
+        archetypes.add_components(entity, Person::default(), Frame::ZERO);
+        let person = id(&archetypes);
+        archetypes.remove_component::<Age>(entity);
+        let name = id(&archetypes);
+        archetypes.add_component(entity, Age(1), Frame::ZERO);
+        assert_eq!(id(&archetypes), person);
+        archetypes.remove_component::<Age>(entity);
+        assert_eq!(id(&archetypes), name);
+
+        assert_eq!(archetypes.archetypes().len(), 4);
+    }
+
     #[test]
     fn remove_components() {
         let mut archetypes = Archetypes::new();
@@ -111,6 +111,11 @@ impl Column {
         self.data.get_mut::<T>(index)
     }
 
+    pub fn get_raw(&self, index: usize) -> Option<&[u8]> {
+        let size = self.data.meta().layout.size();
+        self.data.data().get(index * size..(index + 1) * size)
+    }
+
     pub(crate) unsafe fn get_ptr<T: Component>(&self) -> (Ptr<'_, T>, Ptr<'_, ObjectStatus>) {
         let components = unsafe { self.data.ptr::<T>() };
         let frames = self.frames.as_ptr() as *mut ObjectStatus;
@@ -340,6 +345,14 @@ impl Table {
         self.entities.iter()
     }
 
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
     pub fn components(&self) -> impl Iterator<Item = (&ComponentId, &Column)> {
         self.columns.iter()
     }
@@ -348,6 +361,11 @@ impl Table {
         self.columns.iter_mut()
     }
 
+    /// Reserves space for `additional` more entities.
+    pub fn reserve(&mut self, additional: usize) {
+        self.entities.reserve(additional);
+    }
+
     pub fn add_entity(&mut self, entity: Entity, mut row: Row) -> RowIndex {
         self.entities.insert(entity);
 
@@ -0,0 +1,223 @@
+use super::{ArchetypeId, ResourceId};
+
+#[cfg(debug_assertions)]
+use std::{
+    collections::HashMap,
+    sync::{Mutex, MutexGuard},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BorrowKey {
+    Resource(ResourceId),
+    /// Held by queries while they iterate an archetype, and checked before
+    /// entities are moved out of it.
+    Archetype(ArchetypeId),
+    /// The world as a whole, borrowed by exclusive systems and
+    /// [`WorldCell::borrow_world_mut`](super::WorldCell::borrow_world_mut).
+    World,
+}
+
+impl From<ResourceId> for BorrowKey {
+    fn from(value: ResourceId) -> Self {
+        Self::Resource(value)
+    }
+}
+
+impl From<ArchetypeId> for BorrowKey {
+    fn from(value: ArchetypeId) -> Self {
+        Self::Archetype(value)
+    }
+}
+
+#[cfg(debug_assertions)]
+#[derive(Debug, Clone, Copy)]
+enum BorrowState {
+    Shared(usize),
+    Exclusive,
+}
+
+/// Records the resources and archetypes currently borrowed through a
+/// [`WorldCell`](super::WorldCell) and panics on conflicting borrows.
+/// The checks only exist in debug builds.
+#[derive(Default)]
+pub struct BorrowTracker {
+    #[cfg(debug_assertions)]
+    borrows: Mutex<HashMap<BorrowKey, BorrowState>>,
+}
+
+impl BorrowTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn borrow(&self, key: BorrowKey) -> WorldBorrow<'_> {
+        #[cfg(debug_assertions)]
+        self.acquire(key, false);
+
+        WorldBorrow { tracker: self, key }
+    }
+
+    pub fn borrow_mut(&self, key: BorrowKey) -> WorldBorrow<'_> {
+        #[cfg(debug_assertions)]
+        self.acquire(key, true);
+
+        WorldBorrow { tracker: self, key }
+    }
+
+    #[cfg(debug_assertions)]
+    pub fn is_borrowed(&self, key: BorrowKey) -> bool {
+        self.lock().contains_key(&key)
+    }
+
+    /// Borrows aren't tracked in release builds.
+    #[cfg(not(debug_assertions))]
+    pub fn is_borrowed(&self, _: BorrowKey) -> bool {
+        false
+    }
+
+    #[cfg(debug_assertions)]
+    fn lock(&self) -> MutexGuard<'_, HashMap<BorrowKey, BorrowState>> {
+        // A conflicting borrow panics, so don't let poisoning hide the original message.
+        self.borrows.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    #[cfg(debug_assertions)]
+    fn acquire(&self, key: BorrowKey, exclusive: bool) {
+        let mut borrows = self.lock();
+        let state = match (borrows.get(&key).copied(), exclusive) {
+            (None, true) => BorrowState::Exclusive,
+            (None, false) => BorrowState::Shared(1),
+            (Some(BorrowState::Shared(count)), false) => BorrowState::Shared(count + 1),
+            (Some(state), _) => {
+                drop(borrows);
+                match state {
+                    BorrowState::Exclusive => panic!("{:?} is already borrowed mutably", key),
+                    BorrowState::Shared(_) => panic!("{:?} is already borrowed", key),
+                }
+            }
+        };
+
+        borrows.insert(key, state);
+    }
+
+    #[cfg(debug_assertions)]
+    fn release(&self, key: BorrowKey) {
+        let mut borrows = self.lock();
+        match borrows.get_mut(&key) {
+            Some(BorrowState::Shared(count)) if *count > 1 => *count -= 1,
+            _ => {
+                borrows.remove(&key);
+            }
+        }
+    }
+}
+
+/// Releases its borrow when dropped.
+pub struct WorldBorrow<'a> {
+    tracker: &'a BorrowTracker,
+    key: BorrowKey,
+}
+
+impl WorldBorrow<'_> {
+    pub fn key(&self) -> BorrowKey {
+        self.key
+    }
+}
+
+impl Drop for WorldBorrow<'_> {
+    fn drop(&mut self) {
+        #[cfg(debug_assertions)]
+        self.tracker.release(self.key);
+
+        #[cfg(not(debug_assertions))]
+        let _ = self.tracker;
+    }
+}
+
+#[allow(unused_imports, dead_code)]
+mod tests {
+    use super::{BorrowKey, BorrowTracker};
+    use crate::{ArchetypeAccess, Entity, Query, QueryState, ResourceId, World};
+
+    #[test]
+    fn shared_borrows() {
+        let tracker = BorrowTracker::new();
+        let key = BorrowKey::Resource(ResourceId(0));
+
+        let a = tracker.borrow(key);
+        let b = tracker.borrow(key);
+        drop(a);
+        drop(b);
+
+        let _c = tracker.borrow_mut(key);
+        assert_eq!(tracker.is_borrowed(key), cfg!(debug_assertions));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "World is already borrowed mutably")]
+    fn conflicting_world_borrows() {
+        let world = World::new();
+        let mut cell = unsafe { world.cell() };
+
+        let _a = unsafe { cell.borrow_world_mut() };
+        let _b = unsafe { cell.borrow_world_mut() };
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "is already borrowed")]
+    fn move_entity_while_iterating() {
+        struct Value;
+        impl crate::Component for Value {}
+
+        let mut world = World::new();
+        world.register::<Value>();
+        world.spawn();
+
+        let mut state = QueryState::<Entity>::new(&mut world, &mut ArchetypeAccess::new());
+        let mut cell = unsafe { world.cell() };
+        let query = Query::new(cell, &mut state);
+        for entity in query.iter() {
+            unsafe { cell.get_mut() }.add_component(entity, Value);
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn par_iter_borrows_archetypes() {
+        use crate::core::task::TaskPool;
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let mut world = World::new();
+        let entity = world.spawn();
+        let archetype = world.archetypes().entity_archetype(entity).unwrap().id();
+        let key = BorrowKey::Archetype(archetype);
+
+        let mut state = QueryState::<Entity>::new(&mut world, &mut ArchetypeAccess::new());
+        let query = Query::new(unsafe { world.cell() }, &mut state);
+        let pool = TaskPool::builder().size(2).build();
+        let borrowed = AtomicBool::new(false);
+        query.par_iter().for_each_in(&pool, |_| {
+            borrowed.store(world.borrows.is_borrowed(key), Ordering::Relaxed);
+        });
+
+        assert!(borrowed.load(Ordering::Relaxed));
+        assert!(!world.borrows.is_borrowed(key));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "already borrowed mutably")]
+    fn conflicting_cell_borrows() {
+        struct Value;
+        impl crate::Resource for Value {}
+
+        let mut world = World::new();
+        let id = world.register_resource::<Value>();
+        let cell = unsafe { world.cell() };
+
+        let _a = cell.borrow_mut(id);
+        let _b = cell.borrow_mut(id);
+    }
+}
@@ -1,5 +1,8 @@
-use super::World;
-use std::marker::PhantomData;
+use super::{BorrowKey, World, WorldBorrow};
+use std::{
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct WorldCell<'w>(*mut World, PhantomData<&'w mut World>);
@@ -19,6 +22,49 @@ impl<'w> WorldCell<'w> {
     pub unsafe fn get_mut(&mut self) -> &'w mut World {
         unsafe { &mut *self.0 }
     }
+
+    /// Records a shared borrow of `key` until the returned guard is dropped.
+    /// Panics in debug builds if `key` is already borrowed mutably.
+    pub fn borrow(&self, key: impl Into<BorrowKey>) -> WorldBorrow<'w> {
+        unsafe { self.get() }.borrows.borrow(key.into())
+    }
+
+    /// Records an exclusive borrow of `key` until the returned guard is
+    /// dropped. Panics in debug builds if `key` is already borrowed.
+    pub fn borrow_mut(&self, key: impl Into<BorrowKey>) -> WorldBorrow<'w> {
+        unsafe { self.get() }.borrows.borrow_mut(key.into())
+    }
+
+    /// Like [`WorldCell::get_mut`], but records an exclusive borrow of the
+    /// whole world until the returned guard is dropped. Panics in debug
+    /// builds if the world is already borrowed.
+    pub unsafe fn borrow_world_mut(&mut self) -> WorldMut<'w> {
+        let borrow = self.borrow_mut(BorrowKey::World);
+        WorldMut {
+            world: unsafe { self.get_mut() },
+            _borrow: borrow,
+        }
+    }
+}
+
+/// A mutable reference to the [`World`] that releases its borrow when dropped.
+pub struct WorldMut<'w> {
+    world: &'w mut World,
+    _borrow: WorldBorrow<'w>,
+}
+
+impl Deref for WorldMut<'_> {
+    type Target = World;
+
+    fn deref(&self) -> &Self::Target {
+        self.world
+    }
+}
+
+impl DerefMut for WorldMut<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.world
+    }
 }
 
 unsafe impl<'w> Send for WorldCell<'w> {}
@@ -1,9 +1,83 @@
-use crate::{SparseIndex, core::TypeMeta, impl_sparse_index_wrapper};
+use crate::{
+    SparseIndex,
+    core::{BlobCell, Frame, ObjectStatus, TypeMeta},
+    impl_sparse_index_wrapper,
+};
 use fixedbitset::FixedBitSet;
 use std::{alloc::Layout, any::TypeId, collections::HashMap};
 
 pub trait Component: Send + Sync + 'static {}
 
+/// A mutable component that is only marked as modified when it's mutably
+/// dereferenced, and can tell whether it was added or changed since the
+/// system last ran.
+pub struct Mut<'a, C: Component> {
+    component: &'a mut C,
+    status: &'a mut ObjectStatus,
+    current: Frame,
+    last: Frame,
+}
+
+impl<'a, C: Component> Mut<'a, C> {
+    pub fn new(
+        component: &'a mut C,
+        status: &'a mut ObjectStatus,
+        current: Frame,
+        last: Frame,
+    ) -> Self {
+        Self {
+            component,
+            status,
+            current,
+            last,
+        }
+    }
+
+    pub fn is_added(&self) -> bool {
+        self.status.added.is_newer(self.current, self.last)
+    }
+
+    /// True if the component was added or mutably accessed since the system
+    /// last ran, including by this system.
+    pub fn is_changed(&self) -> bool {
+        self.is_added() || self.status.modified.is_newer(self.current, self.last)
+    }
+
+    /// Marks the component as modified and returns the inner reference.
+    pub fn into_inner(self) -> &'a mut C {
+        self.status.modified = self.current;
+        self.component
+    }
+}
+
+impl<'a, C: Component> std::ops::Deref for Mut<'a, C> {
+    type Target = C;
+
+    fn deref(&self) -> &Self::Target {
+        self.component
+    }
+}
+
+impl<'a, C: Component> std::ops::DerefMut for Mut<'a, C> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.status.modified = self.current;
+        self.component
+    }
+}
+
+impl<'a, C: Component> AsRef<C> for Mut<'a, C> {
+    fn as_ref(&self) -> &C {
+        self.component
+    }
+}
+
+impl<'a, C: Component> AsMut<C> for Mut<'a, C> {
+    fn as_mut(&mut self) -> &mut C {
+        self.status.modified = self.current;
+        self.component
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ComponentId(pub(crate) u32);
 impl_sparse_index_wrapper!(crate, ComponentId);
@@ -12,6 +86,8 @@ impl_sparse_index_wrapper!(crate, ComponentId);
 pub struct ComponentMeta {
     id: ComponentId,
     meta: TypeMeta,
+    cloner: Option<fn(data: *const u8) -> BlobCell>,
+    exclusive: FixedBitSet,
 }
 
 impl ComponentMeta {
@@ -19,6 +95,8 @@ impl ComponentMeta {
         Self {
             id,
             meta: TypeMeta::new::<C>(),
+            cloner: None,
+            exclusive: FixedBitSet::new(),
         }
     }
 
@@ -41,6 +119,23 @@ impl ComponentMeta {
     pub fn meta(&self) -> &TypeMeta {
         &self.meta
     }
+
+    /// Copies a component out of raw storage. Only set for components
+    /// registered with [`Components::register_cloneable`].
+    pub fn cloner(&self) -> Option<fn(data: *const u8) -> BlobCell> {
+        self.cloner
+    }
+
+    /// The components that can't be on the same entity as this one. See
+    /// [`Components::register_exclusive`].
+    pub fn exclusive(&self) -> &FixedBitSet {
+        &self.exclusive
+    }
+
+    fn clone_component<C: Component + Clone>(data: *const u8) -> BlobCell {
+        let component = unsafe { &*(data as *const C) };
+        BlobCell::new(component.clone())
+    }
 }
 
 pub struct Components {
@@ -76,6 +171,26 @@ impl Components {
         }
     }
 
+    pub fn register_cloneable<C: Component + Clone>(&mut self) -> ComponentId {
+        let id = self.register::<C>();
+        self.components[id.to_usize()].cloner =
+            Some(ComponentMeta::clone_component::<C> as fn(*const u8) -> BlobCell);
+        id
+    }
+
+    /// Registers `A` and `B` as components that can't be on the same entity.
+    /// Adding one to an entity that has the other panics.
+    pub fn register_exclusive<A: Component, B: Component>(&mut self) {
+        let a = self.register::<A>();
+        let b = self.register::<B>();
+        self.components[a.to_usize()]
+            .exclusive
+            .grow_and_insert(b.to_usize());
+        self.components[b.to_usize()]
+            .exclusive
+            .grow_and_insert(a.to_usize());
+    }
+
     pub fn register_kit<C: ComponentKit>(&mut self) -> ComponentId {
         let ty = TypeId::of::<C>();
         match self.kit_map.get(&ty).copied() {
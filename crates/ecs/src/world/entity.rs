@@ -1,4 +1,7 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicU32, Ordering},
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Entity {
@@ -51,7 +54,7 @@ impl PartialOrd for Entity {
 }
 
 pub struct Entities {
-    current: u32,
+    current: AtomicU32,
     free: Vec<u32>,
     generations: HashMap<u32, u32>,
 }
@@ -59,7 +62,7 @@ pub struct Entities {
 impl Entities {
     pub fn new() -> Self {
         Self {
-            current: 0,
+            current: AtomicU32::new(0),
             free: vec![],
             generations: HashMap::new(),
         }
@@ -67,26 +70,43 @@ impl Entities {
 
     pub fn spawn(&mut self) -> Entity {
         if let Some(id) = self.free.pop() {
-            let generation = self.generations.entry(id).or_default();
+            // Reserved entities aren't tracked until their id is reused.
+            let generation = self.generations.entry(id).or_insert(1);
             *generation += 1;
 
             Entity::new(id, *generation)
         } else {
-            let id = self.current;
+            let current = self.current.get_mut();
+            let id = *current;
             let generation = 1;
             self.generations.insert(id, generation);
-            self.current += 1;
+            *current += 1;
 
             Entity::new(id, generation)
         }
     }
 
+    /// Spawns `count` entities, reusing freed ids first.
+    pub fn spawn_batch(&mut self, count: usize) -> Vec<Entity> {
+        let fresh = count.saturating_sub(self.free.len());
+        self.generations.reserve(fresh);
+
+        (0..count).map(|_| self.spawn()).collect()
+    }
+
+    /// Reserves a new entity id without mutable access. Freed ids are never
+    /// reused by reservations, so this is safe to call from parallel systems.
+    pub fn reserve(&self) -> Entity {
+        let id = self.current.fetch_add(1, Ordering::Relaxed);
+        Entity::new(id, 1)
+    }
+
     pub fn despawn(&mut self, entity: Entity) {
         self.free.push(entity.id);
     }
 
     pub fn clear(&mut self) {
-        self.current = 0;
+        *self.current.get_mut() = 0;
         self.free.clear();
         self.generations.clear();
     }
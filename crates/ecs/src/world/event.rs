@@ -1,5 +1,8 @@
 use super::{Entity, World, WorldCell, resource::Resource};
-use std::{any::TypeId, collections::HashMap};
+use std::{
+    any::TypeId,
+    collections::{HashMap, VecDeque},
+};
 
 pub trait Event: Send + Sync + Sized + 'static {}
 
@@ -7,30 +10,130 @@ pub type EventId = u32;
 pub type EventIndex = usize;
 
 pub struct EventStorage<E: Event> {
-    pub(crate) events: Vec<E>,
+    pub(crate) events: VecDeque<E>,
+    /// Indices are absolute, so they stay valid when old events are dropped.
     pub(crate) entities: HashMap<Entity, Vec<EventIndex>>,
+    /// Absolute index of the first event in `events`.
+    pub(crate) offset: EventIndex,
+    capacity: Option<usize>,
+    dropped: usize,
 }
 
 impl<E: Event> EventStorage<E> {
+    /// Storage that keeps at most `capacity` events, dropping the oldest
+    /// when full. `None` keeps every event.
+    pub fn new(capacity: Option<usize>) -> Self {
+        Self {
+            events: VecDeque::new(),
+            entities: HashMap::new(),
+            offset: 0,
+            capacity,
+            dropped: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// The number of events dropped because the storage was full.
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    pub fn get(&self, index: EventIndex) -> Option<&E> {
+        self.events.get(index.checked_sub(self.offset)?)
+    }
+
+    /// The absolute index the next event will be stored at.
+    pub fn next_index(&self) -> EventIndex {
+        self.offset + self.events.len()
+    }
+
+    pub fn send(&mut self, event: E) -> EventIndex {
+        let index = self.next_index();
+        self.events.push_back(event);
+        self.enforce_capacity();
+        index
+    }
+
+    pub fn extend(&mut self, events: impl IntoIterator<Item = E>) {
+        let events = events.into_iter();
+        let (lower, _) = events.size_hint();
+        let reserve = match self.capacity {
+            Some(capacity) => lower.min(capacity.saturating_sub(self.events.len())),
+            None => lower,
+        };
+
+        self.events.reserve(reserve);
+        self.events.extend(events);
+        self.enforce_capacity();
+    }
+
     pub fn add_entity_event(&mut self, entity: Entity, event: E) {
-        let index = self.events.len();
-        self.events.push(event);
-        self.entities.entry(entity).or_default().push(index);
+        let index = self.send(event);
+        if index >= self.offset {
+            self.entities.entry(entity).or_default().push(index);
+        }
+    }
+
+    /// Moves every event in `other` to the end of this storage.
+    pub fn append(&mut self, other: &mut Self) {
+        let base = self.next_index();
+        for (entity, indices) in other.entities.drain() {
+            let indices = indices.iter().map(|index| index - other.offset + base);
+            self.entities.entry(entity).or_default().extend(indices);
+        }
+
+        self.events.append(&mut other.events);
+        self.dropped += std::mem::take(&mut other.dropped);
+        other.offset = 0;
+        self.enforce_capacity();
+    }
+
+    fn enforce_capacity(&mut self) {
+        let Some(capacity) = self.capacity else {
+            return;
+        };
+
+        let excess = self.events.len().saturating_sub(capacity);
+        if excess == 0 {
+            return;
+        }
+
+        self.events.drain(..excess);
+        self.offset += excess;
+        self.dropped += excess;
+
+        let offset = self.offset;
+        self.entities.retain(|_, indices| {
+            indices.retain(|index| *index >= offset);
+            !indices.is_empty()
+        });
     }
 }
 
 impl<E: Event> Default for EventStorage<E> {
     fn default() -> Self {
-        Self {
-            events: vec![],
-            entities: HashMap::new(),
-        }
+        Self::new(None)
     }
 }
 
+/// Double buffered events. Events sent during a frame become readable after
+/// the next [`Events::update`], and are kept for two updates in total, so an
+/// [`EventReader`] system that skips a frame still sees every event. Events
+/// older than that are dropped.
 pub struct Events<E: Event> {
     pub(crate) write: EventStorage<E>,
     pub(crate) read: EventStorage<E>,
+    /// The read buffer from the previous update.
+    pub(crate) previous: EventStorage<E>,
+    capacity: Option<usize>,
+    generation: usize,
 }
 
 impl<E: Event> Events<E> {
@@ -38,11 +141,42 @@ impl<E: Event> Events<E> {
         Self {
             write: EventStorage::default(),
             read: EventStorage::default(),
+            previous: EventStorage::default(),
+            capacity: None,
+            generation: 0,
+        }
+    }
+
+    /// Events that keep at most `capacity` events per frame. When full, the
+    /// oldest events are dropped and counted in [`Events::dropped`].
+    pub fn bounded(capacity: usize) -> Self {
+        let mut events = Self::new();
+        events.set_capacity(Some(capacity));
+        events
+    }
+
+    pub fn capacity(&self) -> Option<usize> {
+        self.capacity
+    }
+
+    pub fn set_capacity(&mut self, capacity: Option<usize>) {
+        self.capacity = capacity;
+        for storage in [&mut self.write, &mut self.read, &mut self.previous] {
+            storage.capacity = capacity;
+            storage.enforce_capacity();
         }
     }
 
+    /// The number of events dropped from the events readers currently see.
+    pub fn dropped(&self) -> usize {
+        self.read.dropped
+    }
+
     pub fn update(&mut self) {
-        self.read = std::mem::take(&mut self.write);
+        let write = EventStorage::new(self.capacity);
+        let read = std::mem::replace(&mut self.write, write);
+        self.previous = std::mem::replace(&mut self.read, read);
+        self.generation = self.generation.wrapping_add(1);
     }
 
     pub fn writer(&'_ mut self) -> EventWriter<'_, E> {
@@ -54,13 +188,11 @@ impl<E: Event> Events<E> {
     }
 
     pub fn send_immediate(&mut self, event: E) {
-        self.read.events.push(event);
+        self.read.send(event);
     }
 
     pub fn trigger(&mut self, entity: Entity, event: E) {
-        let index = self.read.events.len();
-        self.read.events.push(event);
-        self.read.entities.entry(entity).or_default().push(index);
+        self.read.add_entity_event(entity, event);
     }
 
     pub fn entity(&self, entity: Entity) -> std::slice::Iter<'_, usize> {
@@ -130,18 +262,90 @@ impl EventRegistry {
     }
 }
 
+/// Tracks which events a reader has already seen, across updates of
+/// [`Events`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct EventCursor {
+    generation: Option<usize>,
+    index: EventIndex,
+}
+
 pub struct EventReader<'state, E: Event> {
     events: &'state Events<E>,
-    index: usize,
+    previous: EventIndex,
+    index: EventIndex,
 }
 
 impl<'state, E: Event> EventReader<'state, E> {
+    /// Reads the events from the last update.
     pub(crate) fn new(events: &'state Events<E>) -> Self {
-        Self { events, index: 0 }
+        Self {
+            events,
+            previous: events.previous.next_index(),
+            index: events.read.offset,
+        }
     }
 
+    /// Reads every retained event `cursor` hasn't seen yet, and moves the
+    /// cursor past them.
+    pub fn with_cursor(events: &'state Events<E>, cursor: &mut EventCursor) -> Self {
+        let (previous, index) = match cursor.generation {
+            Some(generation) if generation == events.generation => {
+                (events.previous.next_index(), cursor.index)
+            }
+            Some(generation) if generation.wrapping_add(1) == events.generation => {
+                (cursor.index, events.read.offset)
+            }
+            _ => (events.previous.offset, events.read.offset),
+        };
+
+        cursor.generation = Some(events.generation);
+        cursor.index = events.read.next_index();
+
+        Self {
+            events,
+            previous: previous.max(events.previous.offset),
+            index: index.max(events.read.offset),
+        }
+    }
+
+    /// The number of events left to read.
     pub fn len(&self) -> usize {
-        self.events.read.events.len()
+        let previous = self
+            .events
+            .previous
+            .next_index()
+            .saturating_sub(self.previous);
+        previous + self.events.read.next_index().saturating_sub(self.index)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of events dropped because the event type's capacity was
+    /// reached. See [`World::register_event_capacity`].
+    pub fn dropped(&self) -> usize {
+        self.events.dropped()
+    }
+
+    /// The entities of the entity events left to read, in the order the
+    /// events were sent.
+    pub fn entities(&self) -> Vec<Entity> {
+        let unread = |storage: &EventStorage<E>, start: EventIndex| {
+            let mut entities = storage
+                .entities
+                .iter()
+                .flat_map(|(entity, indices)| indices.iter().map(move |index| (*index, *entity)))
+                .filter(|(index, _)| *index >= start)
+                .collect::<Vec<_>>();
+            entities.sort_by_key(|(index, _)| *index);
+            entities.into_iter().map(|(_, entity)| entity)
+        };
+
+        unread(&self.events.previous, self.previous)
+            .chain(unread(&self.events.read, self.index))
+            .collect()
     }
 }
 
@@ -149,13 +353,14 @@ impl<'state, E: Event> Iterator for EventReader<'state, E> {
     type Item = &'state E;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.index < self.events.read.events.len() {
-            let event = &self.events.read.events[self.index];
-            self.index += 1;
-            Some(event)
-        } else {
-            None
+        if let Some(event) = self.events.previous.get(self.previous) {
+            self.previous += 1;
+            return Some(event);
         }
+
+        let event = self.events.read.get(self.index)?;
+        self.index += 1;
+        Some(event)
     }
 }
 
@@ -183,10 +388,8 @@ impl<'state, E: Event> Iterator for EntityEvents<'state, E> {
     type Item = &'state E;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.indicies
-            .next()
-            .copied()
-            .map(|index| &self.events.read.events[index])
+        let events = &self.events.read;
+        self.indicies.find_map(|index| events.get(*index))
     }
 }
 
@@ -200,16 +403,104 @@ impl<'state, E: Event> EventWriter<'state, E> {
     }
 
     pub fn send(&mut self, event: E) {
-        self.storage.events.push(event);
+        self.storage.send(event);
     }
 
     pub fn trigger(&mut self, entity: Entity, event: E) {
-        let index = self.storage.events.len();
-        self.storage.events.push(event);
-        self.storage.entities.entry(entity).or_default().push(index);
+        self.storage.add_entity_event(entity, event);
+    }
+
+    /// Sends every event in `events`, reserving space for them up front.
+    pub fn send_batch(&mut self, events: impl IntoIterator<Item = E>) {
+        self.storage.extend(events);
+    }
+}
+
+#[allow(unused_imports, dead_code)]
+mod tests {
+    use super::{Event, EventCursor, EventReader, Events};
+    use crate::world::{Entity, World};
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Hit(u32);
+    impl Event for Hit {}
+
+    #[test]
+    fn bounded_events_drop_oldest() {
+        let mut world = World::new();
+        world.register_event_capacity::<Hit>(4);
+
+        let events = world.resource_mut::<Events<Hit>>();
+        let mut writer = events.writer();
+        for index in 0..10 {
+            writer.send(Hit(index));
+        }
+
+        events.update();
+
+        let reader = events.reader();
+        assert_eq!(reader.dropped(), 6);
+        assert_eq!(
+            reader.map(|hit| hit.0).collect::<Vec<_>>(),
+            vec![6, 7, 8, 9]
+        );
+    }
+
+    #[test]
+    fn reader_cursor_spans_skipped_frame() {
+        let mut events = Events::<Hit>::new();
+        let mut cursor = EventCursor::default();
+        let read = |events: &Events<Hit>, cursor: &mut EventCursor| {
+            EventReader::with_cursor(events, cursor)
+                .map(|hit| hit.0)
+                .collect::<Vec<_>>()
+        };
+
+        events.writer().send(Hit(0));
+        events.update();
+        assert_eq!(read(&events, &mut cursor), vec![0]);
+
+        // The reader doesn't run in the frame where Hit(1) becomes readable.
+        events.writer().send(Hit(1));
+        events.update();
+        events.writer().send(Hit(2));
+        events.update();
+
+        assert_eq!(read(&events, &mut cursor), vec![1, 2]);
+        assert_eq!(read(&events, &mut cursor), Vec::<u32>::new());
+
+        events.send_immediate(Hit(3));
+        assert_eq!(read(&events, &mut cursor), vec![3]);
+    }
+
+    #[test]
+    fn send_batch() {
+        let mut events = Events::<Hit>::new();
+        events.writer().send_batch((0..1000).map(Hit));
+        events.update();
+
+        let reader = events.reader();
+        assert_eq!(reader.len(), 1000);
+        assert!(!reader.is_empty());
+        assert!(reader.map(|hit| hit.0).eq(0..1000));
     }
 
-    pub fn send_batch(&mut self, events: Vec<E>) {
-        self.storage.events.extend(events);
+    #[test]
+    fn bounded_entity_events() {
+        let mut events = Events::<Hit>::bounded(2);
+        let first = Entity::root(0);
+        let second = Entity::root(1);
+
+        events.trigger(first, Hit(0));
+        events.trigger(second, Hit(1));
+        events.trigger(second, Hit(2));
+
+        assert_eq!(events.dropped(), 1);
+        assert_eq!(events.entity(first).count(), 0);
+        assert_eq!(events.entity(second).count(), 2);
+        assert_eq!(
+            events.reader().map(|hit| hit.0).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
     }
 }
@@ -1,12 +1,20 @@
-use crate::{SparseIndex, core::Frame, impl_sparse_index_wrapper, system::Removed};
+use crate::{
+    AddChild, AddChildren, BaseFilter, BaseQuery, Children, Command, Parent, Query, QueryIter,
+    QueryState, ReadQuery, RemoveChild, SparseIndex,
+    core::{ChangeTick, Frame},
+    impl_sparse_index_wrapper,
+    system::Removed,
+};
 use std::{
     any::TypeId,
     collections::HashMap,
     ops::{Index, IndexMut},
+    sync::atomic::{AtomicU32, Ordering},
 };
 
 pub mod access;
 pub mod archetype;
+pub mod borrow;
 pub mod cell;
 pub mod component;
 pub mod entity;
@@ -15,6 +23,7 @@ pub mod resource;
 
 pub use access::*;
 pub use archetype::*;
+pub use borrow::*;
 pub use cell::*;
 pub use component::*;
 pub use entity::*;
@@ -25,12 +34,8 @@ pub use resource::*;
 pub struct WorldId(pub u32);
 impl WorldId {
     fn new() -> Self {
-        static mut ID: u32 = 0;
-        unsafe {
-            let id = ID;
-            ID += 1;
-            WorldId(id)
-        }
+        static ID: AtomicU32 = AtomicU32::new(0);
+        WorldId(ID.fetch_add(1, Ordering::Relaxed))
     }
 }
 
@@ -42,6 +47,9 @@ pub struct World {
     pub(crate) events: EventRegistry,
     pub(crate) modes: WorldModeCategories,
     pub(crate) frame: Frame,
+    pub(crate) change_tick: ChangeTick,
+    refreshed: Frame,
+    pub(crate) borrows: BorrowTracker,
 }
 
 impl World {
@@ -54,6 +62,9 @@ impl World {
             events: EventRegistry::new(),
             modes: WorldModeCategories(HashMap::new()),
             frame: Frame(1),
+            change_tick: ChangeTick::new(Frame(1)),
+            refreshed: Frame(1),
+            borrows: BorrowTracker::new(),
         }
     }
 
@@ -85,6 +96,24 @@ impl World {
         self.frame
     }
 
+    /// The tick that changes are stamped with. Unlike [`World::frame`] it
+    /// advances every time a system runs, so a system that runs before a
+    /// writer in the same frame still sees the change on its next run.
+    pub fn change_tick(&self) -> Frame {
+        self.change_tick.get()
+    }
+
+    /// Advances the change tick and returns the new value.
+    pub fn increment_change_tick(&self) -> Frame {
+        self.change_tick.increment()
+    }
+
+    /// Moves the change tick forward to `tick` if it's behind, so worlds
+    /// that read each other's changes share one timeline.
+    pub fn sync_change_tick(&mut self, tick: Frame) {
+        self.change_tick.sync(tick);
+    }
+
     pub fn register<C: Component>(&mut self) -> ComponentId {
         let id = self.archetypes.register::<C>();
 
@@ -93,6 +122,21 @@ impl World {
         id
     }
 
+    /// Registers `C` so that [`World::clone_entity`] copies it.
+    pub fn register_cloneable<C: Component + Clone>(&mut self) -> ComponentId {
+        let id = self.register::<C>();
+        self.archetypes.components_mut().register_cloneable::<C>();
+        id
+    }
+
+    /// Registers `A` and `B` as components that can't be on the same entity.
+    /// Adding one to an entity that has the other panics.
+    pub fn register_exclusive<A: Component, B: Component>(&mut self) {
+        self.archetypes
+            .components_mut()
+            .register_exclusive::<A, B>();
+    }
+
     pub fn register_resource<R: Resource + Send>(&mut self) -> ResourceId {
         self.resources.register::<true, R>()
     }
@@ -109,6 +153,15 @@ impl World {
         self.events.register::<E>()
     }
 
+    /// Registers `E` and keeps at most `capacity` events of it per frame.
+    /// When full, the oldest events are dropped and counted by
+    /// [`EventReader::dropped`].
+    pub fn register_event_capacity<E: Event>(&mut self, capacity: usize) -> EventId {
+        let id = self.register_event::<E>();
+        self.resource_mut::<Events<E>>().set_capacity(Some(capacity));
+        id
+    }
+
     pub fn add_mode<M: WorldMode>(&mut self) -> ModeId {
         self.modes
             .entry(M::CATEGORY)
@@ -118,12 +171,12 @@ impl World {
 
     pub fn add_resource<R: Resource + Send>(&mut self, resource: R) -> ResourceId {
         self.resources
-            .add_with_frame::<true, R>(resource, self.frame)
+            .add_with_frame::<true, R>(resource, self.change_tick.get())
     }
 
     pub fn add_non_send_resource<R: Resource>(&mut self, resource: R) -> ResourceId {
         self.resources
-            .add_with_frame::<false, R>(resource, self.frame)
+            .add_with_frame::<false, R>(resource, self.change_tick.get())
     }
 
     pub fn resource<R: Resource + Send>(&self) -> &R {
@@ -139,7 +192,7 @@ impl World {
     pub fn resource_mut<R: Resource + Send>(&mut self) -> &mut R {
         self.resources
             .get_id::<R>()
-            .and_then(|id| self.resources.get_mut::<R>(id, self.frame))
+            .and_then(|id| self.resources.get_mut::<R>(id, self.change_tick.get()))
             .expect(&format!(
                 "Resource not found: {}",
                 std::any::type_name::<R>()
@@ -155,7 +208,7 @@ impl World {
     pub fn try_resource_mut<R: Resource + Send>(&mut self) -> Option<&mut R> {
         self.resources
             .get_id::<R>()
-            .and_then(|id| self.resources.get_mut::<R>(id, self.frame))
+            .and_then(|id| self.resources.get_mut::<R>(id, self.change_tick.get()))
     }
 
     pub fn non_send_resource<R: Resource>(&self) -> &R {
@@ -171,7 +224,7 @@ impl World {
     pub fn non_send_resource_mut<R: Resource>(&mut self) -> &mut R {
         self.resources
             .get_id::<R>()
-            .and_then(|id| self.resources.get_mut::<R>(id, self.frame))
+            .and_then(|id| self.resources.get_mut::<R>(id, self.change_tick.get()))
             .expect(&format!(
                 "Non Send Resource not found: {}",
                 std::any::type_name::<R>()
@@ -187,7 +240,7 @@ impl World {
     pub fn try_non_send_resource_mut<R: Resource>(&mut self) -> Option<&mut R> {
         self.resources
             .get_id::<R>()
-            .and_then(|id| self.resources.get_mut::<R>(id, self.frame))
+            .and_then(|id| self.resources.get_mut::<R>(id, self.change_tick.get()))
     }
 
     pub fn get_or_insert_resource<R: Resource + Send>(&mut self, get: impl Fn() -> R) -> &mut R {
@@ -198,7 +251,10 @@ impl World {
             } else {
                 let world = world.get_mut();
                 let id = world.add_resource(get());
-                world.resources.get_mut(id, self.frame).unwrap()
+                world
+                    .resources
+                    .get_mut(id, world.change_tick.get())
+                    .unwrap()
             }
         }
     }
@@ -211,17 +267,20 @@ impl World {
             } else {
                 let world = world.get_mut();
                 let id = world.add_non_send_resource(get());
-                world.resources.get_mut(id, self.frame).unwrap()
+                world
+                    .resources
+                    .get_mut(id, world.change_tick.get())
+                    .unwrap()
             }
         }
     }
 
     pub fn remove_resource<R: Resource>(&mut self) -> Option<R> {
-        self.resources.remove::<R>(self.frame)
+        self.resources.remove::<R>(self.change_tick.get())
     }
 
     pub fn remove_resource_by_id(&mut self, id: ResourceId) -> Option<Vec<u8>> {
-        self.resources.remove_by_id(id, self.frame)
+        self.resources.remove_by_id(id, self.change_tick.get())
     }
 
     pub unsafe fn cell(&'_ self) -> WorldCell<'_> {
@@ -236,9 +295,154 @@ impl World {
         entity
     }
 
+    /// Spawns `count` entities without components in one pass.
+    pub fn spawn_batch(&mut self, count: usize) -> Vec<Entity> {
+        let entities = self.entities.spawn_batch(count);
+        self.archetypes.add_entities(&entities);
+        entities
+    }
+
+    /// Spawns an entity for every kit in `kits`.
+    pub fn spawn_batch_with<C: ComponentKit>(
+        &mut self,
+        kits: impl IntoIterator<Item = C>,
+    ) -> Vec<Entity> {
+        let kits = kits.into_iter().collect::<Vec<_>>();
+        let entities = self.entities.spawn_batch(kits.len());
+        self.archetypes
+            .add_entities_with(entities.iter().copied().zip(kits), self.change_tick.get());
+
+        entities
+    }
+
     pub fn despawn(&mut self, entity: Entity) -> Option<Row> {
+        #[cfg(debug_assertions)]
+        self.check_archetype(entity);
+
+        let row = self.archetypes.remove_entity(entity)?;
         self.entities.despawn(entity);
-        self.archetypes.remove_entity(entity)
+        Some(row)
+    }
+
+    /// Despawns `entity` and all of its descendants, and removes it from its
+    /// parent's [`Children`]. Returns the despawned entities, parents before
+    /// their children.
+    pub fn despawn_recursive(&mut self, entity: Entity) -> Vec<Entity> {
+        if let Some(parent) = self.parent(entity) {
+            RemoveChild::new(parent, entity).execute(self);
+        }
+
+        let children = self.components().get_id::<Children>();
+
+        let mut stack = vec![entity];
+        let mut despawned = Vec::new();
+        while let Some(entity) = stack.pop() {
+            // Entities that were already despawned are skipped, so a cycle in
+            // the hierarchy can't loop forever.
+            let Some(row) = self.despawn(entity) else {
+                continue;
+            };
+
+            despawned.push(entity);
+
+            if let Some(children) = children.and_then(|id| row.get::<Children>(id)) {
+                stack.extend(children.as_slice().iter().rev());
+            }
+        }
+
+        despawned
+    }
+
+    /// Clones `entity` and all of its descendants. Only components registered
+    /// with [`World::register_cloneable`] are copied; the rest are listed in
+    /// [`ClonedEntity::skipped`]. The clone is added as a child of the same
+    /// parent as `entity`.
+    pub fn clone_entity(&mut self, entity: Entity) -> ClonedEntity {
+        let mut skipped = vec![];
+        let clone = self.clone_hierarchy(entity, &mut skipped);
+
+        if let Some(parent) = self.parent(entity) {
+            AddChild::new(parent, clone).execute(self);
+        }
+
+        let components = self.archetypes.components();
+        let hierarchy = [
+            components.get_id::<Parent>(),
+            components.get_id::<Children>(),
+        ];
+        skipped.retain(|id| !hierarchy.contains(&Some(*id)));
+        skipped.sort();
+        skipped.dedup();
+
+        ClonedEntity {
+            entity: clone,
+            skipped,
+        }
+    }
+
+    fn clone_hierarchy(&mut self, entity: Entity, skipped: &mut Vec<ComponentId>) -> Entity {
+        let row = self
+            .archetypes
+            .clone_row(entity, self.change_tick.get(), skipped)
+            .unwrap_or_else(Row::new);
+
+        let clone = self.entities.spawn();
+        self.archetypes.add_row(clone, row);
+
+        let children = self.children(entity).to_vec();
+
+        if !children.is_empty() {
+            let children = children
+                .into_iter()
+                .map(|child| self.clone_hierarchy(child, skipped))
+                .collect();
+
+            AddChildren::new(clone, children).execute(self);
+        }
+
+        clone
+    }
+
+    /// The parent of `entity`, if it has one.
+    pub fn parent(&self, entity: Entity) -> Option<Entity> {
+        match self.has_component::<Parent>(entity) {
+            true => self.get_component::<Parent>(entity).map(|p| p.get()),
+            false => None,
+        }
+    }
+
+    /// The children of `entity`. Empty if it has none.
+    pub fn children(&self, entity: Entity) -> &[Entity] {
+        match self.has_component::<Children>(entity) {
+            true => self
+                .get_component::<Children>(entity)
+                .map(|children| children.as_slice())
+                .unwrap_or_default(),
+            false => &[],
+        }
+    }
+
+    /// Creates the state for a query run outside of a system. The state can
+    /// be kept and passed to [`World::query_iter`] repeatedly; it picks up
+    /// archetypes created in between.
+    pub fn query<Q: BaseQuery, F: BaseFilter>(&mut self) -> QueryState<Q, F> {
+        QueryState::new(self, &mut ArchetypeAccess::new())
+    }
+
+    /// Iterates a read only query. Every component counts as changed, since
+    /// there's no system frame to compare against.
+    pub fn query_iter<'w, 's, Q: ReadQuery, F: BaseFilter>(
+        &'w self,
+        state: &'s mut QueryState<Q, F>,
+    ) -> QueryIter<'w, 's, Q, F> {
+        Query::new(unsafe { self.cell() }, state).into_iter()
+    }
+
+    pub fn query_iter_mut<'w, 's, Q: BaseQuery, F: BaseFilter>(
+        &'w mut self,
+        state: &'s mut QueryState<Q, F>,
+    ) -> QueryIter<'w, 's, Q, F> {
+        Query::new(unsafe { self.cell() }, state).into_iter()
     }
 
     pub fn has_component<C: Component>(&self, entity: Entity) -> bool {
@@ -261,11 +465,17 @@ impl World {
     }
 
     pub fn add_component<C: Component>(&mut self, entity: Entity, component: C) -> EntityIndex {
+        #[cfg(debug_assertions)]
+        self.check_archetype(entity);
+
         self.archetypes
-            .add_component(entity, component, self.frame)
+            .add_component(entity, component, self.change_tick.get())
     }
 
     pub fn remove_component<C: Component>(&mut self, entity: Entity) -> Option<EntityIndex> {
+        #[cfg(debug_assertions)]
+        self.check_archetype(entity);
+
         let (index, component) = self.archetypes.remove_component::<C>(entity)?;
         let events = self.get_or_insert_resource(|| Events::<Removed<C>>::new());
         events
@@ -276,8 +486,11 @@ impl World {
     }
 
     pub fn add_components<C: ComponentKit>(&mut self, entity: Entity, components: C) {
+        #[cfg(debug_assertions)]
+        self.check_archetype(entity);
+
         self.archetypes
-            .add_components(entity, components, self.frame);
+            .add_components(entity, components, self.change_tick.get());
     }
 
     pub fn remove_components<C: ComponentKit>(&mut self, entity: Entity) -> Option<EntityIndex> {
@@ -301,6 +514,9 @@ impl World {
             }
         }
 
+        #[cfg(debug_assertions)]
+        self.check_archetype(entity);
+
         let (index, components) = self.archetypes.remove_components::<C>(entity)?;
 
         let mut remover = Remover {
@@ -314,6 +530,15 @@ impl World {
         Some(index)
     }
 
+    /// Panics if a query is iterating `entity`'s archetype, since moving the
+    /// entity out of it would invalidate the iterator.
+    #[cfg(debug_assertions)]
+    fn check_archetype(&self, entity: Entity) {
+        if let Some(archetype) = self.archetypes.entity_archetype(entity) {
+            drop(self.borrows.borrow_mut(archetype.id().into()));
+        }
+    }
+
     pub fn send<E: Event>(&mut self, event: E) {
         let events = self.resource_mut::<Events<E>>();
         events.writer().send(event);
@@ -340,11 +565,11 @@ impl World {
         let id = if modes.current != Some(current) {
             if let Some(prev) = modes.current {
                 modes.get(prev).exit(self);
-                modes[prev].set_frame(self.frame);
+                modes[prev].set_frame(self.change_tick.get());
             }
 
             modes.get(current).enter(self);
-            modes[current].set_frame(self.frame);
+            modes[current].set_frame(self.change_tick.get());
             modes.current.replace(current)
         } else {
             None
@@ -362,7 +587,7 @@ impl World {
         let exited = if let Some(prev) = modes.current {
             modes.get(prev).exit(self);
             modes.current = None;
-            modes[prev].set_frame(self.frame);
+            modes[prev].set_frame(self.change_tick.get());
             true
         } else {
             false
@@ -376,10 +601,12 @@ impl World {
         self.frame += 1;
         self.events.update(unsafe { self.cell() });
 
-        if self.frame.get() % Frame::AGE_REFRESH_RATE == 0 {
-            self.archetypes.update(self.frame);
-            self.resources.update(self.frame);
-            self.modes.update(self.frame);
+        let tick = self.change_tick.get();
+        if tick.get().wrapping_sub(self.refreshed.get()) >= Frame::AGE_REFRESH_RATE {
+            self.refreshed = tick;
+            self.archetypes.update(tick);
+            self.resources.update(tick);
+            self.modes.update(tick);
         }
     }
 }
@@ -390,6 +617,16 @@ impl Default for World {
     }
 }
 
+/// The result of [`World::clone_entity`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClonedEntity {
+    /// The root of the cloned hierarchy.
+    pub entity: Entity,
+    /// Components found in the hierarchy that were not copied because they
+    /// weren't registered with [`World::register_cloneable`].
+    pub skipped: Vec<ComponentId>,
+}
+
 pub struct EntityMut<'w> {
     pub(crate) world: &'w mut World,
     entity: Entity,
@@ -567,3 +804,114 @@ impl IndexMut<ModeId> for WorldModes {
         &mut self.modes[index.to_usize()]
     }
 }
+
+#[allow(unused_imports, dead_code)]
+mod tests {
+    use super::{Component, Entity, World, WorldId};
+    use crate::{
+        ComponentKit,
+        query::{With, Without},
+    };
+    use std::collections::HashSet;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    struct Age(u32);
+    impl Component for Age {}
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    struct Height(u32);
+    impl Component for Height {}
+
+    #[derive(ComponentKit)]
+    struct Person {
+        age: Age,
+        height: Height,
+    }
+
+    #[test]
+    fn world_query() {
+        let mut world = World::new();
+        let people = world.spawn_batch_with((0..3).map(|index| Person {
+            age: Age(index),
+            height: Height(index * 10),
+        }));
+        let loner = world.spawn();
+        world.add_component(loner, Age(99));
+
+        let mut state = world.query::<(&Age, &Height), ()>();
+        let mut pairs = world
+            .query_iter(&mut state)
+            .map(|(age, height)| (age.0, height.0))
+            .collect::<Vec<_>>();
+        pairs.sort();
+        assert_eq!(pairs, vec![(0, 0), (1, 10), (2, 20)]);
+
+        let mut state = world.query::<Entity, Without<Height>>();
+        let entities = world.query_iter(&mut state).collect::<Vec<_>>();
+        assert_eq!(entities, vec![loner]);
+
+        let mut state = world.query::<&mut Age, With<Height>>();
+        for age in world.query_iter_mut(&mut state) {
+            age.0 += 1;
+        }
+        assert_eq!(world.get_component::<Age>(people[0]), Some(&Age(1)));
+        assert_eq!(world.get_component::<Age>(loner), Some(&Age(99)));
+    }
+
+    #[test]
+    fn spawn_batch() {
+        let mut world = World::new();
+        let entities = world.spawn_batch(100_000);
+
+        let unique = entities.iter().copied().collect::<HashSet<_>>();
+        assert_eq!(unique.len(), entities.len());
+        for entity in &entities {
+            assert!(world.archetypes().entity_archetype(*entity).is_some());
+        }
+    }
+
+    #[test]
+    fn spawn_batch_with() {
+        let mut world = World::new();
+        world.register::<Age>();
+        world.register::<Height>();
+
+        let people = (0..10).map(|index| Person {
+            age: Age(index),
+            height: Height(index * 10),
+        });
+        let entities = world.spawn_batch_with(people);
+
+        assert_eq!(entities.len(), 10);
+        for (index, entity) in entities.iter().enumerate() {
+            let index = index as u32;
+            assert_eq!(world.get_component::<Age>(*entity), Some(&Age(index)));
+            assert_eq!(
+                world.get_component::<Height>(*entity),
+                Some(&Height(index * 10))
+            );
+        }
+
+        let archetype = world.archetypes().entity_archetype(entities[0]).unwrap();
+        assert_eq!(archetype.table().entities().len(), 10);
+        assert!(entities.iter().all(|entity| archetype.contains(*entity)));
+    }
+
+    #[test]
+    fn unique_world_ids() {
+        let threads = (0..16)
+            .map(|_| {
+                std::thread::spawn(|| (0..64).map(|_| World::new().id()).collect::<Vec<WorldId>>())
+            })
+            .collect::<Vec<_>>();
+
+        let ids = threads
+            .into_iter()
+            .flat_map(|thread| thread.join().unwrap())
+            .map(|id| id.0)
+            .collect::<Vec<_>>();
+
+        let unique = ids.iter().copied().collect::<HashSet<_>>();
+        assert_eq!(unique.len(), ids.len());
+    }
+}
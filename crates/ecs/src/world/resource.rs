@@ -202,6 +202,30 @@ impl Resources {
         Some(unsafe { &mut *(data.as_mut_ptr() as *mut R) })
     }
 
+    /// Gets the resource along with its status without marking it as
+    /// modified.
+    pub fn get_with_status<R: Resource>(&self, id: ResourceId) -> Option<(&R, &ObjectStatus)> {
+        let meta = self.meta.get(id.to_usize())?;
+        let resource = self.get::<R>(id)?;
+        Some((resource, &meta.status))
+    }
+
+    /// Gets the resource along with its status without marking it as
+    /// modified. See [`ResMut`].
+    pub fn get_mut_with_status<R: Resource>(
+        &mut self,
+        id: ResourceId,
+    ) -> Option<(&mut R, &mut ObjectStatus)> {
+        let meta = self.meta.get_mut(id.to_usize())?;
+        if !meta.exists || !meta.has_access() {
+            return None;
+        }
+
+        let data = &mut self.data[meta.offset..meta.offset + meta.size];
+        let resource = unsafe { &mut *(data.as_mut_ptr() as *mut R) };
+        Some((resource, &mut meta.status))
+    }
+
     pub fn get_meta(&self, id: ResourceId) -> Option<&ResourceMeta> {
         self.meta.get(id.to_usize())
     }
@@ -318,6 +342,118 @@ impl<'a, R: Resource> AsMut<R> for NonSendMut<'a, R> {
     }
 }
 
+/// A resource that can tell whether it was added or changed since the system
+/// last ran.
+pub struct Res<'a, R: Resource> {
+    resource: &'a R,
+    status: ObjectStatus,
+    current: Frame,
+    last: Frame,
+}
+
+impl<'a, R: Resource> Res<'a, R> {
+    pub fn new(resource: &'a R, status: ObjectStatus, current: Frame, last: Frame) -> Self {
+        Self {
+            resource,
+            status,
+            current,
+            last,
+        }
+    }
+
+    pub fn is_added(&self) -> bool {
+        self.status.added.is_newer(self.current, self.last)
+    }
+
+    /// True if the resource was added or mutably accessed since the system
+    /// last ran.
+    pub fn is_changed(&self) -> bool {
+        self.is_added() || self.status.modified.is_newer(self.current, self.last)
+    }
+
+    pub fn into_inner(self) -> &'a R {
+        self.resource
+    }
+}
+
+impl<'a, R: Resource> std::ops::Deref for Res<'a, R> {
+    type Target = R;
+
+    fn deref(&self) -> &Self::Target {
+        self.resource
+    }
+}
+
+impl<'a, R: Resource> AsRef<R> for Res<'a, R> {
+    fn as_ref(&self) -> &R {
+        self.resource
+    }
+}
+
+/// A mutable resource that is only marked as modified when it's mutably
+/// dereferenced, and can tell whether it was added or changed since the
+/// system last ran.
+pub struct ResMut<'a, R: Resource> {
+    resource: &'a mut R,
+    status: &'a mut ObjectStatus,
+    current: Frame,
+    last: Frame,
+}
+
+impl<'a, R: Resource> ResMut<'a, R> {
+    pub fn new(
+        resource: &'a mut R,
+        status: &'a mut ObjectStatus,
+        current: Frame,
+        last: Frame,
+    ) -> Self {
+        Self {
+            resource,
+            status,
+            current,
+            last,
+        }
+    }
+
+    pub fn is_added(&self) -> bool {
+        self.status.added.is_newer(self.current, self.last)
+    }
+
+    /// True if the resource was added or mutably accessed since the system
+    /// last ran, including by this system.
+    pub fn is_changed(&self) -> bool {
+        self.is_added() || self.status.modified.is_newer(self.current, self.last)
+    }
+}
+
+impl<'a, R: Resource> std::ops::Deref for ResMut<'a, R> {
+    type Target = R;
+
+    fn deref(&self) -> &Self::Target {
+        self.resource
+    }
+}
+
+impl<'a, R: Resource> std::ops::DerefMut for ResMut<'a, R> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.status.modified = self.current;
+        self.resource
+    }
+}
+
+impl<'a, R: Resource> AsRef<R> for ResMut<'a, R> {
+    fn as_ref(&self) -> &R {
+        self.resource
+    }
+}
+
+impl<'a, R: Resource> AsMut<R> for ResMut<'a, R> {
+    fn as_mut(&mut self) -> &mut R {
+        self.status.modified = self.current;
+        self.resource
+    }
+}
+
 pub struct Cloned<R: Resource + Clone>(R);
 impl<R: Resource + Clone> Cloned<R> {
     pub fn new(resource: R) -> Self {
@@ -1,8 +1,11 @@
 use ecs::{End, EventWriter, Plugin, Resource};
 use input::{GamepadAxis, GamepadEvent, GamepadId, GamepadInputEvent, plugin::GamepadPlugin};
 
+pub mod rumble;
 pub mod translate;
 
+pub use rumble::GilrsRumbles;
+
 #[derive(Resource)]
 pub struct Gilrs(pub(crate) gilrs::Gilrs);
 
@@ -13,6 +16,7 @@ impl Gilrs {
 
     pub fn process_events(gilrs: &mut Gilrs, mut events: EventWriter<GamepadEvent>) {
         while let Some(gilrs::Event { id, event, .. }) = gilrs.0.next_event() {
+            let gilrs_id = id;
             let id = GamepadId::from(usize::from(id));
             match event {
                 gilrs::EventType::ButtonPressed(button, _) => events.send(GamepadEvent::Input {
@@ -43,12 +47,20 @@ impl Gilrs {
                         value,
                     },
                 }),
-                gilrs::EventType::Connected => events.send(GamepadEvent::Connected {
-                    id,
-                    name: String::new(),
-                    vendor_id: None,
-                    product_id: None,
-                }),
+                gilrs::EventType::Connected => {
+                    // The gamepad may already be gone by the time the event is read.
+                    let event = match gilrs.0.connected_gamepad(gilrs_id) {
+                        Some(gamepad) => translate::gamepad_connected(
+                            id,
+                            gamepad.name(),
+                            gamepad.vendor_id(),
+                            gamepad.product_id(),
+                        ),
+                        None => translate::gamepad_connected(id, "", None, None),
+                    };
+
+                    events.send(event)
+                }
                 gilrs::EventType::Disconnected => events.send(GamepadEvent::Disconnected(id)),
                 _ => continue,
             }
@@ -64,16 +76,50 @@ impl std::ops::Deref for Gilrs {
     }
 }
 
+/// Added in place of [`Gilrs`] when the gamepad backend could not be created,
+/// so systems can tell that no gamepad input will arrive.
+#[derive(Debug, Resource)]
+pub struct GilrsUnavailable(pub String);
+
 pub struct GilrsPlugin;
 
-impl Plugin for GilrsPlugin {
-    fn setup(&mut self, app: &mut ecs::AppBuilder) {
-        match Gilrs::new() {
+impl GilrsPlugin {
+    fn add_backend(app: &mut ecs::AppBuilder, backend: Result<Gilrs, gilrs::Error>) {
+        app.add_plugins(GamepadPlugin);
+
+        match backend {
             Ok(gilrs) => app
-                .add_plugins(GamepadPlugin)
                 .add_resource(gilrs)
-                .add_systems(End, Gilrs::process_events),
-            Err(error) => unreachable!("{error}"),
+                .add_resource(GilrsRumbles::new())
+                .add_systems(End, Gilrs::process_events)
+                .add_systems(End, GilrsRumbles::process_requests),
+            Err(error) => {
+                log::warn!("Gamepad input is unavailable: {error}");
+                app.add_resource(GilrsUnavailable(error.to_string()))
+            }
         };
     }
 }
+
+impl Plugin for GilrsPlugin {
+    fn setup(&mut self, app: &mut ecs::AppBuilder) {
+        Self::add_backend(app, Gilrs::new());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GilrsPlugin, GilrsUnavailable};
+    use ecs::App;
+    use input::Gamepads;
+
+    #[test]
+    fn missing_backend_still_builds() {
+        let mut app = App::new();
+        GilrsPlugin::add_backend(&mut app, Err(gilrs::Error::InvalidAxisToBtn));
+
+        let apps = app.build();
+        assert!(apps.world().try_resource::<GilrsUnavailable>().is_some());
+        assert!(apps.world().try_resource::<Gamepads>().is_some());
+    }
+}
@@ -0,0 +1,84 @@
+use crate::Gilrs;
+use ecs::{EventReader, EventWriter, Resource};
+use gilrs::ff::{BaseEffect, BaseEffectType, Effect, EffectBuilder, Replay, Ticks};
+use input::{GamepadRumbleFailed, GamepadRumbleRequest};
+use std::time::Instant;
+
+/// The rumble effects that are still playing. gilrs stops an effect as soon
+/// as it is dropped, so each one is kept alive until its duration has passed.
+#[derive(Default, Resource)]
+pub struct GilrsRumbles(Vec<(Effect, Instant)>);
+
+impl GilrsRumbles {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn process_requests(
+        gilrs: &mut Gilrs,
+        rumbles: &mut GilrsRumbles,
+        requests: EventReader<GamepadRumbleRequest>,
+        mut failed: EventWriter<GamepadRumbleFailed>,
+    ) {
+        let now = Instant::now();
+        rumbles.0.retain(|(_, end)| *end > now);
+
+        for request in requests {
+            match Self::play(gilrs, request) {
+                Ok(effect) => rumbles.0.push((effect, now + request.duration)),
+                Err(reason) => failed.send(GamepadRumbleFailed {
+                    id: request.id,
+                    reason,
+                }),
+            }
+        }
+    }
+
+    fn play(gilrs: &mut Gilrs, request: &GamepadRumbleRequest) -> Result<Effect, String> {
+        let id = usize::from(request.id);
+        let Some((gamepad, supported)) = gilrs
+            .0
+            .gamepads()
+            .find(|(gamepad, _)| usize::from(*gamepad) == id)
+            .map(|(gamepad, state)| (gamepad, state.is_ff_supported()))
+        else {
+            return Err(String::from("Gamepad is not connected"));
+        };
+
+        if !supported {
+            return Err(String::from("Gamepad does not support force feedback"));
+        }
+
+        let scheduling = Replay {
+            play_for: Ticks::from_ms(request.duration.as_millis() as u32),
+            ..Default::default()
+        };
+
+        let effect = EffectBuilder::new()
+            .add_effect(BaseEffect {
+                kind: BaseEffectType::Strong {
+                    magnitude: magnitude(request.strong),
+                },
+                scheduling,
+                ..Default::default()
+            })
+            .add_effect(BaseEffect {
+                kind: BaseEffectType::Weak {
+                    magnitude: magnitude(request.weak),
+                },
+                scheduling,
+                ..Default::default()
+            })
+            .gamepads(&[gamepad])
+            .finish(&mut gilrs.0)
+            .map_err(|error| error.to_string())?;
+
+        effect.play().map_err(|error| error.to_string())?;
+
+        Ok(effect)
+    }
+}
+
+fn magnitude(value: f32) -> u16 {
+    (value.clamp(0.0, 1.0) * u16::MAX as f32) as u16
+}
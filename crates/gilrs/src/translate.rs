@@ -1,4 +1,4 @@
-use input::{GamepadAxis, GamepadButton};
+use input::{GamepadAxis, GamepadButton, GamepadEvent, GamepadId};
 
 pub fn gamepad_button(button: gilrs::Button) -> GamepadButton {
     match button {
@@ -38,3 +38,47 @@ pub fn gamepad_axis(axis: gilrs::Axis) -> GamepadAxis {
         gilrs::Axis::Unknown => GamepadAxis::Unknown,
     }
 }
+
+pub fn gamepad_connected(
+    id: GamepadId,
+    name: &str,
+    vendor_id: Option<u16>,
+    product_id: Option<u16>,
+) -> GamepadEvent {
+    GamepadEvent::Connected {
+        id,
+        name: name.to_string(),
+        vendor_id,
+        product_id,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::gamepad_connected;
+    use input::{GamepadEvent, GamepadId};
+
+    #[test]
+    fn connected_forwards_identification() {
+        let event = gamepad_connected(
+            GamepadId::from(0),
+            "Xbox Wireless Controller",
+            Some(0x045e),
+            Some(0x0b13),
+        );
+
+        let GamepadEvent::Connected {
+            name,
+            vendor_id,
+            product_id,
+            ..
+        } = event
+        else {
+            panic!("Expected a connected event");
+        };
+
+        assert_eq!(name, "Xbox Wireless Controller");
+        assert_eq!(vendor_id, Some(0x045e));
+        assert_eq!(product_id, Some(0x0b13));
+    }
+}
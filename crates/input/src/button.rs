@@ -1,8 +1,5 @@
 use ecs::Resource;
-use std::{
-    collections::{HashMap, HashSet},
-    hash::Hash,
-};
+use std::{collections::HashSet, hash::Hash};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ButtonState {
@@ -12,101 +9,267 @@ pub enum ButtonState {
     Released,
 }
 
+/// The state of a set of buttons.
+///
+/// `pressed` holds for as long as a button is down. `just_pressed` and
+/// `just_released` hold for exactly one frame: from the frame's input events
+/// being processed in `Start` until [`ButtonInput::end_frame`] runs in `End`.
+/// A button pressed and released within the same frame is both just pressed
+/// and just released for that frame, but not pressed.
 #[derive(Resource)]
-pub struct Buttons<T: Hash + Clone + Eq + 'static> {
-    /// The states of the buttons.
-    states: HashMap<T, ButtonState>,
+pub struct ButtonInput<T: Hash + Clone + Eq + 'static> {
     /// The buttons that are currently being held down.
-    down: HashSet<T>,
+    pressed: HashSet<T>,
+    /// The buttons that were pressed this frame.
+    just_pressed: HashSet<T>,
+    /// The buttons that were released this frame.
+    just_released: HashSet<T>,
 }
 
-impl<T: Hash + Clone + Eq> Default for Buttons<T> {
+impl<T: Hash + Clone + Eq> Default for ButtonInput<T> {
     fn default() -> Self {
         Self {
-            states: HashMap::new(),
-            down: HashSet::new(),
+            pressed: HashSet::new(),
+            just_pressed: HashSet::new(),
+            just_released: HashSet::new(),
         }
     }
 }
 
-impl<T: Hash + Clone + Eq + std::fmt::Debug> std::fmt::Debug for Buttons<T> {
+impl<T: Hash + Clone + Eq + std::fmt::Debug> std::fmt::Debug for ButtonInput<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("Buttons")
-            .field("states", &self.states)
-            .field("down", &self.down)
+        f.debug_struct("ButtonInput")
+            .field("pressed", &self.pressed)
+            .field("just_pressed", &self.just_pressed)
+            .field("just_released", &self.just_released)
             .finish()
     }
 }
 
-impl<T: Hash + Clone + Eq> Buttons<T> {
+impl<T: Hash + Clone + Eq> ButtonInput<T> {
     pub fn new() -> Self {
-        Self {
-            states: HashMap::new(),
-            down: HashSet::new(),
-        }
+        Self::default()
     }
 
     pub fn set(&mut self, key: T, state: ButtonState) {
-        if state == ButtonState::Pressed {
-            self.down.insert(key.clone());
-            self.states.insert(key, state);
-        } else if let Some(ButtonState::Pressed) = self.states.get(&key) {
-            self.down.remove(&key);
-            self.states.insert(key, state);
+        match state {
+            ButtonState::Pressed => self.press(key),
+            ButtonState::Released => self.release(key),
+        }
+    }
+
+    /// Presses `key`. Repeated presses of a held button are ignored.
+    pub fn press(&mut self, key: T) {
+        if self.pressed.insert(key.clone()) {
+            self.just_pressed.insert(key);
+        }
+    }
+
+    /// Releases `key` if it is held.
+    pub fn release(&mut self, key: T) {
+        if self.pressed.remove(&key) {
+            self.just_released.insert(key);
         }
     }
 
     pub fn release_all(&mut self) {
-        self.states.iter_mut().for_each(|(key, state)| {
-            if *state == ButtonState::Pressed {
-                *state = ButtonState::Released;
-                self.down.remove(key);
-            }
-        });
+        self.just_released.extend(self.pressed.drain());
     }
 
+    /// The state of `key` this frame. Held buttons are [`ButtonState::Pressed`]
+    /// and buttons released this frame are [`ButtonState::Released`].
     pub fn get(&self, key: &T) -> Option<ButtonState> {
-        self.states.get(key).cloned()
+        if self.pressed.contains(key) {
+            Some(ButtonState::Pressed)
+        } else if self.just_released.contains(key) {
+            Some(ButtonState::Released)
+        } else {
+            None
+        }
+    }
+
+    pub fn pressed(&self, key: &T) -> bool {
+        self.pressed.contains(key)
+    }
+
+    pub fn just_pressed(&self, key: &T) -> bool {
+        self.just_pressed.contains(key)
+    }
+
+    pub fn just_released(&self, key: &T) -> bool {
+        self.just_released.contains(key)
+    }
+
+    pub fn any_pressed(&self, keys: impl IntoIterator<Item = T>) -> bool {
+        keys.into_iter().any(|key| self.pressed(&key))
     }
 
     pub fn get_pressed(&self) -> impl Iterator<Item = &T> {
-        self.states
-            .iter()
-            .filter_map(|(key, state)| (*state == ButtonState::Pressed).then_some(key))
+        self.pressed.iter()
     }
 
-    pub fn get_released(&self) -> impl Iterator<Item = &T> {
-        self.states
-            .iter()
-            .filter_map(|(key, state)| (*state == ButtonState::Released).then_some(key))
+    pub fn get_just_pressed(&self) -> impl Iterator<Item = &T> {
+        self.just_pressed.iter()
     }
 
-    pub fn get_down(&self) -> impl Iterator<Item = &T> {
-        self.down.iter()
+    pub fn get_just_released(&self) -> impl Iterator<Item = &T> {
+        self.just_released.iter()
     }
 
-    pub fn pressed(&self, key: &T) -> bool {
-        matches!(self.get(key), Some(ButtonState::Pressed))
+    /// Ends the frame by forgetting which buttons were just pressed or
+    /// released. Held buttons stay pressed.
+    pub fn end_frame(&mut self) {
+        self.just_pressed.clear();
+        self.just_released.clear();
     }
 
-    pub fn released(&self, key: &T) -> bool {
-        matches!(self.get(key), Some(ButtonState::Released))
+    /// Forgets the state of every button, including held ones.
+    pub fn clear(&mut self) {
+        self.pressed.clear();
+        self.end_frame();
+    }
+
+    #[deprecated(note = "use `get_just_released` instead")]
+    pub fn get_released(&self) -> impl Iterator<Item = &T> {
+        self.get_just_released()
     }
 
+    #[deprecated(note = "use `get_pressed` instead")]
+    pub fn get_down(&self) -> impl Iterator<Item = &T> {
+        self.get_pressed()
+    }
+
+    #[deprecated(note = "use `pressed` instead")]
     pub fn down(&self, key: &T) -> bool {
-        self.down.contains(key)
+        self.pressed(key)
     }
 
+    #[deprecated(note = "use `just_released` instead")]
+    pub fn released(&self, key: &T) -> bool {
+        self.just_released(key)
+    }
+
+    /// Held buttons paired with [`ButtonState::Pressed`] and buttons
+    /// released this frame paired with [`ButtonState::Released`].
+    #[deprecated(note = "use `get_pressed` and `get_just_released` instead")]
     pub fn iter(&self) -> impl Iterator<Item = (&T, &ButtonState)> {
-        self.states.iter()
+        let pressed = self.pressed.iter().map(|key| (key, &ButtonState::Pressed));
+        let released = self
+            .just_released
+            .iter()
+            .map(|key| (key, &ButtonState::Released));
+
+        pressed.chain(released)
     }
 
+    #[deprecated(note = "use `get_pressed` and `get_just_released` instead")]
     pub fn buttons(&self) -> impl Iterator<Item = &T> {
-        self.states.keys()
+        self.pressed.iter().chain(&self.just_released)
     }
 
-    pub fn clear(&mut self) {
-        self.states.clear();
-        self.down.clear();
+    pub(crate) fn tick(input: &mut Self) {
+        input.end_frame();
+    }
+}
+
+#[deprecated(note = "renamed to `ButtonInput`")]
+pub type Buttons<T> = ButtonInput<T>;
+
+#[cfg(test)]
+mod tests {
+    use super::{ButtonInput, ButtonState};
+
+    #[test]
+    fn press() {
+        let mut input = ButtonInput::<u32>::new();
+        input.set(0, ButtonState::Pressed);
+
+        assert!(input.pressed(&0));
+        assert!(input.just_pressed(&0));
+        assert!(!input.just_released(&0));
+        assert_eq!(input.get(&0), Some(ButtonState::Pressed));
+    }
+
+    #[test]
+    fn hold() {
+        let mut input = ButtonInput::<u32>::new();
+        input.set(0, ButtonState::Pressed);
+        input.end_frame();
+
+        input.set(0, ButtonState::Pressed);
+        assert!(input.pressed(&0));
+        assert!(!input.just_pressed(&0));
+
+        input.end_frame();
+        assert!(input.pressed(&0));
+        assert!(!input.just_pressed(&0));
+    }
+
+    #[test]
+    fn release() {
+        let mut input = ButtonInput::<u32>::new();
+        input.set(0, ButtonState::Pressed);
+        input.end_frame();
+
+        input.set(0, ButtonState::Released);
+        assert!(!input.pressed(&0));
+        assert!(input.just_released(&0));
+        assert_eq!(input.get(&0), Some(ButtonState::Released));
+
+        input.end_frame();
+        assert!(!input.just_released(&0));
+        assert_eq!(input.get(&0), None);
+    }
+
+    #[test]
+    fn press_and_release_in_one_frame() {
+        let mut input = ButtonInput::<u32>::new();
+        input.set(0, ButtonState::Pressed);
+        input.set(0, ButtonState::Released);
+
+        assert!(!input.pressed(&0));
+        assert!(input.just_pressed(&0));
+        assert!(input.just_released(&0));
+
+        input.end_frame();
+        assert!(!input.just_pressed(&0));
+        assert!(!input.just_released(&0));
+    }
+
+    #[test]
+    fn clear_forgets_held_buttons() {
+        let mut input = ButtonInput::<u32>::new();
+        input.set(0, ButtonState::Pressed);
+        input.end_frame();
+
+        input.clear();
+        assert!(!input.pressed(&0));
+        assert_eq!(input.get(&0), None);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn deprecated_accessors() {
+        let mut input = super::Buttons::<u32>::new();
+        input.set(0, ButtonState::Pressed);
+        input.set(1, ButtonState::Pressed);
+        input.end_frame();
+        input.set(1, ButtonState::Released);
+
+        assert!(input.down(&0));
+        assert!(input.released(&1));
+        assert_eq!(input.get_down().collect::<Vec<_>>(), vec![&0]);
+        assert_eq!(input.get_released().collect::<Vec<_>>(), vec![&1]);
+
+        let mut states = input.iter().collect::<Vec<_>>();
+        states.sort_by_key(|(key, _)| **key);
+        assert_eq!(
+            states,
+            vec![(&0, &ButtonState::Pressed), (&1, &ButtonState::Released)]
+        );
+
+        let mut buttons = input.buttons().copied().collect::<Vec<_>>();
+        buttons.sort();
+        assert_eq!(buttons, vec![0, 1]);
     }
 }
@@ -1,7 +1,7 @@
-use crate::{ButtonState, analog::Analogs, button::Buttons};
+use crate::{ButtonState, analog::Analogs, button::ButtonInput};
 use ecs::{Event, EventReader, Resource};
 use math::Vec2;
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct GamepadId(pub(crate) usize);
@@ -11,6 +11,12 @@ impl From<usize> for GamepadId {
     }
 }
 
+impl From<GamepadId> for usize {
+    fn from(value: GamepadId) -> Self {
+        value.0
+    }
+}
+
 #[derive(Debug)]
 pub struct Gamepad {
     /// The status of the gamepad.
@@ -20,7 +26,7 @@ pub struct Gamepad {
     /// The USB product id assigned by the vendor.
     pub(crate) product_id: Option<u16>,
     /// The state of the gamepad buttons.
-    pub(crate) buttons: Buttons<GamepadButton>,
+    pub(crate) buttons: ButtonInput<GamepadButton>,
     /// The state of the gamepad axes.
     pub(crate) analogs: Analogs<GamepadInput>,
 }
@@ -38,11 +44,11 @@ impl Gamepad {
         self.product_id
     }
 
-    pub fn buttons(&self) -> &Buttons<GamepadButton> {
+    pub fn buttons(&self) -> &ButtonInput<GamepadButton> {
         &self.buttons
     }
 
-    pub fn buttons_mut(&mut self) -> &mut Buttons<GamepadButton> {
+    pub fn buttons_mut(&mut self) -> &mut ButtonInput<GamepadButton> {
         &mut self.buttons
     }
 
@@ -59,15 +65,15 @@ impl Gamepad {
     }
 
     pub fn pressed(&self, button: GamepadButton) -> bool {
-        self.buttons.get(&button) == Some(ButtonState::Pressed)
+        self.buttons.pressed(&button)
     }
 
-    pub fn released(&self, button: GamepadButton) -> bool {
-        self.buttons.get(&button) == Some(ButtonState::Released)
+    pub fn just_pressed(&self, button: GamepadButton) -> bool {
+        self.buttons.just_pressed(&button)
     }
 
-    pub fn down(&self, button: GamepadButton) -> bool {
-        self.buttons.down(&button)
+    pub fn just_released(&self, button: GamepadButton) -> bool {
+        self.buttons.just_released(&button)
     }
 
     pub fn analog(&self, input: impl Into<GamepadInput>) -> Option<f32> {
@@ -134,6 +140,37 @@ pub enum GamepadEvent {
     },
 }
 
+/// Asks the gamepad backend to rumble a gamepad for `duration`.
+///
+/// Magnitudes range from zero to one. The strong motor is the low frequency
+/// one and the weak motor is the high frequency one.
+#[derive(Debug, Clone, Copy, Event)]
+pub struct GamepadRumbleRequest {
+    pub id: GamepadId,
+    pub strong: f32,
+    pub weak: f32,
+    pub duration: Duration,
+}
+
+impl GamepadRumbleRequest {
+    pub fn new(id: GamepadId, strong: f32, weak: f32, duration: Duration) -> Self {
+        Self {
+            id,
+            strong,
+            weak,
+            duration,
+        }
+    }
+}
+
+/// Sent by the gamepad backend when a [`GamepadRumbleRequest`] could not be
+/// played, such as when the gamepad has no force feedback support.
+#[derive(Debug, Clone, Event)]
+pub struct GamepadRumbleFailed {
+    pub id: GamepadId,
+    pub reason: String,
+}
+
 #[derive(Resource, Debug)]
 pub struct Gamepads {
     gamepads: HashMap<GamepadId, Gamepad>,
@@ -180,6 +217,12 @@ impl Gamepads {
             .filter(|(_, gamepad)| gamepad.status == GamepadStatus::Disconnected)
     }
 
+    pub(crate) fn tick(gamepads: &mut Gamepads) {
+        for gamepad in gamepads.gamepads.values_mut() {
+            gamepad.buttons.end_frame();
+        }
+    }
+
     pub(crate) fn process_events(gamepads: &mut Gamepads, events: EventReader<GamepadEvent>) {
         for event in events {
             match event {
@@ -193,7 +236,7 @@ impl Gamepads {
                         status: GamepadStatus::Connected,
                         vendor_id: *vendor_id,
                         product_id: *product_id,
-                        buttons: Buttons::new(),
+                        buttons: ButtonInput::new(),
                         analogs: Analogs::new(),
                     };
 
@@ -0,0 +1,388 @@
+use crate::{GamepadAxis, GamepadButton, GamepadEvent, GamepadId, GamepadInputEvent};
+use ecs::{EventReader, Resource, app::time::Time};
+use math::Vec2;
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Duration,
+};
+
+/// A gamepad input and the time it was received, relative to app startup.
+#[derive(Debug, Clone, Copy)]
+pub struct InputRecord {
+    pub input: GamepadInputEvent,
+    pub time: Duration,
+}
+
+/// Recent gamepad inputs for each gamepad. Inputs older than the window
+/// are pruned every update.
+#[derive(Resource, Debug)]
+pub struct InputHistory {
+    window: Duration,
+    records: HashMap<GamepadId, VecDeque<InputRecord>>,
+}
+
+impl InputHistory {
+    pub const DEFAULT_WINDOW: Duration = Duration::from_secs(1);
+
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            records: HashMap::new(),
+        }
+    }
+
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    pub fn set_window(&mut self, window: Duration) {
+        self.window = window;
+    }
+
+    pub fn get(&self, id: &GamepadId) -> impl Iterator<Item = &InputRecord> {
+        self.records.get(id).into_iter().flatten()
+    }
+
+    pub fn record(&mut self, id: GamepadId, input: GamepadInputEvent, time: Duration) {
+        self.records
+            .entry(id)
+            .or_default()
+            .push_back(InputRecord { input, time });
+    }
+
+    /// Removes every input received more than [`InputHistory::window`] before `now`.
+    pub fn prune(&mut self, now: Duration) {
+        let Some(oldest) = now.checked_sub(self.window) else {
+            return;
+        };
+
+        self.records.retain(|_, records| {
+            while records.front().is_some_and(|record| record.time < oldest) {
+                records.pop_front();
+            }
+
+            !records.is_empty()
+        });
+    }
+
+    pub fn clear(&mut self, id: &GamepadId) {
+        self.records.remove(id);
+    }
+
+    pub(crate) fn process_events(
+        history: &mut InputHistory,
+        time: &Time,
+        events: EventReader<GamepadEvent>,
+    ) {
+        let now = time.elapsed().unscaled();
+
+        for event in events {
+            match event {
+                GamepadEvent::Input { id, value } => history.record(*id, *value, now),
+                GamepadEvent::Disconnected(id) => history.clear(id),
+                GamepadEvent::Connected { .. } => {}
+            }
+        }
+
+        history.prune(now);
+    }
+}
+
+impl Default for InputHistory {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_WINDOW)
+    }
+}
+
+/// Stick or d-pad direction, with forward being right.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Neutral,
+    Up,
+    UpForward,
+    Forward,
+    DownForward,
+    Down,
+    DownBack,
+    Back,
+    UpBack,
+}
+
+impl Direction {
+    /// Axis values below this are treated as neutral.
+    pub const DEADZONE: f32 = 0.5;
+
+    pub fn from_axes(value: Vec2) -> Self {
+        let x = if value.x >= Self::DEADZONE {
+            1
+        } else if value.x <= -Self::DEADZONE {
+            -1
+        } else {
+            0
+        };
+
+        let y = if value.y >= Self::DEADZONE {
+            1
+        } else if value.y <= -Self::DEADZONE {
+            -1
+        } else {
+            0
+        };
+
+        match (x, y) {
+            (0, 1) => Self::Up,
+            (1, 1) => Self::UpForward,
+            (1, 0) => Self::Forward,
+            (1, -1) => Self::DownForward,
+            (0, -1) => Self::Down,
+            (-1, -1) => Self::DownBack,
+            (-1, 0) => Self::Back,
+            (-1, 1) => Self::UpBack,
+            _ => Self::Neutral,
+        }
+    }
+
+    /// The same direction for a player facing left.
+    pub fn mirror(self) -> Self {
+        match self {
+            Self::UpForward => Self::UpBack,
+            Self::Forward => Self::Back,
+            Self::DownForward => Self::DownBack,
+            Self::DownBack => Self::DownForward,
+            Self::Back => Self::Forward,
+            Self::UpBack => Self::UpForward,
+            direction => direction,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MotionStep {
+    Direction(Direction),
+    Button(GamepadButton),
+}
+
+/// Tracks the d-pad and left stick while replaying an [`InputHistory`].
+#[derive(Default)]
+struct DirectionState {
+    dpad: [bool; 4],
+    dpad_axes: Vec2,
+    stick: Vec2,
+    current: Option<Direction>,
+}
+
+impl DirectionState {
+    fn apply(&mut self, input: GamepadInputEvent) -> Option<MotionStep> {
+        match input {
+            GamepadInputEvent::ButtonPressed(button) => match Self::dpad_index(button) {
+                Some(index) => self.dpad[index] = true,
+                None => return Some(MotionStep::Button(button)),
+            },
+            GamepadInputEvent::ButtonReleased(button) => match Self::dpad_index(button) {
+                Some(index) => self.dpad[index] = false,
+                None => return None,
+            },
+            GamepadInputEvent::AxisChanged { axis, value } => match axis {
+                GamepadAxis::LeftStickX => self.stick.x = value,
+                GamepadAxis::LeftStickY => self.stick.y = value,
+                GamepadAxis::DPadX => self.dpad_axes.x = value,
+                GamepadAxis::DPadY => self.dpad_axes.y = value,
+                _ => return None,
+            },
+            GamepadInputEvent::ButtonRepeated(_) | GamepadInputEvent::ButtonChanged { .. } => {
+                return None;
+            }
+        }
+
+        let direction = self.direction();
+        if self.current == Some(direction) {
+            return None;
+        }
+
+        self.current = Some(direction);
+        Some(MotionStep::Direction(direction))
+    }
+
+    fn direction(&self) -> Direction {
+        let [up, down, left, right] = self.dpad.map(|pressed| pressed as i32 as f32);
+        let buttons = Vec2::new(right - left, up - down);
+
+        let direction = Direction::from_axes(buttons + self.dpad_axes);
+        if direction != Direction::Neutral {
+            direction
+        } else {
+            Direction::from_axes(self.stick)
+        }
+    }
+
+    fn dpad_index(button: GamepadButton) -> Option<usize> {
+        match button {
+            GamepadButton::DPadUp => Some(0),
+            GamepadButton::DPadDown => Some(1),
+            GamepadButton::DPadLeft => Some(2),
+            GamepadButton::DPadRight => Some(3),
+            _ => None,
+        }
+    }
+}
+
+/// Tests whether a sequence of directions and button presses was entered
+/// in order within a time window. Other inputs may occur between steps.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MotionMatcher {
+    steps: Vec<MotionStep>,
+    window: Duration,
+}
+
+impl MotionMatcher {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            steps: Vec::new(),
+            window,
+        }
+    }
+
+    /// Down, down-forward, forward.
+    pub fn quarter_circle_forward(window: Duration) -> Self {
+        Self::new(window)
+            .with_direction(Direction::Down)
+            .with_direction(Direction::DownForward)
+            .with_direction(Direction::Forward)
+    }
+
+    /// Down, down-back, back.
+    pub fn quarter_circle_back(window: Duration) -> Self {
+        Self::new(window)
+            .with_direction(Direction::Down)
+            .with_direction(Direction::DownBack)
+            .with_direction(Direction::Back)
+    }
+
+    /// Forward, down, down-forward.
+    pub fn dragon_punch(window: Duration) -> Self {
+        Self::new(window)
+            .with_direction(Direction::Forward)
+            .with_direction(Direction::Down)
+            .with_direction(Direction::DownForward)
+    }
+
+    pub fn with_direction(mut self, direction: Direction) -> Self {
+        self.steps.push(MotionStep::Direction(direction));
+        self
+    }
+
+    pub fn with_button(mut self, button: GamepadButton) -> Self {
+        self.steps.push(MotionStep::Button(button));
+        self
+    }
+
+    pub fn steps(&self) -> &[MotionStep] {
+        &self.steps
+    }
+
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// The same motion for a player facing left.
+    pub fn mirror(&self) -> Self {
+        let steps = self.steps.iter().map(|step| match step {
+            MotionStep::Direction(direction) => MotionStep::Direction(direction.mirror()),
+            step => *step,
+        });
+
+        Self {
+            steps: steps.collect(),
+            window: self.window,
+        }
+    }
+
+    /// Returns true if the motion was completed within the window, with the
+    /// first step entered no earlier than `now - window`.
+    pub fn matches(&self, history: &InputHistory, id: &GamepadId, now: Duration) -> bool {
+        if self.steps.is_empty() {
+            return false;
+        }
+
+        let oldest = now.saturating_sub(self.window);
+        let mut state = DirectionState::default();
+        let steps = history
+            .get(id)
+            .filter_map(|record| Some((state.apply(record.input)?, record.time)))
+            .collect::<Vec<_>>();
+
+        steps.iter().enumerate().any(|(start, (step, time))| {
+            if *step != self.steps[0] || *time < oldest {
+                return false;
+            }
+
+            let mut remaining = self.steps[1..].iter().peekable();
+            for (step, _) in &steps[start + 1..] {
+                if remaining.peek() == Some(&step) {
+                    remaining.next();
+                }
+            }
+
+            remaining.peek().is_none()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{InputHistory, MotionMatcher};
+    use crate::{GamepadAxis, GamepadId, GamepadInputEvent};
+    use std::time::Duration;
+
+    fn stick(history: &mut InputHistory, x: f32, y: f32, time: Duration) {
+        let id = GamepadId(0);
+        let x = GamepadInputEvent::AxisChanged {
+            axis: GamepadAxis::LeftStickX,
+            value: x,
+        };
+        let y = GamepadInputEvent::AxisChanged {
+            axis: GamepadAxis::LeftStickY,
+            value: y,
+        };
+
+        history.record(id, x, time);
+        history.record(id, y, time);
+    }
+
+    fn quarter_circle_forward(step: Duration) -> (InputHistory, Duration) {
+        let mut history = InputHistory::new(Duration::from_secs(1));
+        stick(&mut history, 0.0, -1.0, step);
+        stick(&mut history, 1.0, -1.0, step * 2);
+        stick(&mut history, 1.0, 0.0, step * 3);
+
+        (history, step * 3)
+    }
+
+    #[test]
+    fn quarter_circle_forward_matches() {
+        let (history, now) = quarter_circle_forward(Duration::from_millis(50));
+        let motion = MotionMatcher::quarter_circle_forward(Duration::from_millis(300));
+
+        assert!(motion.matches(&history, &GamepadId(0), now));
+        assert!(!motion.mirror().matches(&history, &GamepadId(0), now));
+    }
+
+    #[test]
+    fn quarter_circle_forward_too_slow() {
+        let (history, now) = quarter_circle_forward(Duration::from_millis(200));
+        let motion = MotionMatcher::quarter_circle_forward(Duration::from_millis(300));
+
+        assert!(!motion.matches(&history, &GamepadId(0), now));
+    }
+
+    #[test]
+    fn input_history_prune() {
+        let (mut history, now) = quarter_circle_forward(Duration::from_millis(600));
+
+        history.prune(now);
+        assert_eq!(history.get(&GamepadId(0)).count(), 4);
+
+        history.prune(now + Duration::from_secs(2));
+        assert_eq!(history.get(&GamepadId(0)).count(), 0);
+    }
+}
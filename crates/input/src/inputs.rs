@@ -1,6 +1,6 @@
 use crate::{
     GamepadId, MouseScrollAccumulator, MouseScrollUnit,
-    button::{ButtonState, Buttons},
+    button::{ButtonInput, ButtonState},
     gamepad::{GamepadAxis, GamepadButton, Gamepads},
     keyboard::{Key, KeyCode},
     mouse::MouseButton,
@@ -29,9 +29,9 @@ pub struct InputContext<'a> {
     entity: Entity,
     device: InputDevice,
     gamepads: &'a Gamepads,
-    codes: &'a Buttons<KeyCode>,
-    keys: &'a Buttons<Key>,
-    mouse: &'a Buttons<MouseButton>,
+    codes: &'a ButtonInput<KeyCode>,
+    keys: &'a ButtonInput<Key>,
+    mouse: &'a ButtonInput<MouseButton>,
     scrolls: &'a MouseScrollAccumulator,
 }
 
@@ -289,9 +289,9 @@ impl ActionRegistry {
         mut commands: Commands,
         actions: &ActionRegistry,
         gamepads: &Gamepads,
-        codes: &Buttons<KeyCode>,
-        keys: &Buttons<Key>,
-        mouse: &Buttons<MouseButton>,
+        codes: &ButtonInput<KeyCode>,
+        keys: &ButtonInput<Key>,
+        mouse: &ButtonInput<MouseButton>,
         scrolls: &MouseScrollAccumulator,
         query: Query<(Entity, Option<&InputDevice>), With<R>>,
     ) {
@@ -1,4 +1,4 @@
-use crate::{Buttons, button::ButtonState};
+use crate::{ButtonInput, button::ButtonState};
 use ecs::{Event, EventReader};
 use smol_str::SmolStr;
 
@@ -1229,8 +1229,8 @@ pub struct KeyboardInput {
 
 impl KeyboardInput {
     pub(crate) fn process_events(
-        key_codes: &mut Buttons<KeyCode>,
-        keys: &mut Buttons<Key>,
+        key_codes: &mut ButtonInput<KeyCode>,
+        keys: &mut ButtonInput<Key>,
         events: EventReader<KeyboardInput>,
     ) {
         for event in events {
@@ -1,6 +1,7 @@
 pub mod analog;
 pub mod button;
 pub mod gamepad;
+pub mod history;
 pub mod touch;
 pub mod inputs;
 pub mod keyboard;
@@ -10,6 +11,7 @@ pub mod plugin;
 pub use analog::*;
 pub use button::*;
 pub use gamepad::*;
+pub use history::*;
 pub use touch::*;
 pub use inputs::*;
 pub use keyboard::*;
@@ -1,4 +1,4 @@
-use crate::{Buttons, button::ButtonState};
+use crate::{ButtonInput, button::ButtonState};
 use ecs::{Event, EventReader, Resource};
 use math::Vec2;
 
@@ -28,7 +28,7 @@ pub struct MouseInput {
 
 impl MouseInput {
     pub(crate) fn process_events(
-        mouse_buttons: &mut Buttons<MouseButton>,
+        mouse_buttons: &mut ButtonInput<MouseButton>,
         events: EventReader<MouseInput>,
     ) {
         for event in events {
@@ -1,9 +1,14 @@
 use crate::{
-    ActionRegistry, Actions, Buttons, DoubleTapGesture, GamepadEvent, Gamepads, InputMode,
-    InputReceiver, Key, KeyCode, KeyboardInput, MouseButton, MouseInput, MouseScroll, PinchGesture,
-    RotationGesture, TouchInput, TouchpadPressure,
+    ActionRegistry, Actions, ButtonInput, DoubleTapGesture, GamepadEvent, GamepadRumbleFailed,
+    GamepadRumbleRequest, Gamepads, InputHistory, InputMode, InputReceiver, Key, KeyCode,
+    KeyboardInput, MouseButton, MouseInput, MouseScroll, PinchGesture, RotationGesture, TouchInput,
+    TouchpadPressure,
+};
+use ecs::{
+    AppBuilder, End, IntoSystemConfig, Plugin, Start,
+    app::{PreUpdate, time::TimePlugin},
+    system::CurrentMode,
 };
-use ecs::{AppBuilder, IntoSystemConfig, Plugin, Start, app::PreUpdate, system::CurrentMode};
 
 pub struct InputPlugin;
 
@@ -19,8 +24,9 @@ impl Plugin for MousePlugin {
         app.add_plugins(InputPlugin)
             .register_event::<MouseInput>()
             .register_event::<MouseScroll>()
-            .add_resource(Buttons::<MouseButton>::default())
-            .add_systems(Start, MouseInput::process_events);
+            .add_resource(ButtonInput::<MouseButton>::default())
+            .add_systems(Start, MouseInput::process_events)
+            .add_systems(End, ButtonInput::<MouseButton>::tick);
     }
 }
 
@@ -29,9 +35,11 @@ impl Plugin for KeyboardPlugin {
     fn setup(&mut self, app: &mut AppBuilder) {
         app.add_plugins(InputPlugin)
             .register_event::<KeyboardInput>()
-            .add_resource(Buttons::<KeyCode>::default())
-            .add_resource(Buttons::<Key>::default())
-            .add_systems(Start, KeyboardInput::process_events);
+            .add_resource(ButtonInput::<KeyCode>::default())
+            .add_resource(ButtonInput::<Key>::default())
+            .add_systems(Start, KeyboardInput::process_events)
+            .add_systems(End, ButtonInput::<KeyCode>::tick)
+            .add_systems(End, ButtonInput::<Key>::tick);
     }
 }
 
@@ -41,7 +49,27 @@ impl Plugin for GamepadPlugin {
         app.add_plugins(InputPlugin)
             .add_resource(Gamepads::new())
             .add_systems(Start, Gamepads::process_events)
-            .register_event::<GamepadEvent>();
+            .add_systems(End, Gamepads::tick)
+            .register_event::<GamepadEvent>()
+            .register_event::<GamepadRumbleRequest>()
+            .register_event::<GamepadRumbleFailed>();
+    }
+}
+
+/// Records recent gamepad inputs in an [`InputHistory`] for motion detection.
+pub struct InputHistoryPlugin(pub std::time::Duration);
+
+impl Default for InputHistoryPlugin {
+    fn default() -> Self {
+        Self(InputHistory::DEFAULT_WINDOW)
+    }
+}
+
+impl Plugin for InputHistoryPlugin {
+    fn setup(&mut self, app: &mut AppBuilder) {
+        app.add_plugins((GamepadPlugin, TimePlugin))
+            .add_resource(InputHistory::new(self.0))
+            .add_systems(Start, InputHistory::process_events);
     }
 }
 
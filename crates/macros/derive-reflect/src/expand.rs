@@ -7,7 +7,7 @@ use macro_utils::{
 use std::borrow::Cow;
 
 pub fn expand_reflect(input: &mut DeriveInput) -> Result<TokenStream> {
-    let crate_path = get_crate_path("reflector_core");
+    let crate_path = get_crate_path("reflection");
     let name = &input.ident;
 
     let expanded = match &input.data {
@@ -172,6 +172,12 @@ fn derive_struct<'a>(
     name: &'a Ident,
     data: &'a DataStruct,
 ) -> proc_macro2::TokenStream {
+    if let Fields::Unit = data.fields {
+        return derive_unit_struct(path, name);
+    }
+
+    // Tuple struct fields are named by their index, so `struct Velocity(u32, u32)`
+    // reflects as an object with the fields "0" and "1".
     let fields = get_fields(&data.fields);
     let reflect_fields = reflect_fields(&fields);
     let set_fields = set_fields(&fields);
@@ -183,28 +189,52 @@ fn derive_struct<'a>(
             }
 
             fn reflect(&self) -> #path::ReflectType {
-                use #path::{ReflectType, ReflectObject, ReflectValue, ReflectError};
+                use #path::{Reflect, ReflectType, ReflectValue};
 
                 ReflectType::Object(#reflect_fields)
             }
 
             fn set_reflect(&mut self, value: #path::ReflectType) -> Result<(), #path::ReflectError> {
-                use #path::{ReflectType, ReflectObject, ReflectValue, ReflectError};
+                use #path::{Reflect, ReflectType, ReflectError};
 
                 match value {
                     ReflectType::Object(fields) => {
                         for field in fields {
                             match field.name {
                                 #(#set_fields)*
-                                _ => return Err(ReflectError::UnknownField {
-                                    name: String::from("unknown"),
+                                name => return Err(ReflectError::UnknownField {
+                                    name: String::from(name),
                                 }),
                             }
                         }
                         Ok(())
                     }
-                    _ => Err(ReflectError::InvalidType {
-                        name: self.type_name(),
+                    v => Err(ReflectError::InvalidType {
+                        name: v.type_name(),
+                        expected: stringify!(#name),
+                    }),
+                }
+            }
+        }
+    }
+}
+
+fn derive_unit_struct<'a>(path: &'a Ident, name: &'a Ident) -> proc_macro2::TokenStream {
+    quote! {
+        impl #path::Reflect for #name {
+            fn type_name(&self) -> &'static str {
+                stringify!(#name)
+            }
+
+            fn reflect(&self) -> #path::ReflectType {
+                #path::ReflectType::Unit
+            }
+
+            fn set_reflect(&mut self, value: #path::ReflectType) -> Result<(), #path::ReflectError> {
+                match value {
+                    #path::ReflectType::Unit => Ok(()),
+                    v => Err(#path::ReflectError::InvalidType {
+                        name: v.type_name(),
                         expected: stringify!(#name),
                     }),
                 }
@@ -15,6 +15,7 @@ use std::collections::HashMap;
 [storage(0, read_write, visibility="compute")]
 [texture(0, dimension=2d, visibility="fragment")]
 [sampler(0, sampler_type="uint", visibility="vertex, fragment")]
+[storage_texture(0, format="rgba8unorm", access="write", dimension="d2", visibility(compute))]
 */
 
 #[allow(non_snake_case)]
@@ -99,6 +100,29 @@ pub fn expand_create_bind_group(input: &mut DeriveInput) -> Result<TokenStream>
                         #BINDINGS.with_texture(#binding, texture.view());
                     });
                 }
+                BindingType::StorageTexture {
+                    dimension,
+                    format,
+                    access,
+                    visibility,
+                } => {
+                    let view_dimension = dimension.view_tokens();
+                    layouts.extend(quote! {
+                        #LAYOUTS.with_storage_texture(#binding, #visibility, #view_dimension, #format, #access);
+                    });
+
+                    bindings.extend(quote! {
+                        let texture = self.#ident.into_optional_id()
+                            .and_then(|id| #TEXTURES.get(&id))
+                            .ok_or(CreateBindGroupError::MissingStorageTexture { binding: #binding })?;
+
+                        if !texture.texture().usage().contains(TextureUsages::STORAGE_BINDING) {
+                            return Err(CreateBindGroupError::MissingStorageUsage { binding: #binding });
+                        }
+
+                        #BINDINGS.with_storage_texture(#binding, texture.view());
+                    });
+                }
                 BindingType::Sampler { ty, visibility } => {
                     layouts.extend(quote! {
                         #LAYOUTS.with_sampler(#binding, #visibility, #ty);
@@ -153,30 +177,32 @@ pub fn expand_create_bind_group(input: &mut DeriveInput) -> Result<TokenStream>
                 Some(#label)
             }
 
+            #[allow(unused_imports, unused_variables)]
             fn create_bind_group(
                 &self,
                 device: &#render::RenderDevice,
                 layout: &#render::BindGroupLayout,
                 arg: &#ecs::system::ArgItem<Self::Arg>
             ) -> Result<#render::BindGroup, #render::CreateBindGroupError> {
-                use #render::{BindGroupBuilder, OptionalAssetId, uniform::UniformBuffer, storage::StorageBuffer, GpuTexture, ShaderType, TextureDimension, wgpu::TextureViewDimension};
+                use #render::{BindGroupBuilder, CreateBindGroupError, OptionalAssetId, uniform::UniformBuffer, storage::StorageBuffer, GpuTexture, ShaderType, TextureDimension, wgpu::{TextureUsages, TextureViewDimension}};
 
                 let (#TEXTURES, #FALLBACKS) = arg;
                 let mut #BINDINGS = BindGroupBuilder::new(layout);
 
                 #bindings
 
-               Ok(#BINDINGS.build(device))
+                Ok(#BINDINGS.build(device)?)
             }
 
-            fn create_bind_group_layout(device: &#render::RenderDevice)  -> #render::BindGroupLayout {
-                use #render::{BindGroupLayoutBuilder, wgpu::{TextureSampleType, TextureViewDimension, SamplerBindingType, ShaderStages}};
+            #[allow(unused_imports)]
+            fn layout_builder() -> #render::BindGroupLayoutBuilder {
+                use #render::{BindGroupLayoutBuilder, wgpu::{TextureSampleType, TextureViewDimension, TextureFormat, StorageTextureAccess, SamplerBindingType, ShaderStages}};
 
                 let mut #LAYOUTS = BindGroupLayoutBuilder::new();
 
                 #layouts
 
-                #LAYOUTS.build(device)
+                #LAYOUTS
             }
         }
     };
@@ -218,6 +244,12 @@ enum BindingType {
         ty: SamplerType,
         visibility: Visibility,
     },
+    StorageTexture {
+        dimension: TextureDimension,
+        format: StorageTextureFormat,
+        access: StorageTextureAccess,
+        visibility: Visibility,
+    },
 }
 
 impl BindingType {
@@ -225,6 +257,7 @@ impl BindingType {
     const STORAGE: Symbol = Symbol::new("storage");
     const TEXTURE: Symbol = Symbol::new("texture");
     const SAMPLER: Symbol = Symbol::new("sampler");
+    const STORAGE_TEXTURE: Symbol = Symbol::new("storage_texture");
 
     fn parse(path: &syn::Path, meta: &BindingMeta) -> Result<Self> {
         if path == Self::UNIFORM {
@@ -293,6 +326,43 @@ impl BindingType {
             }
 
             Ok(Self::Sampler { ty, visibility })
+        } else if path == Self::STORAGE_TEXTURE {
+            let mut dimension = TextureDimension::D2;
+            let mut format = None;
+            let mut access = StorageTextureAccess::WriteOnly;
+            let mut visibility = Visibility::COMPUTE;
+
+            for meta in &meta.meta {
+                match meta {
+                    Meta::List(meta) if meta.path == Visibility::PATH => {
+                        visibility = Visibility::parse(meta, Visibility::empty())?;
+                    }
+                    Meta::NameValue(meta) if meta.path == TextureDimension::PATH => {
+                        dimension = TextureDimension::parse(meta)?
+                    }
+                    Meta::NameValue(meta) if meta.path == StorageTextureFormat::PATH => {
+                        format = Some(StorageTextureFormat::parse(meta)?)
+                    }
+                    Meta::NameValue(meta) if meta.path == StorageTextureAccess::PATH => {
+                        access = StorageTextureAccess::parse(meta)?
+                    }
+                    _ => continue,
+                }
+            }
+
+            let Some(format) = format else {
+                return Err(Error::new_spanned(
+                    path,
+                    "storage textures require a format",
+                ));
+            };
+
+            Ok(Self::StorageTexture {
+                dimension,
+                format,
+                access,
+                visibility,
+            })
         } else {
             Err(syn::Error::new_spanned(
                 path,
@@ -305,7 +375,8 @@ impl BindingType {
         let v = path == Self::UNIFORM
             || path == Self::STORAGE
             || path == Self::TEXTURE
-            || path == Self::SAMPLER;
+            || path == Self::SAMPLER
+            || path == Self::STORAGE_TEXTURE;
 
         return v;
     }
@@ -434,6 +505,105 @@ impl ToTokens for SamplerType {
     }
 }
 
+/// Formats that support storage usage without optional device features.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StorageTextureFormat {
+    Rgba8Unorm,
+    Rgba8Snorm,
+    Rgba8Uint,
+    Rgba8Sint,
+    Rgba16Uint,
+    Rgba16Sint,
+    Rgba16Float,
+    R32Uint,
+    R32Sint,
+    R32Float,
+    Rg32Uint,
+    Rg32Sint,
+    Rg32Float,
+    Rgba32Uint,
+    Rgba32Sint,
+    Rgba32Float,
+}
+
+impl StorageTextureFormat {
+    const PATH: Symbol = Symbol::new("format");
+    const FORMATS: [(&str, Self); 16] = [
+        ("rgba8unorm", Self::Rgba8Unorm),
+        ("rgba8snorm", Self::Rgba8Snorm),
+        ("rgba8uint", Self::Rgba8Uint),
+        ("rgba8sint", Self::Rgba8Sint),
+        ("rgba16uint", Self::Rgba16Uint),
+        ("rgba16sint", Self::Rgba16Sint),
+        ("rgba16float", Self::Rgba16Float),
+        ("r32uint", Self::R32Uint),
+        ("r32sint", Self::R32Sint),
+        ("r32float", Self::R32Float),
+        ("rg32uint", Self::Rg32Uint),
+        ("rg32sint", Self::Rg32Sint),
+        ("rg32float", Self::Rg32Float),
+        ("rgba32uint", Self::Rgba32Uint),
+        ("rgba32sint", Self::Rgba32Sint),
+        ("rgba32float", Self::Rgba32Float),
+    ];
+
+    fn parse(meta: &syn::MetaNameValue) -> Result<Self> {
+        let value = meta.value.parse_str()?;
+
+        Self::FORMATS
+            .iter()
+            .find(|(name, _)| *name == value)
+            .map(|(_, format)| *format)
+            .ok_or_else(|| {
+                Error::new_spanned(
+                    meta,
+                    format!("format {value} does not support storage usage"),
+                )
+            })
+    }
+}
+
+impl ToTokens for StorageTextureFormat {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let format = Ident::new(&format!("{:?}", self), Span::call_site());
+        tokens.extend(quote! { TextureFormat::#format });
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StorageTextureAccess {
+    ReadOnly,
+    WriteOnly,
+    ReadWrite,
+}
+
+impl StorageTextureAccess {
+    const PATH: Symbol = Symbol::new("access");
+    const READ: &str = "read";
+    const WRITE: &str = "write";
+    const READ_WRITE: &str = "read_write";
+
+    fn parse(meta: &syn::MetaNameValue) -> Result<Self> {
+        match meta.value.parse_str().as_deref() {
+            Ok(Self::READ) => Ok(Self::ReadOnly),
+            Ok(Self::WRITE) => Ok(Self::WriteOnly),
+            Ok(Self::READ_WRITE) => Ok(Self::ReadWrite),
+            Err(e) => Err(Error::new_spanned(meta, e)),
+            _ => Err(Error::new_spanned(meta, "unknown storage texture access")),
+        }
+    }
+}
+
+impl ToTokens for StorageTextureAccess {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            Self::ReadOnly => tokens.extend(quote! { StorageTextureAccess::ReadOnly }),
+            Self::WriteOnly => tokens.extend(quote! { StorageTextureAccess::WriteOnly }),
+            Self::ReadWrite => tokens.extend(quote! { StorageTextureAccess::ReadWrite }),
+        }
+    }
+}
+
 bitflags::bitflags! {
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     struct Visibility: u32 {
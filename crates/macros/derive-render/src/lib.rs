@@ -8,7 +8,10 @@ use proc_macro::TokenStream;
 
 mod expand;
 
-#[proc_macro_derive(AsBinding, attributes(uniform, texture, sampler, storage))]
+#[proc_macro_derive(
+    AsBinding,
+    attributes(uniform, texture, sampler, storage, storage_texture)
+)]
 pub fn derive_create_bind_group(input: proc_macro::TokenStream) -> TokenStream {
     let mut input = syn::parse_macro_input!(input as syn::DeriveInput);
     expand::expand_create_bind_group(&mut input)
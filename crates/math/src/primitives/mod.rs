@@ -1,5 +1,6 @@
 pub mod bounds;
 pub mod circle;
 pub mod point;
+pub mod ray;
 pub mod rect;
 pub mod sphere;
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Ray {
+    pub origin: glam::Vec3,
+    /// Always normalized.
+    pub direction: glam::Vec3,
+}
+
+impl Ray {
+    pub fn new(origin: glam::Vec3, direction: glam::Vec3) -> Self {
+        Ray {
+            origin,
+            direction: direction.normalize(),
+        }
+    }
+
+    pub fn at(&self, distance: f32) -> glam::Vec3 {
+        self.origin + self.direction * distance
+    }
+
+    /// Distance along the ray to the plane through `point` with the given
+    /// `normal`, or `None` if the ray is parallel to or points away from it.
+    pub fn intersect_plane(&self, point: glam::Vec3, normal: glam::Vec3) -> Option<f32> {
+        let denom = normal.dot(self.direction);
+        if denom.abs() <= f32::EPSILON {
+            return None;
+        }
+
+        let distance = normal.dot(point - self.origin) / denom;
+        (distance >= 0.0).then_some(distance)
+    }
+}
@@ -1,4 +1,5 @@
 pub use derive_reflect::Reflect;
+use std::collections::HashMap;
 
 #[derive(Clone, Debug)]
 pub struct ReflectValue {
@@ -26,8 +27,11 @@ pub enum ReflectType {
     i32(i32),
     i64(i64),
     i128(i128),
+    f32(f32),
+    f64(f64),
     String(String),
     Array(Vec<ReflectType>),
+    Map(Vec<(String, ReflectType)>),
     Object(Vec<ReflectValue>),
     Enum {
         value: Box<ReflectObject>,
@@ -49,8 +53,11 @@ impl ReflectType {
             Self::i32(_) => "i32",
             Self::i64(_) => "i64",
             Self::i128(_) => "i128",
+            Self::f32(_) => "f32",
+            Self::f64(_) => "f64",
             Self::String(_) => "String",
             Self::Array(_) => "Array",
+            Self::Map(_) => "Map",
             Self::Object(_) => "Object",
             Self::Enum { .. } => "Enum",
         }
@@ -124,6 +131,8 @@ impl_reflect_primitive! {
     i32 => i32,
     i64 => i64,
     i128 => i128,
+    f32 => f32,
+    f64 => f64,
 }
 
 impl<R: Reflect> Reflect for Vec<R> {
@@ -151,6 +160,70 @@ impl<R: Reflect> Reflect for Vec<R> {
     }
 }
 
+impl<R: Reflect> Reflect for Option<R> {
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<Option<R>>()
+    }
+
+    fn reflect(&self) -> ReflectType {
+        match self {
+            Some(value) => value.reflect(),
+            None => ReflectType::Unit,
+        }
+    }
+
+    fn set_reflect(&mut self, value: ReflectType) -> Result<(), ReflectError> {
+        match value {
+            ReflectType::Unit => *self = None,
+            value => {
+                let mut inner = R::default();
+                inner.set_reflect(value)?;
+                *self = Some(inner);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Maps reflect their entries sorted by key, so the same map always produces
+/// the same [`ReflectType::Map`].
+impl<R: Reflect> Reflect for HashMap<String, R> {
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<HashMap<String, R>>()
+    }
+
+    fn reflect(&self) -> ReflectType {
+        let mut entries = self
+            .iter()
+            .map(|(key, value)| (key.clone(), value.reflect()))
+            .collect::<Vec<_>>();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+        ReflectType::Map(entries)
+    }
+
+    fn set_reflect(&mut self, value: ReflectType) -> Result<(), ReflectError> {
+        match value {
+            ReflectType::Map(entries) => {
+                let mut map = HashMap::with_capacity(entries.len());
+                for (key, value) in entries {
+                    let mut inner = R::default();
+                    inner.set_reflect(value)?;
+                    map.insert(key, inner);
+                }
+
+                *self = map;
+                Ok(())
+            }
+            _ => Err(ReflectError::InvalidType {
+                name: value.type_name(),
+                expected: self.type_name(),
+            }),
+        }
+    }
+}
+
 impl Reflect for String {
     fn type_name(&self) -> &'static str {
         "String"
@@ -187,3 +260,122 @@ impl Reflect for () {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{Reflect, ReflectError, ReflectType, ReflectValue};
+
+    #[derive(Clone, Debug, Default, PartialEq, Reflect)]
+    struct Velocity(u32, i32);
+
+    #[derive(Clone, Debug, Default, PartialEq, Reflect)]
+    struct Marker;
+
+    #[derive(Clone, Debug, Default, PartialEq, Reflect)]
+    struct Player {
+        name: String,
+        health: u32,
+    }
+
+    fn round_trip<R: Reflect + PartialEq + std::fmt::Debug>(value: R) {
+        let mut target = R::default();
+        target.set_reflect(value.reflect()).unwrap();
+        assert_eq!(target, value);
+    }
+
+    #[test]
+    fn tuple_struct() {
+        let velocity = Velocity(3, -4);
+        let ReflectType::Object(fields) = velocity.reflect() else {
+            panic!("Expected an object");
+        };
+
+        let names = fields.iter().map(|field| field.name).collect::<Vec<_>>();
+        assert_eq!(names, vec!["0", "1"]);
+        round_trip(velocity);
+
+        let unknown = ReflectType::Object(vec![ReflectValue {
+            name: "2",
+            value: ReflectType::u32(0),
+        }]);
+        let result = Velocity::default().set_reflect(unknown);
+        assert!(matches!(result, Err(ReflectError::UnknownField { name }) if name == "2"));
+    }
+
+    #[test]
+    fn unit_struct() {
+        assert!(matches!(Marker.reflect(), ReflectType::Unit));
+        round_trip(Marker);
+
+        let result = Marker.set_reflect(ReflectType::u32(0));
+        assert!(matches!(result, Err(ReflectError::InvalidType { .. })));
+    }
+
+    #[derive(Clone, Debug, Default, PartialEq, Reflect)]
+    struct Curve {
+        scale: f32,
+        points: Vec<f32>,
+    }
+
+    #[test]
+    fn float_fields() {
+        let curve = Curve {
+            scale: 0.5,
+            points: vec![0.0, 0.25, 1.0],
+        };
+
+        assert_eq!(Curve::construct(curve.reflect()), curve);
+        assert_eq!(f64::construct(ReflectType::f64(2.5)), 2.5);
+    }
+
+    #[test]
+    fn option() {
+        assert!(matches!(Some(5u32).reflect(), ReflectType::u32(5)));
+        assert!(matches!(None::<u32>.reflect(), ReflectType::Unit));
+
+        assert_eq!(Option::<u32>::construct(Some(5u32).reflect()), Some(5));
+        assert_eq!(Option::<u32>::construct(None::<u32>.reflect()), None);
+
+        let result = Some(5u32).set_reflect(ReflectType::String(String::from("5")));
+        assert!(matches!(result, Err(ReflectError::InvalidType { .. })));
+    }
+
+    #[test]
+    fn map() {
+        use std::collections::HashMap;
+
+        let map = HashMap::from([(String::from("b"), 2u32), (String::from("a"), 1u32)]);
+        let ReflectType::Map(entries) = map.reflect() else {
+            panic!("Expected a map");
+        };
+
+        let keys = entries
+            .iter()
+            .map(|(key, _)| key.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(keys, vec!["a", "b"]);
+
+        let constructed = HashMap::<String, u32>::construct(ReflectType::Map(entries.clone()));
+        assert_eq!(constructed, map);
+
+        let ReflectType::Map(reflected) = constructed.reflect() else {
+            panic!("Expected a map");
+        };
+        let reflected = reflected
+            .iter()
+            .map(|(key, _)| key.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(reflected, keys);
+
+        let result = HashMap::<String, u32>::new().set_reflect(ReflectType::u32(0));
+        assert!(matches!(result, Err(ReflectError::InvalidType { .. })));
+    }
+
+    #[test]
+    fn named_struct() {
+        round_trip(Player {
+            name: String::from("player"),
+            health: 100,
+        });
+    }
+}
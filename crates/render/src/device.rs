@@ -1,6 +1,6 @@
 use ecs::Resource;
-use std::sync::Arc;
-use wgpu::{Adapter, Device, Queue, RequestDeviceError};
+use std::sync::{Arc, OnceLock};
+use wgpu::{Adapter, Device, Features, Queue, RequestDeviceError, TextureFormat};
 
 #[derive(Resource, Clone)]
 pub struct RenderDevice {
@@ -10,11 +10,9 @@ pub struct RenderDevice {
 
 impl RenderDevice {
     pub async fn new(adapter: &Adapter) -> Result<Self, RequestDeviceError> {
+        let optional = adapter.features() & RenderFeatures::OPTIONAL;
         let desc = wgpu::DeviceDescriptor {
-            required_features: wgpu::Features::ADDRESS_MODE_CLAMP_TO_BORDER
-                | wgpu::Features::ADDRESS_MODE_CLAMP_TO_ZERO
-                | wgpu::Features::INDIRECT_FIRST_INSTANCE
-                | wgpu::Features::default(),
+            required_features: RenderFeatures::REQUIRED | optional,
             ..Default::default()
         };
 
@@ -34,3 +32,158 @@ impl std::ops::Deref for RenderDevice {
         &self.device
     }
 }
+
+/// The optional device features the renderer was able to enable. Render code
+/// should check this instead of the device so that fallbacks can be tested
+/// without a GPU.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RenderFeatures(Features);
+
+static SELECTED_FEATURES: OnceLock<RenderFeatures> = OnceLock::new();
+
+impl RenderFeatures {
+    /// Features the renderer can't run without. Instance data is indexed
+    /// with `first_instance`, which indirect draws ignore without
+    /// `INDIRECT_FIRST_INSTANCE`.
+    pub const REQUIRED: Features = Features::ADDRESS_MODE_CLAMP_TO_BORDER
+        .union(Features::ADDRESS_MODE_CLAMP_TO_ZERO)
+        .union(Features::INDIRECT_FIRST_INSTANCE);
+
+    /// Features that are enabled when the adapter supports them.
+    pub const OPTIONAL: Features = Features::MULTI_DRAW_INDIRECT
+        .union(Features::MULTI_DRAW_INDIRECT_COUNT)
+        .union(Features::PUSH_CONSTANTS)
+        .union(Features::POLYGON_MODE_LINE)
+        .union(Features::TEXTURE_COMPRESSION_BC);
+
+    pub fn new(features: Features) -> Self {
+        Self(features)
+    }
+
+    pub fn features(&self) -> Features {
+        self.0
+    }
+
+    /// Makes these the features code outside the render world checks, e.g.
+    /// importers rejecting assets the device can't use. Only the first
+    /// selection takes effect.
+    pub fn select(self) -> Self {
+        let _ = SELECTED_FEATURES.set(self);
+        self
+    }
+
+    /// The features of the device the renderer selected, or `None` before
+    /// a device was created.
+    pub fn selected() -> Option<Self> {
+        SELECTED_FEATURES.get().copied()
+    }
+
+    pub fn contains(&self, features: Features) -> bool {
+        self.0.contains(features)
+    }
+
+    pub fn multi_draw_indirect(&self) -> bool {
+        self.contains(Features::MULTI_DRAW_INDIRECT)
+    }
+
+    pub fn multi_draw_indirect_count(&self) -> bool {
+        self.contains(Features::MULTI_DRAW_INDIRECT_COUNT)
+    }
+
+    pub fn push_constants(&self) -> bool {
+        self.contains(Features::PUSH_CONSTANTS)
+    }
+
+    pub fn polygon_mode_line(&self) -> bool {
+        self.contains(Features::POLYGON_MODE_LINE)
+    }
+
+    pub fn texture_compression_bc(&self) -> bool {
+        self.contains(Features::TEXTURE_COMPRESSION_BC)
+    }
+
+    /// Batches of draws go out as one `multi_draw_indirect` call when the
+    /// device supports it, and as a direct draw per batch otherwise.
+    pub fn draw_path(&self) -> DrawPath {
+        if self.multi_draw_indirect() {
+            DrawPath::Indirect
+        } else {
+            DrawPath::Direct
+        }
+    }
+
+    /// Returns true if textures of `format` can be uploaded, e.g. BC
+    /// compressed formats need `TEXTURE_COMPRESSION_BC`.
+    pub fn supports_format(&self, format: TextureFormat) -> bool {
+        self.contains(format.required_features())
+    }
+}
+
+impl From<&RenderDevice> for RenderFeatures {
+    fn from(device: &RenderDevice) -> Self {
+        Self(device.features())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrawPath {
+    Direct,
+    Indirect,
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::{DrawPath, RenderDevice, RenderFeatures};
+    use wgpu::{Features, TextureFormat};
+
+    /// Creates a device on the default adapter. Tests that use it need a GPU,
+    /// so they're marked `#[ignore = "requires a GPU adapter"]` and run with
+    /// `cargo test -- --ignored`.
+    pub(crate) fn device() -> RenderDevice {
+        smol::block_on(async {
+            let adapter = wgpu::Instance::default()
+                .request_adapter(&wgpu::RequestAdapterOptions::default())
+                .await
+                .expect("No GPU adapter available");
+
+            RenderDevice::new(&adapter)
+                .await
+                .expect("Failed to create a render device")
+        })
+    }
+
+    #[test]
+    fn optional_features() {
+        assert!(RenderFeatures::REQUIRED.contains(Features::INDIRECT_FIRST_INSTANCE));
+        assert!(!RenderFeatures::OPTIONAL.intersects(RenderFeatures::REQUIRED));
+
+        let features = RenderFeatures::new(RenderFeatures::REQUIRED);
+        assert!(!features.push_constants());
+        assert!(!features.multi_draw_indirect_count());
+
+        let features = RenderFeatures::new(RenderFeatures::REQUIRED | Features::PUSH_CONSTANTS);
+        assert!(features.push_constants());
+        assert!(!features.polygon_mode_line());
+
+        let features = RenderFeatures::new(RenderFeatures::REQUIRED | RenderFeatures::OPTIONAL);
+        assert!(features.push_constants());
+        assert!(features.polygon_mode_line());
+        assert!(features.multi_draw_indirect_count());
+    }
+
+    #[test]
+    fn draw_path_selection() {
+        let features = RenderFeatures::new(RenderFeatures::REQUIRED);
+        assert_eq!(features.draw_path(), DrawPath::Direct);
+        assert!(!features.supports_format(TextureFormat::Bc7RgbaUnorm));
+        assert!(features.supports_format(TextureFormat::Rgba8Unorm));
+
+        let features =
+            RenderFeatures::new(RenderFeatures::REQUIRED | Features::MULTI_DRAW_INDIRECT);
+        assert_eq!(features.draw_path(), DrawPath::Indirect);
+
+        let features = RenderFeatures::new(RenderFeatures::REQUIRED | RenderFeatures::OPTIONAL);
+        assert_eq!(features.draw_path(), DrawPath::Indirect);
+        assert!(features.supports_format(TextureFormat::Bc7RgbaUnorm));
+    }
+}
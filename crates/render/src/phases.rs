@@ -21,6 +21,9 @@ pub struct QueueDraws;
 #[derive(Phase)]
 pub struct PreRender;
 
+#[derive(Phase)]
+pub struct Compute;
+
 #[derive(Phase)]
 pub struct Render;
 
@@ -1,8 +1,8 @@
 use crate::{
-    ActiveCamera, Camera, CameraAttachments, CameraPhase, CameraSortOrder, PostRender, PreRender,
-    Render, RenderApp, plugins::RenderPlugin,
+    ActiveCamera, Camera, CameraAttachments, CameraPhase, CameraSortOrder, Environment, PostRender,
+    PreRender, Render, RenderApp, plugins::RenderPlugin,
 };
-use ecs::{AppBuilder, Plugin, app::sync::SyncComponentPlugin};
+use ecs::{AppBuilder, Extract, Plugin, app::sync::SyncComponentPlugin};
 
 pub struct CameraPlugin;
 impl Plugin for CameraPlugin {
@@ -11,11 +11,14 @@ impl Plugin for CameraPlugin {
             SyncComponentPlugin::<Camera, RenderApp>::new(),
             RenderPlugin,
         ))
+        .add_resource(Environment::default())
         .sub_app_mut(RenderApp)
         .add_sub_phase(Render, CameraPhase)
         .register::<CameraAttachments>()
         .register::<ActiveCamera>()
         .add_resource(CameraSortOrder::default())
+        .add_resource(Environment::default())
+        .add_systems(Extract, Environment::extract)
         .add_systems(PreRender, CameraAttachments::queue)
         .add_systems(PostRender, CameraAttachments::cleanup);
     }
@@ -1,5 +1,5 @@
 use crate::{
-    Mesh, Process, RenderApp, SubMesh,
+    GltfImporter, Mesh, Process, RenderApp, SubMesh,
     allocator::{MeshAllocator, MeshAllocatorConfig},
     plugins::RenderPlugin,
 };
@@ -14,6 +14,7 @@ impl Plugin for MeshPlugin {
             .register_asset::<Mesh>()
             .register_asset::<SubMesh>()
             .add_loader::<SubMesh>()
+            .add_importer::<GltfImporter>()
             .add_resource(MeshAllocatorConfig::default());
     }
 
@@ -1,12 +1,13 @@
 use crate::{
-    ExtractError, GlobalShaderConstant, GlobalShaderConstants, GpuShader, GpuTexture,
-    ProcessAssets, QueueDraws, QueueViews, RenderDevice, RenderMesh, RenderTarget,
-    phases::{PostRender, PreRender, Present, Process, Queue, Render, RenderApp},
+    ComputeDispatch, DrawSorting, ExtractError, FULLSCREEN_SHADER, FrameTracker, FramesInFlight,
+    FullscreenPass, GlobalShaderConstant, GlobalShaderConstants, GpuShader, GpuTexture,
+    ProcessAssets, QueueDraws, QueueViews, RenderDevice, RenderFeatures, RenderMesh, RenderTarget,
+    phases::{Compute, PostRender, PreRender, Present, Process, Queue, Render, RenderApp},
     resources::{
         AssetExtractors, ExtractInfo, Fallbacks, PipelineCache, RenderAsset, RenderAssets,
         RenderResource, ResourceExtractors, Shader,
     },
-    surface::{RenderSurface, RenderSurfaceTexture},
+    surface::{RenderSurface, RenderSurfaceTexture, SurfaceMode},
 };
 use asset::plugin::{AssetAppExt, AssetPlugin};
 use ecs::{AppBuilder, Extract, Init, IntoSystemConfig, Phase, Plugin, Run};
@@ -16,10 +17,12 @@ use window::{Window, plugin::WindowPlugin};
 mod camera;
 mod mesh;
 mod texture;
+mod tonemap;
 
 pub use camera::*;
 pub use mesh::*;
 pub use texture::*;
+pub use tonemap::*;
 
 pub struct RenderPlugin;
 
@@ -34,15 +37,23 @@ impl Plugin for RenderPlugin {
             .add_sub_phase(Queue, QueueViews)
             .add_sub_phase(Queue, QueueDraws)
             .add_sub_phase(Run, PreRender)
+            .add_sub_phase(Run, Compute)
             .add_sub_phase(Run, Render)
             .add_sub_phase(Run, Present)
             .add_sub_phase(Run, PostRender)
             .add_systems(Init, GlobalShaderConstants::init)
             .add_systems(Extract, RenderSurface::resize_surface)
             .add_systems(Queue, RenderSurface::queue_surface)
+            .add_systems(Compute, ComputeDispatch::dispatch)
             .add_systems(Present, RenderSurface::present_surface)
+            .add_systems(PostRender, FramesInFlight::advance)
+            .add_systems(PostRender, FrameTracker::clear)
             .add_resource(RenderSurfaceTexture::new())
+            .add_resource(FramesInFlight::default())
             .add_resource(PipelineCache::default())
+            .add_resource(ComputeDispatch::default())
+            .add_resource(FrameTracker::default())
+            .add_resource(DrawSorting::default())
             .add_resource(GlobalShaderConstants::new())
             .register_event::<ExtractError>();
 
@@ -52,25 +63,43 @@ impl Plugin for RenderPlugin {
             .add_render_asset::<GpuTexture>()
             .add_render_asset::<RenderTarget>()
             .add_importer::<Shader>()
-            .set_default_processor::<Shader>();
+            .set_default_processor::<Shader>()
+            .add_asset::<Shader>(FULLSCREEN_SHADER, FullscreenPass::shader());
     }
 
     fn build(&mut self, app: &mut AppBuilder) {
-        let window = app.resource::<Window>();
+        let mode = app.try_resource::<SurfaceMode>().copied();
         let task = async {
-            let (surface, adapter) = RenderSurface::new(window).await.unwrap();
-            let device = RenderDevice::new(&adapter).await.unwrap();
-
-            surface.configure(&device);
-
-            (surface, device)
+            match mode.unwrap_or_default() {
+                SurfaceMode::Window => {
+                    let window = app.resource::<Window>();
+                    let (surface, adapter) = RenderSurface::new(window).await.unwrap();
+                    let device = RenderDevice::new(&adapter).await.unwrap();
+
+                    surface.configure(&device);
+
+                    (surface, device)
+                }
+                SurfaceMode::Headless { size, format } => {
+                    let adapter = wgpu::Instance::default()
+                        .request_adapter(&wgpu::RequestAdapterOptions::default())
+                        .await
+                        .unwrap();
+                    let device = RenderDevice::new(&adapter).await.unwrap();
+                    let surface = RenderSurface::headless(&device, size, format);
+
+                    (surface, device)
+                }
+            }
         };
 
         let (surface, device) = smol::block_on(task);
+        let features = RenderFeatures::from(&device).select();
 
         app.sub_app_mut(RenderApp)
             .add_resource(surface)
-            .add_resource(device);
+            .add_resource(device)
+            .add_resource(features);
     }
 
     fn finish(&mut self, app: &mut AppBuilder) {
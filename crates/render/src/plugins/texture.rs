@@ -1,5 +1,13 @@
-use crate::{Texture, TextureDimension, plugins::RenderPlugin};
-use asset::{AssetImporter, Settings, plugin::AssetAppExt};
+use crate::{
+    CompressedTextureImporter, Texture, TextureArrayError, TextureDimension, plugins::RenderPlugin,
+};
+use asset::{
+    AssetImporter, Settings, SettingsError,
+    ext::{DeserializeExt, PathExt},
+    importer::ImportContext,
+    io::AsyncIoError,
+    plugin::AssetAppExt,
+};
 use ecs::Plugin;
 
 pub struct Texture2dPlugin;
@@ -8,7 +16,9 @@ impl Plugin for Texture2dPlugin {
     fn setup(&mut self, app: &mut ecs::AppBuilder) {
         app.add_plugins(RenderPlugin)
             .register_asset::<Texture>()
-            .add_importer::<Texture2dImporter>();
+            .add_importer::<Texture2dImporter>()
+            .add_importer::<TextureArrayImporter>()
+            .add_importer::<CompressedTextureImporter>();
     }
 }
 
@@ -24,6 +34,17 @@ pub enum TextureImportFormat {
     RGBA32,
 }
 
+impl TextureImportFormat {
+    /// The texture format to import as. `RGBA8` uses its srgb variant when
+    /// `srgb` is set, while the wider formats always stay linear.
+    pub fn texture_format(self, srgb: bool) -> wgpu::TextureFormat {
+        match self {
+            TextureImportFormat::RGBA8 if srgb => wgpu::TextureFormat::Rgba8UnormSrgb,
+            format => format.into(),
+        }
+    }
+}
+
 impl From<TextureImportFormat> for wgpu::TextureFormat {
     fn from(format: TextureImportFormat) -> Self {
         match format {
@@ -34,9 +55,98 @@ impl From<TextureImportFormat> for wgpu::TextureFormat {
     }
 }
 
-#[derive(Settings, Default, serde::Serialize, serde::Deserialize)]
+#[derive(serde::Serialize, serde::Deserialize)]
 pub struct Texture2dSettings {
     format: TextureImportFormat,
+    /// Splits the image into this many vertically stacked, equally sized
+    /// layers and imports it as a [`TextureDimension::D2Array`].
+    layers: u32,
+    /// Whether the image holds srgb encoded color. Leave this on for albedo
+    /// and other color textures, and turn it off for normal and data maps.
+    is_srgb: bool,
+}
+
+impl Texture2dSettings {
+    pub fn texture_format(&self) -> wgpu::TextureFormat {
+        self.format.texture_format(self.is_srgb)
+    }
+}
+
+impl Default for Texture2dSettings {
+    fn default() -> Self {
+        Self {
+            format: TextureImportFormat::default(),
+            layers: 0,
+            is_srgb: true,
+        }
+    }
+}
+
+impl Settings for Texture2dSettings {
+    const VERSION: u32 = 2;
+
+    fn migrate(version: u32, data: &[u8]) -> Result<Self, SettingsError> {
+        match version {
+            0 => {
+                let settings = Texture2dSettingsV0::from_bytes(data)?;
+                Ok(Self {
+                    format: settings.format,
+                    ..Default::default()
+                })
+            }
+            1 => {
+                let settings = Texture2dSettingsV1::from_bytes(data)?;
+                Ok(Self {
+                    format: settings.format,
+                    layers: settings.layers,
+                    ..Default::default()
+                })
+            }
+            _ => Err(SettingsError::Unmigratable {
+                version,
+                current: Self::VERSION,
+            }),
+        }
+    }
+}
+
+/// [`Texture2dSettings`] before `layers` was added.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Texture2dSettingsV0 {
+    format: TextureImportFormat,
+}
+
+/// [`Texture2dSettings`] before `is_srgb` was added.
+#[derive(Default, serde::Serialize, serde::Deserialize)]
+struct Texture2dSettingsV1 {
+    format: TextureImportFormat,
+    layers: u32,
+}
+
+/// Decodes an image file into `(width, height, pixels)` in `format`.
+fn decode_image(
+    buffer: Vec<u8>,
+    format: TextureImportFormat,
+) -> Result<(u32, u32, Vec<u8>), image::ImageError> {
+    let img = image::ImageReader::new(std::io::Cursor::new(buffer))
+        .with_guessed_format()?
+        .decode()?;
+
+    let width = img.width();
+    let height = img.height();
+    let pixels = match format {
+        TextureImportFormat::RGBA8 => img.into_rgba8().into_raw(),
+        TextureImportFormat::RGBA16 => {
+            let rgba16 = img.into_rgba16().into_raw();
+            bytemuck::cast_vec(rgba16)
+        }
+        TextureImportFormat::RGBA32 => {
+            let rgba32 = img.into_rgba32f().into_raw();
+            bytemuck::cast_vec(rgba32)
+        }
+    };
+
+    Ok((width, height, pixels))
 }
 
 impl AssetImporter for Texture2dImporter {
@@ -46,6 +156,10 @@ impl AssetImporter for Texture2dImporter {
 
     type Error = image::ImageError;
 
+    /// Bumped when [`Texture`] gained `mip_levels`, so textures imported
+    /// before it are imported again.
+    const VERSION: u32 = 1;
+
     async fn import(
         ctx: &mut asset::importer::ImportContext<'_>,
         reader: &mut dyn asset::io::AsyncReader,
@@ -61,21 +175,14 @@ impl AssetImporter for Texture2dImporter {
             ))
         })?;
 
-        let img = ImageReader::new(std::io::Cursor::new(buffer))
-            .with_guessed_format()?
-            .decode()?;
-
-        let width = img.width();
-        let height = img.height();
-        let pixels = match metadata.format {
-            TextureImportFormat::RGBA8 => img.into_rgba8().into_raw(),
-            TextureImportFormat::RGBA16 => {
-                let rgba16 = img.into_rgba16().into_raw();
-                bytemuck::cast_vec(rgba16)
-            }
-            TextureImportFormat::RGBA32 => {
-                let rgba32 = img.into_rgba32f().into_raw();
-                bytemuck::cast_vec(rgba32)
+        let (width, height, pixels) = decode_image(buffer, metadata.format)?;
+
+        let (height, layers, dimension) = match metadata.layers {
+            0 | 1 => (height, 1, TextureDimension::D2),
+            layers if height % layers == 0 => (height / layers, layers, TextureDimension::D2Array),
+            _ => {
+                let kind = ParameterErrorKind::DimensionMismatch;
+                return Err(ImageError::Parameter(ParameterError::from_kind(kind)));
             }
         };
 
@@ -83,10 +190,10 @@ impl AssetImporter for Texture2dImporter {
             wgpu::Extent3d {
                 width,
                 height,
-                depth_or_array_layers: 1,
+                depth_or_array_layers: layers,
             },
-            TextureDimension::D2,
-            metadata.format.into(),
+            dimension,
+            metadata.texture_format(),
             pixels,
         );
 
@@ -95,8 +202,267 @@ impl AssetImporter for Texture2dImporter {
 
     fn extensions() -> &'static [&'static str] {
         &[
-            "png", "jpg", "jpeg", "gif", "webp", "tiff", "tga", "dds", "bmp", "ico", "hrd", "avif",
-            "qoi",
+            "png", "jpg", "jpeg", "gif", "webp", "tiff", "tga", "bmp", "ico", "hrd", "avif", "qoi",
         ]
     }
 }
+
+/// Imports the images in a folder as the layers of a
+/// [`TextureDimension::D2Array`] texture. Add an empty `.texarray` file to the
+/// folder; the images next to it are stacked in file name order.
+pub struct TextureArrayImporter;
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct TextureArraySettings {
+    format: TextureImportFormat,
+    is_srgb: bool,
+}
+
+impl TextureArraySettings {
+    pub fn texture_format(&self) -> wgpu::TextureFormat {
+        self.format.texture_format(self.is_srgb)
+    }
+}
+
+impl Default for TextureArraySettings {
+    fn default() -> Self {
+        Self {
+            format: TextureImportFormat::default(),
+            is_srgb: true,
+        }
+    }
+}
+
+impl Settings for TextureArraySettings {
+    const VERSION: u32 = 1;
+
+    fn migrate(version: u32, data: &[u8]) -> Result<Self, SettingsError> {
+        match version {
+            0 => {
+                let settings = TextureArraySettingsV0::from_bytes(data)?;
+                Ok(Self {
+                    format: settings.format,
+                    ..Default::default()
+                })
+            }
+            _ => Err(SettingsError::Unmigratable {
+                version,
+                current: Self::VERSION,
+            }),
+        }
+    }
+}
+
+/// [`TextureArraySettings`] before `is_srgb` was added.
+#[derive(Default, serde::Serialize, serde::Deserialize)]
+struct TextureArraySettingsV0 {
+    format: TextureImportFormat,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TextureArrayImportError {
+    #[error(transparent)]
+    Io(#[from] AsyncIoError),
+
+    #[error(transparent)]
+    Image(#[from] image::ImageError),
+
+    #[error(transparent)]
+    Array(#[from] TextureArrayError),
+}
+
+impl AssetImporter for TextureArrayImporter {
+    type Asset = Texture;
+
+    type Settings = TextureArraySettings;
+
+    type Error = TextureArrayImportError;
+
+    async fn import(
+        ctx: &mut ImportContext<'_>,
+        _: &mut dyn asset::io::AsyncReader,
+        metadata: &asset::AssetSettings<Self::Settings>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let images = Texture2dImporter::extensions();
+
+        let mut layers = Vec::new();
+        for path in ctx.sibling_paths().await? {
+            let (Some(name), Some(ext)) = (path.file_name(), path.ext()) else {
+                continue;
+            };
+
+            if !images.contains(&ext.to_lowercase().as_str()) {
+                continue;
+            }
+
+            let mut buffer = Vec::new();
+            ctx.read_sibling(name)
+                .await?
+                .read_to_end(&mut buffer)
+                .await?;
+
+            let (width, height, pixels) = decode_image(buffer, metadata.format)?;
+            layers.push(Texture::new(
+                wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+                TextureDimension::D2,
+                metadata.texture_format(),
+                pixels,
+            ));
+        }
+
+        Ok(Texture::stack(&layers)?)
+    }
+
+    fn extensions() -> &'static [&'static str] {
+        &["texarray"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Texture2dSettings, Texture2dSettingsV0, Texture2dSettingsV1, TextureArrayImporter,
+        TextureArraySettings, TextureArraySettingsV0, TextureImportFormat,
+    };
+    use crate::{Texture, TextureDimension};
+    use asset::{
+        AssetSettings, AssetType, ErasedId, Settings,
+        ext::SerializeExt,
+        importer::{AssetImporter, ImportContext},
+        io::{AssetPath, AsyncWriter, FileSystem, VirtualFs},
+        registry::AssetRegistry,
+        sources::AssetFileSystem,
+    };
+    use smol::io::AsyncWriteExt;
+
+    #[test]
+    fn migrate_settings_without_layers() {
+        let id = ErasedId::new();
+        let settings = Texture2dSettingsV0 {
+            format: TextureImportFormat::RGBA16,
+        };
+        let bytes = (id, settings).to_bytes().unwrap();
+
+        let settings = AssetSettings::<Texture2dSettings>::decode(&bytes).unwrap();
+        assert_eq!(settings.id, id);
+        assert_eq!(settings.format, TextureImportFormat::RGBA16);
+        assert_eq!(settings.layers, 0);
+        assert!(settings.is_srgb);
+    }
+
+    #[test]
+    fn migrate_settings_without_srgb() {
+        impl Settings for Texture2dSettingsV1 {
+            const VERSION: u32 = 1;
+        }
+
+        let settings = Texture2dSettingsV1 {
+            format: TextureImportFormat::RGBA8,
+            layers: 4,
+        };
+        let settings = AssetSettings::new(ErasedId::new(), settings);
+        let bytes = settings.to_bytes().unwrap();
+
+        let migrated = AssetSettings::<Texture2dSettings>::decode(&bytes).unwrap();
+        assert_eq!(migrated.id, settings.id);
+        assert_eq!(migrated.layers, 4);
+        assert!(migrated.is_srgb);
+        assert_eq!(
+            migrated.texture_format(),
+            wgpu::TextureFormat::Rgba8UnormSrgb
+        );
+
+        let bytes = migrated.to_bytes().unwrap();
+        let settings = AssetSettings::<Texture2dSettings>::decode(&bytes).unwrap();
+        assert_eq!(settings.layers, 4);
+        assert!(settings.is_srgb);
+    }
+
+    #[test]
+    fn migrate_array_settings_without_srgb() {
+        impl Settings for TextureArraySettingsV0 {}
+
+        let settings = TextureArraySettingsV0 {
+            format: TextureImportFormat::RGBA8,
+        };
+        let settings = AssetSettings::new(ErasedId::new(), settings);
+        let bytes = settings.to_bytes().unwrap();
+
+        let migrated = AssetSettings::<TextureArraySettings>::decode(&bytes).unwrap();
+        assert_eq!(migrated.id, settings.id);
+        assert!(migrated.is_srgb);
+        assert_eq!(
+            migrated.texture_format(),
+            wgpu::TextureFormat::Rgba8UnormSrgb
+        );
+    }
+
+    fn png(value: u8) -> Vec<u8> {
+        let image = image::RgbaImage::from_pixel(8, 8, image::Rgba([value; 4]));
+        let mut bytes = std::io::Cursor::new(Vec::new());
+        image.write_to(&mut bytes, image::ImageFormat::Png).unwrap();
+        bytes.into_inner()
+    }
+
+    #[test]
+    fn import_texture_array_folder() {
+        let files = [
+            ("sprites/walk.texarray", Vec::new()),
+            ("sprites/walk_0.png", png(0)),
+            ("sprites/walk_1.png", png(1)),
+            ("sprites/walk_2.png", png(2)),
+        ];
+
+        let source = VirtualFs::new();
+        smol::block_on(async {
+            source.create_dir("sprites".as_ref()).await.unwrap();
+            for (path, data) in &files {
+                let mut writer = source.writer(path.as_ref()).await.unwrap();
+                writer.write_all(data).await.unwrap();
+                AsyncWriter::flush(&mut writer).await.unwrap();
+            }
+        });
+
+        let fs = AssetFileSystem::new(source);
+        let registry = AssetRegistry::new();
+        let path = AssetPath::from("sprites/walk.texarray");
+        let mut ctx =
+            ImportContext::new(&fs, &registry, &path, ErasedId::new(), AssetType::UNKNOWN);
+        let settings = AssetSettings::new(ErasedId::new(), TextureArraySettings::default());
+
+        let texture: Texture = smol::block_on(async {
+            let mut reader = fs.reader(path.path()).await.unwrap();
+            TextureArrayImporter::import(&mut ctx, &mut *reader, &settings).await
+        })
+        .unwrap();
+
+        assert_eq!(texture.dimension, TextureDimension::D2Array);
+        assert_eq!(texture.depth_or_layers, 3);
+        assert_eq!((texture.width, texture.height), (8, 8));
+
+        let layer_size = 8 * 8 * 4;
+        for (index, pixels) in texture.pixels.chunks_exact(layer_size).enumerate() {
+            assert!(pixels.iter().all(|value| *value == index as u8));
+        }
+    }
+
+    #[test]
+    fn srgb_format() {
+        let mut settings = Texture2dSettings::default();
+        assert_eq!(
+            settings.texture_format(),
+            wgpu::TextureFormat::Rgba8UnormSrgb
+        );
+
+        settings.is_srgb = false;
+        assert_eq!(settings.texture_format(), wgpu::TextureFormat::Rgba8Unorm);
+
+        settings.is_srgb = true;
+        settings.format = TextureImportFormat::RGBA16;
+        assert_eq!(settings.texture_format(), wgpu::TextureFormat::Rgba16Unorm);
+    }
+}
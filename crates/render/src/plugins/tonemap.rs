@@ -0,0 +1,25 @@
+use crate::{
+    CameraPhase, Msaa, PreRender, Render, RenderApp, Shader, TONEMAP_SHADER, TonemapPhase,
+    TonemapPipeline, TonemapTargets, Tonemapping,
+    plugins::{CameraPlugin, RenderAppExt, RenderPlugin},
+};
+use asset::plugin::AssetAppExt;
+use ecs::{AppBuilder, Plugin};
+
+pub struct TonemapPlugin;
+
+impl Plugin for TonemapPlugin {
+    fn setup(&mut self, app: &mut AppBuilder) {
+        app.add_plugins((RenderPlugin, CameraPlugin))
+            .add_asset::<Shader>(TONEMAP_SHADER, TonemapPipeline::shader())
+            .add_render_resource::<TonemapPipeline>()
+            .sub_app_mut(RenderApp)
+            .add_sub_phase(Render, TonemapPhase)
+            .run_after(TonemapPhase, CameraPhase)
+            .add_resource(Tonemapping::default())
+            .add_resource(Msaa::default())
+            .add_resource(TonemapTargets::default())
+            .add_systems(PreRender, TonemapTargets::queue)
+            .add_systems(TonemapPhase, TonemapTargets::tonemap);
+    }
+}
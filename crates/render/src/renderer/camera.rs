@@ -1,7 +1,6 @@
 use crate::{
-    RenderAssets, RenderDevice, RenderSurface, RenderSurfaceTexture, RenderTarget,
-    primitives::{Color, Viewport},
-    resources::RenderTexture,
+    ClearColor, Environment, RenderAssets, RenderDevice, RenderSurface, RenderSurfaceTexture,
+    RenderTarget, primitives::Viewport, resources::RenderTexture,
 };
 use asset::AssetId;
 use ecs::{
@@ -9,13 +8,14 @@ use ecs::{
     world::WorldCell,
 };
 use encase::ShaderType;
-use math::{Mat4, Size, Vec3, Vec3A, Vec4, bounds::Bounds, sphere::Sphere};
+use math::{Mat4, Size, Vec2, Vec3, Vec3A, Vec4, bounds::Bounds, ray::Ray, sphere::Sphere};
+use transform::basis;
 
 #[derive(Debug, Clone, Component, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct Camera {
     pub viewport: Viewport,
     pub order: i32,
-    pub clear_color: Option<Color>,
+    pub clear_color: ClearColor,
     pub target: Option<AssetId<RenderTexture>>,
 }
 
@@ -30,6 +30,92 @@ impl Default for Camera {
     }
 }
 
+impl Camera {
+    /// Casts a ray from a cursor position in window pixels, with the origin
+    /// at the top left of the window. `world_from_view` is the camera's
+    /// global transform.
+    ///
+    /// The ray starts on the near plane. Orthographic rays all point along
+    /// the camera's forward axis, while perspective rays point away from the
+    /// camera position. Returns `None` if the viewport has no area.
+    pub fn screen_to_ray(
+        &self,
+        projection: &Projection,
+        world_from_view: Mat4,
+        cursor: Vec2,
+        window: Size,
+    ) -> Option<Ray> {
+        let viewport = self.viewport.scale(window.width, window.height);
+        if viewport.width <= 0.0 || viewport.height <= 0.0 {
+            return None;
+        }
+
+        // Screen space y points down while NDC y points up.
+        let ndc = Vec2::new(
+            (cursor.x - viewport.x) / viewport.width * 2.0 - 1.0,
+            1.0 - (cursor.y - viewport.y) / viewport.height * 2.0,
+        );
+
+        let clip_from_view = projection.matrix(viewport.width, viewport.height);
+        let world_from_clip = world_from_view * clip_from_view.inverse();
+
+        match projection {
+            Projection::Orthographic { .. } => {
+                let origin = world_from_clip.project_point3(ndc.extend(0.0));
+                let forward = world_from_view.transform_vector3(basis::VIEW_FORWARD);
+                Some(Ray::new(origin, forward))
+            }
+            Projection::Perspective { .. } | Projection::PerspectiveFinite { .. } => {
+                // Reverse z puts the near plane at a depth of one.
+                let origin = world_from_clip.project_point3(ndc.extend(1.0));
+                let eye = world_from_view.transform_point3(Vec3::ZERO);
+                Some(Ray::new(origin, origin - eye))
+            }
+        }
+    }
+
+    /// Converts a cursor position in window pixels to a point on the camera's
+    /// near plane. For 2D orthographic cameras the x and y components are the
+    /// world position under the cursor.
+    pub fn screen_to_world(
+        &self,
+        projection: &Projection,
+        world_from_view: Mat4,
+        cursor: Vec2,
+        window: Size,
+    ) -> Option<Vec3> {
+        self.screen_to_ray(projection, world_from_view, cursor, window)
+            .map(|ray| ray.origin)
+    }
+
+    /// Converts a world space point to window pixels, with the origin at the
+    /// top left of the window. Returns `None` if the point is behind the camera.
+    pub fn world_to_screen(
+        &self,
+        projection: &Projection,
+        world_from_view: Mat4,
+        point: Vec3,
+        window: Size,
+    ) -> Option<Vec2> {
+        let viewport = self.viewport.scale(window.width, window.height);
+        if viewport.width <= 0.0 || viewport.height <= 0.0 {
+            return None;
+        }
+
+        let clip_from_view = projection.matrix(viewport.width, viewport.height);
+        let clip = clip_from_view * world_from_view.inverse() * point.extend(1.0);
+        if clip.w <= 0.0 {
+            return None;
+        }
+
+        let ndc = clip.truncate() / clip.w;
+        Some(Vec2::new(
+            viewport.x + (ndc.x + 1.0) * 0.5 * viewport.width,
+            viewport.y + (1.0 - ndc.y) * 0.5 * viewport.height,
+        ))
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum Projection {
     Orthographic {
@@ -37,11 +123,20 @@ pub enum Projection {
         far: f32,
         size: f32,
     },
+    /// A perspective projection with an infinite far plane. `far` only
+    /// bounds the frustum used for culling and shadow cascades.
     Perspective {
         fov: f32, // in radians
         near: f32,
         far: f32,
     },
+    /// A perspective projection that clips geometry beyond `far`, trading
+    /// view distance for depth precision.
+    PerspectiveFinite {
+        fov: f32, // in radians
+        near: f32,
+        far: f32,
+    },
 }
 
 impl Projection {
@@ -49,6 +144,10 @@ impl Projection {
         Projection::Perspective { fov, near, far }
     }
 
+    pub fn perspective_finite(fov: f32, near: f32, far: f32) -> Self {
+        Projection::PerspectiveFinite { fov, near, far }
+    }
+
     pub fn orthographic(near: f32, far: f32, size: f32) -> Self {
         Projection::Orthographic { near, far, size }
     }
@@ -57,6 +156,7 @@ impl Projection {
         match self {
             Projection::Orthographic { near, .. } => *near,
             Projection::Perspective { near, .. } => *near,
+            Projection::PerspectiveFinite { near, .. } => *near,
         }
     }
 
@@ -64,6 +164,7 @@ impl Projection {
         match self {
             Projection::Orthographic { far, .. } => *far,
             Projection::Perspective { far, .. } => *far,
+            Projection::PerspectiveFinite { far, .. } => *far,
         }
     }
 
@@ -72,10 +173,13 @@ impl Projection {
         match *self {
             Projection::Orthographic { near, far, size } => {
                 let width = size * aspect_ratio;
-                Mat4::orthographic_rh(-width, width, -size, size, near, far)
+                basis::orthographic(-width, width, -size, size, near, far)
             }
             Projection::Perspective { fov, near, .. } => {
-                Mat4::perspective_infinite_reverse_rh(fov, aspect_ratio, near)
+                basis::perspective_infinite_reverse(fov, aspect_ratio, near)
+            }
+            Projection::PerspectiveFinite { fov, near, far } => {
+                basis::perspective_reverse(fov, aspect_ratio, near, far)
             }
         }
     }
@@ -100,7 +204,8 @@ impl Projection {
                     Vec3A::new(left, bottom, far),   // bottom left
                 ]
             }
-            Projection::Perspective { fov, near, far } => {
+            Projection::Perspective { fov, near, far }
+            | Projection::PerspectiveFinite { fov, near, far } => {
                 let aspect_ratio = width as f32 / height as f32;
                 let tan_half_fov = f32::tan(fov / 2.0);
                 let a = near.abs() * tan_half_fov;
@@ -124,7 +229,7 @@ impl Projection {
 #[derive(Component)]
 pub struct CameraAttachments {
     pub size: Size<u32>,
-    pub clear: Option<Color>,
+    pub clear: ClearColor,
     pub color: Option<wgpu::TextureView>,
     pub depth: wgpu::TextureView,
     pub target: Option<AssetId<RenderTexture>>,
@@ -138,6 +243,7 @@ impl CameraAttachments {
         targets: &RenderAssets<RenderTarget>,
         device: &RenderDevice,
         surface: &RenderSurfaceTexture,
+        render_surface: &RenderSurface,
         sort_order: &mut CameraSortOrder,
         mut commands: Commands,
     ) {
@@ -146,10 +252,10 @@ impl CameraAttachments {
         for (entity, camera) in cameras.iter() {
             let target = match camera.target {
                 Some(id) => targets.get(&id).map(|t| (t.size(), t.view().clone())),
-                None => surface.get().map(|s| {
+                None => surface.get().map(|texture| {
                     (
-                        Size::new(s.texture.width(), s.texture.height()),
-                        s.texture.create_view(&Default::default()),
+                        Size::new(texture.width(), texture.height()),
+                        texture.create_view(&Default::default()),
                     )
                 }),
             };
@@ -159,12 +265,11 @@ impl CameraAttachments {
             };
 
             let viewport = camera.viewport.scale(size.width as f32, size.height as f32);
-            let depth = Self::create_depth_texture(device, size, RenderSurface::DEPTH_FORMAT);
             let attachments = CameraAttachments {
                 size,
                 clear: camera.clear_color,
                 color: Some(color),
-                depth: depth.create_view(&Default::default()),
+                depth: Self::depth_view(device, render_surface, camera.target, size),
                 target: camera.target,
                 viewport,
             };
@@ -176,10 +281,10 @@ impl CameraAttachments {
         for (entity, camera, attachments) in attachments.iter() {
             let target = match camera.target {
                 Some(id) => targets.get(&id).map(|t| (t.size(), t.view().clone())),
-                None => surface.get().map(|s| {
+                None => surface.get().map(|texture| {
                     (
-                        Size::new(s.texture.width(), s.texture.height()),
-                        s.texture.create_view(&Default::default()),
+                        Size::new(texture.width(), texture.height()),
+                        texture.create_view(&Default::default()),
                     )
                 }),
             };
@@ -194,8 +299,7 @@ impl CameraAttachments {
             attachments.viewport = camera.viewport.scale(size.width as f32, size.height as f32);
 
             if camera.target != attachments.target || size != attachments.size {
-                let depth = Self::create_depth_texture(device, size, RenderSurface::DEPTH_FORMAT);
-                attachments.depth = depth.create_view(&Default::default());
+                attachments.depth = Self::depth_view(device, render_surface, camera.target, size);
                 attachments.size = size;
                 attachments.target = camera.target;
             }
@@ -214,6 +318,21 @@ impl CameraAttachments {
         }
     }
 
+    /// Cameras that draw to a headless surface share its depth texture, so
+    /// it can be read back along with the color.
+    fn depth_view(
+        device: &RenderDevice,
+        surface: &RenderSurface,
+        target: Option<AssetId<RenderTexture>>,
+        size: Size<u32>,
+    ) -> wgpu::TextureView {
+        match surface.depth_texture() {
+            Some(depth) if target.is_none() => depth.create_view(&Default::default()),
+            _ => Self::create_depth_texture(device, size, RenderSurface::DEPTH_FORMAT)
+                .create_view(&Default::default()),
+        }
+    }
+
     fn create_depth_texture(
         device: &RenderDevice,
         size: Size<u32>,
@@ -262,16 +381,14 @@ impl CameraPhase {
     fn clear_screen(&self, world: WorldCell, camera: Entity) -> Option<()> {
         let world = unsafe { world.get() };
         let device = world.resource::<RenderDevice>();
+        let environment = world.resource::<Environment>();
         let attachments = world.get_component::<CameraAttachments>(camera)?;
         let color = attachments.color.as_ref()?;
         let color_attachments = vec![Some(wgpu::RenderPassColorAttachment {
             view: color,
             resolve_target: None,
             ops: wgpu::Operations {
-                load: attachments
-                    .clear
-                    .map(|c| wgpu::LoadOp::Clear(c.into()))
-                    .unwrap_or(wgpu::LoadOp::Load),
+                load: environment.load_op(attachments.clear),
                 store: wgpu::StoreOp::Store,
             },
         })];
@@ -309,9 +426,9 @@ impl Phase for CameraPhase {
         unsafe {
             for (camera, _) in cameras.0 {
                 if self.clear_screen(world, camera).is_some() {
-                    world.get_mut().add_component(camera, ActiveCamera);
+                    world.borrow_world_mut().add_component(camera, ActiveCamera);
                     ctx.execute();
-                    world.get_mut().remove_component::<ActiveCamera>(camera);
+                    world.borrow_world_mut().remove_component::<ActiveCamera>(camera);
                 }
             }
         }
@@ -413,3 +530,116 @@ impl Frustum {
         true // Intersects
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Camera, Projection};
+    use math::{Mat4, Size, Vec2, Vec3};
+    use transform::basis;
+
+    const WINDOW: Size = Size {
+        width: 800.0,
+        height: 600.0,
+    };
+
+    fn assert_near(a: Vec3, b: Vec3) {
+        assert!(a.abs_diff_eq(b, 1e-3), "{} != {}", a, b);
+    }
+
+    #[test]
+    fn orthographic_screen_to_world() {
+        let camera = Camera::default();
+        let projection = Projection::orthographic(0.1, 100.0, 5.0);
+        let world_from_view = Mat4::from_translation(Vec3::new(10.0, 20.0, 50.0));
+
+        let ray = camera
+            .screen_to_ray(
+                &projection,
+                world_from_view,
+                Vec2::new(400.0, 300.0),
+                WINDOW,
+            )
+            .unwrap();
+        assert_near(ray.origin, Vec3::new(10.0, 20.0, 49.9));
+        assert_near(ray.direction, Vec3::NEG_Z);
+
+        let top_left = camera
+            .screen_to_world(&projection, world_from_view, Vec2::ZERO, WINDOW)
+            .unwrap();
+        assert_near(top_left, Vec3::new(10.0 - 5.0 * 800.0 / 600.0, 25.0, 49.9));
+
+        let bottom_right = camera
+            .screen_to_world(
+                &projection,
+                world_from_view,
+                Vec2::new(800.0, 600.0),
+                WINDOW,
+            )
+            .unwrap();
+        assert_near(
+            bottom_right,
+            Vec3::new(10.0 + 5.0 * 800.0 / 600.0, 15.0, 49.9),
+        );
+
+        let screen = camera
+            .world_to_screen(&projection, world_from_view, top_left, WINDOW)
+            .unwrap();
+        assert!(screen.abs_diff_eq(Vec2::ZERO, 1e-2));
+    }
+
+    #[test]
+    fn perspective_far_plane() {
+        let fov = std::f32::consts::FRAC_PI_2;
+        let infinite = Projection::perspective(fov, 0.1, 100.0).matrix(800.0, 600.0);
+        let finite = Projection::perspective_finite(fov, 0.1, 100.0).matrix(800.0, 600.0);
+
+        let depth =
+            |matrix: Mat4, distance: f32| matrix.project_point3(basis::VIEW_FORWARD * distance).z;
+
+        assert!((depth(infinite, 0.1) - 1.0).abs() < 1e-4);
+        assert!((depth(finite, 0.1) - 1.0).abs() < 1e-4);
+        assert!(depth(finite, 100.0).abs() < 1e-4);
+        assert!(depth(infinite, 100.0) > 1e-4);
+        assert!(depth(finite, 200.0) < 0.0);
+        assert!(depth(infinite, 200.0) > 0.0);
+    }
+
+    #[test]
+    fn perspective_screen_to_ray() {
+        let camera = Camera::default();
+        let projection = Projection::perspective(std::f32::consts::FRAC_PI_2, 0.1, 100.0);
+        let eye = Vec3::new(1.0, 2.0, 3.0);
+        let world_from_view = Mat4::from_translation(eye);
+
+        let ray = camera
+            .screen_to_ray(
+                &projection,
+                world_from_view,
+                Vec2::new(400.0, 300.0),
+                WINDOW,
+            )
+            .unwrap();
+        assert_near(ray.origin, eye + Vec3::new(0.0, 0.0, -0.1));
+        assert_near(ray.direction, Vec3::NEG_Z);
+
+        // A 90 degree fov puts the top left corner one unit up and
+        // `aspect` units left for every unit forward.
+        let corner = Vec3::new(-800.0 / 600.0, 1.0, -1.0);
+        let ray = camera
+            .screen_to_ray(&projection, world_from_view, Vec2::ZERO, WINDOW)
+            .unwrap();
+        assert_near(ray.direction, corner.normalize());
+
+        let screen = camera
+            .world_to_screen(&projection, world_from_view, eye + corner * 10.0, WINDOW)
+            .unwrap();
+        assert!(screen.abs_diff_eq(Vec2::ZERO, 1e-2));
+
+        let behind = eye + Vec3::Z;
+        assert!(
+            camera
+                .world_to_screen(&projection, world_from_view, behind, WINDOW)
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,313 @@
+use crate::{
+    BindGroup, BufferId, ComputePipelineDesc, FrameTracker, PassKind, PipelineCache, PipelineId,
+    RenderCommandEncoder,
+};
+use ecs::Resource;
+
+/// A step recorded into a compute pass. Returning false means the step
+/// couldn't run yet (e.g. its pipeline is still compiling), and the rest of
+/// the command is skipped for this frame.
+pub trait ComputeCommand: Send + Sync + 'static {
+    fn execute(&self, pass: &mut wgpu::ComputePass<'_>, pipelines: &PipelineCache) -> bool;
+}
+
+pub struct SetComputePipeline(pub PipelineId);
+
+impl ComputeCommand for SetComputePipeline {
+    fn execute(&self, pass: &mut wgpu::ComputePass<'_>, pipelines: &PipelineCache) -> bool {
+        match pipelines.get_compute_pipeline(&self.0) {
+            Some(pipeline) => {
+                pass.set_pipeline(pipeline);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+pub struct SetComputeBindGroup {
+    pub group: u32,
+    pub bind_group: BindGroup,
+    pub offsets: Vec<u32>,
+}
+
+impl SetComputeBindGroup {
+    pub fn new(group: u32, bind_group: BindGroup) -> Self {
+        Self {
+            group,
+            bind_group,
+            offsets: vec![],
+        }
+    }
+}
+
+impl ComputeCommand for SetComputeBindGroup {
+    fn execute(&self, pass: &mut wgpu::ComputePass<'_>, _: &PipelineCache) -> bool {
+        pass.set_bind_group(self.group, Some(self.bind_group.as_ref()), &self.offsets);
+        true
+    }
+}
+
+pub struct DispatchWorkgroups {
+    pub x: u32,
+    pub y: u32,
+    pub z: u32,
+}
+
+impl DispatchWorkgroups {
+    pub fn new(x: u32, y: u32, z: u32) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl ComputeCommand for DispatchWorkgroups {
+    fn execute(&self, pass: &mut wgpu::ComputePass<'_>, _: &PipelineCache) -> bool {
+        pass.dispatch_workgroups(self.x, self.y, self.z);
+        true
+    }
+}
+
+impl<C: ComputeCommand> ComputeCommand for Vec<C> {
+    fn execute(&self, pass: &mut wgpu::ComputePass<'_>, pipelines: &PipelineCache) -> bool {
+        self.iter().all(|command| command.execute(pass, pipelines))
+    }
+}
+
+macro_rules! impl_compute_command_for_tuples {
+    ($($name:ident),*) => {
+        #[allow(non_snake_case)]
+        impl<$($name: ComputeCommand),*> ComputeCommand for ($($name),*) {
+            fn execute(&self, pass: &mut wgpu::ComputePass<'_>, pipelines: &PipelineCache) -> bool {
+                let ($($name),*) = self;
+                $(
+                    if !$name.execute(pass, pipelines) {
+                        return false;
+                    }
+                )*
+
+                true
+            }
+        }
+    };
+}
+
+variadics::variable_impl!(impl_compute_command_for_tuples, C, 2, 16);
+
+/// Compute work dispatched every frame in the [`Compute`](crate::Compute)
+/// phase, before any render passes. Commands run in the order they were
+/// added and share a single compute pass.
+#[derive(Resource, Default)]
+pub struct ComputeDispatch {
+    commands: Vec<Box<dyn ComputeCommand>>,
+    writes: Vec<BufferId>,
+}
+
+impl ComputeDispatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, command: impl ComputeCommand) -> &mut Self {
+        self.commands.push(Box::new(command));
+        self
+    }
+
+    /// Queues `desc` and adds a command that binds `bind_groups` in order
+    /// before dispatching `workgroups`.
+    pub fn add_dispatch(
+        &mut self,
+        pipelines: &mut PipelineCache,
+        desc: ComputePipelineDesc,
+        bind_groups: Vec<BindGroup>,
+        workgroups: [u32; 3],
+    ) -> PipelineId {
+        let pipeline = pipelines.queue_compute_pipeline(desc);
+        let bind_groups = bind_groups
+            .into_iter()
+            .enumerate()
+            .map(|(group, bind_group)| SetComputeBindGroup::new(group as u32, bind_group))
+            .collect::<Vec<_>>();
+
+        let [x, y, z] = workgroups;
+        self.add((
+            SetComputePipeline(pipeline),
+            bind_groups,
+            DispatchWorkgroups::new(x, y, z),
+        ));
+
+        pipeline
+    }
+
+    /// Declares that the dispatched commands write `buffer`, so render
+    /// passes reading it are checked against the compute pass.
+    pub fn writes(&mut self, buffer: BufferId) -> &mut Self {
+        if !self.writes.contains(&buffer) {
+            self.writes.push(buffer);
+        }
+
+        self
+    }
+
+    /// Records the declared writes into `tracker`.
+    pub fn record(&self, tracker: &FrameTracker) {
+        for buffer in &self.writes {
+            tracker.write(*buffer, PassKind::Compute);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.commands.clear();
+        self.writes.clear();
+    }
+
+    /// Records every command into one compute pass. Returns the number of
+    /// commands that ran to completion.
+    pub fn encode(&self, encoder: &mut wgpu::CommandEncoder, pipelines: &PipelineCache) -> usize {
+        if self.commands.is_empty() {
+            return 0;
+        }
+
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("ComputeDispatch"),
+            timestamp_writes: None,
+        });
+
+        self.commands
+            .iter()
+            .filter(|command| command.execute(&mut pass, pipelines))
+            .count()
+    }
+
+    pub(crate) fn dispatch(
+        mut encoder: RenderCommandEncoder,
+        dispatch: &ComputeDispatch,
+        pipelines: &PipelineCache,
+    ) {
+        if dispatch.is_empty() {
+            return;
+        }
+
+        dispatch.record(encoder.tracker());
+        dispatch.encode(&mut encoder, pipelines);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ComputeDispatch;
+    use crate::{
+        BindGroupBuilder, BindGroupLayoutBuilder, Buffer, BufferId, ComputePipelineDesc,
+        FrameTracker, GpuShader, PassKind, PipelineCache, RenderAssets, ResourceOrderError, Shader,
+        device::tests::device, processor::ShaderConstants,
+    };
+    use asset::AssetId;
+    use std::{borrow::Cow, collections::HashMap};
+
+    const SHADER: &str = r#"
+        @group(0) @binding(0) var<storage, read_write> output: array<u32>;
+
+        @compute @workgroup_size(1)
+        fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+            output[id.x] = 42u + id.x;
+        }
+    "#;
+
+    #[test]
+    fn dispatch_records_writes() {
+        let buffer = BufferId::new();
+        let mut dispatch = ComputeDispatch::new();
+        dispatch.writes(buffer);
+
+        let tracker = FrameTracker::new();
+        dispatch.record(&tracker);
+        tracker.read(buffer, PassKind::Render);
+        assert_eq!(tracker.validate(), Ok(()));
+
+        let tracker = FrameTracker::new();
+        tracker.read(buffer, PassKind::Render);
+        dispatch.record(&tracker);
+        assert_eq!(
+            tracker.validate(),
+            Err(ResourceOrderError::ReadBeforeWrite {
+                buffer,
+                read: PassKind::Render,
+                write: PassKind::Compute,
+            })
+        );
+    }
+
+    #[test]
+    #[ignore = "requires a GPU adapter"]
+    fn dispatch_writes_storage_buffer() {
+        let device = device();
+
+        let id = AssetId::<Shader>::from_u128(1);
+        let source = Shader::Wgsl {
+            data: Cow::Borrowed(SHADER),
+            dependencies: HashMap::new(),
+            constants: Default::default(),
+        };
+
+        let mut shaders = RenderAssets::<GpuShader>::new();
+        let shader = GpuShader::new(&device, source, &ShaderConstants::new(), None).unwrap();
+        shaders.add(id, shader);
+
+        let layout = BindGroupLayoutBuilder::new()
+            .with_storage(0, wgpu::ShaderStages::COMPUTE, false, false, None, None)
+            .build(&device);
+
+        let size = 4 * std::mem::size_of::<u32>() as u64;
+        let usage = wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC;
+        let output = Buffer::new(&device, size, usage, None);
+        let bind_group = BindGroupBuilder::new(&layout)
+            .with_storage(0, &output, 0, None)
+            .build(&device)
+            .unwrap();
+
+        let mut pipelines = PipelineCache::new();
+        let mut dispatch = ComputeDispatch::new();
+        let desc = ComputePipelineDesc {
+            label: None,
+            layout: vec![layout.clone()],
+            shader: *id.as_ref(),
+            entry: "main".into(),
+        };
+        dispatch.add_dispatch(&mut pipelines, desc, vec![bind_group], [4, 1, 1]);
+
+        let mut encoder = device.create_command_encoder(&Default::default());
+        assert_eq!(dispatch.encode(&mut encoder, &pipelines), 0);
+
+        pipelines.process_queue(&device, &shaders);
+        assert_eq!(dispatch.encode(&mut encoder, &pipelines), 1);
+
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        encoder.copy_buffer_to_buffer(output.as_ref(), 0, &readback, 0, size);
+        device.queue.submit(std::iter::once(encoder.finish()));
+
+        let slice = readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |result| result.unwrap());
+        device.poll(wgpu::PollType::Wait).unwrap();
+
+        let data = slice.get_mapped_range();
+        let values = data
+            .chunks_exact(4)
+            .map(|bytes| u32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .collect::<Vec<_>>();
+
+        assert_eq!(values, [42, 43, 44, 45]);
+    }
+}
@@ -1,12 +1,148 @@
-use crate::RenderDevice;
-use ecs::SystemArg;
+use crate::{BufferId, RenderDevice, RenderState};
+use ecs::{Resource, SystemArg};
+use std::{collections::HashMap, sync::Mutex};
 use wgpu::CommandEncoder;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PassKind {
+    Compute,
+    Render,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum ResourceOrderError {
+    #[error("Buffer {buffer:?} read by a {read:?} pass before a {write:?} pass wrote it")]
+    ReadBeforeWrite {
+        buffer: BufferId,
+        read: PassKind,
+        write: PassKind,
+    },
+}
+
+/// Records which passes read and write buffers, in encoding order.
+/// Passes run in the order they are encoded, so a buffer written after
+/// it was read means the read sees last frame's data.
+#[derive(Debug, Default)]
+pub struct ResourceTracker {
+    reads: HashMap<BufferId, PassKind>,
+    errors: Vec<ResourceOrderError>,
+}
+
+impl ResourceTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn read(&mut self, buffer: BufferId, pass: PassKind) {
+        self.reads.entry(buffer).or_insert(pass);
+    }
+
+    pub fn write(&mut self, buffer: BufferId, pass: PassKind) {
+        if let Some(read) = self.reads.get(&buffer).copied() {
+            self.errors.push(ResourceOrderError::ReadBeforeWrite {
+                buffer,
+                read,
+                write: pass,
+            });
+        }
+    }
+
+    pub fn errors(&self) -> &[ResourceOrderError] {
+        &self.errors
+    }
+
+    pub fn validate(&self) -> Result<(), ResourceOrderError> {
+        match self.errors.first() {
+            Some(error) => Err(*error),
+            None => Ok(()),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.reads.clear();
+        self.errors.clear();
+    }
+}
+
+/// The [`ResourceTracker`] shared by every encoder recorded this frame, so
+/// a compute pass and the render pass reading its output are checked
+/// against each other even though different systems encode them.
+#[derive(Resource, Default)]
+pub struct FrameTracker(Mutex<ResourceTracker>);
+
+impl FrameTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn read(&self, buffer: BufferId, pass: PassKind) {
+        self.0.lock().unwrap().read(buffer, pass);
+    }
+
+    pub fn write(&self, buffer: BufferId, pass: PassKind) {
+        self.0.lock().unwrap().write(buffer, pass);
+    }
+
+    pub fn validate(&self) -> Result<(), ResourceOrderError> {
+        self.0.lock().unwrap().validate()
+    }
+
+    /// Resets the tracker for the next frame. In debug builds this panics
+    /// if a buffer was written after a pass this frame already read it.
+    pub(crate) fn clear(tracker: &mut FrameTracker) {
+        let tracker = tracker.0.get_mut().unwrap();
+
+        #[cfg(debug_assertions)]
+        if let Err(error) = tracker.validate() {
+            panic!("{}", error);
+        }
+
+        tracker.clear();
+    }
+}
+
 pub struct RenderCommandEncoder<'w> {
     encoder: Option<CommandEncoder>,
+    tracker: &'w FrameTracker,
     pub device: &'w RenderDevice,
 }
 
+impl<'w> RenderCommandEncoder<'w> {
+    pub fn tracker(&self) -> &FrameTracker {
+        self.tracker
+    }
+
+    /// Records that `pass` reads `buffer`.
+    pub fn read_buffer(&mut self, buffer: BufferId, pass: PassKind) {
+        self.tracker.read(buffer, pass);
+    }
+
+    /// Records that `pass` writes `buffer`. In debug builds this panics if an
+    /// earlier pass this frame already read the buffer.
+    pub fn write_buffer(&mut self, buffer: BufferId, pass: PassKind) {
+        self.tracker.write(buffer, pass);
+
+        #[cfg(debug_assertions)]
+        if let Err(error) = self.tracker.validate() {
+            panic!("{}", error);
+        }
+    }
+
+    /// Begins a render pass that reads `buffers`, recording each read so a
+    /// later write to them this frame is reported.
+    pub fn begin_tracked_render_pass(
+        &mut self,
+        desc: &wgpu::RenderPassDescriptor<'_>,
+        buffers: &[BufferId],
+    ) -> RenderState<'_> {
+        for buffer in buffers {
+            self.read_buffer(*buffer, PassKind::Render);
+        }
+
+        RenderState::new(self.encoder.as_mut().unwrap().begin_render_pass(desc))
+    }
+}
+
 impl<'w> std::ops::Deref for RenderCommandEncoder<'w> {
     type Target = CommandEncoder;
 
@@ -27,8 +163,10 @@ unsafe impl SystemArg for RenderCommandEncoder<'_> {
     type State = ();
 
     fn init(world: &mut ecs::World, access: &mut ecs::WorldAccess) -> Self::State {
-        let id = world.register_resource::<RenderDevice>();
-        access.resources_mut().reads(id);
+        let device = world.register_resource::<RenderDevice>();
+        let tracker = world.register_resource::<FrameTracker>();
+        access.resources_mut().read(device);
+        access.resources_mut().read(tracker);
         ()
     }
 
@@ -37,11 +175,13 @@ unsafe impl SystemArg for RenderCommandEncoder<'_> {
         world: ecs::world::WorldCell<'world>,
         _: &'world ecs::SystemMeta,
     ) -> Self::Item<'world, 'state> {
-        let device = unsafe { world.get() }.resource::<RenderDevice>();
+        let world = unsafe { world.get() };
+        let device = world.resource::<RenderDevice>();
         let encoder = device.create_command_encoder(&Default::default());
 
         RenderCommandEncoder {
             encoder: Some(encoder),
+            tracker: world.resource::<FrameTracker>(),
             device,
         }
     }
@@ -54,3 +194,122 @@ impl Drop for RenderCommandEncoder<'_> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        FrameTracker, PassKind, RenderCommandEncoder, ResourceOrderError, ResourceTracker,
+    };
+    use crate::{
+        Buffer, BufferId, ComputeCommand, ComputeDispatch, PipelineCache, RenderDevice,
+        device::tests::device,
+    };
+
+    #[test]
+    fn write_before_read() {
+        let buffer = BufferId::new();
+        let mut tracker = ResourceTracker::new();
+        tracker.write(buffer, PassKind::Compute);
+        tracker.read(buffer, PassKind::Render);
+
+        assert_eq!(tracker.validate(), Ok(()));
+    }
+
+    #[test]
+    fn read_before_write() {
+        let buffer = BufferId::new();
+        let mut tracker = ResourceTracker::new();
+        tracker.read(buffer, PassKind::Render);
+        tracker.write(buffer, PassKind::Compute);
+
+        assert_eq!(
+            tracker.validate(),
+            Err(ResourceOrderError::ReadBeforeWrite {
+                buffer,
+                read: PassKind::Render,
+                write: PassKind::Compute,
+            })
+        );
+    }
+
+    struct Noop;
+
+    impl ComputeCommand for Noop {
+        fn execute(&self, _: &mut wgpu::ComputePass<'_>, _: &PipelineCache) -> bool {
+            true
+        }
+    }
+
+    fn encoder<'w>(
+        device: &'w RenderDevice,
+        tracker: &'w FrameTracker,
+    ) -> RenderCommandEncoder<'w> {
+        RenderCommandEncoder {
+            encoder: Some(device.create_command_encoder(&Default::default())),
+            tracker,
+            device,
+        }
+    }
+
+    fn draw(device: &RenderDevice, tracker: &FrameTracker, buffer: &Buffer) {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width: 1,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&Default::default());
+
+        let mut encoder = encoder(device, tracker);
+        encoder.begin_tracked_render_pass(
+            &wgpu::RenderPassDescriptor {
+                label: None,
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations::default(),
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            },
+            &[buffer.id()],
+        );
+    }
+
+    #[test]
+    #[ignore = "requires a GPU adapter"]
+    fn dispatch_then_render_pass() {
+        let device = device();
+        let pipelines = PipelineCache::new();
+        let buffer = Buffer::new(&device, 16, wgpu::BufferUsages::STORAGE, None);
+
+        let mut dispatch = ComputeDispatch::new();
+        dispatch.add(Noop).writes(buffer.id());
+
+        let tracker = FrameTracker::new();
+        ComputeDispatch::dispatch(encoder(&device, &tracker), &dispatch, &pipelines);
+        draw(&device, &tracker, &buffer);
+        assert_eq!(tracker.validate(), Ok(()));
+
+        let tracker = FrameTracker::new();
+        draw(&device, &tracker, &buffer);
+        ComputeDispatch::dispatch(encoder(&device, &tracker), &dispatch, &pipelines);
+        assert_eq!(
+            tracker.validate(),
+            Err(ResourceOrderError::ReadBeforeWrite {
+                buffer: buffer.id(),
+                read: PassKind::Render,
+                write: PassKind::Compute,
+            })
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use crate::primitives::Color;
+use ecs::{Resource, system::Main};
+
+/// Scene wide clear color and ambient light. Set it in the main world; it is
+/// copied to the render world during extraction.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Environment {
+    pub clear_color: Color,
+    pub ambient: Color,
+    pub ambient_intensity: f32,
+}
+
+impl Default for Environment {
+    fn default() -> Self {
+        Self {
+            clear_color: Color::black(),
+            ambient: Color::white(),
+            ambient_intensity: 0.1,
+        }
+    }
+}
+
+/// How a camera's color attachment is cleared before the camera draws.
+#[derive(Debug, Default, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum ClearColor {
+    /// Keeps what is already in the target.
+    #[default]
+    None,
+    /// Clears with [`Environment::clear_color`].
+    Environment,
+    /// Clears with the given color.
+    Color(Color),
+}
+
+impl Environment {
+    /// Load operation for a camera's color attachment.
+    pub fn load_op(&self, clear: ClearColor) -> wgpu::LoadOp<wgpu::Color> {
+        match clear {
+            ClearColor::None => wgpu::LoadOp::Load,
+            ClearColor::Environment => wgpu::LoadOp::Clear(self.clear_color.into()),
+            ClearColor::Color(color) => wgpu::LoadOp::Clear(color.into()),
+        }
+    }
+
+    pub(crate) fn extract(main: Main<&Environment>, environment: &mut Environment) {
+        if *environment != **main {
+            *environment = **main;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ClearColor, Environment};
+    use crate::primitives::Color;
+
+    #[test]
+    fn environment_clear_color() {
+        let environment = Environment {
+            clear_color: Color::blue(),
+            ..Default::default()
+        };
+
+        assert_eq!(environment.load_op(ClearColor::None), wgpu::LoadOp::Load);
+        assert_eq!(
+            environment.load_op(ClearColor::Environment),
+            wgpu::LoadOp::Clear(Color::blue().into())
+        );
+        assert_eq!(
+            environment.load_op(ClearColor::Color(Color::red())),
+            wgpu::LoadOp::Clear(Color::red().into())
+        );
+    }
+}
@@ -0,0 +1,222 @@
+use crate::{
+    BindGroup, BindGroupBuilder, BindGroupLayout, BindGroupLayoutBuilder, FilterMode,
+    FragmentState, GpuShader, Label, PipelineCache, PipelineId, RenderDevice, RenderPipelineDesc,
+    RenderState, Sampler, SamplerDesc, Shader, VertexState,
+};
+use asset::AssetId;
+use std::{borrow::Cow, collections::HashMap};
+
+pub const FULLSCREEN_SHADER: AssetId<Shader> =
+    AssetId::from_u128(0x5b0e3c8f2d9a4e7b8c1f6a3d2e9b7c41u128);
+
+pub struct FullscreenPassDesc {
+    pub label: Label,
+    pub shader: AssetId<GpuShader>,
+    pub entry: Cow<'static, str>,
+    pub format: wgpu::TextureFormat,
+    pub blend: Option<wgpu::BlendState>,
+}
+
+/// Runs a fragment shader over a fullscreen triangle, sampling an input
+/// texture and writing to an output texture.
+///
+/// The fragment shader reads the input from group 0, binding 0 (texture) and
+/// binding 1 (sampler), and receives the uv at location 0.
+pub struct FullscreenPass {
+    layout: BindGroupLayout,
+    sampler: Sampler,
+    pipeline: PipelineId,
+}
+
+impl FullscreenPass {
+    pub const VERTEX_ENTRY: &'static str = "vs_fullscreen";
+
+    pub fn new(
+        device: &RenderDevice,
+        pipelines: &mut PipelineCache,
+        desc: FullscreenPassDesc,
+    ) -> Self {
+        let layout = Self::layout_builder().build(device);
+        let sampler = Sampler::new(
+            device,
+            &SamplerDesc {
+                label: Some("fullscreen_sampler".into()),
+                filter_mode: FilterMode::Linear,
+                ..Default::default()
+            },
+        );
+
+        let pipeline = Self::queue_pipeline(pipelines, &layout, desc);
+
+        Self {
+            layout,
+            sampler,
+            pipeline,
+        }
+    }
+
+    /// Creates a pass with another fragment entry or target that shares this
+    /// pass's layout and sampler, so their bind groups are interchangeable.
+    pub fn variant(&self, pipelines: &mut PipelineCache, desc: FullscreenPassDesc) -> Self {
+        Self {
+            layout: self.layout.clone(),
+            sampler: self.sampler.clone(),
+            pipeline: Self::queue_pipeline(pipelines, &self.layout, desc),
+        }
+    }
+
+    fn queue_pipeline(
+        pipelines: &mut PipelineCache,
+        layout: &BindGroupLayout,
+        desc: FullscreenPassDesc,
+    ) -> PipelineId {
+        pipelines.queue_render_pipeline(RenderPipelineDesc {
+            label: desc.label,
+            layout: vec![layout.clone()],
+            vertex: Self::vertex_state(),
+            fragment: Some(FragmentState {
+                shader: desc.shader,
+                entry: desc.entry,
+                targets: vec![Some(wgpu::ColorTargetState {
+                    format: desc.format,
+                    blend: desc.blend,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: Default::default(),
+            push_constants: vec![],
+        })
+    }
+
+    pub fn shader() -> Shader {
+        Shader::Wgsl {
+            data: Cow::Borrowed(include_str!("../shaders/fullscreen.wgsl")),
+            dependencies: HashMap::new(),
+            constants: Default::default(),
+        }
+    }
+
+    pub fn layout_builder() -> BindGroupLayoutBuilder {
+        let mut builder = BindGroupLayoutBuilder::new();
+        builder
+            .with_label("fullscreen_pass")
+            .with_texture(
+                0,
+                wgpu::ShaderStages::FRAGMENT,
+                wgpu::TextureViewDimension::D2,
+                wgpu::TextureSampleType::Float { filterable: true },
+            )
+            .with_sampler(
+                1,
+                wgpu::ShaderStages::FRAGMENT,
+                wgpu::SamplerBindingType::Filtering,
+            );
+        builder
+    }
+
+    pub fn vertex_state() -> VertexState {
+        VertexState {
+            shader: *FULLSCREEN_SHADER.as_ref(),
+            entry: Self::VERTEX_ENTRY.into(),
+            buffers: vec![],
+        }
+    }
+
+    pub fn layout(&self) -> &BindGroupLayout {
+        &self.layout
+    }
+
+    pub fn pipeline(&self) -> PipelineId {
+        self.pipeline
+    }
+
+    pub fn create_bind_group(&self, device: &RenderDevice, input: &wgpu::TextureView) -> BindGroup {
+        BindGroupBuilder::new(&self.layout)
+            .with_label("fullscreen_pass")
+            .with_texture(0, input)
+            .with_sampler(1, &self.sampler)
+            .build(device)
+            .expect("Fullscreen bindings match the pass layout")
+    }
+
+    /// Draws the pass into `output`. Returns false if the pipeline
+    /// hasn't finished compiling yet.
+    pub fn draw(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        pipelines: &PipelineCache,
+        input: &BindGroup,
+        output: &wgpu::TextureView,
+    ) -> bool {
+        let Some(pipeline) = pipelines.get_render_pipeline(&self.pipeline) else {
+            return false;
+        };
+
+        let pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("FullscreenPass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: output,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        let mut state = RenderState::new(pass);
+        state.set_pipeline(pipeline);
+        state.set_bind_group(0, input, &[]);
+        state.draw(0..3, 0..1);
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FULLSCREEN_SHADER, FullscreenPass};
+    use crate::{GpuShader, Shader};
+    use asset::AssetId;
+
+    #[test]
+    fn fullscreen_pass_layout() {
+        let builder = FullscreenPass::layout_builder();
+        let entries = builder.entries();
+
+        assert!(entries.iter().any(|entry| entry.binding == 0
+            && matches!(
+                entry.ty,
+                wgpu::BindingType::Texture {
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    ..
+                }
+            )));
+        assert!(
+            entries.iter().any(
+                |entry| entry.binding == 1 && matches!(entry.ty, wgpu::BindingType::Sampler(_))
+            )
+        );
+    }
+
+    #[test]
+    fn fullscreen_pass_vertex() {
+        let vertex = FullscreenPass::vertex_state();
+        let shader: &AssetId<GpuShader> = FULLSCREEN_SHADER.as_ref();
+
+        assert_eq!(&vertex.shader, shader);
+        assert_eq!(vertex.entry, FullscreenPass::VERTEX_ENTRY);
+        assert!(vertex.buffers.is_empty());
+
+        let Shader::Wgsl { data, .. } = FullscreenPass::shader() else {
+            panic!("Expected a wgsl shader");
+        };
+
+        assert!(data.contains(&format!("fn {}", FullscreenPass::VERTEX_ENTRY)));
+    }
+}
@@ -1,7 +1,17 @@
 pub mod camera;
+pub mod compute;
 pub mod encoder;
+pub mod environment;
+pub mod fullscreen;
+pub mod sort;
 pub mod state;
+pub mod tonemap;
 
 pub use camera::*;
+pub use compute::*;
 pub use encoder::*;
+pub use environment::*;
+pub use fullscreen::*;
+pub use sort::*;
 pub use state::*;
+pub use tonemap::*;
@@ -0,0 +1,212 @@
+use crate::BlendMode;
+use ecs::Resource;
+use math::{Mat4, Vec3};
+use std::{cmp::Ordering, collections::HashMap, hash::Hash, ops::Range};
+use transform::basis;
+
+/// The distance of a draw in front of the view, used to order draws.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ZDistance(pub f32);
+
+impl ZDistance {
+    /// The distance of `position` along the view's forward axis.
+    /// `view_from_world` is the view matrix of the camera.
+    pub fn new(view_from_world: &Mat4, position: Vec3) -> Self {
+        let position = view_from_world.transform_point3(position);
+        Self(position.dot(basis::VIEW_FORWARD))
+    }
+}
+
+impl Eq for ZDistance {}
+
+impl PartialOrd for ZDistance {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ZDistance {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// Configures how [`sort_draws`] orders draws.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DrawSorting {
+    /// Sort opaque draws front to back. This only pays off with a depth
+    /// prepass or early-Z, so opaque draws keep their submission order by
+    /// default.
+    pub opaque_front_to_back: bool,
+}
+
+/// Orders draws for `mode`. Transparent draws are sorted back to front so
+/// they blend over whatever is behind them. Other draws are sorted front to
+/// back to reduce overdraw when [`DrawSorting::opaque_front_to_back`] is set,
+/// and left in submission order otherwise.
+pub fn sort_draws<T>(
+    draws: &mut [T],
+    mode: BlendMode,
+    sorting: DrawSorting,
+    distance: impl Fn(&T) -> ZDistance,
+) {
+    if mode.is_transparent() {
+        draws.sort_by_key(|draw| std::cmp::Reverse(distance(draw)));
+    } else if sorting.opaque_front_to_back {
+        draws.sort_by_key(distance);
+    }
+}
+
+/// A run of draws that share a key and are submitted as one instanced draw.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DrawBatch<K> {
+    pub key: K,
+    /// The batch's draws, as indices into the slice given to [`batch_draws`].
+    pub instances: Range<u32>,
+}
+
+/// Groups sorted draws into instanced batches by `key`. Opaque draws are
+/// reordered so every draw sharing a key lands in one batch, keeping their
+/// order within it. Transparent draws keep the order from [`sort_draws`] and
+/// only merge with their neighbours, so a batch never spans a draw that was
+/// sorted between its instances.
+pub fn batch_draws<T, K: Copy + Eq + Hash>(
+    draws: &mut [T],
+    mode: BlendMode,
+    key: impl Fn(&T) -> K,
+) -> Vec<DrawBatch<K>> {
+    if !mode.is_transparent() {
+        let mut groups = HashMap::new();
+        for draw in draws.iter() {
+            let next = groups.len();
+            groups.entry(key(draw)).or_insert(next);
+        }
+
+        draws.sort_by_key(|draw| groups[&key(draw)]);
+    }
+
+    let mut batches: Vec<DrawBatch<K>> = Vec::new();
+    for (index, draw) in draws.iter().enumerate() {
+        let key = key(draw);
+        let index = index as u32;
+        match batches.last_mut() {
+            Some(batch) if batch.key == key => batch.instances.end = index + 1,
+            _ => batches.push(DrawBatch {
+                key,
+                instances: index..index + 1,
+            }),
+        }
+    }
+
+    batches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DrawBatch, DrawSorting, ZDistance, batch_draws, sort_draws};
+    use crate::BlendMode;
+    use math::{Mat4, Vec3};
+    use transform::basis;
+
+    #[test]
+    fn transparent_back_to_front() {
+        let eye = Vec3::new(0.0, 0.0, 10.0);
+        let view = basis::look_to(eye, basis::VIEW_FORWARD, Vec3::Y);
+        let quads = [
+            ("middle", eye + basis::VIEW_FORWARD * 5.0),
+            ("near", eye + basis::VIEW_FORWARD * 1.0),
+            ("far", eye + basis::VIEW_FORWARD * 9.0),
+        ];
+        let distance = |quad: &(&str, Vec3)| ZDistance::new(&view, quad.1);
+
+        let mut draws = quads;
+        sort_draws(
+            &mut draws,
+            BlendMode::Transparent,
+            DrawSorting::default(),
+            distance,
+        );
+        assert_eq!(draws.map(|d| d.0), ["far", "middle", "near"]);
+
+        assert_eq!(
+            ZDistance::new(&Mat4::IDENTITY, basis::VIEW_FORWARD * 2.0).0,
+            2.0
+        );
+    }
+
+    #[test]
+    fn opaque_front_to_back() {
+        let eye = Vec3::new(0.0, 0.0, 10.0);
+        let view = basis::look_to(eye, basis::VIEW_FORWARD, Vec3::Y);
+        let batches = [
+            ("middle", eye + basis::VIEW_FORWARD * 5.0),
+            ("near", eye + basis::VIEW_FORWARD * 1.0),
+            ("far", eye + basis::VIEW_FORWARD * 9.0),
+        ];
+        let distance = |batch: &(&str, Vec3)| ZDistance::new(&view, batch.1);
+
+        let sorting = DrawSorting {
+            opaque_front_to_back: true,
+        };
+        let mut draws = batches;
+        sort_draws(&mut draws, BlendMode::Opaque, sorting, distance);
+        assert_eq!(draws.map(|d| d.0), ["near", "middle", "far"]);
+
+        let mut draws = batches;
+        sort_draws(
+            &mut draws,
+            BlendMode::Opaque,
+            DrawSorting::default(),
+            distance,
+        );
+        assert_eq!(draws.map(|d| d.0), ["middle", "near", "far"]);
+    }
+
+    #[test]
+    fn transparent_batches_keep_depth_order() {
+        let eye = Vec3::new(0.0, 0.0, 10.0);
+        let view = basis::look_to(eye, basis::VIEW_FORWARD, Vec3::Y);
+        let quad = |material: &'static str, name: &'static str, depth: f32| {
+            (material, name, eye + basis::VIEW_FORWARD * depth)
+        };
+        let distance = |quad: &(&str, &str, Vec3)| ZDistance::new(&view, quad.2);
+        let batch = |key: &'static str, instances| DrawBatch { key, instances };
+
+        let mut draws = [
+            quad("glass", "middle", 5.0),
+            quad("glass", "near", 1.0),
+            quad("glass", "far", 9.0),
+        ];
+        let mode = BlendMode::Transparent;
+        sort_draws(&mut draws, mode, DrawSorting::default(), distance);
+        let batches = batch_draws(&mut draws, mode, |d| d.0);
+        assert_eq!(draws.map(|d| d.1), ["far", "middle", "near"]);
+        assert_eq!(batches, [batch("glass", 0..3)]);
+
+        let mut draws = [
+            quad("glass", "middle", 5.0),
+            quad("glass", "near", 1.0),
+            quad("smoke", "between", 7.0),
+            quad("glass", "far", 9.0),
+        ];
+        sort_draws(&mut draws, mode, DrawSorting::default(), distance);
+        let batches = batch_draws(&mut draws, mode, |d| d.0);
+        assert_eq!(draws.map(|d| d.1), ["far", "between", "middle", "near"]);
+        assert_eq!(
+            batches,
+            [
+                batch("glass", 0..1),
+                batch("smoke", 1..2),
+                batch("glass", 2..4)
+            ]
+        );
+
+        let sorting = DrawSorting {
+            opaque_front_to_back: true,
+        };
+        sort_draws(&mut draws, BlendMode::Opaque, sorting, distance);
+        let batches = batch_draws(&mut draws, BlendMode::Opaque, |d| d.0);
+        assert_eq!(draws.map(|d| d.1), ["near", "middle", "far", "between"]);
+        assert_eq!(batches, [batch("glass", 0..3), batch("smoke", 3..4)]);
+    }
+}
@@ -1,11 +1,13 @@
 use crate::{
+    device::DrawPath,
     primitives::{Color, Viewport},
     resources::{
-        BindGroup, BindGroupId, Buffer, BufferSlice, BufferSliceId, PipelineId, RenderPipeline,
+        ArrayBuffer, BindGroup, BindGroupId, Buffer, BufferSlice, BufferSliceId, PipelineId,
+        RenderPipeline,
     },
 };
 use std::{collections::HashMap, ops::Range};
-use wgpu::{IndexFormat, QuerySet, RenderBundle, ShaderStages};
+use wgpu::{IndexFormat, QuerySet, RenderBundle, ShaderStages, util::DrawIndirectArgs};
 
 pub struct RenderState<'a> {
     pass: wgpu::RenderPass<'a>,
@@ -112,6 +114,31 @@ impl<'a> RenderState<'a> {
         self.pass.draw(vertices, instances);
     }
 
+    /// Issues the draw `commands` stored in `buffer`. [`DrawPath::Indirect`]
+    /// reads them from the GPU buffer with one `multi_draw_indirect` call,
+    /// while [`DrawPath::Direct`] issues a `draw` for each from the CPU copy.
+    pub fn draw_commands(
+        &mut self,
+        path: DrawPath,
+        buffer: &ArrayBuffer<DrawIndirectArgs>,
+        commands: Range<u32>,
+    ) {
+        match path {
+            DrawPath::Indirect => {
+                let offset = commands.start as u64 * size_of::<DrawIndirectArgs>() as u64;
+                self.multi_draw_indirect(buffer.as_ref(), offset, commands.len() as u32);
+            }
+            DrawPath::Direct => {
+                let values = &buffer.values()[commands.start as usize..commands.end as usize];
+                for args in values {
+                    let vertices = args.first_vertex..args.first_vertex + args.vertex_count;
+                    let instances = args.first_instance..args.first_instance + args.instance_count;
+                    self.pass.draw(vertices, instances);
+                }
+            }
+        }
+    }
+
     pub fn draw_indexed(&mut self, indices: Range<u32>, base_vertex: i32, instances: Range<u32>) {
         self.pass.draw_indexed(indices, base_vertex, instances);
     }
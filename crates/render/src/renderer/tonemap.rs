@@ -0,0 +1,412 @@
+use crate::{
+    BindGroup, BindGroupLayout, CameraAttachments, ExtractError, FullscreenPass,
+    FullscreenPassDesc, PipelineCache, RenderDevice, RenderResource, RenderSurface, RenderTexture,
+    Shader,
+};
+use asset::AssetId;
+use ecs::{
+    Phase, Query, Resource,
+    system::{
+        Always, ArgItem,
+        unlifetime::{Read, Write},
+    },
+};
+use math::Size;
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet, hash_map::Entry},
+};
+
+pub const TONEMAP_SHADER: AssetId<Shader> =
+    AssetId::from_u128(0x8e4d2a7c1b3f4d9e9a6c5b2e7f1d3a58u128);
+
+#[derive(
+    Resource, Default, Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize,
+)]
+pub enum Tonemapping {
+    None,
+    Reinhard,
+    #[default]
+    Aces,
+}
+
+impl Tonemapping {
+    pub const ALL: [Tonemapping; 3] = [Self::None, Self::Reinhard, Self::Aces];
+
+    pub fn entry(&self) -> &'static str {
+        match self {
+            Tonemapping::None => "fs_none",
+            Tonemapping::Reinhard => "fs_reinhard",
+            Tonemapping::Aces => "fs_aces",
+        }
+    }
+}
+
+/// Sample count of the HDR targets cameras draw into before tonemapping.
+/// Camera pipelines must be created with the same count and
+/// [`TonemapPipeline::HDR_FORMAT`] as their color target.
+#[derive(
+    Resource, Default, Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize,
+)]
+pub enum Msaa {
+    Off,
+    #[default]
+    Sample4,
+}
+
+impl Msaa {
+    pub fn samples(&self) -> u32 {
+        match self {
+            Msaa::Off => 1,
+            Msaa::Sample4 => 4,
+        }
+    }
+}
+
+/// Fullscreen passes for each [`Tonemapping`] mode, writing to the surface format.
+/// The passes share one layout and sampler, so an input bind group can be
+/// drawn with any mode.
+#[derive(Resource)]
+pub struct TonemapPipeline {
+    passes: HashMap<Tonemapping, FullscreenPass>,
+}
+
+impl TonemapPipeline {
+    pub const HDR_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+    pub fn new(
+        device: &RenderDevice,
+        pipelines: &mut PipelineCache,
+        format: wgpu::TextureFormat,
+    ) -> Self {
+        let [first, modes @ ..] = Tonemapping::ALL;
+        let pass = FullscreenPass::new(device, pipelines, Self::desc(first, format));
+
+        let mut passes: HashMap<_, _> = modes
+            .iter()
+            .map(|mode| (*mode, pass.variant(pipelines, Self::desc(*mode, format))))
+            .collect();
+        passes.insert(first, pass);
+
+        Self { passes }
+    }
+
+    fn desc(mode: Tonemapping, format: wgpu::TextureFormat) -> FullscreenPassDesc {
+        FullscreenPassDesc {
+            label: Some(Cow::Borrowed("Tonemap")),
+            shader: *TONEMAP_SHADER.as_ref(),
+            entry: mode.entry().into(),
+            format,
+            blend: None,
+        }
+    }
+
+    pub fn shader() -> Shader {
+        Shader::Wgsl {
+            data: Cow::Borrowed(include_str!("../shaders/tonemap.wgsl")),
+            dependencies: HashMap::new(),
+            constants: Default::default(),
+        }
+    }
+
+    pub fn pass(&self, mode: Tonemapping) -> &FullscreenPass {
+        &self.passes[&mode]
+    }
+
+    pub fn layout(&self) -> &BindGroupLayout {
+        self.pass(Tonemapping::default()).layout()
+    }
+
+    pub fn create_bind_group(&self, device: &RenderDevice, input: &wgpu::TextureView) -> BindGroup {
+        self.pass(Tonemapping::default())
+            .create_bind_group(device, input)
+    }
+
+    /// Tonemaps `input` into `output`. Returns false if the pipeline
+    /// hasn't finished compiling yet.
+    pub fn draw(
+        &self,
+        mode: Tonemapping,
+        encoder: &mut wgpu::CommandEncoder,
+        pipelines: &PipelineCache,
+        input: &BindGroup,
+        output: &wgpu::TextureView,
+    ) -> bool {
+        self.pass(mode).draw(encoder, pipelines, input, output)
+    }
+}
+
+impl RenderResource for TonemapPipeline {
+    type Arg = (
+        Read<RenderDevice>,
+        Read<RenderSurface>,
+        Write<PipelineCache>,
+    );
+
+    type Condition = Always<true>;
+
+    fn extract((device, surface, pipelines): ArgItem<Self::Arg>) -> Result<Self, ExtractError> {
+        Ok(Self::new(device, pipelines, surface.format()))
+    }
+}
+
+/// HDR attachments that cameras draw into in place of their output. After
+/// [`CameraPhase`](crate::CameraPhase) the color is resolved and tonemapped
+/// into the output.
+pub struct TonemapTarget {
+    size: Size<u32>,
+    samples: u32,
+    color: wgpu::TextureView,
+    depth: wgpu::TextureView,
+    resolve: Option<wgpu::TextureView>,
+    input: BindGroup,
+    output: Option<wgpu::TextureView>,
+}
+
+impl TonemapTarget {
+    pub fn new(
+        device: &RenderDevice,
+        pipeline: &TonemapPipeline,
+        size: Size<u32>,
+        samples: u32,
+    ) -> Self {
+        let attachment = wgpu::TextureUsages::RENDER_ATTACHMENT;
+        let sampled = attachment | wgpu::TextureUsages::TEXTURE_BINDING;
+
+        let (color, resolve) = if samples > 1 {
+            let color = Self::create_texture(device, size, samples, attachment);
+            let resolve = Self::create_texture(device, size, 1, sampled);
+            (color, Some(resolve))
+        } else {
+            (Self::create_texture(device, size, 1, sampled), None)
+        };
+
+        let depth = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("TonemapDepth"),
+            size: wgpu::Extent3d {
+                width: size.width,
+                height: size.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: samples,
+            dimension: wgpu::TextureDimension::D2,
+            format: RenderSurface::DEPTH_FORMAT,
+            usage: attachment,
+            view_formats: &[],
+        });
+
+        let input = pipeline.create_bind_group(device, resolve.as_ref().unwrap_or(&color));
+
+        Self {
+            size,
+            samples,
+            color,
+            depth: depth.create_view(&Default::default()),
+            resolve,
+            input,
+            output: None,
+        }
+    }
+
+    pub fn size(&self) -> Size<u32> {
+        self.size
+    }
+
+    pub fn samples(&self) -> u32 {
+        self.samples
+    }
+
+    /// The color attachment cameras draw into. Multisampled if [`Msaa`] is on.
+    pub fn color(&self) -> &wgpu::TextureView {
+        &self.color
+    }
+
+    pub fn depth(&self) -> &wgpu::TextureView {
+        &self.depth
+    }
+
+    /// Resolves the multisampled color into the single sampled texture that
+    /// tonemapping reads. Does nothing if the color isn't multisampled.
+    pub fn resolve(&self, encoder: &mut wgpu::CommandEncoder) {
+        let Some(resolve) = &self.resolve else {
+            return;
+        };
+
+        encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("MsaaResolve"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &self.color,
+                resolve_target: Some(resolve),
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+    }
+
+    fn create_texture(
+        device: &RenderDevice,
+        size: Size<u32>,
+        samples: u32,
+        usage: wgpu::TextureUsages,
+    ) -> wgpu::TextureView {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("TonemapColor"),
+            size: wgpu::Extent3d {
+                width: size.width,
+                height: size.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: samples,
+            dimension: wgpu::TextureDimension::D2,
+            format: TonemapPipeline::HDR_FORMAT,
+            usage,
+            view_formats: &[],
+        });
+
+        texture.create_view(&Default::default())
+    }
+}
+
+/// One [`TonemapTarget`] per camera output, shared by every camera that
+/// draws to it so cameras still composite before tonemapping.
+#[derive(Resource, Default)]
+pub struct TonemapTargets(HashMap<Option<AssetId<RenderTexture>>, TonemapTarget>);
+
+impl TonemapTargets {
+    pub fn get(&self, target: Option<AssetId<RenderTexture>>) -> Option<&TonemapTarget> {
+        self.0.get(&target)
+    }
+
+    /// Points each camera at the HDR target for its output, keeping the
+    /// output to tonemap into after the cameras have drawn.
+    pub(crate) fn queue(
+        cameras: Query<&mut CameraAttachments>,
+        device: &RenderDevice,
+        pipeline: Option<&TonemapPipeline>,
+        msaa: &Msaa,
+        targets: &mut TonemapTargets,
+    ) {
+        let Some(pipeline) = pipeline else {
+            return;
+        };
+
+        let mut used = HashSet::new();
+        for attachments in cameras.iter() {
+            let Some(output) = attachments.color.take() else {
+                continue;
+            };
+
+            let target = match targets.0.entry(attachments.target) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => entry.insert(TonemapTarget::new(
+                    device,
+                    pipeline,
+                    attachments.size,
+                    msaa.samples(),
+                )),
+            };
+
+            if target.size != attachments.size || target.samples != msaa.samples() {
+                *target = TonemapTarget::new(device, pipeline, attachments.size, msaa.samples());
+            }
+
+            target.output = Some(output);
+            attachments.color = Some(target.color.clone());
+            attachments.depth = target.depth.clone();
+            used.insert(attachments.target);
+        }
+
+        targets.0.retain(|target, _| used.contains(target));
+    }
+
+    /// Resolves each target and tonemaps it into its output with the
+    /// current [`Tonemapping`] mode.
+    pub(crate) fn tonemap(
+        device: &RenderDevice,
+        pipeline: Option<&TonemapPipeline>,
+        pipelines: &PipelineCache,
+        mode: &Tonemapping,
+        targets: &mut TonemapTargets,
+    ) {
+        let Some(pipeline) = pipeline else {
+            return;
+        };
+
+        let mut encoder = device.create_command_encoder(&Default::default());
+        for target in targets.0.values_mut() {
+            let Some(output) = target.output.take() else {
+                continue;
+            };
+
+            target.resolve(&mut encoder);
+            pipeline.draw(*mode, &mut encoder, pipelines, &target.input, &output);
+        }
+
+        device.queue.submit(std::iter::once(encoder.finish()));
+    }
+}
+
+/// Runs after [`CameraPhase`](crate::CameraPhase) to tonemap what the
+/// cameras drew.
+#[derive(Phase)]
+pub struct TonemapPhase;
+
+#[cfg(test)]
+mod tests {
+    use super::{Msaa, TonemapPipeline, TonemapTarget, Tonemapping};
+    use crate::{PipelineCache, Shader, device::tests::device};
+    use math::Size;
+    use std::collections::HashSet;
+
+    #[test]
+    fn tonemap_entries() {
+        let Shader::Wgsl { data, .. } = TonemapPipeline::shader() else {
+            panic!("Expected a wgsl shader");
+        };
+
+        for mode in Tonemapping::ALL {
+            assert!(data.contains(&format!("fn {}", mode.entry())));
+        }
+    }
+
+    #[test]
+    fn msaa_samples() {
+        assert_eq!(Msaa::Off.samples(), 1);
+        assert_eq!(Msaa::Sample4.samples(), 4);
+    }
+
+    #[test]
+    #[ignore = "requires a GPU adapter"]
+    fn tonemap_modes_share_layout() {
+        let device = device();
+        let mut pipelines = PipelineCache::new();
+        let pipeline =
+            TonemapPipeline::new(&device, &mut pipelines, wgpu::TextureFormat::Bgra8Unorm);
+
+        let ids = Tonemapping::ALL
+            .iter()
+            .map(|mode| pipeline.pass(*mode).pipeline())
+            .collect::<HashSet<_>>();
+        assert_eq!(ids.len(), Tonemapping::ALL.len());
+
+        for mode in Tonemapping::ALL {
+            assert_eq!(pipeline.pass(mode).layout(), pipeline.layout());
+        }
+
+        let size = Size::new(4, 4);
+        let target = TonemapTarget::new(&device, &pipeline, size, Msaa::Sample4.samples());
+        assert_eq!(target.samples(), 4);
+        assert!(target.resolve.is_some());
+
+        let target = TonemapTarget::new(&device, &pipeline, size, Msaa::Off.samples());
+        assert_eq!(target.samples(), 1);
+        assert!(target.resolve.is_none());
+    }
+}
@@ -1,27 +1,60 @@
 use super::{AtomicId, buffer::Buffer};
-use crate::{Texture, device::RenderDevice};
+use crate::{SamplerDesc, Texture, device::RenderDevice};
 use asset::AssetId;
 use ecs::system::{ArgItem, SystemArg};
 use std::{error::Error, num::NonZero, sync::Arc};
 
-#[derive(Clone, Debug, PartialEq)]
-pub struct BindGroupLayout(Arc<wgpu::BindGroupLayout>);
+#[derive(Clone, Debug)]
+pub struct BindGroupLayout {
+    layout: Arc<wgpu::BindGroupLayout>,
+    entries: Arc<[wgpu::BindGroupLayoutEntry]>,
+}
+
+impl BindGroupLayout {
+    pub fn new(layout: wgpu::BindGroupLayout, entries: &[wgpu::BindGroupLayoutEntry]) -> Self {
+        Self {
+            layout: Arc::new(layout),
+            entries: Arc::from(entries),
+        }
+    }
+
+    /// The entries this layout was created with. Empty if the layout was
+    /// created directly from a [`wgpu::BindGroupLayout`].
+    pub fn entries(&self) -> &[wgpu::BindGroupLayoutEntry] {
+        &self.entries
+    }
+}
+
 impl From<wgpu::BindGroupLayout> for BindGroupLayout {
     fn from(layout: wgpu::BindGroupLayout) -> Self {
-        Self(Arc::new(layout))
+        Self::new(layout, &[])
+    }
+}
+
+impl PartialEq for BindGroupLayout {
+    fn eq(&self, other: &Self) -> bool {
+        self.layout == other.layout
+    }
+}
+
+impl Eq for BindGroupLayout {}
+
+impl std::hash::Hash for BindGroupLayout {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.layout.hash(state);
     }
 }
 
 impl std::ops::Deref for BindGroupLayout {
     type Target = wgpu::BindGroupLayout;
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.layout
     }
 }
 
 impl AsRef<wgpu::BindGroupLayout> for BindGroupLayout {
     fn as_ref(&self) -> &wgpu::BindGroupLayout {
-        &self.0
+        &self.layout
     }
 }
 
@@ -147,6 +180,36 @@ impl BindGroupLayoutBuilder {
         self
     }
 
+    /// Adds a storage texture binding.
+    ///
+    /// Panics if `format` doesn't support storage usage.
+    pub fn with_storage_texture(
+        &mut self,
+        binding: u32,
+        visibility: wgpu::ShaderStages,
+        dimension: wgpu::TextureViewDimension,
+        format: wgpu::TextureFormat,
+        access: wgpu::StorageTextureAccess,
+    ) -> &mut Self {
+        assert!(
+            Texture::supports_storage(format),
+            "Texture format {:?} does not support storage usage",
+            format
+        );
+
+        self.entries.push(wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility,
+            ty: wgpu::BindingType::StorageTexture {
+                access,
+                format,
+                view_dimension: dimension,
+            },
+            count: None,
+        });
+        self
+    }
+
     pub fn with_sampler(
         &mut self,
         binding: u32,
@@ -162,18 +225,151 @@ impl BindGroupLayoutBuilder {
         self
     }
 
+    /// Adds a sampler binding with the binding type required by `desc`.
+    pub fn with_sampler_desc(
+        &mut self,
+        binding: u32,
+        visibility: wgpu::ShaderStages,
+        desc: &SamplerDesc,
+    ) -> &mut Self {
+        self.with_sampler(binding, visibility, desc.binding_type())
+    }
+
     pub fn with_label(&mut self, label: impl ToString) -> &mut Self {
         self.label = Some(label.to_string());
         self
     }
 
+    pub fn entries(&self) -> &[wgpu::BindGroupLayoutEntry] {
+        &self.entries
+    }
+
     pub fn build(&self, device: &RenderDevice) -> BindGroupLayout {
-        BindGroupLayout::from(
-            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                entries: &self.entries,
-                label: self.label.as_ref().map(|x| x.as_str()),
-            }),
-        )
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &self.entries,
+            label: self.label.as_ref().map(|x| x.as_str()),
+        });
+
+        BindGroupLayout::new(layout, &self.entries)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BindingKind {
+    Uniform,
+    Storage { read_only: bool },
+    Texture,
+    StorageTexture,
+    Sampler,
+    AccelerationStructure,
+}
+
+impl BindingKind {
+    pub fn of(ty: &wgpu::BindingType) -> Self {
+        match ty {
+            wgpu::BindingType::Buffer { ty, .. } => match ty {
+                wgpu::BufferBindingType::Uniform => Self::Uniform,
+                wgpu::BufferBindingType::Storage { read_only } => Self::Storage {
+                    read_only: *read_only,
+                },
+            },
+            wgpu::BindingType::Texture { .. } => Self::Texture,
+            wgpu::BindingType::StorageTexture { .. } => Self::StorageTexture,
+            wgpu::BindingType::Sampler(_) => Self::Sampler,
+            wgpu::BindingType::AccelerationStructure { .. } => Self::AccelerationStructure,
+        }
+    }
+
+    /// Access is declared by the layout, so any storage buffer can fill a
+    /// storage binding whether or not it is read only.
+    fn accepts(&self, provided: BindingKind) -> bool {
+        match (self, provided) {
+            (Self::Texture | Self::StorageTexture, BindingKind::Texture) => true,
+            (Self::Storage { .. }, BindingKind::Storage { .. }) => true,
+            (expected, provided) => *expected == provided,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BindGroupValidationError {
+    UnknownBinding {
+        binding: u32,
+    },
+    MismatchedBinding {
+        binding: u32,
+        expected: BindingKind,
+        provided: BindingKind,
+    },
+    DuplicateBinding {
+        binding: u32,
+    },
+    MissingBinding {
+        binding: u32,
+        expected: BindingKind,
+    },
+}
+
+impl std::fmt::Display for BindGroupValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownBinding { binding } => {
+                write!(f, "Binding {} does not exist in the layout", binding)
+            }
+            Self::MismatchedBinding {
+                binding,
+                expected,
+                provided,
+            } => write!(
+                f,
+                "Binding {} expects a {:?} but a {:?} was provided",
+                binding, expected, provided
+            ),
+            Self::DuplicateBinding { binding } => {
+                write!(f, "Binding {} was provided more than once", binding)
+            }
+            Self::MissingBinding { binding, expected } => {
+                write!(f, "Missing {:?} for binding {}", expected, binding)
+            }
+        }
+    }
+}
+
+impl Error for BindGroupValidationError {}
+
+/// Validates the provided bindings against the layout entries.
+/// Every provided binding must exist in the layout with a matching type,
+/// and every layout entry must be provided exactly once.
+pub fn validate_bindings(
+    layout: &[wgpu::BindGroupLayoutEntry],
+    bindings: impl IntoIterator<Item = (u32, BindingKind)>,
+) -> Result<(), BindGroupValidationError> {
+    let mut provided = vec![false; layout.len()];
+    for (binding, kind) in bindings {
+        let Some(index) = layout.iter().position(|entry| entry.binding == binding) else {
+            return Err(BindGroupValidationError::UnknownBinding { binding });
+        };
+
+        let expected = BindingKind::of(&layout[index].ty);
+        if !expected.accepts(kind) {
+            return Err(BindGroupValidationError::MismatchedBinding {
+                binding,
+                expected,
+                provided: kind,
+            });
+        }
+
+        if std::mem::replace(&mut provided[index], true) {
+            return Err(BindGroupValidationError::DuplicateBinding { binding });
+        }
+    }
+
+    match provided.iter().position(|provided| !provided) {
+        Some(index) => Err(BindGroupValidationError::MissingBinding {
+            binding: layout[index].binding,
+            expected: BindingKind::of(&layout[index].ty),
+        }),
+        None => Ok(()),
     }
 }
 
@@ -181,6 +377,7 @@ pub struct BindGroupBuilder<'a> {
     label: Option<String>,
     layout: &'a BindGroupLayout,
     entries: Vec<wgpu::BindGroupEntry<'a>>,
+    kinds: Vec<BindingKind>,
 }
 
 impl<'a> BindGroupBuilder<'a> {
@@ -189,15 +386,17 @@ impl<'a> BindGroupBuilder<'a> {
             label: None,
             layout,
             entries: Vec::new(),
+            kinds: Vec::new(),
         }
     }
 
-    pub fn with_buffer(
+    fn with_buffer(
         &mut self,
         binding: u32,
         buffer: &'a Buffer,
         offset: wgpu::BufferAddress,
         size: Option<wgpu::BufferSize>,
+        kind: BindingKind,
     ) -> &mut Self {
         self.entries.push(wgpu::BindGroupEntry {
             binding,
@@ -207,6 +406,7 @@ impl<'a> BindGroupBuilder<'a> {
                 size,
             }),
         });
+        self.kinds.push(kind);
         self
     }
 
@@ -217,7 +417,7 @@ impl<'a> BindGroupBuilder<'a> {
         offset: wgpu::BufferAddress,
         size: Option<wgpu::BufferSize>,
     ) -> &mut Self {
-        self.with_buffer(binding, buffer, offset, size)
+        self.with_buffer(binding, buffer, offset, size, BindingKind::Uniform)
     }
 
     pub fn with_storage(
@@ -227,7 +427,8 @@ impl<'a> BindGroupBuilder<'a> {
         offset: wgpu::BufferAddress,
         size: Option<wgpu::BufferSize>,
     ) -> &mut Self {
-        self.with_buffer(binding, buffer, offset, size)
+        let kind = BindingKind::Storage { read_only: false };
+        self.with_buffer(binding, buffer, offset, size, kind)
     }
 
     pub fn with_texture(&mut self, binding: u32, view: &'a wgpu::TextureView) -> &mut Self {
@@ -235,6 +436,16 @@ impl<'a> BindGroupBuilder<'a> {
             binding,
             resource: wgpu::BindingResource::TextureView(view),
         });
+        self.kinds.push(BindingKind::Texture);
+        self
+    }
+
+    pub fn with_storage_texture(&mut self, binding: u32, view: &'a wgpu::TextureView) -> &mut Self {
+        self.entries.push(wgpu::BindGroupEntry {
+            binding,
+            resource: wgpu::BindingResource::TextureView(view),
+        });
+        self.kinds.push(BindingKind::StorageTexture);
         self
     }
 
@@ -243,6 +454,7 @@ impl<'a> BindGroupBuilder<'a> {
             binding,
             resource: wgpu::BindingResource::Sampler(sampler),
         });
+        self.kinds.push(BindingKind::Sampler);
         self
     }
 
@@ -251,12 +463,30 @@ impl<'a> BindGroupBuilder<'a> {
         self
     }
 
-    pub fn build(&self, device: &RenderDevice) -> BindGroup {
-        BindGroup::from(device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: self.layout,
-            entries: &self.entries,
-            label: self.label.as_ref().map(|x| x.as_str()),
-        }))
+    /// Validates the bindings against the layout. Layouts created without
+    /// entries (see [`BindGroupLayout::from`]) are not validated.
+    pub fn validate(&self) -> Result<(), BindGroupValidationError> {
+        if self.layout.entries().is_empty() {
+            return Ok(());
+        }
+
+        let bindings = self.entries.iter().map(|entry| entry.binding);
+        validate_bindings(
+            self.layout.entries(),
+            bindings.zip(self.kinds.iter().copied()),
+        )
+    }
+
+    pub fn build(&self, device: &RenderDevice) -> Result<BindGroup, BindGroupValidationError> {
+        self.validate()?;
+
+        Ok(BindGroup::from(device.create_bind_group(
+            &wgpu::BindGroupDescriptor {
+                layout: self.layout,
+                entries: &self.entries,
+                label: self.label.as_ref().map(|x| x.as_str()),
+            },
+        )))
     }
 }
 
@@ -264,7 +494,10 @@ impl<'a> BindGroupBuilder<'a> {
 pub enum CreateBindGroupError {
     Error(Arc<dyn Error + Send + Sync + 'static>),
     InvalidLayout,
+    InvalidBinding(BindGroupValidationError),
     MissingTexture { id: AssetId<Texture> },
+    MissingStorageTexture { binding: u32 },
+    MissingStorageUsage { binding: u32 },
     MissingBuffer,
 }
 
@@ -279,7 +512,16 @@ impl std::fmt::Display for CreateBindGroupError {
         match self {
             Self::Error(error) => write!(f, "{}", error),
             Self::InvalidLayout => write!(f, "Invalid bind group layout"),
+            Self::InvalidBinding(error) => write!(f, "{}", error),
             Self::MissingTexture { id } => write!(f, "Missing texture: {:?}", id),
+            Self::MissingStorageTexture { binding } => {
+                write!(f, "Missing storage texture for binding {}", binding)
+            }
+            Self::MissingStorageUsage { binding } => write!(
+                f,
+                "Texture for binding {} was not created with storage usage",
+                binding
+            ),
             Self::MissingBuffer => write!(f, "Missing buffer"),
         }
     }
@@ -287,6 +529,12 @@ impl std::fmt::Display for CreateBindGroupError {
 
 impl Error for CreateBindGroupError {}
 
+impl From<BindGroupValidationError> for CreateBindGroupError {
+    fn from(error: BindGroupValidationError) -> Self {
+        Self::InvalidBinding(error)
+    }
+}
+
 pub trait AsBinding {
     type Arg: SystemArg;
 
@@ -300,5 +548,176 @@ pub trait AsBinding {
         layout: &BindGroupLayout,
         arg: &ArgItem<Self::Arg>,
     ) -> Result<BindGroup, CreateBindGroupError>;
-    fn create_bind_group_layout(device: &RenderDevice) -> BindGroupLayout;
+
+    fn layout_builder() -> BindGroupLayoutBuilder;
+
+    fn create_bind_group_layout(device: &RenderDevice) -> BindGroupLayout {
+        Self::layout_builder().build(device)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        AsBinding, BindGroupLayoutBuilder, BindGroupValidationError, BindingKind, validate_bindings,
+    };
+    use crate::{FilterMode, SamplerDesc, Texture, TextureDimension};
+    use asset::AssetId;
+
+    fn layout() -> Vec<wgpu::BindGroupLayoutEntry> {
+        vec![
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                count: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn valid_bindings() {
+        let bindings = [(0, BindingKind::Uniform), (1, BindingKind::Sampler)];
+        assert_eq!(validate_bindings(&layout(), bindings), Ok(()));
+    }
+
+    #[test]
+    fn missing_binding() {
+        let error = validate_bindings(&layout(), [(0, BindingKind::Uniform)]).unwrap_err();
+
+        assert_eq!(
+            error,
+            BindGroupValidationError::MissingBinding {
+                binding: 1,
+                expected: BindingKind::Sampler
+            }
+        );
+        assert_eq!(error.to_string(), "Missing Sampler for binding 1");
+    }
+
+    #[test]
+    fn mismatched_binding() {
+        let bindings = [(0, BindingKind::Texture), (1, BindingKind::Sampler)];
+        let error = validate_bindings(&layout(), bindings).unwrap_err();
+
+        assert_eq!(
+            error,
+            BindGroupValidationError::MismatchedBinding {
+                binding: 0,
+                expected: BindingKind::Uniform,
+                provided: BindingKind::Texture
+            }
+        );
+    }
+
+    #[test]
+    fn unknown_binding() {
+        let bindings = [(0, BindingKind::Uniform), (3, BindingKind::Sampler)];
+        let error = validate_bindings(&layout(), bindings).unwrap_err();
+
+        assert_eq!(
+            error,
+            BindGroupValidationError::UnknownBinding { binding: 3 }
+        );
+    }
+
+    #[test]
+    fn uniform_in_storage_binding() {
+        let layout = [wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::VERTEX,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only: true },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        }];
+
+        let error = validate_bindings(&layout, [(0, BindingKind::Uniform)]).unwrap_err();
+        assert_eq!(
+            error,
+            BindGroupValidationError::MismatchedBinding {
+                binding: 0,
+                expected: BindingKind::Storage { read_only: true },
+                provided: BindingKind::Uniform
+            }
+        );
+
+        let storage = BindingKind::Storage { read_only: false };
+        assert_eq!(validate_bindings(&layout, [(0, storage)]), Ok(()));
+    }
+
+    #[test]
+    fn comparison_sampler_layout() {
+        let desc = SamplerDesc {
+            filter_mode: FilterMode::Linear,
+            compare: Some(wgpu::CompareFunction::LessEqual),
+            lod_min_clamp: 0.0,
+            lod_max_clamp: 4.0,
+            ..Default::default()
+        };
+
+        let mut builder = BindGroupLayoutBuilder::new();
+        builder.with_sampler_desc(0, wgpu::ShaderStages::FRAGMENT, &desc);
+
+        assert_eq!(
+            builder.entries()[0].ty,
+            wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Comparison)
+        );
+    }
+
+    #[derive(crate::AsBinding)]
+    struct Output {
+        #[storage_texture(0, format = "rgba8unorm", access = "write", visibility(compute))]
+        target: AssetId<Texture>,
+    }
+
+    #[test]
+    fn storage_texture_layout() {
+        let builder = Output::layout_builder();
+        let entry = &builder.entries()[0];
+
+        assert_eq!(entry.visibility, wgpu::ShaderStages::COMPUTE);
+        assert_eq!(
+            entry.ty,
+            wgpu::BindingType::StorageTexture {
+                access: wgpu::StorageTextureAccess::WriteOnly,
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                view_dimension: wgpu::TextureViewDimension::D2,
+            }
+        );
+
+        let size = wgpu::Extent3d {
+            width: 1,
+            height: 1,
+            depth_or_array_layers: 1,
+        };
+        let texture = Texture::new(
+            size,
+            TextureDimension::D2,
+            wgpu::TextureFormat::Rgba8Unorm,
+            vec![0; 4],
+        );
+
+        let usage = texture.clone().with_storage().unwrap().usage;
+        assert!(usage.contains(wgpu::TextureUsages::STORAGE_BINDING));
+        assert!(usage.contains(wgpu::TextureUsages::TEXTURE_BINDING));
+
+        let srgb = Texture {
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            ..texture
+        };
+        assert!(srgb.with_storage().is_err());
+    }
 }
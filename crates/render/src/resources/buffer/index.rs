@@ -28,20 +28,20 @@ pub struct Indices {
 
 impl Indices {
     pub fn new<T: Index>(indices: &[T]) -> Self {
-        let size = std::mem::size_of::<T>() as u64 * indices.len() as u64;
-        let format = T::format();
-
-        match format {
-            wgpu::IndexFormat::Uint16 => assert!(size <= u16::MAX as u64),
-            wgpu::IndexFormat::Uint32 => assert!(size <= u32::MAX as u64),
-        }
-
         Indices {
             data: bytemuck::cast_slice(indices).to_vec(),
             format: T::format(),
         }
     }
 
+    pub fn u16(indices: &[u16]) -> Self {
+        Self::new(indices)
+    }
+
+    pub fn u32(indices: &[u32]) -> Self {
+        Self::new(indices)
+    }
+
     pub fn format(&self) -> IndexFormat {
         self.format
     }
@@ -50,6 +50,14 @@ impl Indices {
         &self.data
     }
 
+    /// Iterates the indices as `u32` regardless of the underlying format.
+    pub fn iter(&self) -> IndexIter<'_> {
+        IndexIter {
+            data: self.data.chunks_exact(self.format.byte_size()),
+            format: self.format,
+        }
+    }
+
     pub fn as_ref<T: Index>(&self) -> &[T] {
         bytemuck::cast_slice(&self.data)
     }
@@ -87,6 +95,40 @@ impl Indices {
     }
 }
 
+impl<'a> IntoIterator for &'a Indices {
+    type Item = u32;
+    type IntoIter = IndexIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct IndexIter<'a> {
+    data: std::slice::ChunksExact<'a, u8>,
+    format: IndexFormat,
+}
+
+impl<'a> Iterator for IndexIter<'a> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bytes = self.data.next()?;
+        match self.format {
+            IndexFormat::Uint16 => Some(u16::from_ne_bytes([bytes[0], bytes[1]]) as u32),
+            IndexFormat::Uint32 => {
+                Some(u32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.data.size_hint()
+    }
+}
+
+impl<'a> ExactSizeIterator for IndexIter<'a> {}
+
 pub struct IndexBuffer {
     buffer: Buffer,
     format: IndexFormat,
@@ -130,16 +172,19 @@ impl IndexBuffer {
         }
     }
 
-    pub fn update(&mut self, device: &RenderDevice, indices: &Indices) {
+    /// Returns true if the buffer was reallocated.
+    pub fn update(&mut self, device: &RenderDevice, indices: &Indices) -> bool {
         let size = indices.size() as usize;
         if size > self.buffer.size() as usize {
             let usage = self.buffer.usage();
             self.buffer = Buffer::with_data(device, indices.data(), usage, None);
             self.len = indices.len();
             self.format = indices.format();
+            true
         } else {
             let data = indices.data();
             device.queue.write_buffer(self.buffer.as_ref(), 0, data);
+            false
         }
     }
 }
@@ -174,3 +219,40 @@ impl<'a> std::ops::Deref for IndexSlice<'a> {
         &self.slice.slice()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Indices;
+    use wgpu::IndexFormat;
+
+    #[test]
+    fn u16_indices() {
+        let indices = Indices::u16(&[0, 1, 2, 2, 1, u16::MAX]);
+
+        assert_eq!(indices.format(), IndexFormat::Uint16);
+        assert_eq!(indices.len(), 6);
+        assert_eq!(indices.data().len(), 6 * std::mem::size_of::<u16>());
+        assert_eq!(
+            indices.iter().collect::<Vec<_>>(),
+            vec![0, 1, 2, 2, 1, u16::MAX as u32]
+        );
+    }
+
+    #[test]
+    fn large_u16_indices() {
+        let data = (0..u16::MAX).collect::<Vec<_>>();
+        let indices = Indices::u16(&data);
+
+        assert_eq!(indices.len(), data.len());
+    }
+
+    #[test]
+    fn u32_indices() {
+        let indices = Indices::u32(&[0, 1, 2, 70_000]);
+
+        assert_eq!(indices.format(), IndexFormat::Uint32);
+        assert_eq!(indices.len(), 4);
+        assert_eq!(indices.data().len(), 4 * std::mem::size_of::<u32>());
+        assert_eq!(indices.iter().collect::<Vec<_>>(), vec![0, 1, 2, 70_000]);
+    }
+}
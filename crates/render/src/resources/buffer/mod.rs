@@ -5,11 +5,13 @@ use std::ops::{Deref, RangeBounds};
 use wgpu::{BufferUsages, util::DeviceExt};
 
 pub mod index;
+pub mod ring;
 pub mod storage;
 pub mod uniform;
 pub mod vertex;
 
 pub use index::*;
+pub use ring::*;
 pub use vertex::*;
 
 pub type BufferId = AtomicId<Buffer>;
@@ -124,7 +126,10 @@ impl Buffer {
         self.inner = buffer;
     }
 
-    pub fn update(&mut self, device: &RenderDevice, data: &[u8]) {
+    /// Writes `data` to the buffer, reallocating it if `data` doesn't fit.
+    /// Returns true if the buffer was reallocated, in which case any bind
+    /// groups that reference it need to be rebuilt.
+    pub fn update(&mut self, device: &RenderDevice, data: &[u8]) -> bool {
         if data.len() as u64 > self.size() {
             let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
                 label: None,
@@ -133,8 +138,10 @@ impl Buffer {
             });
 
             self.inner = buffer;
+            true
         } else {
             device.queue.write_buffer(&self.inner, 0, data);
+            false
         }
     }
 }
@@ -230,10 +237,45 @@ impl<'a> Deref for BufferSlice<'a> {
     }
 }
 
+/// Controls when an [`ArrayBuffer`] reallocates a smaller buffer after its
+/// values shrink. Growing always reallocates right away. Buffers never
+/// shrink unless a [`ShrinkPolicy::LowWater`] is set.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum ShrinkPolicy {
+    /// Keeps the largest buffer that was allocated.
+    #[default]
+    Never,
+    /// Shrinks once the values have stayed below `fraction` of the buffer's
+    /// size for `updates` consecutive updates.
+    LowWater { fraction: f32, updates: u32 },
+}
+
+impl ShrinkPolicy {
+    /// Returns true if a buffer of `capacity` bytes holding `size` bytes
+    /// should shrink. `below` counts the consecutive updates spent under the
+    /// low-water mark.
+    fn should_shrink(&self, size: u64, capacity: u64, below: &mut u32) -> bool {
+        match *self {
+            ShrinkPolicy::Never => false,
+            ShrinkPolicy::LowWater { fraction, updates } => {
+                if (size as f64) < capacity as f64 * fraction as f64 {
+                    *below += 1;
+                    *below >= updates
+                } else {
+                    *below = 0;
+                    false
+                }
+            }
+        }
+    }
+}
+
 pub struct ArrayBuffer<T: NoUninit> {
     values: Vec<T>,
     buffer: Buffer,
     is_dirty: bool,
+    shrink: ShrinkPolicy,
+    below: u32,
 }
 
 impl<T: NoUninit> ArrayBuffer<T> {
@@ -243,9 +285,25 @@ impl<T: NoUninit> ArrayBuffer<T> {
             values: Vec::with_capacity(capacity),
             buffer: Buffer::new(device, size, usages, label),
             is_dirty: false,
+            shrink: ShrinkPolicy::default(),
+            below: 0,
         }
     }
 
+    pub fn with_shrink_policy(mut self, policy: ShrinkPolicy) -> Self {
+        self.shrink = policy;
+        self
+    }
+
+    pub fn shrink_policy(&self) -> ShrinkPolicy {
+        self.shrink
+    }
+
+    pub fn set_shrink_policy(&mut self, policy: ShrinkPolicy) {
+        self.shrink = policy;
+        self.below = 0;
+    }
+
     pub fn values(&self) -> &[T] {
         &self.values
     }
@@ -287,8 +345,9 @@ impl<T: NoUninit> ArrayBuffer<T> {
     }
 
     /// Updates the buffer if it is dirty or if the size exceeds the current buffer size.
-    /// Returns the size of the buffer if it was updated, or `None` if no update was needed.
-    /// If the buffer is empty, it will set the buffer to `None`.
+    /// Returns the size of the buffer if it was reallocated, or `None` if the
+    /// existing buffer was written to. Smaller buffers are only reallocated
+    /// according to the [`ShrinkPolicy`].
     pub fn update(&mut self, device: &RenderDevice) -> Option<u64> {
         if !self.is_dirty {
             return None;
@@ -300,6 +359,17 @@ impl<T: NoUninit> ArrayBuffer<T> {
             let data = bytemuck::cast_slice(&self.values);
             self.buffer.resize_with_data(device, data);
             self.is_dirty = false;
+            self.below = 0;
+            Some(size)
+        } else if self
+            .shrink
+            .should_shrink(size, self.buffer.size(), &mut self.below)
+        {
+            let size = size.max(std::mem::size_of::<T>() as u64);
+            self.buffer.resize(device, size);
+            device.queue.write_buffer(self.buffer.as_ref(), 0, data);
+            self.is_dirty = false;
+            self.below = 0;
             Some(size)
         } else {
             let data = bytemuck::cast_slice(&self.values);
@@ -315,3 +385,99 @@ impl<T: NoUninit> AsRef<Buffer> for ArrayBuffer<T> {
         &self.buffer
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{ArrayBuffer, Buffer, ShrinkPolicy};
+    use crate::{BindGroupBuilder, BindGroupLayoutBuilder, device::tests::device};
+    use wgpu::BufferUsages;
+
+    #[test]
+    fn shrink_policy() {
+        let policy = ShrinkPolicy::LowWater {
+            fraction: 0.25,
+            updates: 3,
+        };
+
+        let mut below = 0;
+        assert!(!policy.should_shrink(40, 100, &mut below));
+        assert!(!policy.should_shrink(20, 100, &mut below));
+        assert!(!policy.should_shrink(20, 100, &mut below));
+        assert!(!policy.should_shrink(60, 100, &mut below));
+        assert_eq!(below, 0);
+
+        assert!(!policy.should_shrink(20, 100, &mut below));
+        assert!(!policy.should_shrink(20, 100, &mut below));
+        assert!(policy.should_shrink(20, 100, &mut below));
+        assert!(!ShrinkPolicy::Never.should_shrink(0, 100, &mut 0));
+    }
+
+    #[test]
+    #[ignore = "requires a GPU adapter"]
+    fn growing_reallocates() {
+        let device = device();
+
+        let usage = BufferUsages::UNIFORM | BufferUsages::COPY_DST;
+        let mut buffer = Buffer::new(&device, 16, usage, None);
+        let layout = BindGroupLayoutBuilder::new()
+            .with_uniform(0, wgpu::ShaderStages::all(), false, None, None)
+            .build(&device);
+        let mut bind_group = BindGroupBuilder::new(&layout)
+            .with_uniform(0, &buffer, 0, None)
+            .build(&device)
+            .unwrap();
+        let first = bind_group.id;
+
+        assert!(!buffer.update(&device, &[0; 16]));
+        assert!(buffer.update(&device, &[0; 32]));
+        assert_eq!(buffer.size(), 32);
+
+        bind_group = BindGroupBuilder::new(&layout)
+            .with_uniform(0, &buffer, 0, None)
+            .build(&device)
+            .unwrap();
+        assert_ne!(bind_group.id, first);
+    }
+
+    #[test]
+    #[ignore = "requires a GPU adapter"]
+    fn oscillating_updates_keep_buffer() {
+        let device = device();
+
+        let usages = BufferUsages::STORAGE | BufferUsages::COPY_DST;
+        let mut buffer = ArrayBuffer::<u32>::new(&device, 64, usages, None);
+        let mut reallocations = 0;
+
+        for frame in 0..120 {
+            let count = if frame % 2 == 0 { 40 } else { 20 };
+            buffer.clear();
+            for value in 0..count {
+                buffer.push(value);
+            }
+
+            if buffer.update(&device).is_some() {
+                reallocations += 1;
+            }
+        }
+
+        assert_eq!(reallocations, 0);
+        assert_eq!(buffer.as_ref().size(), 64 * 4);
+
+        buffer.set_shrink_policy(ShrinkPolicy::LowWater {
+            fraction: 0.25,
+            updates: 10,
+        });
+
+        for _ in 0..30 {
+            buffer.clear();
+            buffer.push(0);
+
+            if buffer.update(&device).is_some() {
+                reallocations += 1;
+            }
+        }
+
+        assert_eq!(reallocations, 1);
+        assert_eq!(buffer.as_ref().size(), 4);
+    }
+}
@@ -0,0 +1,186 @@
+use super::Buffer;
+use crate::{device::RenderDevice, resources::Label};
+use ecs::Resource;
+use wgpu::BufferUsages;
+
+/// Tracks the frame being recorded and how many frames the GPU may have queued
+/// at once.
+#[derive(Debug, Clone, Copy, Resource)]
+pub struct FramesInFlight {
+    count: usize,
+    frame: u64,
+}
+
+impl FramesInFlight {
+    pub const DEFAULT: usize = 2;
+
+    pub fn new(count: usize) -> Self {
+        assert!(count > 0, "At least one frame must be in flight");
+
+        Self { count, frame: 0 }
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// The ring slot used by the frame being recorded.
+    pub fn slot(&self) -> usize {
+        (self.frame % self.count as u64) as usize
+    }
+
+    pub fn advance(&mut self) {
+        self.frame += 1;
+    }
+}
+
+impl Default for FramesInFlight {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT)
+    }
+}
+
+/// One `T` per frame in flight. Writes go to the slot of the frame being
+/// recorded while the GPU keeps reading the slots of earlier frames.
+pub struct FrameRing<T> {
+    slots: Vec<T>,
+    index: usize,
+}
+
+impl<T> FrameRing<T> {
+    pub fn new(frames: &FramesInFlight, create: impl FnMut(usize) -> T) -> Self {
+        Self {
+            slots: (0..frames.count()).map(create).collect(),
+            index: frames.slot(),
+        }
+    }
+
+    /// Selects the slot for the frame being recorded. Call this before
+    /// writing to or binding the ring each frame.
+    pub fn set_frame(&mut self, frames: &FramesInFlight) {
+        self.index = (frames.frame() % self.slots.len() as u64) as usize;
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn current(&self) -> &T {
+        &self.slots[self.index]
+    }
+
+    pub fn current_mut(&mut self) -> &mut T {
+        &mut self.slots[self.index]
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.slots.get(index)
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.slots.get_mut(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.slots.iter()
+    }
+}
+
+/// The [`Buffer`] of each frame in flight for the buffer arrays, which are
+/// usually rewritten every frame. A slot is only written during its own
+/// frame, so slots still read by earlier frames are left alone.
+pub(crate) struct BufferRing {
+    buffers: FrameRing<Buffer>,
+    stale: Vec<bool>,
+}
+
+impl BufferRing {
+    pub fn new(
+        device: &RenderDevice,
+        frames: &FramesInFlight,
+        size: u64,
+        usages: BufferUsages,
+        label: Label,
+    ) -> Self {
+        Self {
+            buffers: FrameRing::new(frames, |_| Buffer::new(device, size, usages, label.clone())),
+            stale: vec![false; frames.count()],
+        }
+    }
+
+    pub fn set_frame(&mut self, frames: &FramesInFlight) {
+        self.buffers.set_frame(frames);
+    }
+
+    pub fn index(&self) -> usize {
+        self.buffers.index()
+    }
+
+    pub fn current(&self) -> &Buffer {
+        self.buffers.current()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Buffer> {
+        self.buffers.get(index)
+    }
+
+    /// Marks every slot as needing the latest data.
+    pub fn invalidate(&mut self) {
+        self.stale.fill(true);
+    }
+
+    /// Writes `data` to the current slot if it is stale. Returns the new size
+    /// if the slot was reallocated, in which case the bind group of that slot
+    /// needs to be rebuilt.
+    pub fn write(&mut self, device: &RenderDevice, data: &[u8], min_size: u64) -> Option<u64> {
+        let index = self.buffers.index();
+        if !std::mem::take(&mut self.stale[index]) {
+            return None;
+        }
+
+        let buffer = self.buffers.current_mut();
+        let size = data.len() as u64;
+        if size > buffer.size() {
+            buffer.resize_with_data(device, data);
+            Some(size.max(min_size))
+        } else {
+            device.queue.write_buffer(buffer.as_ref(), 0, data);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FrameRing, FramesInFlight};
+
+    #[test]
+    fn frames_use_own_slot() {
+        let mut frames = FramesInFlight::new(3);
+        let mut ring = FrameRing::new(&frames, |_| Vec::<u64>::new());
+
+        for frame in 0..7u64 {
+            ring.set_frame(&frames);
+            assert_eq!(ring.index(), (frame % 3) as usize);
+
+            let slot = ring.current_mut();
+            slot.clear();
+            slot.push(frame);
+
+            for previous in frame.saturating_sub(2)..frame {
+                let slot = (previous % 3) as usize;
+                assert_eq!(ring.get(slot), Some(&vec![previous]));
+            }
+
+            frames.advance();
+        }
+    }
+}
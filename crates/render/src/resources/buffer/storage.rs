@@ -1,6 +1,6 @@
 use std::num::NonZero;
 
-use super::Buffer;
+use super::{Buffer, BufferRing, FramesInFlight};
 use crate::{device::RenderDevice, resources::Label};
 use encase::{
     DynamicStorageBuffer as EncaseDynamicStorageBuffer, ShaderType,
@@ -89,16 +89,24 @@ impl<T: ShaderType + WriteInto> AsRef<Buffer> for StorageBuffer<T> {
     }
 }
 
+/// An array of values that is rewritten every frame. There is a buffer per
+/// frame in flight, so call [`StorageBufferArray::set_frame`] before writing
+/// to or binding the array each frame.
 pub struct StorageBufferArray<T: ShaderType> {
     data: EncaseDynamicStorageBuffer<Vec<u8>>,
-    buffer: Buffer,
+    buffers: BufferRing,
     alignment: u32,
     is_dirty: bool,
     _marker: std::marker::PhantomData<T>,
 }
 
 impl<T: ShaderType> StorageBufferArray<T> {
-    pub fn new(device: &RenderDevice, usages: Option<BufferUsages>, label: Label) -> Self {
+    pub fn new(
+        device: &RenderDevice,
+        frames: &FramesInFlight,
+        usages: Option<BufferUsages>,
+        label: Label,
+    ) -> Self {
         let alignment = device
             .limits()
             .min_uniform_buffer_offset_alignment
@@ -106,6 +114,7 @@ impl<T: ShaderType> StorageBufferArray<T> {
 
         Self::with_size(
             device,
+            frames,
             NonZero::new(alignment as u64),
             alignment,
             usages,
@@ -115,12 +124,14 @@ impl<T: ShaderType> StorageBufferArray<T> {
 
     pub fn with_alignment(
         device: &RenderDevice,
+        frames: &FramesInFlight,
         alignment: u32,
         usages: Option<BufferUsages>,
         label: Label,
     ) -> Self {
         Self::with_size(
             device,
+            frames,
             NonZero::new(alignment as u64),
             alignment,
             usages,
@@ -130,6 +141,7 @@ impl<T: ShaderType> StorageBufferArray<T> {
 
     pub fn with_size(
         device: &RenderDevice,
+        frames: &FramesInFlight,
         size: Option<NonZero<u64>>,
         alignment: u32,
         usages: Option<BufferUsages>,
@@ -140,23 +152,40 @@ impl<T: ShaderType> StorageBufferArray<T> {
             | BufferUsages::COPY_DST;
 
         let size = size.map(|s| s.get()).unwrap_or(T::min_size().get());
-        let buffer = Buffer::new(device, size, usages, label);
+        let buffers = BufferRing::new(device, frames, size, usages, label);
 
         Self {
             data: EncaseDynamicStorageBuffer::new(Vec::new()),
-            buffer,
+            buffers,
             alignment,
             is_dirty: false,
             _marker: std::marker::PhantomData,
         }
     }
 
+    /// Selects the buffer of the frame being recorded.
+    pub fn set_frame(&mut self, frames: &FramesInFlight) {
+        self.buffers.set_frame(frames);
+    }
+
+    /// The ring slot of the frame being recorded.
+    pub fn slot(&self) -> usize {
+        self.buffers.index()
+    }
+
+    /// The buffer of the frame being recorded.
     pub fn inner(&self) -> &Buffer {
-        &self.buffer
+        self.buffers.current()
+    }
+
+    /// The buffer of ring slot `slot`, e.g. to create the bind group of
+    /// every slot up front.
+    pub fn slot_buffer(&self, slot: usize) -> Option<&Buffer> {
+        self.buffers.get(slot)
     }
 
     pub fn binding(&'_ self) -> BindingResource<'_> {
-        self.buffer.as_entire_binding()
+        self.buffers.current().as_entire_binding()
     }
 
     pub fn data(&self) -> &[u8] {
@@ -175,25 +204,16 @@ impl<T: ShaderType> StorageBufferArray<T> {
         self.data.as_ref().len().div_ceil(self.alignment as usize)
     }
 
-    /// Updates the buffer if it is dirty or if the size exceeds the current buffer size.
-    /// Returns the size of the buffer if it was updated, or `None` if no update was needed.
+    /// Writes the values to the buffer of the current frame if they changed
+    /// since that buffer was last written. Returns the size of the buffer if
+    /// it was reallocated, or `None` if the existing buffer was used.
     pub fn update(&mut self, device: &RenderDevice) -> Option<u64> {
-        if !self.is_dirty {
-            return None;
+        if std::mem::take(&mut self.is_dirty) {
+            self.buffers.invalidate();
         }
 
-        self.is_dirty = false;
-
-        let size = self.data.as_ref().len() as u64;
-        if size > self.buffer.size() {
-            let size = size.max(self.alignment as u64);
-            self.buffer.resize_with_data(device, self.data.as_ref());
-            Some(size)
-        } else {
-            let data = bytemuck::cast_slice(self.data());
-            device.queue.write_buffer(self.buffer.as_ref(), 0, data);
-            None
-        }
+        self.buffers
+            .write(device, self.data.as_ref(), self.alignment as u64)
     }
 }
 
@@ -236,6 +256,6 @@ impl<T: ShaderType + WriteInto> StorageBufferArray<T> {
 
 impl<T: ShaderType> AsRef<Buffer> for StorageBufferArray<T> {
     fn as_ref(&self) -> &Buffer {
-        &self.buffer
+        self.buffers.current()
     }
 }
@@ -1,6 +1,6 @@
 use std::num::NonZero;
 
-use super::Buffer;
+use super::{Buffer, BufferRing, FramesInFlight};
 use crate::{device::RenderDevice, resources::Label};
 use encase::{
     DynamicUniformBuffer as EncaseDynamicUniformBuffer, ShaderType,
@@ -8,6 +8,8 @@ use encase::{
 };
 use wgpu::{BindingResource, BufferUsages, DynamicOffset};
 
+/// A single uniform value, such as per-frame globals. The buffer is padded
+/// to at least `T::min_size()` and is only written when the value changes.
 pub struct UniformBuffer<T: ShaderType + WriteInto> {
     value: T,
     data: EncaseUniformBuffer<Vec<u8>>,
@@ -28,7 +30,9 @@ impl<T: ShaderType + WriteInto> UniformBuffer<T> {
         let usages = usages.unwrap_or(BufferUsages::empty())
             | BufferUsages::UNIFORM
             | BufferUsages::COPY_DST;
-        let buffer = Buffer::with_data(device, data.as_ref(), usages, label);
+        let mut contents = data.as_ref().clone();
+        contents.resize(contents.len().max(T::min_size().get() as usize), 0);
+        let buffer = Buffer::with_data(device, &contents, usages, label);
 
         Self {
             value,
@@ -65,14 +69,15 @@ impl<T: ShaderType + WriteInto> UniformBuffer<T> {
 
     pub fn set(&mut self, value: T) {
         self.value = value;
-        self.data.write(&self.value).unwrap();
         self.is_dirty = true;
     }
 
-    /// Updates the buffer if it is dirty or creates a new buffer if it doesn't exist.
-    /// Returns `true` only if a new buffer was created.
+    /// Encodes the value and writes it to the buffer if it changed since the
+    /// last update, either through [`UniformBuffer::set`] or
+    /// [`UniformBuffer::get_mut`]. Returns `true` if the buffer was written to.
     pub fn update(&mut self, device: &RenderDevice) -> bool {
         if self.is_dirty {
+            self.data.write(&self.value).unwrap();
             let data = self.data.as_ref();
             device.queue.write_buffer(self.buffer.as_ref(), 0, &data);
             self.is_dirty = false;
@@ -89,16 +94,24 @@ impl<T: ShaderType + WriteInto> AsRef<Buffer> for UniformBuffer<T> {
     }
 }
 
+/// An array of values that is rewritten every frame. There is a buffer per
+/// frame in flight, so call [`UniformBufferArray::set_frame`] before writing
+/// to or binding the array each frame.
 pub struct UniformBufferArray<T: ShaderType> {
     data: EncaseDynamicUniformBuffer<Vec<u8>>,
-    buffer: Buffer,
+    buffers: BufferRing,
     alignment: u32,
     is_dirty: bool,
     _marker: std::marker::PhantomData<T>,
 }
 
 impl<T: ShaderType> UniformBufferArray<T> {
-    pub fn new(device: &RenderDevice, usages: Option<BufferUsages>, label: Label) -> Self {
+    pub fn new(
+        device: &RenderDevice,
+        frames: &FramesInFlight,
+        usages: Option<BufferUsages>,
+        label: Label,
+    ) -> Self {
         let alignment = device
             .limits()
             .min_uniform_buffer_offset_alignment
@@ -106,6 +119,7 @@ impl<T: ShaderType> UniformBufferArray<T> {
 
         Self::with_size(
             device,
+            frames,
             NonZero::new(alignment as u64),
             alignment,
             usages,
@@ -115,12 +129,14 @@ impl<T: ShaderType> UniformBufferArray<T> {
 
     pub fn with_alignment(
         device: &RenderDevice,
+        frames: &FramesInFlight,
         alignment: u32,
         usages: Option<BufferUsages>,
         label: Label,
     ) -> Self {
         Self::with_size(
             device,
+            frames,
             NonZero::new(alignment as u64),
             alignment,
             usages,
@@ -130,6 +146,7 @@ impl<T: ShaderType> UniformBufferArray<T> {
 
     pub fn with_size(
         device: &RenderDevice,
+        frames: &FramesInFlight,
         size: Option<NonZero<u64>>,
         alignment: u32,
         usages: Option<BufferUsages>,
@@ -141,23 +158,40 @@ impl<T: ShaderType> UniformBufferArray<T> {
 
         let size = size.map(|s| s.get()).unwrap_or(T::min_size().get());
 
-        let buffer = Buffer::new(device, size, usages, label);
+        let buffers = BufferRing::new(device, frames, size, usages, label);
 
         Self {
             data: EncaseDynamicUniformBuffer::new(Vec::new()),
-            buffer,
+            buffers,
             alignment,
             is_dirty: false,
             _marker: std::marker::PhantomData,
         }
     }
 
+    /// Selects the buffer of the frame being recorded.
+    pub fn set_frame(&mut self, frames: &FramesInFlight) {
+        self.buffers.set_frame(frames);
+    }
+
+    /// The ring slot of the frame being recorded.
+    pub fn slot(&self) -> usize {
+        self.buffers.index()
+    }
+
+    /// The buffer of the frame being recorded.
     pub fn inner(&self) -> &Buffer {
-        &self.buffer
+        self.buffers.current()
+    }
+
+    /// The buffer of ring slot `slot`, e.g. to create the bind group of
+    /// every slot up front.
+    pub fn slot_buffer(&self, slot: usize) -> Option<&Buffer> {
+        self.buffers.get(slot)
     }
 
     pub fn binding(&'_ self) -> BindingResource<'_> {
-        self.buffer.as_entire_binding()
+        self.buffers.current().as_entire_binding()
     }
 
     pub fn data(&self) -> &[u8] {
@@ -178,25 +212,16 @@ impl<T: ShaderType> UniformBufferArray<T> {
         self.data.as_ref().len().div_ceil(self.alignment as usize)
     }
 
-    /// Updates the buffer if it is dirty or if the size exceeds the current buffer size.
-    /// Returns the size of the buffer if it was updated, or `None` if no update was needed.
+    /// Writes the values to the buffer of the current frame if they changed
+    /// since that buffer was last written. Returns the size of the buffer if
+    /// it was reallocated, or `None` if the existing buffer was used.
     pub fn update(&mut self, device: &RenderDevice) -> Option<u64> {
-        if !self.is_dirty {
-            return None;
+        if std::mem::take(&mut self.is_dirty) {
+            self.buffers.invalidate();
         }
 
-        self.is_dirty = false;
-
-        let size = self.data.as_ref().len() as u64;
-        if size > self.buffer.size() {
-            let size = size.max(self.alignment as u64);
-            self.buffer.resize_with_data(device, self.data.as_ref());
-            Some(size)
-        } else {
-            let data = bytemuck::cast_slice(self.data());
-            device.queue.write_buffer(self.buffer.as_ref(), 0, data);
-            None
-        }
+        self.buffers
+            .write(device, self.data.as_ref(), self.alignment as u64)
     }
 }
 
@@ -239,6 +264,92 @@ impl<T: ShaderType + WriteInto> UniformBufferArray<T> {
 
 impl<T: ShaderType> AsRef<Buffer> for UniformBufferArray<T> {
     fn as_ref(&self) -> &Buffer {
-        &self.buffer
+        self.buffers.current()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{UniformBuffer, UniformBufferArray};
+    use crate::{FramesInFlight, device::tests::device};
+    use encase::ShaderType;
+    use math::{Mat4, Vec3};
+
+    #[test]
+    #[ignore = "requires a GPU adapter"]
+    fn uniform_mat4() {
+        let device = device();
+
+        let mut buffer = UniformBuffer::new(&device, Mat4::IDENTITY, None, None);
+        assert!(!buffer.is_dirty());
+
+        buffer.set(Mat4::from_scale(math::Vec3::splat(2.0)));
+        assert!(buffer.is_dirty());
+        assert!(buffer.update(&device));
+        assert!(!buffer.update(&device));
+
+        assert_eq!(buffer.inner().size(), Mat4::min_size().get());
+        assert_eq!(buffer.get(), &Mat4::from_scale(math::Vec3::splat(2.0)));
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, ShaderType)]
+    struct Light {
+        intensity: f32,
+        color: Vec3,
+    }
+
+    #[test]
+    #[ignore = "requires a GPU adapter"]
+    fn uniform_get_mut_reencodes_padded_value() {
+        let device = device();
+
+        let light = Light {
+            intensity: 1.0,
+            color: Vec3::ZERO,
+        };
+        let mut buffer = UniformBuffer::new(&device, light, None, None);
+
+        buffer.get_mut().color = Vec3::new(0.25, 0.5, 0.75);
+        assert!(buffer.update(&device));
+
+        // `color` is 16 byte aligned, so 12 bytes of padding follow `intensity`.
+        let data = buffer.data();
+        let read = |offset: usize| f32::from_le_bytes(data[offset..offset + 4].try_into().unwrap());
+        assert_eq!(data.len() as u64, Light::min_size().get());
+        assert_eq!(read(0), 1.0);
+        assert_eq!([read(16), read(20), read(24)], [0.25, 0.5, 0.75]);
+        assert_eq!(buffer.inner().size(), Light::min_size().get());
+    }
+
+    #[test]
+    #[ignore = "requires a GPU adapter"]
+    fn array_frames_use_own_buffer() {
+        let device = device();
+        let mut frames = FramesInFlight::new(3);
+        let mut array = UniformBufferArray::<Mat4>::new(&device, &frames, None, None);
+
+        let mut written = Vec::new();
+        for frame in 0..7u64 {
+            array.set_frame(&frames);
+            assert_eq!(array.slot(), (frame % 3) as usize);
+
+            array.clear();
+            array.push(&Mat4::from_scale(Vec3::splat(frame as f32)));
+            array.update(&device);
+            written.push(array.inner().id());
+
+            // The buffers of the frames still in flight are different ones.
+            for previous in frame.saturating_sub(2)..frame {
+                assert_ne!(written[previous as usize], array.inner().id());
+            }
+
+            // Clean slots are left alone until their frame comes around.
+            assert!(array.update(&device).is_none());
+            frames.advance();
+        }
+
+        for slot in 0..3 {
+            assert_eq!(array.slot_buffer(slot).map(|b| b.id()), Some(written[slot]));
+        }
     }
 }
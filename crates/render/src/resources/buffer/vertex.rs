@@ -55,15 +55,18 @@ impl VertexBuffer {
         self.len
     }
 
-    pub fn update<T: Pod + Zeroable>(&mut self, device: &RenderDevice, vertices: &[T]) {
+    /// Returns true if the buffer was reallocated.
+    pub fn update<T: Pod + Zeroable>(&mut self, device: &RenderDevice, vertices: &[T]) -> bool {
         let size = vertices.len() * std::mem::size_of::<T>();
         if size > self.buffer.size() as usize {
             let usage = self.buffer.usage();
             self.buffer = Buffer::with_data(device, bytemuck::cast_slice(vertices), usage, None);
             self.len = vertices.len();
+            true
         } else {
             let data = bytemuck::cast_slice(vertices);
             device.queue.write_buffer(self.buffer.as_ref(), 0, data);
+            false
         }
     }
 }
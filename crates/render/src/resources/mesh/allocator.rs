@@ -54,6 +54,15 @@ impl ChunkMeta {
         }
     }
 
+    /// The size of one element in the chunk. Index chunks count indices
+    /// while vertex chunks count aligned vertices.
+    fn stride(&self) -> u32 {
+        match self.usage {
+            ChunkUsage::Vertex => self.alignment,
+            ChunkUsage::Index => self.size,
+        }
+    }
+
     fn align(size: u32) -> u32 {
         use wgpu::COPY_BUFFER_ALIGNMENT;
 
@@ -269,15 +278,15 @@ impl MeshAllocator {
         match chunk {
             MeshChunk::Inline(chunk) => {
                 let alloc = chunk.allocations.get(id)?;
-                let range = alloc.offset / chunk.meta.alignment
-                    ..(alloc.offset + alloc.size) / chunk.meta.alignment;
+                let stride = chunk.meta.stride();
+                let range = alloc.offset / stride..(alloc.offset + alloc.size) / stride;
                 Some(MeshBufferSlice {
                     buffer: &chunk.buffer,
                     range,
                 })
             }
             MeshChunk::Block(chunk) => {
-                let range = 0..(chunk.buffer.size() as u32).div_ceil(chunk.meta.alignment);
+                let range = 0..(chunk.buffer.size() as u32).div_ceil(chunk.meta.stride());
                 Some(MeshBufferSlice {
                     buffer: &chunk.buffer,
                     range,
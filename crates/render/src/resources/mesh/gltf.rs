@@ -0,0 +1,563 @@
+use super::{Mesh, MeshAttribute, MeshAttributeType, MeshAttributeValues, MeshTopology, SubMesh};
+use crate::resources::{buffer::Indices, extract::ReadWrite};
+use asset::{
+    AssetImporter, AssetSettings, Settings,
+    importer::{AddChildAssetError, ImportContext},
+    io::{AsyncIoError, AsyncReader},
+};
+use math::{Vec2, Vec3, Vec4};
+use std::collections::HashMap;
+
+#[derive(Settings, Default, serde::Serialize, serde::Deserialize)]
+pub struct MeshSettings {
+    pub read_write: ReadWrite,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum GltfError {
+    #[error(transparent)]
+    Io(#[from] AsyncIoError),
+    #[error("Invalid glb container")]
+    InvalidGlb,
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("Buffer {0} could not be found")]
+    MissingBuffer(usize),
+    #[error("Accessor {0} is out of bounds")]
+    InvalidAccessor(usize),
+    #[error("Accessor {0} has an unsupported component type or shape")]
+    UnsupportedAccessor(usize),
+    #[error("Primitive mode {0} is not supported, only triangle lists are")]
+    UnsupportedMode(u32),
+    #[error("Primitive has no POSITION attribute")]
+    MissingPositions,
+    #[error("{attribute} has {found} elements but POSITION has {expected}")]
+    AttributeCount {
+        attribute: &'static str,
+        expected: usize,
+        found: usize,
+    },
+    #[error(transparent)]
+    AddChild(#[from] AddChildAssetError),
+}
+
+/// Imports the meshes of a `.gltf` or `.glb` file as a single [`Mesh`] with
+/// a [`SubMesh`] child per primitive. Indices are local to their primitive,
+/// so each sub mesh is drawn with its index range and its start vertex as the
+/// base vertex.
+///
+/// Primitives without normals have them calculated from their triangles.
+pub struct GltfImporter;
+
+impl AssetImporter for GltfImporter {
+    type Asset = Mesh;
+
+    type Settings = MeshSettings;
+
+    type Error = GltfError;
+
+    async fn import(
+        ctx: &mut ImportContext<'_>,
+        reader: &mut dyn AsyncReader,
+        metadata: &AssetSettings<Self::Settings>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let (document, buffers) = match bytes.starts_with(GLB_MAGIC) {
+            true => parse_glb(&bytes)?,
+            false => {
+                let document = serde_json::from_slice::<Document>(&bytes)?;
+                let mut buffers = Vec::with_capacity(document.buffers.len());
+                for (index, buffer) in document.buffers.iter().enumerate() {
+                    let uri = match &buffer.uri {
+                        Some(uri) if !uri.starts_with("data:") => uri,
+                        _ => return Err(GltfError::MissingBuffer(index)),
+                    };
+
+                    let mut data = Vec::new();
+                    ctx.read_sibling(uri).await?.read_to_end(&mut data).await?;
+                    buffers.push(data);
+                }
+
+                (document, buffers)
+            }
+        };
+
+        let (mesh, sub_meshes) = build_mesh(&document, &buffers)?;
+        for (index, sub_mesh) in sub_meshes.into_iter().enumerate() {
+            ctx.add_child(&format!("primitive{index}"), sub_mesh)?;
+        }
+
+        Ok(mesh.with_read_write(metadata.read_write))
+    }
+
+    fn extensions() -> &'static [&'static str] {
+        &["gltf", "glb"]
+    }
+}
+
+const GLB_MAGIC: &[u8] = b"glTF";
+const GLB_JSON: u32 = 0x4E4F534A;
+const GLB_BIN: u32 = 0x004E4942;
+
+const TRIANGLES: u32 = 4;
+const UNSIGNED_BYTE: u32 = 5121;
+const UNSIGNED_SHORT: u32 = 5123;
+const UNSIGNED_INT: u32 = 5125;
+const FLOAT: u32 = 5126;
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Document {
+    #[serde(default)]
+    meshes: Vec<MeshDef>,
+    #[serde(default)]
+    accessors: Vec<Accessor>,
+    #[serde(default)]
+    buffer_views: Vec<BufferView>,
+    #[serde(default)]
+    buffers: Vec<Buffer>,
+}
+
+#[derive(serde::Deserialize)]
+struct MeshDef {
+    primitives: Vec<Primitive>,
+}
+
+#[derive(serde::Deserialize)]
+struct Primitive {
+    attributes: HashMap<String, usize>,
+    indices: Option<usize>,
+    mode: Option<u32>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Accessor {
+    buffer_view: Option<usize>,
+    #[serde(default)]
+    byte_offset: usize,
+    component_type: u32,
+    count: usize,
+    #[serde(rename = "type")]
+    ty: String,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BufferView {
+    buffer: usize,
+    #[serde(default)]
+    byte_offset: usize,
+    byte_length: usize,
+    byte_stride: Option<usize>,
+}
+
+#[derive(serde::Deserialize)]
+struct Buffer {
+    uri: Option<String>,
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    let bytes = bytes.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// Splits a binary glTF into its JSON document and the buffer stored in its
+/// `BIN` chunk, which is the document's first buffer.
+fn parse_glb(bytes: &[u8]) -> Result<(Document, Vec<Vec<u8>>), GltfError> {
+    let length = read_u32(bytes, 8).ok_or(GltfError::InvalidGlb)? as usize;
+    let bytes = bytes.get(..length).ok_or(GltfError::InvalidGlb)?;
+
+    let mut json = None;
+    let mut bin = None;
+    let mut offset = 12;
+    while offset < bytes.len() {
+        let length = read_u32(bytes, offset).ok_or(GltfError::InvalidGlb)? as usize;
+        let ty = read_u32(bytes, offset + 4).ok_or(GltfError::InvalidGlb)?;
+        let start = offset + 8;
+        let chunk = bytes
+            .get(start..start + length)
+            .ok_or(GltfError::InvalidGlb)?;
+
+        match ty {
+            GLB_JSON => json = Some(chunk),
+            GLB_BIN => bin = Some(chunk),
+            _ => (),
+        }
+
+        offset = start + length;
+    }
+
+    let document = serde_json::from_slice::<Document>(json.ok_or(GltfError::InvalidGlb)?)?;
+    let buffers = document
+        .buffers
+        .iter()
+        .enumerate()
+        .map(|(index, buffer)| match (index, &buffer.uri, bin) {
+            (0, None, Some(bin)) => Ok(bin.to_vec()),
+            _ => Err(GltfError::MissingBuffer(index)),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok((document, buffers))
+}
+
+fn components(ty: &str) -> Option<usize> {
+    match ty {
+        "SCALAR" => Some(1),
+        "VEC2" => Some(2),
+        "VEC3" => Some(3),
+        "VEC4" => Some(4),
+        _ => None,
+    }
+}
+
+/// Returns the bytes of each element of an accessor.
+fn elements<'a>(
+    document: &Document,
+    buffers: &'a [Vec<u8>],
+    index: usize,
+    size: usize,
+) -> Result<Vec<&'a [u8]>, GltfError> {
+    let accessor = document
+        .accessors
+        .get(index)
+        .ok_or(GltfError::InvalidAccessor(index))?;
+    let view = accessor
+        .buffer_view
+        .and_then(|view| document.buffer_views.get(view))
+        .ok_or(GltfError::InvalidAccessor(index))?;
+    let buffer = buffers
+        .get(view.buffer)
+        .ok_or(GltfError::MissingBuffer(view.buffer))?;
+    let data = buffer
+        .get(view.byte_offset..view.byte_offset + view.byte_length)
+        .ok_or(GltfError::InvalidAccessor(index))?;
+
+    let stride = view.byte_stride.unwrap_or(size);
+    (0..accessor.count)
+        .map(|element| {
+            let start = accessor.byte_offset + element * stride;
+            data.get(start..start + size)
+                .ok_or(GltfError::InvalidAccessor(index))
+        })
+        .collect()
+}
+
+fn read_floats<const N: usize>(
+    document: &Document,
+    buffers: &[Vec<u8>],
+    index: usize,
+) -> Result<Vec<[f32; N]>, GltfError> {
+    let accessor = document
+        .accessors
+        .get(index)
+        .ok_or(GltfError::InvalidAccessor(index))?;
+    if accessor.component_type != FLOAT || components(&accessor.ty) != Some(N) {
+        return Err(GltfError::UnsupportedAccessor(index));
+    }
+
+    let elements = elements(document, buffers, index, N * 4)?;
+    Ok(elements
+        .into_iter()
+        .map(|bytes| std::array::from_fn(|i| bytemuck::pod_read_unaligned(&bytes[i * 4..][..4])))
+        .collect())
+}
+
+fn read_indices(
+    document: &Document,
+    buffers: &[Vec<u8>],
+    index: usize,
+) -> Result<Vec<u32>, GltfError> {
+    let accessor = document
+        .accessors
+        .get(index)
+        .ok_or(GltfError::InvalidAccessor(index))?;
+    if components(&accessor.ty) != Some(1) {
+        return Err(GltfError::UnsupportedAccessor(index));
+    }
+
+    let size = match accessor.component_type {
+        UNSIGNED_BYTE => 1,
+        UNSIGNED_SHORT => 2,
+        UNSIGNED_INT => 4,
+        _ => return Err(GltfError::UnsupportedAccessor(index)),
+    };
+
+    let elements = elements(document, buffers, index, size)?;
+    Ok(elements
+        .into_iter()
+        .map(|bytes| match bytes {
+            [a] => *a as u32,
+            [a, b] => u16::from_le_bytes([*a, *b]) as u32,
+            _ => bytemuck::pod_read_unaligned::<u32>(bytes),
+        })
+        .collect())
+}
+
+/// Merges every primitive of every mesh in `document` into one [`Mesh`].
+/// Each primitive's indices stay relative to its first vertex, which is
+/// stored as the start vertex of its [`SubMesh`].
+fn build_mesh(document: &Document, buffers: &[Vec<u8>]) -> Result<(Mesh, Vec<SubMesh>), GltfError> {
+    let primitives = document.meshes.iter().flat_map(|mesh| &mesh.primitives);
+    let has_uvs = primitives
+        .clone()
+        .any(|p| p.attributes.contains_key("TEXCOORD_0"));
+    let has_tangents = primitives
+        .clone()
+        .any(|p| p.attributes.contains_key("TANGENT"));
+
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut uvs = Vec::new();
+    let mut tangents = Vec::new();
+    let mut indices = Vec::new();
+    let mut sub_meshes = Vec::new();
+
+    for primitive in primitives {
+        let mode = primitive.mode.unwrap_or(TRIANGLES);
+        if mode != TRIANGLES {
+            return Err(GltfError::UnsupportedMode(mode));
+        }
+
+        let attribute = |name: &str| primitive.attributes.get(name).copied();
+        let position = attribute("POSITION").ok_or(GltfError::MissingPositions)?;
+        let local_positions = read_floats::<3>(document, buffers, position)?
+            .into_iter()
+            .map(Vec3::from)
+            .collect::<Vec<_>>();
+        let count = local_positions.len();
+
+        let local_indices = match primitive.indices {
+            Some(index) => read_indices(document, buffers, index)?,
+            None => (0..count as u32).collect(),
+        };
+        if local_indices.iter().any(|index| *index as usize >= count) {
+            return Err(GltfError::InvalidAccessor(
+                primitive.indices.unwrap_or(position),
+            ));
+        }
+
+        let check_count = |attribute: &'static str, found: usize| match found == count {
+            true => Ok(()),
+            false => Err(GltfError::AttributeCount {
+                attribute,
+                expected: count,
+                found,
+            }),
+        };
+
+        let local_normals = match attribute("NORMAL") {
+            Some(index) => {
+                let normals = read_floats::<3>(document, buffers, index)?;
+                check_count("NORMAL", normals.len())?;
+                normals.into_iter().map(Vec3::from).collect()
+            }
+            None => Mesh::calculate_normals(&local_positions, &local_indices),
+        };
+
+        let local_uvs = match attribute("TEXCOORD_0") {
+            Some(index) => {
+                let uvs = read_floats::<2>(document, buffers, index)?;
+                check_count("TEXCOORD_0", uvs.len())?;
+                uvs.into_iter().map(Vec2::from).collect()
+            }
+            None => vec![Vec2::ZERO; count],
+        };
+
+        if has_tangents {
+            match attribute("TANGENT") {
+                Some(index) => {
+                    let local_tangents = read_floats::<4>(document, buffers, index)?;
+                    check_count("TANGENT", local_tangents.len())?;
+                    tangents.extend(local_tangents.into_iter().map(Vec4::from));
+                }
+                None => tangents.extend(Mesh::calculate_tangents(
+                    &local_positions,
+                    &local_normals,
+                    &local_uvs,
+                    &local_indices,
+                )),
+            }
+        }
+
+        sub_meshes.push(SubMesh::new(
+            positions.len() as u32,
+            count as u32,
+            indices.len() as u32,
+            local_indices.len() as u32,
+        ));
+
+        indices.extend(local_indices);
+        positions.extend(local_positions);
+        normals.extend(local_normals);
+        uvs.extend(local_uvs);
+    }
+
+    let mut mesh = Mesh::new(MeshTopology::TriangleList)
+        .with_attribute(MeshAttribute::new(
+            MeshAttributeType::Position,
+            MeshAttributeValues::Vec3(positions),
+        ))
+        .with_attribute(MeshAttribute::new(
+            MeshAttributeType::Normal,
+            MeshAttributeValues::Vec3(normals),
+        ));
+
+    if has_uvs {
+        mesh.add_attribute(MeshAttribute::new(
+            MeshAttributeType::TexCoord0,
+            MeshAttributeValues::Vec2(uvs),
+        ));
+    }
+
+    if has_tangents {
+        mesh.add_attribute(MeshAttribute::new(
+            MeshAttributeType::Tangent,
+            MeshAttributeValues::Vec4(tangents),
+        ));
+    }
+
+    mesh.set_indices(Indices::u32(&indices));
+    mesh.calculate_bounds();
+
+    Ok((mesh, sub_meshes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GltfError, build_mesh, parse_glb};
+    use crate::resources::mesh::{MeshAttributeType, MeshAttributeValues};
+    use math::Vec3;
+
+    const POSITIONS: [[f32; 3]; 3] = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+    const OFFSET_POSITIONS: [[f32; 3]; 3] = [[2.0, 0.0, 0.0], [3.0, 0.0, 0.0], [2.0, 1.0, 0.0]];
+
+    /// Builds a glb with two triangle primitives sharing one buffer. Only the
+    /// first primitive has texture coordinates and indices, and neither has
+    /// normals.
+    fn glb() -> Vec<u8> {
+        let uvs: [[f32; 2]; 3] = [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]];
+        let indices: [u16; 4] = [2, 1, 0, 0];
+
+        let mut bin = Vec::new();
+        bin.extend_from_slice(bytemuck::cast_slice(&POSITIONS));
+        bin.extend_from_slice(bytemuck::cast_slice(&uvs));
+        bin.extend_from_slice(bytemuck::cast_slice(&OFFSET_POSITIONS));
+        bin.extend_from_slice(bytemuck::cast_slice(&indices));
+
+        let json = format!(
+            r#"{{
+                "asset": {{ "version": "2.0" }},
+                "buffers": [{{ "byteLength": {} }}],
+                "bufferViews": [
+                    {{ "buffer": 0, "byteOffset": 0, "byteLength": 36 }},
+                    {{ "buffer": 0, "byteOffset": 36, "byteLength": 24 }},
+                    {{ "buffer": 0, "byteOffset": 60, "byteLength": 36 }},
+                    {{ "buffer": 0, "byteOffset": 96, "byteLength": 6 }}
+                ],
+                "accessors": [
+                    {{ "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3" }},
+                    {{ "bufferView": 1, "componentType": 5126, "count": 3, "type": "VEC2" }},
+                    {{ "bufferView": 2, "componentType": 5126, "count": 3, "type": "VEC3" }},
+                    {{ "bufferView": 3, "componentType": 5123, "count": 3, "type": "SCALAR" }}
+                ],
+                "meshes": [
+                    {{ "primitives": [{{ "attributes": {{ "POSITION": 0, "TEXCOORD_0": 1 }}, "indices": 3 }}] }},
+                    {{ "primitives": [{{ "attributes": {{ "POSITION": 2 }} }}] }}
+                ]
+            }}"#,
+            bin.len()
+        );
+
+        let mut json = json.into_bytes();
+        while json.len() % 4 != 0 {
+            json.push(b' ');
+        }
+        while bin.len() % 4 != 0 {
+            bin.push(0);
+        }
+
+        let length = 12 + 8 + json.len() + 8 + bin.len();
+        let mut glb = Vec::with_capacity(length);
+        glb.extend_from_slice(b"glTF");
+        glb.extend_from_slice(&2u32.to_le_bytes());
+        glb.extend_from_slice(&(length as u32).to_le_bytes());
+        glb.extend_from_slice(&(json.len() as u32).to_le_bytes());
+        glb.extend_from_slice(b"JSON");
+        glb.extend_from_slice(&json);
+        glb.extend_from_slice(&(bin.len() as u32).to_le_bytes());
+        glb.extend_from_slice(b"BIN\0");
+        glb.extend_from_slice(&bin);
+        glb
+    }
+
+    #[test]
+    fn import_glb() {
+        let (document, buffers) = parse_glb(&glb()).unwrap();
+        let (mesh, sub_meshes) = build_mesh(&document, &buffers).unwrap();
+
+        let attributes = mesh.attributes().iter().map(|a| a.ty).collect::<Vec<_>>();
+        assert_eq!(
+            attributes,
+            vec![
+                MeshAttributeType::Position,
+                MeshAttributeType::Normal,
+                MeshAttributeType::TexCoord0,
+            ]
+        );
+
+        assert_eq!(sub_meshes.len(), 2);
+        assert_eq!(mesh.vertex_count(), 6);
+        assert_eq!(mesh.index_count(), 6);
+        assert_eq!(sub_meshes[1].start_vertex, 3);
+        assert_eq!(sub_meshes[1].start_index, 3);
+    }
+
+    #[test]
+    fn sub_mesh_draw_args_resolve_primitive_vertices() {
+        let (document, buffers) = parse_glb(&glb()).unwrap();
+        let (mesh, sub_meshes) = build_mesh(&document, &buffers).unwrap();
+
+        let positions = match &mesh.attribute(MeshAttributeType::Position).unwrap().values {
+            MeshAttributeValues::Vec3(positions) => positions,
+            _ => unreachable!(),
+        };
+        let indices = mesh.indices().unwrap().iter().collect::<Vec<_>>();
+
+        // Resolves vertices the way the GPU does for an indexed draw.
+        let resolve = |index: usize| {
+            let args = sub_meshes[index].draw_indexed_args(0..1);
+            let start = args.first_index as usize;
+            indices[start..start + args.index_count as usize]
+                .iter()
+                .map(|index| positions[(*index as i32 + args.base_vertex) as usize])
+                .collect::<Vec<_>>()
+        };
+
+        let expected = |positions: [[f32; 3]; 3], order: [usize; 3]| {
+            order.map(|index| Vec3::from(positions[index])).to_vec()
+        };
+
+        assert_eq!(resolve(0), expected(POSITIONS, [2, 1, 0]));
+        assert_eq!(resolve(1), expected(OFFSET_POSITIONS, [0, 1, 2]));
+    }
+
+    #[test]
+    fn attribute_count_mismatch() {
+        let (mut document, buffers) = parse_glb(&glb()).unwrap();
+        document.accessors[1].count = 2;
+
+        let result = build_mesh(&document, &buffers);
+        assert!(matches!(
+            result,
+            Err(GltfError::AttributeCount {
+                attribute: "TEXCOORD_0",
+                expected: 3,
+                found: 2,
+            })
+        ));
+    }
+}
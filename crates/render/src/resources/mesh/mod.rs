@@ -13,6 +13,14 @@ use std::{
 use wgpu::{IndexFormat, VertexStepMode};
 
 pub mod allocator;
+pub mod gltf;
+mod shapes;
+pub mod strip;
+pub mod uv;
+
+pub use gltf::{GltfError, GltfImporter, MeshSettings};
+pub use strip::*;
+pub use uv::*;
 
 #[derive(
     Copy, Clone, Debug, PartialEq, Eq, Hash, Default, serde::Serialize, serde::Deserialize,
@@ -207,6 +215,31 @@ impl SubMesh {
             index_count,
         }
     }
+
+    /// Indirect arguments that draw `instances` of the sub mesh's vertices.
+    /// `first_instance` is the start of the instances' range in the object
+    /// buffer.
+    pub fn draw_args(&self, instances: Range<u32>) -> wgpu::util::DrawIndirectArgs {
+        wgpu::util::DrawIndirectArgs {
+            vertex_count: self.vertex_count,
+            instance_count: instances.len() as u32,
+            first_vertex: self.start_vertex,
+            first_instance: instances.start,
+        }
+    }
+
+    /// Indirect arguments that draw `instances` of the sub mesh's indices.
+    /// `first_instance` is the start of the instances' range in the object
+    /// buffer.
+    pub fn draw_indexed_args(&self, instances: Range<u32>) -> wgpu::util::DrawIndexedIndirectArgs {
+        wgpu::util::DrawIndexedIndirectArgs {
+            index_count: self.index_count,
+            instance_count: instances.len() as u32,
+            first_index: self.start_index,
+            base_vertex: self.start_vertex as i32,
+            first_instance: instances.start,
+        }
+    }
 }
 
 impl From<&Mesh> for SubMesh {
@@ -394,6 +427,45 @@ impl Mesh {
         self.indices.as_ref().map_or(0, |i| i.len())
     }
 
+    /// Stores `u32` indices as `u16` when every index fits, halving the size
+    /// of the index buffer. Strip restart indices are converted to the `u16`
+    /// restart index. Returns whether the indices were converted.
+    pub fn optimize_index_width(&mut self) -> bool {
+        let Some(indices) = &self.indices else {
+            return false;
+        };
+
+        if indices.format() != IndexFormat::Uint32 {
+            return false;
+        }
+
+        let strip = matches!(
+            self.topology,
+            MeshTopology::LineStrip | MeshTopology::TriangleStrip
+        );
+        let restart = Self::restart_index(IndexFormat::Uint32);
+        let max = match strip {
+            true => u16::MAX as u32 - 1,
+            false => u16::MAX as u32,
+        };
+
+        if !indices.iter().all(|i| i <= max || (strip && i == restart)) {
+            return false;
+        }
+
+        let indices = indices
+            .iter()
+            .map(|i| match i == restart {
+                true => u16::MAX,
+                false => i as u16,
+            })
+            .collect::<Vec<_>>();
+
+        self.set_indices(Indices::u16(&indices));
+
+        true
+    }
+
     pub fn calculate_bounds(&mut self) {
         let bounds_dirty = self.dirty.contains(MeshDirty::BOUNDS);
         let Some(attribute) = self.attribute(MeshAttributeType::Position) else {
@@ -545,43 +617,174 @@ impl Mesh {
         normals
     }
 
-    pub fn build_indices(positions: &[math::Vec3]) -> Vec<u32> {
-        #[derive(Hash, Eq, PartialEq, Clone, Copy)]
-        struct VertexKey {
-            x: i32,
-            y: i32,
-            z: i32,
+    /// Generates the [`MeshAttributeType::Tangent`] attribute of a triangle
+    /// list from its positions, normals, `TexCoord0` and indices. Meshes
+    /// without texture coordinates are left unchanged.
+    pub fn generate_tangents(&mut self) {
+        if self.topology != MeshTopology::TriangleList {
+            return;
         }
 
-        impl From<&math::Vec3> for VertexKey {
-            fn from(vec: &math::Vec3) -> Self {
-                const SCALE: f32 = 1000.0; // Scale to avoid floating point precision issues
+        let positions = self.attribute(MeshAttributeType::Position);
+        let uvs = self.attribute(MeshAttributeType::TexCoord0);
+        let (Some(MeshAttributeValues::Vec3(positions)), Some(MeshAttributeValues::Vec2(uvs))) =
+            (positions.map(|a| &a.values), uvs.map(|a| &a.values))
+        else {
+            return;
+        };
+
+        let indices = match &self.indices {
+            Some(indices) => indices.iter().collect::<Vec<_>>(),
+            None => (0..positions.len() as u32).collect(),
+        };
 
-                Self {
-                    x: (vec.x * SCALE) as i32,
-                    y: (vec.y * SCALE) as i32,
-                    z: (vec.z * SCALE) as i32,
-                }
+        let normals = match self.attribute(MeshAttributeType::Normal).map(|a| &a.values) {
+            Some(MeshAttributeValues::Vec3(normals)) => normals.clone(),
+            _ => Self::calculate_normals(positions, &indices),
+        };
+
+        let tangents = Self::calculate_tangents(positions, &normals, uvs, &indices);
+        self.add_attribute(MeshAttribute::new(
+            MeshAttributeType::Tangent,
+            MeshAttributeValues::Vec4(tangents),
+        ));
+    }
+
+    /// Accumulates the tangent and bitangent of each triangle onto its
+    /// vertices, then orthonormalizes each tangent against the vertex normal.
+    /// The handedness of the bitangent is stored in `w` as `1.0` or `-1.0`.
+    pub fn calculate_tangents(
+        positions: &[math::Vec3],
+        normals: &[math::Vec3],
+        uvs: &[math::Vec2],
+        indices: &[u32],
+    ) -> Vec<math::Vec4> {
+        let mut tangents = vec![math::Vec3::ZERO; positions.len()];
+        let mut bitangents = vec![math::Vec3::ZERO; positions.len()];
+
+        for chunk in indices.chunks_exact(3) {
+            let [a, b, c] = [chunk[0] as usize, chunk[1] as usize, chunk[2] as usize];
+
+            let edge1 = positions[b] - positions[a];
+            let edge2 = positions[c] - positions[a];
+            let delta1 = uvs[b] - uvs[a];
+            let delta2 = uvs[c] - uvs[a];
+
+            let determinant = delta1.x * delta2.y - delta2.x * delta1.y;
+            if determinant.abs() < 1e-8 {
+                continue; // Skip degenerate uvs
+            }
+
+            let r = 1.0 / determinant;
+            let tangent = (edge1 * delta2.y - edge2 * delta1.y) * r;
+            let bitangent = (edge2 * delta1.x - edge1 * delta2.x) * r;
+
+            for index in [a, b, c] {
+                tangents[index] += tangent;
+                bitangents[index] += bitangent;
             }
         }
 
+        normals
+            .iter()
+            .zip(tangents)
+            .zip(bitangents)
+            .map(|((normal, tangent), bitangent)| {
+                let tangent = (tangent - *normal * normal.dot(tangent))
+                    .try_normalize()
+                    .unwrap_or_else(|| normal.any_orthonormal_vector());
+
+                let handedness = match normal.cross(tangent).dot(bitangent) < 0.0 {
+                    true => -1.0,
+                    false => 1.0,
+                };
+
+                tangent.extend(handedness)
+            })
+            .collect()
+    }
+
+    /// Builds indices that point every vertex at the first vertex with the
+    /// same position. Positions are quantized to `epsilon`, or to a step
+    /// derived from the bounds of `positions` when `None`.
+    pub fn build_indices(positions: &[math::Vec3], epsilon: Option<f32>) -> Vec<u32> {
+        Self::weld(positions, None, None, epsilon)
+    }
+
+    /// Builds welded indices like [`Mesh::build_indices`], optionally keeping
+    /// vertices apart when their normals or texture coordinates differ so
+    /// that hard edges and uv seams survive.
+    pub fn weld_indices(&self, options: WeldOptions) -> Vec<u32> {
+        let values = |ty| self.attribute(ty).map(|a: &MeshAttribute| &a.values);
+        let Some(MeshAttributeValues::Vec3(positions)) = values(MeshAttributeType::Position) else {
+            return Vec::new();
+        };
+
+        let normals = match values(MeshAttributeType::Normal) {
+            Some(MeshAttributeValues::Vec3(normals)) if options.normals => Some(normals.as_slice()),
+            _ => None,
+        };
+
+        let uvs = match values(MeshAttributeType::TexCoord0) {
+            Some(MeshAttributeValues::Vec2(uvs)) if options.tex_coords => Some(uvs.as_slice()),
+            _ => None,
+        };
+
+        Self::weld(positions, normals, uvs, options.epsilon)
+    }
+
+    fn weld(
+        positions: &[math::Vec3],
+        normals: Option<&[math::Vec3]>,
+        uvs: Option<&[math::Vec2]>,
+        epsilon: Option<f32>,
+    ) -> Vec<u32> {
+        const DIRECTION_SCALE: f32 = 1000.0;
+        const UV_SCALE: f32 = 10000.0;
+
+        let scale = match epsilon {
+            Some(epsilon) => 1.0 / epsilon.max(f32::EPSILON),
+            None => {
+                let bounds = Aabb::from(positions);
+                let size = (bounds.max - bounds.min).max_element();
+                1.0 / (size * 1e-5).max(1e-6)
+            }
+        };
+
+        let quantize = |value: f32, scale: f32| (value * scale).round() as i64;
+
         let mut keys = IndexMap::new();
-        let mut indices = Vec::new();
+        let mut indices = Vec::with_capacity(positions.len());
 
         for (i, position) in positions.iter().enumerate() {
-            let key = VertexKey::from(position);
-            if let Some(&index) = keys.get(&key) {
-                indices.push(index as u32);
-            } else {
-                keys.insert(key, i);
-                indices.push(i as u32);
-            }
+            let normal = normals.and_then(|n| n.get(i)).copied().unwrap_or_default();
+            let uv = uvs.and_then(|uv| uv.get(i)).copied().unwrap_or_default();
+            let key = (
+                position.to_array().map(|v| quantize(v, scale)),
+                normal.to_array().map(|v| quantize(v, DIRECTION_SCALE)),
+                uv.to_array().map(|v| quantize(v, UV_SCALE)),
+            );
+
+            let index = *keys.entry(key).or_insert(i);
+            indices.push(index as u32);
         }
 
         indices
     }
 }
 
+/// How [`Mesh::weld_indices`] decides that two vertices are the same.
+#[derive(Debug, Default, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct WeldOptions {
+    /// The distance positions are quantized to. `None` derives it from the
+    /// bounds of the mesh.
+    pub epsilon: Option<f32>,
+    /// Keeps vertices with different normals apart.
+    pub normals: bool,
+    /// Keeps vertices with different `TexCoord0` values apart.
+    pub tex_coords: bool,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct MeshAttributeLayout {
     pub ty: MeshAttributeType,
@@ -782,3 +985,115 @@ impl From<AssetId<Mesh>> for MeshFilter {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Mesh, MeshAttribute, MeshAttributeType, MeshAttributeValues, MeshTopology, SubMesh,
+        WeldOptions,
+    };
+    use crate::resources::buffer::Indices;
+    use math::{Vec2, Vec3};
+    use std::collections::HashSet;
+    use wgpu::IndexFormat;
+
+    #[test]
+    fn quad_tangents() {
+        let mut mesh = Mesh::plane(1.0, 0);
+        mesh.generate_tangents();
+
+        let Some(MeshAttributeValues::Vec4(tangents)) = mesh
+            .attribute(MeshAttributeType::Tangent)
+            .map(|a| &a.values)
+        else {
+            panic!("Expected vec4 tangents");
+        };
+
+        assert_eq!(tangents.len(), 4);
+        for tangent in tangents {
+            assert!(tangent.truncate().abs_diff_eq(Vec3::X, 1e-5), "{tangent:?}");
+            assert_eq!(tangent.w.abs(), 1.0);
+        }
+    }
+
+    #[test]
+    fn sub_mesh_draw_args() {
+        let sub_mesh = SubMesh::new(4, 8, 6, 12);
+
+        let args = sub_mesh.draw_indexed_args(5..8);
+        assert_eq!(args.first_instance, 5);
+        assert_eq!(args.instance_count, 3);
+        assert_eq!(args.first_index, 6);
+        assert_eq!(args.index_count, 12);
+        assert_eq!(args.base_vertex, 4);
+
+        let args = sub_mesh.draw_args(5..8);
+        assert_eq!(args.first_instance, 5);
+        assert_eq!(args.instance_count, 3);
+        assert_eq!(args.first_vertex, 4);
+        assert_eq!(args.vertex_count, 8);
+    }
+
+    #[test]
+    fn optimize_index_width() {
+        let mut mesh = Mesh::cube(1.0);
+        let indices = mesh.indices().unwrap().iter().collect::<Vec<_>>();
+        mesh.set_indices(Indices::u32(&indices));
+        let triangles = mesh.index_count() / 3;
+
+        assert!(mesh.optimize_index_width());
+        assert!(!mesh.optimize_index_width());
+
+        let optimized = mesh.indices().unwrap();
+        assert_eq!(optimized.format(), IndexFormat::Uint16);
+        assert_eq!(optimized.len() / 3, triangles);
+        assert_eq!(optimized.iter().collect::<Vec<_>>(), indices);
+
+        let mut large = Mesh::new(MeshTopology::TriangleList);
+        large.set_indices(Indices::u32(&[0, 1, 70_000]));
+        assert!(!large.optimize_index_width());
+        assert_eq!(large.indices().unwrap().format(), IndexFormat::Uint32);
+    }
+
+    #[test]
+    fn weld_cube() {
+        let mesh = Mesh::cube(1.0);
+        let unique = |indices: Vec<u32>| indices.into_iter().collect::<HashSet<_>>().len();
+
+        let Some(MeshAttributeValues::Vec3(positions)) = mesh
+            .attribute(MeshAttributeType::Position)
+            .map(|a| &a.values)
+        else {
+            panic!("Expected vec3 positions");
+        };
+
+        assert_eq!(unique(Mesh::build_indices(positions, None)), 8);
+        assert_eq!(unique(Mesh::build_indices(positions, Some(0.01))), 8);
+        assert_eq!(unique(mesh.weld_indices(WeldOptions::default())), 8);
+
+        let seams = WeldOptions {
+            normals: true,
+            tex_coords: true,
+            ..Default::default()
+        };
+        assert_eq!(unique(mesh.weld_indices(seams)), 24);
+    }
+
+    #[test]
+    fn tangents_need_uvs() {
+        let mut mesh = Mesh::new(MeshTopology::TriangleList);
+        mesh.add_attribute(MeshAttribute::new(
+            MeshAttributeType::Position,
+            MeshAttributeValues::Vec3(vec![Vec3::ZERO, Vec3::X, Vec3::Z]),
+        ));
+        mesh.generate_tangents();
+        assert!(!mesh.has_attribute(MeshAttributeType::Tangent));
+
+        mesh.add_attribute(MeshAttribute::new(
+            MeshAttributeType::TexCoord0,
+            MeshAttributeValues::Vec2(vec![Vec2::ZERO, Vec2::X, Vec2::Y]),
+        ));
+        mesh.generate_tangents();
+        assert!(mesh.has_attribute(MeshAttributeType::Tangent));
+    }
+}
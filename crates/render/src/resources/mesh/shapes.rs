@@ -0,0 +1,281 @@
+use super::{Mesh, MeshAttribute, MeshAttributeType, MeshAttributeValues, MeshTopology};
+use crate::resources::buffer::Indices;
+use math::{Vec2, Vec3};
+use std::f32::consts::{PI, TAU};
+
+impl Mesh {
+    /// Axis aligned cube centered at the origin with 4 vertices per face.
+    pub fn cube(size: f32) -> Self {
+        const FACES: [(Vec3, Vec3, Vec3); 6] = [
+            (Vec3::X, Vec3::NEG_Z, Vec3::Y),
+            (Vec3::NEG_X, Vec3::Z, Vec3::Y),
+            (Vec3::Y, Vec3::X, Vec3::NEG_Z),
+            (Vec3::NEG_Y, Vec3::X, Vec3::Z),
+            (Vec3::Z, Vec3::X, Vec3::Y),
+            (Vec3::NEG_Z, Vec3::NEG_X, Vec3::Y),
+        ];
+
+        let half = size * 0.5;
+        let mut positions = Vec::with_capacity(24);
+        let mut normals = Vec::with_capacity(24);
+        let mut uvs = Vec::with_capacity(24);
+        let mut indices = Vec::with_capacity(36);
+
+        for (normal, u, v) in FACES {
+            let start = positions.len() as u32;
+            let corners = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)];
+            for (x, y) in corners {
+                positions.push((normal + u * x + v * y) * half);
+                normals.push(normal);
+                uvs.push(Vec2::new((x + 1.0) * 0.5, (1.0 - y) * 0.5));
+            }
+
+            indices.extend([start, start + 1, start + 2, start, start + 2, start + 3]);
+        }
+
+        Self::from_shape(positions, normals, uvs, indices)
+    }
+
+    /// Plane on the XZ axis facing up. `subdivisions` is the number of cuts
+    /// made along each side.
+    pub fn plane(size: f32, subdivisions: u32) -> Self {
+        let quads = subdivisions + 1;
+        let side = quads + 1;
+        let half = size * 0.5;
+
+        let mut positions = Vec::with_capacity((side * side) as usize);
+        let mut normals = Vec::with_capacity((side * side) as usize);
+        let mut uvs = Vec::with_capacity((side * side) as usize);
+        let mut indices = Vec::with_capacity((quads * quads * 6) as usize);
+
+        for z in 0..side {
+            for x in 0..side {
+                let uv = Vec2::new(x as f32 / quads as f32, z as f32 / quads as f32);
+                positions.push(Vec3::new(uv.x * size - half, 0.0, uv.y * size - half));
+                normals.push(Vec3::Y);
+                uvs.push(uv);
+            }
+        }
+
+        for z in 0..quads {
+            for x in 0..quads {
+                let a = z * side + x;
+                let b = a + 1;
+                let c = a + side;
+                let d = c + 1;
+                indices.extend([a, c, b, b, c, d]);
+            }
+        }
+
+        Self::from_shape(positions, normals, uvs, indices)
+    }
+
+    /// Sphere centered at the origin. `sectors` are the slices around the Y
+    /// axis and `stacks` the rings from pole to pole.
+    pub fn uv_sphere(radius: f32, sectors: u32, stacks: u32) -> Self {
+        let sectors = sectors.max(3);
+        let stacks = stacks.max(2);
+        let count = ((stacks + 1) * (sectors + 1)) as usize;
+
+        let mut positions = Vec::with_capacity(count);
+        let mut normals = Vec::with_capacity(count);
+        let mut uvs = Vec::with_capacity(count);
+        let mut indices = Vec::with_capacity((sectors * (stacks - 1) * 6) as usize);
+
+        for stack in 0..=stacks {
+            let phi = PI * 0.5 - PI * stack as f32 / stacks as f32;
+            let (y, xz) = phi.sin_cos();
+
+            for sector in 0..=sectors {
+                let theta = TAU * sector as f32 / sectors as f32;
+                let (sin, cos) = theta.sin_cos();
+                let normal = Vec3::new(xz * cos, y, -xz * sin);
+
+                positions.push(normal * radius);
+                normals.push(normal);
+                uvs.push(Vec2::new(
+                    sector as f32 / sectors as f32,
+                    stack as f32 / stacks as f32,
+                ));
+            }
+        }
+
+        for stack in 0..stacks {
+            let top = stack * (sectors + 1);
+            let bottom = top + sectors + 1;
+
+            for sector in 0..sectors {
+                let (k1, k2) = (top + sector, bottom + sector);
+
+                if stack != 0 {
+                    indices.extend([k1, k2, k1 + 1]);
+                }
+
+                if stack != stacks - 1 {
+                    indices.extend([k1 + 1, k2, k2 + 1]);
+                }
+            }
+        }
+
+        Self::from_shape(positions, normals, uvs, indices)
+    }
+
+    /// Capped cylinder centered at the origin and aligned to the Y axis.
+    pub fn cylinder(radius: f32, height: f32, segments: u32) -> Self {
+        let segments = segments.max(3);
+        let half = height * 0.5;
+        let count = (segments * 4 + 6) as usize;
+
+        let mut positions = Vec::with_capacity(count);
+        let mut normals = Vec::with_capacity(count);
+        let mut uvs = Vec::with_capacity(count);
+        let mut indices = Vec::with_capacity((segments * 12) as usize);
+
+        let ring = |segment: u32| {
+            let theta = TAU * segment as f32 / segments as f32;
+            let (sin, cos) = theta.sin_cos();
+            Vec3::new(cos, 0.0, -sin)
+        };
+
+        for segment in 0..=segments {
+            let normal = ring(segment);
+            let u = segment as f32 / segments as f32;
+
+            positions.push(normal * radius + Vec3::Y * half);
+            positions.push(normal * radius - Vec3::Y * half);
+            normals.extend([normal, normal]);
+            uvs.extend([Vec2::new(u, 0.0), Vec2::new(u, 1.0)]);
+        }
+
+        for segment in 0..segments {
+            let top = segment * 2;
+            let bottom = top + 1;
+            indices.extend([top, bottom, top + 2, top + 2, bottom, bottom + 2]);
+        }
+
+        for (y, normal) in [(half, Vec3::Y), (-half, Vec3::NEG_Y)] {
+            let center = positions.len() as u32;
+            positions.push(Vec3::new(0.0, y, 0.0));
+            normals.push(normal);
+            uvs.push(Vec2::splat(0.5));
+
+            for segment in 0..=segments {
+                let point = ring(segment);
+                positions.push(point * radius + Vec3::new(0.0, y, 0.0));
+                normals.push(normal);
+                uvs.push(Vec2::new(0.5 + point.x * 0.5, 0.5 + point.z * 0.5));
+            }
+
+            for segment in 0..segments {
+                let current = center + 1 + segment;
+                if y > 0.0 {
+                    indices.extend([center, current, current + 1]);
+                } else {
+                    indices.extend([center, current + 1, current]);
+                }
+            }
+        }
+
+        Self::from_shape(positions, normals, uvs, indices)
+    }
+
+    fn from_shape(
+        positions: Vec<Vec3>,
+        normals: Vec<Vec3>,
+        uvs: Vec<Vec2>,
+        indices: Vec<u32>,
+    ) -> Self {
+        let mut mesh = Mesh::new(MeshTopology::TriangleList)
+            .with_attribute(MeshAttribute::new(
+                MeshAttributeType::Position,
+                MeshAttributeValues::Vec3(positions),
+            ))
+            .with_attribute(MeshAttribute::new(
+                MeshAttributeType::Normal,
+                MeshAttributeValues::Vec3(normals),
+            ))
+            .with_attribute(MeshAttribute::new(
+                MeshAttributeType::TexCoord0,
+                MeshAttributeValues::Vec2(uvs),
+            ))
+            .with_indices(Indices::u32(&indices));
+
+        mesh.calculate_bounds();
+        mesh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::resources::mesh::{Mesh, MeshAttributeType, MeshAttributeValues};
+    use math::Vec3;
+
+    fn assert_normals(mesh: &Mesh) {
+        let Some(MeshAttributeValues::Vec3(normals)) =
+            mesh.attribute(MeshAttributeType::Normal).map(|a| &a.values)
+        else {
+            panic!("Expected vec3 normals");
+        };
+
+        for normal in normals {
+            assert!((normal.length() - 1.0).abs() < 1e-5);
+        }
+    }
+
+    fn assert_bounds(mesh: &Mesh, min: Vec3, max: Vec3) {
+        let bounds = mesh.bounds();
+        assert!(bounds.min.abs_diff_eq(min, 1e-5), "{:?}", bounds.min);
+        assert!(bounds.max.abs_diff_eq(max, 1e-5), "{:?}", bounds.max);
+    }
+
+    fn assert_indices(mesh: &Mesh) {
+        let count = mesh.vertex_count() as u32;
+        let indices = mesh.indices().unwrap();
+        assert_eq!(indices.len() % 3, 0);
+        assert!(indices.iter().all(|index| index < count));
+    }
+
+    #[test]
+    fn cube() {
+        let mesh = Mesh::cube(2.0);
+
+        assert_eq!(mesh.vertex_count(), 24);
+        assert_eq!(mesh.index_count(), 36);
+        assert_indices(&mesh);
+        assert_normals(&mesh);
+        assert_bounds(&mesh, Vec3::splat(-1.0), Vec3::splat(1.0));
+    }
+
+    #[test]
+    fn plane() {
+        let mesh = Mesh::plane(4.0, 2);
+
+        assert_eq!(mesh.vertex_count(), 16);
+        assert_eq!(mesh.index_count(), 54);
+        assert_indices(&mesh);
+        assert_normals(&mesh);
+        assert_bounds(&mesh, Vec3::new(-2.0, 0.0, -2.0), Vec3::new(2.0, 0.0, 2.0));
+    }
+
+    #[test]
+    fn uv_sphere() {
+        let mesh = Mesh::uv_sphere(1.5, 16, 8);
+
+        assert_eq!(mesh.vertex_count(), 9 * 17);
+        assert_eq!(mesh.index_count(), 16 * 7 * 6);
+        assert_indices(&mesh);
+        assert_normals(&mesh);
+        assert_bounds(&mesh, Vec3::splat(-1.5), Vec3::splat(1.5));
+    }
+
+    #[test]
+    fn cylinder() {
+        let mesh = Mesh::cylinder(0.5, 3.0, 8);
+
+        assert_eq!(mesh.vertex_count(), 8 * 4 + 6);
+        assert_eq!(mesh.index_count(), 8 * 12);
+        assert_indices(&mesh);
+        assert_normals(&mesh);
+        assert_bounds(&mesh, Vec3::new(-0.5, -1.5, -0.5), Vec3::new(0.5, 1.5, 0.5));
+    }
+}
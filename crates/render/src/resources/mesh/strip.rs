@@ -0,0 +1,195 @@
+use super::{Mesh, MeshTopology};
+use crate::resources::buffer::Indices;
+use wgpu::IndexFormat;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum MeshStripError {
+    #[error("Expected a {expected:?} mesh, found {found:?}")]
+    InvalidTopology {
+        expected: MeshTopology,
+        found: MeshTopology,
+    },
+    #[error("Vertex index {0} is reserved for primitive restart")]
+    RestartIndex(u32),
+}
+
+impl Mesh {
+    /// The index that restarts a strip for the given index format.
+    pub fn restart_index(format: IndexFormat) -> u32 {
+        match format {
+            IndexFormat::Uint16 => u16::MAX as u32,
+            IndexFormat::Uint32 => u32::MAX,
+        }
+    }
+
+    /// Converts a triangle list into a triangle strip. Triangles that can't
+    /// continue the current strip start a new one after a restart index.
+    pub fn to_triangle_strip(&self) -> Result<Mesh, MeshStripError> {
+        if self.topology != MeshTopology::TriangleList {
+            return Err(MeshStripError::InvalidTopology {
+                expected: MeshTopology::TriangleList,
+                found: self.topology,
+            });
+        }
+
+        let (list, format) = self.index_list();
+        let restart = Self::restart_index(format);
+        if let Some(index) = list.iter().find(|index| **index == restart) {
+            return Err(MeshStripError::RestartIndex(*index));
+        }
+
+        let mut strip: Vec<u32> = Vec::with_capacity(list.len());
+        let mut start = 0;
+
+        for triangle in list.chunks_exact(3) {
+            let next = match strip.len() - start {
+                len if len >= 3 => {
+                    let (x, y) = (strip[strip.len() - 2], strip[strip.len() - 1]);
+                    // Every other triangle in a strip has its first two vertices swapped.
+                    let edge = if (len - 2) % 2 == 0 { (x, y) } else { (y, x) };
+
+                    (0..3)
+                        .map(|i| (triangle[i], triangle[(i + 1) % 3], triangle[(i + 2) % 3]))
+                        .find(|(a, b, _)| (*a, *b) == edge)
+                        .map(|(_, _, c)| c)
+                }
+                _ => None,
+            };
+
+            match next {
+                Some(index) => strip.push(index),
+                None => {
+                    if !strip.is_empty() {
+                        strip.push(restart);
+                    }
+
+                    start = strip.len();
+                    strip.extend_from_slice(triangle);
+                }
+            }
+        }
+
+        let mut mesh = self.clone();
+        mesh.topology = MeshTopology::TriangleStrip;
+        mesh.set_indices(Self::create_indices(&strip, format));
+
+        Ok(mesh)
+    }
+
+    /// Converts a triangle strip, including any restart indices, back into a
+    /// triangle list. Degenerate triangles are dropped.
+    pub fn to_triangle_list(&self) -> Result<Mesh, MeshStripError> {
+        if self.topology != MeshTopology::TriangleStrip {
+            return Err(MeshStripError::InvalidTopology {
+                expected: MeshTopology::TriangleStrip,
+                found: self.topology,
+            });
+        }
+
+        let (strip, format) = self.index_list();
+        let restart = Self::restart_index(format);
+        let mut list = Vec::with_capacity(strip.len() * 3);
+
+        for strip in strip.split(|index| *index == restart) {
+            for (i, window) in strip.windows(3).enumerate() {
+                let [a, b, c] = [window[0], window[1], window[2]];
+                if a == b || b == c || a == c {
+                    continue;
+                }
+
+                if i % 2 == 0 {
+                    list.extend([a, b, c]);
+                } else {
+                    list.extend([b, a, c]);
+                }
+            }
+        }
+
+        let mut mesh = self.clone();
+        mesh.topology = MeshTopology::TriangleList;
+        mesh.set_indices(Self::create_indices(&list, format));
+
+        Ok(mesh)
+    }
+
+    fn index_list(&self) -> (Vec<u32>, IndexFormat) {
+        match &self.indices {
+            Some(indices) => (indices.iter().collect(), indices.format()),
+            None => {
+                let count = self.vertex_count() as u32;
+                let format = if count < u16::MAX as u32 {
+                    IndexFormat::Uint16
+                } else {
+                    IndexFormat::Uint32
+                };
+
+                ((0..count).collect(), format)
+            }
+        }
+    }
+
+    fn create_indices(indices: &[u32], format: IndexFormat) -> Indices {
+        match format {
+            IndexFormat::Uint16 => {
+                Indices::u16(&indices.iter().map(|i| *i as u16).collect::<Vec<_>>())
+            }
+            IndexFormat::Uint32 => Indices::u32(indices),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MeshStripError;
+    use crate::resources::mesh::{Mesh, MeshTopology};
+
+    fn triangles(mesh: &Mesh) -> Vec<[u32; 3]> {
+        let indices = mesh.indices().unwrap().iter().collect::<Vec<_>>();
+        let mut triangles = indices
+            .chunks_exact(3)
+            .map(|t| {
+                // Rotate the smallest index to the front without changing the winding.
+                let min = (0..3).min_by_key(|i| t[*i]).unwrap();
+                [t[min], t[(min + 1) % 3], t[(min + 2) % 3]]
+            })
+            .collect::<Vec<_>>();
+
+        triangles.sort();
+        triangles
+    }
+
+    #[test]
+    fn triangle_strip_round_trip() {
+        let list = Mesh::plane(1.0, 2);
+        let strip = list.to_triangle_strip().unwrap();
+
+        assert_eq!(strip.topology(), MeshTopology::TriangleStrip);
+        assert!(strip.index_count() < list.index_count());
+
+        let restart = Mesh::restart_index(strip.indices().unwrap().format());
+        assert!(
+            strip
+                .indices()
+                .unwrap()
+                .iter()
+                .any(|index| index == restart)
+        );
+
+        let round_trip = strip.to_triangle_list().unwrap();
+        assert_eq!(round_trip.topology(), MeshTopology::TriangleList);
+        assert_eq!(triangles(&round_trip), triangles(&list));
+    }
+
+    #[test]
+    fn triangle_strip_invalid_topology() {
+        let strip = Mesh::plane(1.0, 0).to_triangle_strip().unwrap();
+
+        assert_eq!(
+            strip.to_triangle_strip().err(),
+            Some(MeshStripError::InvalidTopology {
+                expected: MeshTopology::TriangleList,
+                found: MeshTopology::TriangleStrip,
+            })
+        );
+    }
+}
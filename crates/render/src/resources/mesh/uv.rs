@@ -0,0 +1,158 @@
+use super::{Mesh, MeshAttribute, MeshAttributeType, MeshAttributeValues};
+use crate::Aabb;
+use math::{Vec2, Vec3};
+use std::f32::consts::{PI, TAU};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectionAxis {
+    X,
+    Y,
+    Z,
+}
+
+impl ProjectionAxis {
+    /// The axis the normal points along the most.
+    pub fn dominant(normal: Vec3) -> Self {
+        let normal = normal.abs();
+        if normal.x >= normal.y && normal.x >= normal.z {
+            Self::X
+        } else if normal.y >= normal.z {
+            Self::Y
+        } else {
+            Self::Z
+        }
+    }
+
+    /// Drops this axis from `value`, keeping the other two in order.
+    fn flatten(&self, value: Vec3) -> Vec2 {
+        match self {
+            Self::X => Vec2::new(value.y, value.z),
+            Self::Y => Vec2::new(value.x, value.z),
+            Self::Z => Vec2::new(value.x, value.y),
+        }
+    }
+}
+
+/// How [`Mesh::generate_tex_coords`] maps positions to texture coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TexCoordProjection {
+    /// Projects along `axis` so the bounds of the mesh cover `[0, 1]` on the
+    /// other two axes.
+    Planar { axis: ProjectionAxis },
+    /// Projects each vertex along the dominant axis of its normal.
+    Box,
+    /// Wraps around the center of the bounds, with `u` following longitude
+    /// around the Y axis and `v` latitude from top to bottom.
+    Spherical,
+}
+
+impl Mesh {
+    /// Generates the [`MeshAttributeType::TexCoord0`] attribute from the
+    /// positions and bounds of the mesh.
+    pub fn generate_tex_coords(&mut self, projection: TexCoordProjection) {
+        let Some(MeshAttributeValues::Vec3(positions)) = self
+            .attribute(MeshAttributeType::Position)
+            .map(|a| &a.values)
+        else {
+            return;
+        };
+
+        let bounds = Aabb::from(positions.as_slice());
+        let extent = (bounds.max - bounds.min).max(Vec3::splat(f32::EPSILON));
+        let planar =
+            |axis: ProjectionAxis, position: Vec3| axis.flatten((position - bounds.min) / extent);
+
+        let uvs = match projection {
+            TexCoordProjection::Planar { axis } => {
+                positions.iter().map(|p| planar(axis, *p)).collect()
+            }
+            TexCoordProjection::Box => {
+                let normals = match self.attribute(MeshAttributeType::Normal).map(|a| &a.values) {
+                    Some(MeshAttributeValues::Vec3(normals)) => normals.clone(),
+                    _ => {
+                        let indices = match self.indices() {
+                            Some(indices) => indices.iter().collect::<Vec<_>>(),
+                            None => (0..positions.len() as u32).collect(),
+                        };
+                        Self::calculate_normals(positions, &indices)
+                    }
+                };
+
+                positions
+                    .iter()
+                    .zip(normals)
+                    .map(|(p, normal)| planar(ProjectionAxis::dominant(normal), *p))
+                    .collect()
+            }
+            TexCoordProjection::Spherical => {
+                let center = (bounds.min + bounds.max) * 0.5;
+                positions
+                    .iter()
+                    .map(|p| {
+                        let direction = (*p - center).normalize_or_zero();
+                        let u = 0.5 + direction.z.atan2(direction.x) / TAU;
+                        let v = 0.5 - direction.y.clamp(-1.0, 1.0).asin() / PI;
+                        Vec2::new(u, v)
+                    })
+                    .collect()
+            }
+        };
+
+        self.add_attribute(MeshAttribute::new(
+            MeshAttributeType::TexCoord0,
+            MeshAttributeValues::Vec2(uvs),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ProjectionAxis, TexCoordProjection};
+    use crate::resources::mesh::{
+        Mesh, MeshAttribute, MeshAttributeType, MeshAttributeValues, MeshTopology,
+    };
+    use math::{Vec2, Vec3};
+
+    fn tex_coords(mesh: &Mesh) -> &[Vec2] {
+        match mesh
+            .attribute(MeshAttributeType::TexCoord0)
+            .map(|a| &a.values)
+        {
+            Some(MeshAttributeValues::Vec2(uvs)) => uvs,
+            _ => panic!("Expected vec2 tex coords"),
+        }
+    }
+
+    #[test]
+    fn planar_quad() {
+        let mut mesh = Mesh::new(MeshTopology::TriangleList).with_attribute(MeshAttribute::new(
+            MeshAttributeType::Position,
+            MeshAttributeValues::Vec3(vec![
+                Vec3::new(-0.5, -0.5, 0.0),
+                Vec3::new(0.5, -0.5, 0.0),
+                Vec3::new(0.5, 0.5, 0.0),
+                Vec3::new(-0.5, 0.5, 0.0),
+            ]),
+        ));
+
+        mesh.generate_tex_coords(TexCoordProjection::Planar {
+            axis: ProjectionAxis::Z,
+        });
+
+        let expected = [Vec2::ZERO, Vec2::X, Vec2::ONE, Vec2::Y];
+        for (uv, expected) in tex_coords(&mesh).iter().zip(expected) {
+            assert!(uv.abs_diff_eq(expected, 1e-5), "{uv:?}");
+        }
+    }
+
+    #[test]
+    fn box_cube() {
+        let mut mesh = Mesh::cube(2.0);
+        mesh.generate_tex_coords(TexCoordProjection::Box);
+
+        for uv in tex_coords(&mesh) {
+            assert!(uv.x.abs() < 1e-5 || (uv.x - 1.0).abs() < 1e-5, "{uv:?}");
+            assert!(uv.y.abs() < 1e-5 || (uv.y - 1.0).abs() < 1e-5, "{uv:?}");
+        }
+    }
+}
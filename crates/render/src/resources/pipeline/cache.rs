@@ -29,6 +29,11 @@ pub struct PipelineCache {
     render_pipelines: HashMap<PipelineId, RenderPipeline>,
     compute_pipelines: HashMap<PipelineId, ComputePipeline>,
     pipeline_queue: IndexMap<PipelineId, QueuedPipeline>,
+    render_descs: HashMap<RenderPipelineDesc, PipelineId>,
+    compute_descs: HashMap<ComputePipelineDesc, PipelineId>,
+    /// How many times each pipeline was queued. Identical descriptors share
+    /// a pipeline, so it's only removed once every owner has removed it.
+    references: HashMap<PipelineId, usize>,
 }
 
 impl PipelineCache {
@@ -38,6 +43,9 @@ impl PipelineCache {
             render_pipelines: HashMap::new(),
             compute_pipelines: HashMap::new(),
             pipeline_queue: IndexMap::new(),
+            render_descs: HashMap::new(),
+            compute_descs: HashMap::new(),
+            references: HashMap::new(),
         }
     }
 
@@ -49,8 +57,19 @@ impl PipelineCache {
         self.compute_pipelines.get(id)
     }
 
+    /// Queues a render pipeline for creation. Queuing a descriptor equal to
+    /// one that was already queued returns the existing pipeline's id, and
+    /// each call must be matched by a [`PipelineCache::remove_render_pipeline`]
+    /// before the pipeline is removed.
     pub fn queue_render_pipeline(&mut self, desc: RenderPipelineDesc) -> PipelineId {
+        if let Some(id) = self.render_descs.get(&desc).copied() {
+            *self.references.entry(id).or_default() += 1;
+            return id;
+        }
+
         let id = PipelineId::new();
+        self.render_descs.insert(desc.clone(), id);
+        self.references.insert(id, 1);
 
         self.add_shader_dependency(&desc.vertex.shader, id);
 
@@ -64,8 +83,19 @@ impl PipelineCache {
         id
     }
 
+    /// Queues a compute pipeline for creation. Queuing a descriptor equal to
+    /// one that was already queued returns the existing pipeline's id, and
+    /// each call must be matched by a [`PipelineCache::remove_compute_pipeline`]
+    /// before the pipeline is removed.
     pub fn queue_compute_pipeline(&mut self, desc: ComputePipelineDesc) -> PipelineId {
+        if let Some(id) = self.compute_descs.get(&desc).copied() {
+            *self.references.entry(id).or_default() += 1;
+            return id;
+        }
+
         let id = PipelineId::new();
+        self.compute_descs.insert(desc.clone(), id);
+        self.references.insert(id, 1);
 
         self.add_shader_dependency(&desc.shader, id);
 
@@ -75,16 +105,30 @@ impl PipelineCache {
         id
     }
 
+    /// Releases one reference to the pipeline. The pipeline is removed and
+    /// returned once nothing else references it.
     pub fn remove_render_pipeline(&mut self, id: PipelineId) -> Option<RenderPipeline> {
+        if !self.release(id) {
+            return None;
+        }
+
         let pipeline = self.render_pipelines.remove(&id);
         self.pipeline_queue.shift_remove(&id);
+        self.render_descs.retain(|_, pipeline| *pipeline != id);
 
         pipeline
     }
 
+    /// Releases one reference to the pipeline. The pipeline is removed and
+    /// returned once nothing else references it.
     pub fn remove_compute_pipeline(&mut self, id: PipelineId) -> Option<ComputePipeline> {
+        if !self.release(id) {
+            return None;
+        }
+
         let pipeline = self.compute_pipelines.remove(&id);
         self.pipeline_queue.shift_remove(&id);
+        self.compute_descs.retain(|_, pipeline| *pipeline != id);
 
         pipeline
     }
@@ -124,6 +168,20 @@ impl PipelineCache {
         self.pipeline_queue = queue;
     }
 
+    /// Drops a reference to `id`, returning true if it was the last one.
+    fn release(&mut self, id: PipelineId) -> bool {
+        match self.references.get_mut(&id) {
+            Some(count) if *count > 1 => {
+                *count -= 1;
+                false
+            }
+            _ => {
+                self.references.remove(&id);
+                true
+            }
+        }
+    }
+
     fn add_shader_dependency(&mut self, shader: &AssetId<GpuShader>, id: PipelineId) {
         self.shaders
             .entry(*shader)
@@ -146,3 +204,62 @@ impl PipelineCache {
 }
 
 impl Resource for PipelineCache {}
+
+#[cfg(test)]
+mod tests {
+    use super::PipelineCache;
+    use crate::resources::pipeline::{RenderPipelineDesc, VertexState};
+    use asset::AssetId;
+    use wgpu::{MultisampleState, PrimitiveState, PrimitiveTopology};
+
+    fn desc(topology: PrimitiveTopology) -> RenderPipelineDesc {
+        RenderPipelineDesc {
+            label: None,
+            layout: vec![],
+            vertex: VertexState {
+                shader: AssetId::from_u128(1),
+                entry: "main".into(),
+                buffers: vec![],
+            },
+            fragment: None,
+            primitive: PrimitiveState {
+                topology,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: MultisampleState::default(),
+            push_constants: vec![],
+        }
+    }
+
+    #[test]
+    fn identical_descs_share_pipeline() {
+        let mut cache = PipelineCache::new();
+
+        let a = cache.queue_render_pipeline(desc(PrimitiveTopology::TriangleList));
+        let b = cache.queue_render_pipeline(desc(PrimitiveTopology::TriangleList));
+        let c = cache.queue_render_pipeline(desc(PrimitiveTopology::LineList));
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_eq!(cache.pipeline_queue.len(), 2);
+    }
+
+    #[test]
+    fn shared_pipeline_removed_by_last_owner() {
+        let mut cache = PipelineCache::new();
+
+        let a = cache.queue_render_pipeline(desc(PrimitiveTopology::TriangleList));
+        let b = cache.queue_render_pipeline(desc(PrimitiveTopology::TriangleList));
+
+        cache.remove_render_pipeline(a);
+        assert!(cache.pipeline_queue.contains_key(&b));
+
+        cache.remove_render_pipeline(b);
+        assert!(cache.pipeline_queue.is_empty());
+
+        let c = cache.queue_render_pipeline(desc(PrimitiveTopology::TriangleList));
+        assert_ne!(a, c);
+        assert_eq!(cache.pipeline_queue.len(), 1);
+    }
+}
@@ -21,21 +21,56 @@ pub struct VertexBufferLayout {
     pub attributes: Vec<VertexAttribute>,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct VertexState {
     pub shader: AssetId<GpuShader>,
     pub entry: Cow<'static, str>,
     pub buffers: Vec<VertexBufferLayout>,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct FragmentState {
     pub shader: AssetId<GpuShader>,
     pub entry: Cow<'static, str>,
     pub targets: Vec<Option<ColorTargetState>>,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+/// How a material's fragments are combined with what is already in the
+/// render target.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize,
+)]
+pub enum BlendMode {
+    /// Fragments replace the target.
+    #[default]
+    Opaque,
+    /// Fragments are alpha blended over the target.
+    Transparent,
+    /// Fragments let light through, blended with premultiplied alpha.
+    Transmissive,
+    /// Fragments below an alpha cutoff are discarded by the shader and the
+    /// rest replace the target.
+    AlphaMask,
+}
+
+impl BlendMode {
+    /// Whether the mode needs to be drawn after opaque geometry.
+    pub fn is_transparent(&self) -> bool {
+        matches!(self, BlendMode::Transparent | BlendMode::Transmissive)
+    }
+}
+
+impl From<BlendMode> for wgpu::BlendState {
+    fn from(mode: BlendMode) -> Self {
+        match mode {
+            BlendMode::Opaque | BlendMode::AlphaMask => wgpu::BlendState::REPLACE,
+            BlendMode::Transparent => wgpu::BlendState::ALPHA_BLENDING,
+            BlendMode::Transmissive => wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct RenderPipelineDesc {
     pub label: Label,
     pub layout: Vec<BindGroupLayout>,
@@ -151,7 +186,7 @@ impl AsRef<wgpu::RenderPipeline> for RenderPipeline {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct ComputePipelineDesc {
     pub label: Label,
     pub layout: Vec<BindGroupLayout>,
@@ -230,3 +265,27 @@ impl AsRef<wgpu::ComputePipeline> for ComputePipeline {
         &self.pipeline
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::BlendMode;
+
+    #[test]
+    fn blend_modes() {
+        let modes = [
+            BlendMode::Opaque,
+            BlendMode::Transparent,
+            BlendMode::Transmissive,
+            BlendMode::AlphaMask,
+        ];
+
+        let states = modes.map(|mode| -> wgpu::BlendState { mode.into() });
+        assert_eq!(states[0], wgpu::BlendState::REPLACE);
+        assert_eq!(states[1], wgpu::BlendState::ALPHA_BLENDING);
+        assert_eq!(states[2], wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING);
+        assert_eq!(states[3], wgpu::BlendState::REPLACE);
+
+        let transparent = modes.map(|mode| mode.is_transparent());
+        assert_eq!(transparent, [false, true, true, false]);
+    }
+}
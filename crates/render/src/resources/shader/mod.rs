@@ -1,8 +1,8 @@
 use super::RenderAsset;
 use crate::device::RenderDevice;
 use asset::{
-    Asset, AssetId, AssetProcessor, AssetSettings, Settings,
-    ext::PathExt,
+    Asset, AssetId, AssetProcessor, AssetSettings, Settings, SettingsError,
+    ext::{DeserializeExt, PathExt},
     importer::{AssetImporter, ImportContext},
     io::{AssetPath, AsyncIoError, AsyncReader},
 };
@@ -81,6 +81,42 @@ impl GlobalShaderConstants {
         self.local.insert(shader, constants);
     }
 
+    /// The constants `shader` is built with when its source declares
+    /// `shader_constants`. The shader's own constants override globals, and
+    /// constants added with [`GlobalShaderConstants::add_local`] override
+    /// both.
+    pub fn effective(
+        &self,
+        shader: &AssetId<Shader>,
+        shader_constants: &ShaderConstants,
+    ) -> ShaderConstants {
+        Self::merge(
+            &self.constants,
+            self.local(shader),
+            shader_constants.clone(),
+        )
+    }
+
+    fn merge(
+        globals: &ShaderConstants,
+        local: Option<&ShaderConstants>,
+        mut constants: ShaderConstants,
+    ) -> ShaderConstants {
+        for (name, constant) in globals.iter() {
+            if !constants.contains(name) {
+                constants.set(name.clone(), constant.clone());
+            }
+        }
+
+        if let Some(local) = local {
+            for (name, constant) in local.iter() {
+                constants.set(name.clone(), constant.clone());
+            }
+        }
+
+        constants
+    }
+
     pub(crate) fn init(constants: &mut Self, device: &RenderDevice) {
         let GlobalShaderConstants {
             constants,
@@ -158,24 +194,14 @@ impl GpuShader {
             Shader::Wgsl {
                 data,
                 dependencies,
-                mut constants,
+                constants,
             } => {
                 let mut processor = ShaderProcessor::new();
                 for (path, module) in &dependencies {
                     processor.add_module(&path, &module);
                 }
 
-                for (name, constant) in globals.iter() {
-                    if !constants.contains(name) {
-                        constants.set(name.clone(), constant.clone());
-                    }
-                }
-
-                if let Some(local) = local {
-                    for (name, constant) in local.iter() {
-                        constants.set(name.clone(), constant.clone());
-                    }
-                }
+                let constants = GlobalShaderConstants::merge(globals, local, constants);
 
                 let data = processor
                     .build(&data, &constants)
@@ -309,14 +335,103 @@ impl From<std::io::Error> for ShaderImportError {
 
 impl std::error::Error for ShaderImportError {}
 
-#[derive(Default, Settings, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Default, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ShaderSettings {
     constants: ShaderConstants,
+    /// Resolves and inlines `#import`s when the shader is imported instead of
+    /// when it is processed. Only imports from the shader's own source can
+    /// be inlined.
+    inline_imports: bool,
+}
+
+impl Settings for ShaderSettings {
+    const VERSION: u32 = 1;
+
+    fn migrate(version: u32, data: &[u8]) -> Result<Self, SettingsError> {
+        match version {
+            0 => ShaderSettingsV0::from_bytes(data).map(|v0| Self::from(v0.constants)),
+            _ => Err(SettingsError::Unmigratable {
+                version,
+                current: Self::VERSION,
+            }),
+        }
+    }
+}
+
+/// [`ShaderSettings`] before `inline_imports` was added.
+#[derive(Default, serde::Serialize, serde::Deserialize)]
+struct ShaderSettingsV0 {
+    constants: ShaderConstants,
+}
+
+impl ShaderSettings {
+    pub fn with_inline_imports(mut self, inline_imports: bool) -> Self {
+        self.inline_imports = inline_imports;
+        self
+    }
 }
 
 impl From<ShaderConstants> for ShaderSettings {
     fn from(constants: ShaderConstants) -> Self {
-        Self { constants }
+        Self {
+            constants,
+            inline_imports: false,
+        }
+    }
+}
+
+impl Shader {
+    async fn inline_imports(
+        ctx: &mut ImportContext<'_>,
+        data: &str,
+    ) -> Result<String, ShaderImportError> {
+        let ctx = smol::lock::RwLock::new(ctx);
+        let imports = ShaderProcessor::get_imports(data, &ctx, |path, ctx| async move {
+            let path = AssetPath::from(path);
+            let mut reader = ctx.write().await.read_path(&path).await?;
+            let mut source = String::new();
+            reader
+                .read_to_string(&mut source)
+                .await
+                .map_err(AsyncIoError::from)?;
+
+            Ok::<_, AsyncIoError>(source)
+        })
+        .await
+        .map_err(|e| ShaderImportError::Processor(e.to_string()))?;
+
+        let mut processor = ShaderProcessor::new();
+        for (path, module) in &imports {
+            processor.add_module(path, module);
+        }
+
+        processor
+            .inline(data)
+            .map_err(|e| ShaderImportError::Processor(e.to_string()))
+    }
+}
+
+impl Shader {
+    /// Parses and validates wgsl source so errors point at the shader's
+    /// path instead of surfacing at pipeline creation. Sources that still
+    /// have preprocessor directives are skipped, since they can only be
+    /// validated once their imports and constants are resolved.
+    fn validate_wgsl(path: &std::path::Path, data: &str) -> Result<(), ShaderImportError> {
+        use wgpu::naga::{front::wgsl, valid::*};
+
+        if data.lines().any(|line| line.trim_start().starts_with('#')) {
+            return Ok(());
+        }
+
+        let path = path.display();
+        let module = wgsl::parse_str(data)
+            .map_err(|e| ShaderImportError::Parse(format!("{path}: {}", e.emit_to_string(data))))?;
+
+        Validator::new(ValidationFlags::all(), Capabilities::all())
+            .validate(&module)
+            .map_err(|e| ShaderImportError::Parse(format!("{path}: {}", e.emit_to_string(data))))?;
+
+        Ok(())
     }
 }
 
@@ -363,11 +478,11 @@ impl AssetImporter for Shader {
                     .await
                     .map_err(ShaderImportError::from)?;
 
-                // let module = wgsl::parse_str(&data).map_err(ShaderImportError::from)?;
-                // let mut validator = Validator::new(ValidationFlags::all(), Capabilities::all());
-                // validator
-                //     .validate(&module)
-                //     .map_err(|e| ShaderImportError::Parse(e.to_string()))?;
+                if settings.inline_imports {
+                    data = Self::inline_imports(ctx, &data).await?;
+                }
+
+                Self::validate_wgsl(ctx.path().path(), &data)?;
 
                 let data = Cow::Owned(data);
 
@@ -458,3 +573,163 @@ impl AssetProcessor for Shader {
         return Ok(asset);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        GlobalShaderConstants, Shader, ShaderConstant, ShaderConstants, ShaderImportError,
+        ShaderSettings, ShaderSettingsV0,
+    };
+    use asset::{
+        AssetId, AssetSettings, AssetType, ErasedId, Settings,
+        ext::SerializeExt,
+        importer::{AssetImporter, ImportContext},
+        io::{AssetPath, AsyncWriter, FileSystem, VirtualFs},
+        registry::AssetRegistry,
+        sources::AssetFileSystem,
+    };
+    use smol::io::AsyncWriteExt;
+
+    fn import(settings: ShaderSettings) -> Shader {
+        let files = [
+            ("shaders/utils.wgsl", "fn util() {}"),
+            (
+                "shaders/main.wgsl",
+                "#import shaders/utils.wgsl\nfn main() {}",
+            ),
+        ];
+
+        import_file(&files, "shaders/main.wgsl", settings).unwrap()
+    }
+
+    fn import_file(
+        files: &[(&str, &str)],
+        path: &str,
+        settings: ShaderSettings,
+    ) -> Result<Shader, ShaderImportError> {
+        let source = VirtualFs::new();
+        smol::block_on(async {
+            source.create_dir("shaders".as_ref()).await.unwrap();
+            for (path, data) in files {
+                let mut writer = source.writer(path.as_ref()).await.unwrap();
+                writer.write_all(data.as_bytes()).await.unwrap();
+                AsyncWriter::flush(&mut writer).await.unwrap();
+            }
+        });
+
+        let fs = AssetFileSystem::new(source);
+        let registry = AssetRegistry::new();
+        let path = AssetPath::from(path);
+        let mut ctx =
+            ImportContext::new(&fs, &registry, &path, ErasedId::new(), AssetType::UNKNOWN);
+        let settings = AssetSettings::new(ErasedId::new(), settings);
+
+        smol::block_on(async {
+            let mut reader = fs.reader(path.path()).await.unwrap();
+            Shader::import(&mut ctx, &mut *reader, &settings).await
+        })
+    }
+
+    #[test]
+    fn import_shader_imports() {
+        let Shader::Wgsl {
+            data, dependencies, ..
+        } = import(ShaderSettings::default())
+        else {
+            panic!("Expected a wgsl shader");
+        };
+
+        assert!(data.contains("#import shaders/utils.wgsl"));
+        assert!(dependencies.is_empty());
+
+        let Shader::Wgsl {
+            data, dependencies, ..
+        } = import(ShaderSettings::default().with_inline_imports(true))
+        else {
+            panic!("Expected a wgsl shader");
+        };
+
+        assert_eq!(data, "fn util() {}\nfn main() {}\n");
+        assert!(dependencies.is_empty());
+    }
+
+    #[test]
+    fn validate_wgsl() {
+        let files = [
+            ("shaders/valid.wgsl", "fn main() -> f32 { return 1.0; }"),
+            ("shaders/invalid.wgsl", "fn main() -> f32 { return }"),
+        ];
+
+        let shader = import_file(&files, "shaders/valid.wgsl", ShaderSettings::default());
+        assert!(shader.is_ok());
+
+        let error = import_file(&files, "shaders/invalid.wgsl", ShaderSettings::default());
+        match error {
+            Err(ShaderImportError::Parse(message)) => {
+                assert!(message.contains("invalid.wgsl"), "{message}")
+            }
+            _ => panic!("Expected a parse error"),
+        }
+    }
+
+    #[test]
+    fn cyclic_imports() {
+        let files = [
+            ("shaders/a.wgsl", "#import shaders/b.wgsl\nfn a() {}"),
+            ("shaders/b.wgsl", "#import shaders/a.wgsl\nfn b() {}"),
+        ];
+
+        let settings = ShaderSettings::default().with_inline_imports(true);
+        match import_file(&files, "shaders/a.wgsl", settings) {
+            Err(ShaderImportError::Processor(message)) => assert!(
+                message
+                    .contains("cyclic import: shaders/b.wgsl -> shaders/a.wgsl -> shaders/b.wgsl"),
+                "{message}"
+            ),
+            _ => panic!("Expected a cyclic import error"),
+        }
+    }
+
+    #[test]
+    fn effective_constants() {
+        let shader = AssetId::<Shader>::from_u128(1);
+        let mut globals = GlobalShaderConstants::new();
+        globals.set("GLOBAL", ShaderConstant::U32(0));
+        globals.set("SHARED", ShaderConstant::U32(0));
+        globals.set("OVERRIDDEN", ShaderConstant::U32(0));
+
+        let mut inline = ShaderConstants::new();
+        inline.set("SHARED", ShaderConstant::U32(1));
+        inline.set("OVERRIDDEN", ShaderConstant::U32(1));
+
+        let mut local = ShaderConstants::new();
+        local.set("OVERRIDDEN", ShaderConstant::U32(2));
+        globals.add_local(shader, local);
+
+        let effective = globals.effective(&shader, &inline);
+        assert_eq!(effective.get("GLOBAL"), Some(ShaderConstant::U32(0)));
+        assert_eq!(effective.get("SHARED"), Some(ShaderConstant::U32(1)));
+        assert_eq!(effective.get("OVERRIDDEN"), Some(ShaderConstant::U32(2)));
+
+        let other = globals.effective(&AssetId::from_u128(2), &inline);
+        assert_eq!(other.get("OVERRIDDEN"), Some(ShaderConstant::U32(1)));
+    }
+
+    #[test]
+    fn migrate_settings_without_inline_imports() {
+        impl Settings for ShaderSettingsV0 {}
+
+        let mut constants = ShaderConstants::new();
+        constants.set("COUNT", ShaderConstant::U32(4));
+        let settings = AssetSettings::new(ErasedId::new(), ShaderSettingsV0 { constants });
+        let bytes = settings.to_bytes().unwrap();
+
+        let migrated = AssetSettings::<ShaderSettings>::decode(&bytes).unwrap();
+        assert_eq!(migrated.id, settings.id);
+        assert!(!migrated.inline_imports);
+        assert_eq!(
+            migrated.constants.get("COUNT"),
+            Some(ShaderConstant::U32(4))
+        );
+    }
+}
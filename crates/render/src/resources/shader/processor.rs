@@ -42,6 +42,11 @@ pub enum ShaderProcessorError<'a> {
         line: &'a str,
     },
 
+    /// `#import`s form a cycle. The path starts and ends with the same import.
+    CyclicImport {
+        cycle: Vec<String>,
+    },
+
     /// Internal error due to malformed input or iterator exhaustion.
     UnexpectedEndOfInput,
 
@@ -73,6 +78,9 @@ impl<'a> Display for ShaderProcessorError<'a> {
             ShaderProcessorError::InvalidConstSyntax { line } => {
                 write!(f, "Invalid const syntax in line: {}", line)
             }
+            ShaderProcessorError::CyclicImport { cycle } => {
+                write!(f, "cyclic import: {}", cycle.join(" -> "))
+            }
             ShaderProcessorError::UnexpectedEndOfInput => {
                 write!(f, "Unexpected end of input")
             }
@@ -330,6 +338,44 @@ impl<'a> ShaderProcessor<'a> {
         self.process_tokens(&mut tokens, constants)
     }
 
+    /// Replaces `#import` lines with the source of their modules, leaving
+    /// constants and conditionals to be evaluated by [`ShaderProcessor::build`].
+    pub fn inline(&mut self, src: &'a str) -> Result<String, ShaderProcessorError<'a>> {
+        let mut code = String::new();
+
+        for token in Self::tokenize(src) {
+            match token {
+                Token::Import(line) => {
+                    let path = Self::parse_import(line)?.trim();
+
+                    if self.processed.insert(path) {
+                        let src = *self
+                            .modules
+                            .get(path)
+                            .ok_or(ShaderProcessorError::MissingImport { path })?;
+
+                        code.push_str(&self.inline(src)?);
+                    }
+                }
+                Token::Const(line)
+                | Token::Slot(line)
+                | Token::If(line)
+                | Token::IfDef(line)
+                | Token::IfNotDef(line)
+                | Token::EndIf(line)
+                | Token::Else(line)
+                | Token::ElseIf(line)
+                | Token::ElseIfDef(line)
+                | Token::Chars(line) => {
+                    code.push_str(line);
+                    code.push('\n');
+                }
+            }
+        }
+
+        Ok(code)
+    }
+
     pub async fn get_imports<F, E, Ctx>(
         src: &'a str,
         ctx: &'a Ctx,
@@ -340,40 +386,89 @@ impl<'a> ShaderProcessor<'a> {
         F: Future<Output = Result<String, E>>,
     {
         let mut imports = ShaderImports::new();
+        let mut graph = HashMap::<String, Vec<String>>::new();
+        let mut roots = Vec::new();
         let mut sources = VecDeque::new();
-        sources.push_front(Cow::Borrowed(src));
+        sources.push_front((None, Cow::Borrowed(src)));
 
-        while let Some(src) = sources.pop_front() {
+        while let Some((importer, src)) = sources.pop_front() {
             for token in ShaderProcessor::tokenize(&src) {
                 if let Token::Import(line) = token {
                     let path = match ShaderProcessor::parse_import(line) {
-                        Ok(path) => path,
+                        Ok(path) => path.trim(),
                         Err(e) => {
                             return Err(ShaderProcessorError::Message(e.to_string().into()));
                         }
                     };
 
+                    match &importer {
+                        Some(importer) => graph
+                            .entry(importer.clone())
+                            .or_default()
+                            .push(path.to_string()),
+                        None => roots.push(path.to_string()),
+                    }
+
                     if imports.contains_key(path) {
                         continue;
                     }
 
                     // Resolve the import asynchronously
-                    let source = match resolve(path.trim().to_string(), ctx).await {
+                    let source = match resolve(path.to_string(), ctx).await {
                         Ok(source) => source,
                         Err(e) => {
                             return Err(ShaderProcessorError::Message(e.to_string().into()));
                         }
                     };
 
-                    imports.insert(path.trim().to_string(), source.clone());
-                    sources.push_back(Cow::Owned(source));
+                    imports.insert(path.to_string(), source.clone());
+                    sources.push_back((Some(path.to_string()), Cow::Owned(source)));
                 }
             }
         }
 
+        let mut visited = HashSet::new();
+        for root in &roots {
+            if let Some(cycle) = Self::find_cycle(root, &graph, &mut Vec::new(), &mut visited) {
+                return Err(ShaderProcessorError::CyclicImport { cycle });
+            }
+        }
+
         Ok(imports)
     }
 
+    /// Walks the import graph depth first, returning the import path of the
+    /// first cycle found.
+    fn find_cycle<'g>(
+        path: &'g str,
+        graph: &'g HashMap<String, Vec<String>>,
+        stack: &mut Vec<&'g str>,
+        visited: &mut HashSet<&'g str>,
+    ) -> Option<Vec<String>> {
+        if let Some(start) = stack.iter().position(|import| *import == path) {
+            let mut cycle = stack[start..]
+                .iter()
+                .map(|import| import.to_string())
+                .collect::<Vec<_>>();
+            cycle.push(path.to_string());
+            return Some(cycle);
+        }
+
+        if !visited.insert(path) {
+            return None;
+        }
+
+        stack.push(path);
+        for import in graph.get(path).into_iter().flatten() {
+            if let Some(cycle) = Self::find_cycle(import, graph, stack, visited) {
+                return Some(cycle);
+            }
+        }
+        stack.pop();
+
+        None
+    }
+
     fn tokenize(src: &str) -> impl Iterator<Item = Token<'_>> {
         src.lines().map(|line| {
             let trimmed = line.trim_start();
@@ -730,6 +825,22 @@ const LIGHT_COUNT: u32 = 50;
         assert_eq!(result.trim(), expected.trim());
     }
 
+    #[test]
+    fn test_inline_imports() {
+        let mut processor = ShaderProcessor::new();
+        processor.add_module("embedded://shaders/utils.wgsl", "fn util() {}");
+        processor.add_module(
+            "embedded://shaders/nested.wgsl",
+            "#import embedded://shaders/utils.wgsl\nfn nested() {}",
+        );
+
+        let src = "#import embedded://shaders/nested.wgsl\n#import embedded://shaders/utils.wgsl\n#ifdef LIGHT_COUNT\nfn main() {}\n#end";
+        let expected = "fn util() {}\nfn nested() {}\n#ifdef LIGHT_COUNT\nfn main() {}\n#end\n";
+
+        let result = processor.inline(src).unwrap();
+        assert_eq!(result, expected);
+    }
+
     // #[test]
     // fn test_get_imports() {
     //     let mut imports = HashMap::new();
@@ -0,0 +1,630 @@
+use super::{Texture, TextureDimension};
+use crate::RenderFeatures;
+use asset::{
+    AssetImporter, AssetSettings, Settings,
+    importer::ImportContext,
+    io::{AsyncIoError, AsyncReader},
+};
+use smol::io::AsyncSeekExt;
+use std::{io::SeekFrom, ops::Range};
+use wgpu::TextureFormat;
+
+const DDS_MAGIC: &[u8] = b"DDS ";
+const DDS_HEADER_SIZE: usize = 128;
+const DX10_HEADER_SIZE: usize = 20;
+const DDSD_MIPMAPCOUNT: u32 = 0x20000;
+const DDSD_DEPTH: u32 = 0x800000;
+const DDS_CUBEMAP: u32 = 0x200;
+const DX10_CUBEMAP: u32 = 0x4;
+
+const KTX2_MAGIC: &[u8] = b"\xABKTX 20\xBB\r\n\x1A\n";
+const KTX2_HEADER_SIZE: usize = 80;
+const KTX2_LEVEL_SIZE: usize = 24;
+
+/// A texture can't have more mip levels than there are bits in its size.
+const MAX_MIP_LEVELS: u32 = 32;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CompressedTextureError {
+    #[error(transparent)]
+    Io(#[from] AsyncIoError),
+    #[error("Not a dds or ktx2 file")]
+    UnknownContainer,
+    #[error("Texture data ends before the size given by its header")]
+    Truncated,
+    #[error("Unsupported texture format {0:#x}")]
+    UnsupportedFormat(u32),
+    #[error("Supercompression scheme {0} is not supported")]
+    Supercompressed(u32),
+    #[error("Texture has {0} mip levels, more than the {MAX_MIP_LEVELS} a texture can have")]
+    TooManyMipLevels(u32),
+    #[error("3d textures with a depth of {0} are not supported")]
+    UnsupportedDepth(u32),
+    #[error("The device doesn't support {0:?} textures")]
+    UnsupportedByDevice(TextureFormat),
+}
+
+#[derive(Settings, Default, serde::Serialize, serde::Deserialize)]
+pub struct CompressedTextureSettings {
+    /// Uses the srgb variant of the texture's format when it has one.
+    pub srgb: bool,
+}
+
+/// Imports block compressed `.dds` and `.ktx2` textures without decoding
+/// them. The BC1 to BC7 blocks and every stored mip level are copied into
+/// the [`Texture`] as is, so the device needs
+/// [`wgpu::Features::TEXTURE_COMPRESSION_BC`] to upload them. Once a device
+/// is selected, importing fails on devices without it.
+///
+/// Only the header is read up front. The texture data is then read with
+/// [`AsyncReader::read_range`], with one range per mip level for KTX2.
+pub struct CompressedTextureImporter;
+
+impl CompressedTextureImporter {
+    pub fn parse(bytes: &[u8]) -> Result<Texture, CompressedTextureError> {
+        let layout = Layout::parse(bytes)?;
+        let data = layout
+            .ranges
+            .iter()
+            .map(|range| bytes.get(range.clone()).map(<[u8]>::to_vec))
+            .collect::<Option<Vec<_>>>()
+            .ok_or(CompressedTextureError::Truncated)?;
+
+        Ok(layout.texture(data))
+    }
+
+    /// Reads the header from `reader`, then only the ranges of the file that
+    /// hold texture data.
+    pub async fn read(reader: &mut dyn AsyncReader) -> Result<Texture, CompressedTextureError> {
+        let len = reader
+            .seek(SeekFrom::End(0))
+            .await
+            .map_err(AsyncIoError::from)?;
+        let len = usize::try_from(len).map_err(|_| CompressedTextureError::Truncated)?;
+
+        let mut header = Vec::new();
+        loop {
+            let size = Layout::header_size(&header)?;
+            if size <= header.len() {
+                break;
+            }
+
+            if size > len {
+                return Err(CompressedTextureError::Truncated);
+            }
+
+            let start = header.len();
+            header.extend(reader.read_range(start as u64, size - start).await?);
+        }
+
+        let layout = Layout::parse(&header)?;
+        if layout.ranges.iter().any(|range| range.end > len) {
+            return Err(CompressedTextureError::Truncated);
+        }
+
+        let mut data = Vec::with_capacity(layout.ranges.len());
+        for range in &layout.ranges {
+            data.push(reader.read_range(range.start as u64, range.len()).await?);
+        }
+
+        Ok(layout.texture(data))
+    }
+
+    /// Fails if `features` can't upload the format of `texture`.
+    pub fn check_features(
+        texture: &Texture,
+        features: &RenderFeatures,
+    ) -> Result<(), CompressedTextureError> {
+        if features.supports_format(texture.format) {
+            Ok(())
+        } else {
+            Err(CompressedTextureError::UnsupportedByDevice(texture.format))
+        }
+    }
+}
+
+/// The texture described by a DDS or KTX2 header, and where its data is
+/// stored in the file.
+struct Layout {
+    format: TextureFormat,
+    width: u32,
+    height: u32,
+    layers: u32,
+    cube: bool,
+    mip_levels: u32,
+    /// DDS stores every mip level of a layer before the next layer, which is
+    /// the order textures keep their pixels in, so it has a single range.
+    /// KTX2 stores each mip level for every layer and face together, so it
+    /// has a range per level that is regrouped by layer.
+    ranges: Vec<Range<usize>>,
+}
+
+impl Layout {
+    /// The size of the header at the start of `bytes`, as far as it can be
+    /// told from the bytes so far. Keep reading until `bytes` is at least
+    /// this long.
+    fn header_size(bytes: &[u8]) -> Result<usize, CompressedTextureError> {
+        if bytes.len() < KTX2_MAGIC.len() {
+            return Ok(KTX2_MAGIC.len());
+        }
+
+        if bytes.starts_with(DDS_MAGIC) {
+            match bytes.len() < DDS_HEADER_SIZE {
+                true => Ok(DDS_HEADER_SIZE),
+                false if read_u32(bytes, 84)?.to_le_bytes() == *b"DX10" => {
+                    Ok(DDS_HEADER_SIZE + DX10_HEADER_SIZE)
+                }
+                false => Ok(DDS_HEADER_SIZE),
+            }
+        } else if bytes.starts_with(KTX2_MAGIC) {
+            match bytes.len() < KTX2_HEADER_SIZE {
+                true => Ok(KTX2_HEADER_SIZE),
+                false => {
+                    let mip_levels = mip_levels(read_u32(bytes, 40)?)? as usize;
+                    Ok(KTX2_HEADER_SIZE + mip_levels * KTX2_LEVEL_SIZE)
+                }
+            }
+        } else {
+            Err(CompressedTextureError::UnknownContainer)
+        }
+    }
+
+    fn parse(header: &[u8]) -> Result<Self, CompressedTextureError> {
+        if header.starts_with(DDS_MAGIC) {
+            Self::parse_dds(header)
+        } else if header.starts_with(KTX2_MAGIC) {
+            Self::parse_ktx2(header)
+        } else {
+            Err(CompressedTextureError::UnknownContainer)
+        }
+    }
+
+    fn parse_dds(bytes: &[u8]) -> Result<Self, CompressedTextureError> {
+        let flags = read_u32(bytes, 8)?;
+        let height = read_u32(bytes, 12)?;
+        let width = read_u32(bytes, 16)?;
+        let mip_levels = match flags & DDSD_MIPMAPCOUNT {
+            0 => 1,
+            _ => mip_levels(read_u32(bytes, 28)?)?,
+        };
+        let depth = match flags & DDSD_DEPTH {
+            0 => 1,
+            _ => read_u32(bytes, 24)?.max(1),
+        };
+
+        if depth > 1 {
+            return Err(CompressedTextureError::UnsupportedDepth(depth));
+        }
+
+        let four_cc = read_u32(bytes, 84)?;
+        let caps2 = read_u32(bytes, 112)?;
+
+        let (format, layers, cube, offset) = match &four_cc.to_le_bytes() {
+            b"DX10" => {
+                let dxgi = read_u32(bytes, DDS_HEADER_SIZE)?;
+                let misc = read_u32(bytes, DDS_HEADER_SIZE + 8)?;
+                let layers = read_u32(bytes, DDS_HEADER_SIZE + 12)?.max(1);
+                let format =
+                    dxgi_format(dxgi).ok_or(CompressedTextureError::UnsupportedFormat(dxgi))?;
+                let cube = misc & DX10_CUBEMAP != 0;
+                (format, layers, cube, DDS_HEADER_SIZE + DX10_HEADER_SIZE)
+            }
+            code => {
+                let format = match code {
+                    b"DXT1" => TextureFormat::Bc1RgbaUnorm,
+                    b"DXT2" | b"DXT3" => TextureFormat::Bc2RgbaUnorm,
+                    b"DXT4" | b"DXT5" => TextureFormat::Bc3RgbaUnorm,
+                    b"ATI1" | b"BC4U" => TextureFormat::Bc4RUnorm,
+                    b"BC4S" => TextureFormat::Bc4RSnorm,
+                    b"ATI2" | b"BC5U" => TextureFormat::Bc5RgUnorm,
+                    b"BC5S" => TextureFormat::Bc5RgSnorm,
+                    _ => return Err(CompressedTextureError::UnsupportedFormat(four_cc)),
+                };
+                let cube = caps2 & DDS_CUBEMAP != 0;
+                (format, 1, cube, DDS_HEADER_SIZE)
+            }
+        };
+
+        let layers = match cube {
+            true => layers
+                .checked_mul(6)
+                .ok_or(CompressedTextureError::Truncated)?,
+            false => layers,
+        };
+
+        let len = chain_size(format, width, height, mip_levels)
+            .and_then(|size| size.checked_mul(layers as usize))
+            .ok_or(CompressedTextureError::Truncated)?;
+        let end = offset
+            .checked_add(len)
+            .ok_or(CompressedTextureError::Truncated)?;
+
+        Ok(Self {
+            format,
+            width,
+            height,
+            layers,
+            cube,
+            mip_levels,
+            ranges: vec![offset..end],
+        })
+    }
+
+    fn parse_ktx2(bytes: &[u8]) -> Result<Self, CompressedTextureError> {
+        let vk_format = read_u32(bytes, 12)?;
+        let width = read_u32(bytes, 20)?;
+        let height = read_u32(bytes, 24)?.max(1);
+        let depth = read_u32(bytes, 28)?;
+        let layers = read_u32(bytes, 32)?.max(1);
+        let faces = read_u32(bytes, 36)?.max(1);
+        let mip_levels = mip_levels(read_u32(bytes, 40)?)?;
+        let scheme = read_u32(bytes, 44)?;
+
+        if scheme != 0 {
+            return Err(CompressedTextureError::Supercompressed(scheme));
+        }
+
+        if depth > 1 {
+            return Err(CompressedTextureError::UnsupportedDepth(depth));
+        }
+
+        let format = vk_format_to_wgpu(vk_format)
+            .ok_or(CompressedTextureError::UnsupportedFormat(vk_format))?;
+
+        let layers = layers
+            .checked_mul(faces)
+            .ok_or(CompressedTextureError::Truncated)?;
+        let ranges = (0..mip_levels)
+            .map(|level| {
+                let index = KTX2_HEADER_SIZE + level as usize * KTX2_LEVEL_SIZE;
+                let offset = usize::try_from(read_u64(bytes, index)?).ok();
+                let len = level_size(format, width, height, level)
+                    .and_then(|size| size.checked_mul(layers as usize));
+
+                match offset.zip(len) {
+                    Some((offset, len)) => offset
+                        .checked_add(len)
+                        .map(|end| offset..end)
+                        .ok_or(CompressedTextureError::Truncated),
+                    None => Err(CompressedTextureError::Truncated),
+                }
+            })
+            .collect::<Result<Vec<_>, CompressedTextureError>>()?;
+
+        Ok(Self {
+            format,
+            width,
+            height,
+            layers,
+            cube: faces == 6,
+            mip_levels,
+            ranges,
+        })
+    }
+
+    /// Builds the texture from the bytes of each of its ranges.
+    fn texture(self, data: Vec<Vec<u8>>) -> Texture {
+        let pixels = match data.len() {
+            1 => data.into_iter().flatten().collect(),
+            _ => {
+                let layers = self.layers as usize;
+                let mut pixels = Vec::with_capacity(data.iter().map(Vec::len).sum());
+                for layer in 0..layers {
+                    for level in &data {
+                        let size = level.len() / layers;
+                        pixels.extend_from_slice(&level[layer * size..(layer + 1) * size]);
+                    }
+                }
+                pixels
+            }
+        };
+
+        let dimension = match (self.cube, self.layers) {
+            (true, 6) => TextureDimension::Cube,
+            (true, _) => TextureDimension::CubeArray,
+            (false, 1) => TextureDimension::D2,
+            (false, _) => TextureDimension::D2Array,
+        };
+
+        let size = wgpu::Extent3d {
+            width: self.width,
+            height: self.height,
+            depth_or_array_layers: self.layers,
+        };
+
+        Texture::new(size, dimension, self.format, pixels).with_mip_levels(self.mip_levels)
+    }
+}
+
+impl AssetImporter for CompressedTextureImporter {
+    type Asset = Texture;
+
+    type Settings = CompressedTextureSettings;
+
+    type Error = CompressedTextureError;
+
+    async fn import(
+        _: &mut ImportContext<'_>,
+        reader: &mut dyn AsyncReader,
+        metadata: &AssetSettings<Self::Settings>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut texture = Self::read(reader).await?;
+        if metadata.srgb {
+            texture.format = texture.format.add_srgb_suffix();
+        }
+
+        if let Some(features) = RenderFeatures::selected() {
+            Self::check_features(&texture, &features)?;
+        }
+
+        Ok(texture)
+    }
+
+    fn extensions() -> &'static [&'static str] {
+        &["dds", "ktx2"]
+    }
+}
+
+/// Treats a level count of 0 as 1 and rejects counts above [`MAX_MIP_LEVELS`].
+fn mip_levels(count: u32) -> Result<u32, CompressedTextureError> {
+    match count {
+        0 => Ok(1),
+        count if count > MAX_MIP_LEVELS => Err(CompressedTextureError::TooManyMipLevels(count)),
+        count => Ok(count),
+    }
+}
+
+/// The size of one image of mip `level`, or `None` if it overflows.
+fn level_size(format: TextureFormat, width: u32, height: u32, level: u32) -> Option<usize> {
+    let (block_width, block_height) = format.block_dimensions();
+    let block_size = format.block_copy_size(None).unwrap_or(0) as usize;
+    let width = width.checked_shr(level).unwrap_or(0).max(1);
+    let height = height.checked_shr(level).unwrap_or(0).max(1);
+
+    (width.div_ceil(block_width) as usize)
+        .checked_mul(height.div_ceil(block_height) as usize)?
+        .checked_mul(block_size)
+}
+
+fn chain_size(format: TextureFormat, width: u32, height: u32, mip_levels: u32) -> Option<usize> {
+    (0..mip_levels).try_fold(0usize, |size, level| {
+        size.checked_add(level_size(format, width, height, level)?)
+    })
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32, CompressedTextureError> {
+    bytes
+        .get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or(CompressedTextureError::Truncated)
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> Result<u64, CompressedTextureError> {
+    let low = read_u32(bytes, offset)? as u64;
+    let high = read_u32(bytes, offset + 4)? as u64;
+    Ok(low | high << 32)
+}
+
+fn dxgi_format(format: u32) -> Option<TextureFormat> {
+    Some(match format {
+        70 | 71 => TextureFormat::Bc1RgbaUnorm,
+        72 => TextureFormat::Bc1RgbaUnormSrgb,
+        73 | 74 => TextureFormat::Bc2RgbaUnorm,
+        75 => TextureFormat::Bc2RgbaUnormSrgb,
+        76 | 77 => TextureFormat::Bc3RgbaUnorm,
+        78 => TextureFormat::Bc3RgbaUnormSrgb,
+        79 | 80 => TextureFormat::Bc4RUnorm,
+        81 => TextureFormat::Bc4RSnorm,
+        82 | 83 => TextureFormat::Bc5RgUnorm,
+        84 => TextureFormat::Bc5RgSnorm,
+        94 | 95 => TextureFormat::Bc6hRgbUfloat,
+        96 => TextureFormat::Bc6hRgbFloat,
+        97 | 98 => TextureFormat::Bc7RgbaUnorm,
+        99 => TextureFormat::Bc7RgbaUnormSrgb,
+        _ => return None,
+    })
+}
+
+fn vk_format_to_wgpu(format: u32) -> Option<TextureFormat> {
+    Some(match format {
+        131 | 133 => TextureFormat::Bc1RgbaUnorm,
+        132 | 134 => TextureFormat::Bc1RgbaUnormSrgb,
+        135 => TextureFormat::Bc2RgbaUnorm,
+        136 => TextureFormat::Bc2RgbaUnormSrgb,
+        137 => TextureFormat::Bc3RgbaUnorm,
+        138 => TextureFormat::Bc3RgbaUnormSrgb,
+        139 => TextureFormat::Bc4RUnorm,
+        140 => TextureFormat::Bc4RSnorm,
+        141 => TextureFormat::Bc5RgUnorm,
+        142 => TextureFormat::Bc5RgSnorm,
+        143 => TextureFormat::Bc6hRgbUfloat,
+        144 => TextureFormat::Bc6hRgbFloat,
+        145 => TextureFormat::Bc7RgbaUnorm,
+        146 => TextureFormat::Bc7RgbaUnormSrgb,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CompressedTextureError, CompressedTextureImporter, KTX2_MAGIC};
+    use crate::{RenderFeatures, resources::texture::TextureDimension};
+    use asset::io::FileReader;
+    use wgpu::{Features, TextureFormat};
+
+    /// An 8x8 BC1 dds with a full mip chain: 32, 8, 8 and 8 bytes of blocks.
+    fn bc1_dds() -> Vec<u8> {
+        let mut header = [0u8; 128];
+        header[..4].copy_from_slice(b"DDS ");
+        header[4..8].copy_from_slice(&124u32.to_le_bytes());
+        header[8..12].copy_from_slice(&(0x1007u32 | 0x20000).to_le_bytes());
+        header[12..16].copy_from_slice(&8u32.to_le_bytes());
+        header[16..20].copy_from_slice(&8u32.to_le_bytes());
+        header[28..32].copy_from_slice(&4u32.to_le_bytes());
+        header[76..80].copy_from_slice(&32u32.to_le_bytes());
+        header[80..84].copy_from_slice(&0x4u32.to_le_bytes());
+        header[84..88].copy_from_slice(b"DXT1");
+
+        let mut bytes = header.to_vec();
+        bytes.extend((0..56).map(|i| i as u8));
+        bytes
+    }
+
+    #[test]
+    fn import_bc1_dds() {
+        let texture = CompressedTextureImporter::parse(&bc1_dds()).unwrap();
+
+        assert_eq!(texture.format, TextureFormat::Bc1RgbaUnorm);
+        assert_eq!(texture.mip_levels, 4);
+        assert_eq!((texture.width, texture.height), (8, 8));
+        assert_eq!(texture.dimension, TextureDimension::D2);
+        assert_eq!(texture.pixels, (0..56).map(|i| i as u8).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn bc_needs_texture_compression() {
+        let texture = CompressedTextureImporter::parse(&bc1_dds()).unwrap();
+
+        let features = RenderFeatures::new(Features::empty());
+        let error = CompressedTextureImporter::check_features(&texture, &features).err();
+        assert!(matches!(
+            error,
+            Some(CompressedTextureError::UnsupportedByDevice(
+                TextureFormat::Bc1RgbaUnorm
+            ))
+        ));
+
+        let features = RenderFeatures::new(Features::TEXTURE_COMPRESSION_BC);
+        assert!(CompressedTextureImporter::check_features(&texture, &features).is_ok());
+    }
+
+    #[test]
+    fn truncated_dds() {
+        let mut bytes = bc1_dds();
+        bytes.truncate(bytes.len() - 1);
+
+        let error = CompressedTextureImporter::parse(&bytes).err();
+        assert!(matches!(error, Some(CompressedTextureError::Truncated)));
+    }
+
+    /// A 4x4 BC7 texture with two layers and two mip levels. Each level
+    /// holds one 16 byte block per layer, and level 1 is stored before
+    /// level 0 with padding between them.
+    fn bc7_ktx2_array() -> Vec<u8> {
+        let mut bytes = vec![0u8; 80 + 2 * 24];
+        bytes[..12].copy_from_slice(KTX2_MAGIC);
+        bytes[12..16].copy_from_slice(&145u32.to_le_bytes());
+        bytes[20..24].copy_from_slice(&4u32.to_le_bytes());
+        bytes[24..28].copy_from_slice(&4u32.to_le_bytes());
+        bytes[32..36].copy_from_slice(&2u32.to_le_bytes());
+        bytes[36..40].copy_from_slice(&1u32.to_le_bytes());
+        bytes[40..44].copy_from_slice(&2u32.to_le_bytes());
+
+        let base = bytes.len() as u64;
+        for (level, offset) in [(0, base + 40), (1, base)] {
+            let index = 80 + level * 24;
+            bytes[index..index + 8].copy_from_slice(&offset.to_le_bytes());
+            bytes[index + 8..index + 16].copy_from_slice(&32u64.to_le_bytes());
+        }
+
+        // Level 1 then level 0, each with layer 0 followed by layer 1.
+        for value in [2u8, 3] {
+            bytes.extend([value; 16]);
+        }
+        bytes.extend([0xFF; 8]);
+        for value in [0u8, 1] {
+            bytes.extend([value; 16]);
+        }
+
+        bytes
+    }
+
+    #[test]
+    fn import_bc7_ktx2_array() {
+        let texture = CompressedTextureImporter::parse(&bc7_ktx2_array()).unwrap();
+        assert_eq!(texture.format, TextureFormat::Bc7RgbaUnorm);
+        assert_eq!(texture.mip_levels, 2);
+        assert_eq!(texture.depth_or_layers, 2);
+        assert_eq!(texture.dimension, TextureDimension::D2Array);
+
+        let layers = texture
+            .pixels
+            .chunks_exact(16)
+            .map(|b| b[0])
+            .collect::<Vec<_>>();
+        assert_eq!(layers, vec![0, 2, 1, 3]);
+    }
+
+    #[test]
+    fn read_ranges() {
+        for bytes in [bc1_dds(), bc7_ktx2_array()] {
+            let mut reader = FileReader::new(bytes.clone());
+            let texture = smol::block_on(CompressedTextureImporter::read(&mut reader)).unwrap();
+            let parsed = CompressedTextureImporter::parse(&bytes).unwrap();
+
+            assert_eq!(texture.format, parsed.format);
+            assert_eq!(texture.mip_levels, parsed.mip_levels);
+            assert_eq!(texture.pixels, parsed.pixels);
+        }
+
+        let mut bytes = bc1_dds();
+        bytes.truncate(bytes.len() - 1);
+        let mut reader = FileReader::new(bytes);
+        let result = smol::block_on(CompressedTextureImporter::read(&mut reader));
+        assert!(matches!(result, Err(CompressedTextureError::Truncated)));
+    }
+
+    #[test]
+    fn hostile_headers() {
+        let parse = |bytes: Vec<u8>| {
+            let mut reader = FileReader::new(bytes.clone());
+            let read = smol::block_on(CompressedTextureImporter::read(&mut reader)).err();
+            (CompressedTextureImporter::parse(&bytes).err(), read)
+        };
+
+        let mut bytes = bc7_ktx2_array();
+        bytes[40..44].copy_from_slice(&u32::MAX.to_le_bytes());
+        let (parsed, read) = parse(bytes);
+        assert!(matches!(
+            parsed,
+            Some(CompressedTextureError::TooManyMipLevels(_))
+        ));
+        assert!(matches!(
+            read,
+            Some(CompressedTextureError::TooManyMipLevels(_))
+        ));
+
+        let mut bytes = bc7_ktx2_array();
+        bytes[28..32].copy_from_slice(&4u32.to_le_bytes());
+        let (parsed, read) = parse(bytes);
+        assert!(matches!(
+            parsed,
+            Some(CompressedTextureError::UnsupportedDepth(4))
+        ));
+        assert!(matches!(
+            read,
+            Some(CompressedTextureError::UnsupportedDepth(4))
+        ));
+
+        let mut bytes = bc7_ktx2_array();
+        bytes[32..36].copy_from_slice(&u32::MAX.to_le_bytes());
+        bytes[36..40].copy_from_slice(&6u32.to_le_bytes());
+        let (parsed, read) = parse(bytes);
+        assert!(matches!(parsed, Some(CompressedTextureError::Truncated)));
+        assert!(matches!(read, Some(CompressedTextureError::Truncated)));
+
+        let mut bytes = bc7_ktx2_array();
+        bytes[80..88].copy_from_slice(&u64::MAX.to_le_bytes());
+        let (parsed, read) = parse(bytes);
+        assert!(matches!(parsed, Some(CompressedTextureError::Truncated)));
+        assert!(matches!(read, Some(CompressedTextureError::Truncated)));
+
+        let mut bytes = bc1_dds();
+        bytes[28..32].copy_from_slice(&40u32.to_le_bytes());
+        let (parsed, _) = parse(bytes);
+        assert!(matches!(
+            parsed,
+            Some(CompressedTextureError::TooManyMipLevels(40))
+        ));
+    }
+}
@@ -0,0 +1,245 @@
+use super::{Texture, TextureDimension};
+use wgpu::TextureFormat;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum MipmapError {
+    #[error("Mipmaps can't be generated for {0:?} textures")]
+    UnsupportedFormat(TextureFormat),
+    #[error("Mipmaps can't be generated for {0:?} textures")]
+    UnsupportedDimension(TextureDimension),
+}
+
+#[derive(Clone, Copy)]
+enum Channel {
+    Unorm8,
+    Srgb8,
+    Unorm16,
+    Float32,
+}
+
+impl Channel {
+    fn of(format: TextureFormat) -> Option<(Self, usize)> {
+        use TextureFormat::*;
+
+        Some(match format {
+            R8Unorm => (Channel::Unorm8, 1),
+            Rg8Unorm => (Channel::Unorm8, 2),
+            Rgba8Unorm | Bgra8Unorm => (Channel::Unorm8, 4),
+            Rgba8UnormSrgb | Bgra8UnormSrgb => (Channel::Srgb8, 4),
+            R16Unorm => (Channel::Unorm16, 1),
+            Rg16Unorm => (Channel::Unorm16, 2),
+            Rgba16Unorm => (Channel::Unorm16, 4),
+            R32Float => (Channel::Float32, 1),
+            Rg32Float => (Channel::Float32, 2),
+            Rgba32Float => (Channel::Float32, 4),
+            _ => return None,
+        })
+    }
+
+    fn size(&self) -> usize {
+        match self {
+            Channel::Unorm8 | Channel::Srgb8 => 1,
+            Channel::Unorm16 => 2,
+            Channel::Float32 => 4,
+        }
+    }
+
+    /// Reads a component as a linear value. Alpha is never srgb encoded.
+    fn decode(&self, bytes: &[u8], alpha: bool) -> f32 {
+        match self {
+            Channel::Unorm8 => bytes[0] as f32 / 255.0,
+            Channel::Srgb8 if alpha => bytes[0] as f32 / 255.0,
+            Channel::Srgb8 => srgb_to_linear(bytes[0] as f32 / 255.0),
+            Channel::Unorm16 => u16::from_ne_bytes([bytes[0], bytes[1]]) as f32 / 65535.0,
+            Channel::Float32 => f32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+        }
+    }
+
+    fn encode(&self, value: f32, alpha: bool, out: &mut Vec<u8>) {
+        match self {
+            Channel::Unorm8 => out.push((value.clamp(0.0, 1.0) * 255.0).round() as u8),
+            Channel::Srgb8 if alpha => out.push((value.clamp(0.0, 1.0) * 255.0).round() as u8),
+            Channel::Srgb8 => {
+                out.push((linear_to_srgb(value.clamp(0.0, 1.0)) * 255.0).round() as u8)
+            }
+            Channel::Unorm16 => {
+                let value = (value.clamp(0.0, 1.0) * 65535.0).round() as u16;
+                out.extend_from_slice(&value.to_ne_bytes());
+            }
+            Channel::Float32 => out.extend_from_slice(&value.to_ne_bytes()),
+        }
+    }
+}
+
+impl Texture {
+    /// Replaces the texture's pixels with a full mip chain for every layer,
+    /// box filtering each level from the one above it. Odd sizes are
+    /// rounded down, so every source pixel contributes to the next level.
+    /// srgb textures are filtered in linear space.
+    pub fn generate_mipmaps(&mut self) -> Result<(), MipmapError> {
+        if !matches!(
+            self.dimension,
+            TextureDimension::D2 | TextureDimension::D2Array
+        ) {
+            return Err(MipmapError::UnsupportedDimension(self.dimension));
+        }
+
+        let (channel, channels) =
+            Channel::of(self.format).ok_or(MipmapError::UnsupportedFormat(self.format))?;
+
+        let levels = wgpu::Extent3d {
+            width: self.width,
+            height: self.height,
+            depth_or_array_layers: 1,
+        }
+        .max_mips(wgpu::TextureDimension::D2);
+
+        let base_size = Texture::image_size(self.format, self.width, self.height);
+        let stored_size = (0..self.mip_levels.max(1))
+            .map(|level| {
+                let width = (self.width >> level).max(1);
+                let height = (self.height >> level).max(1);
+                Texture::image_size(self.format, width, height)
+            })
+            .sum::<usize>();
+
+        let mut pixels = Vec::new();
+        for layer in self.pixels.chunks_exact(stored_size) {
+            let mut width = self.width as usize;
+            let mut height = self.height as usize;
+            let mut level = layer[..base_size]
+                .chunks_exact(channel.size())
+                .enumerate()
+                .map(|(i, bytes)| channel.decode(bytes, i % channels == 3))
+                .collect::<Vec<_>>();
+
+            Self::encode_level(channel, channels, &level, &mut pixels);
+
+            for _ in 1..levels {
+                let next_width = (width / 2).max(1);
+                let next_height = (height / 2).max(1);
+                level = Self::downsample(&level, channels, width, height, next_width, next_height);
+                width = next_width;
+                height = next_height;
+
+                Self::encode_level(channel, channels, &level, &mut pixels);
+            }
+        }
+
+        self.pixels = pixels;
+        self.mip_levels = levels;
+        self.mipmaps = true;
+
+        Ok(())
+    }
+
+    fn encode_level(channel: Channel, channels: usize, level: &[f32], out: &mut Vec<u8>) {
+        for (i, value) in level.iter().enumerate() {
+            channel.encode(*value, i % channels == 3, out);
+        }
+    }
+
+    fn downsample(
+        source: &[f32],
+        channels: usize,
+        width: usize,
+        height: usize,
+        next_width: usize,
+        next_height: usize,
+    ) -> Vec<f32> {
+        let mut level = Vec::with_capacity(next_width * next_height * channels);
+        for y in 0..next_height {
+            let rows = y * height / next_height..((y + 1) * height).div_ceil(next_height);
+            for x in 0..next_width {
+                let columns = x * width / next_width..((x + 1) * width).div_ceil(next_width);
+                let count = (rows.len() * columns.len()) as f32;
+
+                for channel in 0..channels {
+                    let mut sum = 0.0;
+                    for row in rows.clone() {
+                        for column in columns.clone() {
+                            sum += source[(row * width + column) * channels + channel];
+                        }
+                    }
+
+                    level.push(sum / count);
+                }
+            }
+        }
+
+        level
+    }
+}
+
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MipmapError;
+    use crate::resources::texture::{Texture, TextureDimension};
+
+    fn texture(width: u32, height: u32, format: wgpu::TextureFormat, pixels: Vec<u8>) -> Texture {
+        let size = wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+
+        Texture::new(size, TextureDimension::D2, format, pixels)
+    }
+
+    #[test]
+    fn white_top_mip() {
+        let pixels = vec![255; 4 * 4 * 4];
+        let mut texture = texture(4, 4, wgpu::TextureFormat::Rgba8Unorm, pixels);
+        texture.generate_mipmaps().unwrap();
+
+        assert_eq!(texture.mip_levels, 3);
+        assert_eq!(texture.pixels.len(), (16 + 4 + 1) * 4);
+        assert_eq!(&texture.pixels[texture.pixels.len() - 4..], &[255; 4]);
+    }
+
+    #[test]
+    fn non_power_of_two() {
+        // A 5x3 texture whose left column is black and the rest white.
+        let pixels = (0..15)
+            .map(|i| if i % 5 == 0 { 0 } else { 255 })
+            .collect::<Vec<u8>>();
+        let mut texture = texture(5, 3, wgpu::TextureFormat::R8Unorm, pixels);
+        texture.generate_mipmaps().unwrap();
+
+        // 5x3, 2x1 and 1x1.
+        assert_eq!(texture.mip_levels, 3);
+        assert_eq!(texture.pixels.len(), 15 + 2 + 1);
+
+        let second = &texture.pixels[15..17];
+        assert!(second[0] < second[1]);
+        assert!(texture.pixels[17] > 0 && texture.pixels[17] < 255);
+    }
+
+    #[test]
+    fn compressed_formats_are_rejected() {
+        let mut texture = texture(4, 4, wgpu::TextureFormat::Bc1RgbaUnorm, vec![0; 8]);
+
+        assert_eq!(
+            texture.generate_mipmaps(),
+            Err(MipmapError::UnsupportedFormat(
+                wgpu::TextureFormat::Bc1RgbaUnorm
+            ))
+        );
+    }
+}
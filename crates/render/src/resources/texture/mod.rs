@@ -1,15 +1,19 @@
 use super::{Label, RenderAsset};
-use crate::device::RenderDevice;
+use crate::device::{RenderDevice, RenderFeatures};
 use asset::{Asset, AssetId};
 use ecs::system::unlifetime::Read;
 use std::sync::Arc;
 use wgpu::{TextureAspect, TextureFormat};
 
+pub mod compressed;
 pub mod fallbacks;
+pub mod mipmap;
 pub mod render;
 pub mod sampler;
 
+pub use compressed::*;
 pub use fallbacks::*;
+pub use mipmap::*;
 pub use render::*;
 pub use sampler::*;
 
@@ -93,6 +97,9 @@ pub struct Texture {
     pub height: u32,
     pub depth_or_layers: u32,
     pub mipmaps: bool,
+    /// The number of mip levels stored in `pixels`. Each layer's levels
+    /// follow its base level, from largest to smallest.
+    pub mip_levels: u32,
     pub format: wgpu::TextureFormat,
     pub dimension: TextureDimension,
     pub filter: FilterMode,
@@ -114,6 +121,7 @@ impl Texture {
             height: size.height,
             depth_or_layers: size.depth_or_array_layers,
             mipmaps: false,
+            mip_levels: 1,
             format,
             dimension,
             filter: FilterMode::Nearest,
@@ -190,6 +198,61 @@ impl Texture {
         }
     }
 
+    /// Stacks same-sized textures into a single [`TextureDimension::D2Array`]
+    /// texture, in the order given. The array inherits the first texture's
+    /// sampling and usage settings.
+    pub fn stack(textures: &[Texture]) -> Result<Self, TextureArrayError> {
+        let Some(first) = textures.first() else {
+            return Err(TextureArrayError::Empty);
+        };
+
+        let mut layers = 0;
+        let mut pixels = Vec::new();
+        for (index, texture) in textures.iter().enumerate() {
+            if (texture.width, texture.height) != (first.width, first.height) {
+                return Err(TextureArrayError::MismatchedSize {
+                    index,
+                    expected: (first.width, first.height),
+                    found: (texture.width, texture.height),
+                });
+            }
+
+            if texture.format != first.format {
+                return Err(TextureArrayError::MismatchedFormat {
+                    index,
+                    expected: first.format,
+                    found: texture.format,
+                });
+            }
+
+            if texture.mip_levels != first.mip_levels {
+                return Err(TextureArrayError::MismatchedMipLevels {
+                    index,
+                    expected: first.mip_levels,
+                    found: texture.mip_levels,
+                });
+            }
+
+            layers += texture.depth_or_layers;
+            pixels.extend_from_slice(&texture.pixels);
+        }
+
+        Ok(Self {
+            label: first.label.clone(),
+            width: first.width,
+            height: first.height,
+            depth_or_layers: layers,
+            mipmaps: first.mipmaps,
+            mip_levels: first.mip_levels,
+            format: first.format,
+            dimension: TextureDimension::D2Array,
+            filter: first.filter,
+            wrap: first.wrap,
+            usage: first.usage,
+            pixels,
+        })
+    }
+
     pub fn with_label(mut self, label: Label) -> Self {
         self.label = label;
         self
@@ -200,6 +263,13 @@ impl Texture {
         self
     }
 
+    /// Marks the texture as storing `levels` mip levels per layer in its
+    /// pixels.
+    pub fn with_mip_levels(mut self, levels: u32) -> Self {
+        self.mip_levels = levels.max(1);
+        self
+    }
+
     pub fn with_filter(mut self, filter: FilterMode) -> Self {
         self.filter = filter;
         self
@@ -214,6 +284,76 @@ impl Texture {
         self.usage = usage;
         self
     }
+
+    /// The size in bytes of a single `width` by `height` image in `format`,
+    /// rounded up to whole blocks for compressed formats.
+    pub fn image_size(format: TextureFormat, width: u32, height: u32) -> usize {
+        let (block_width, block_height) = format.block_dimensions();
+        let block_size = format.block_copy_size(None).unwrap_or(0);
+
+        width.div_ceil(block_width) as usize
+            * height.div_ceil(block_height) as usize
+            * block_size as usize
+    }
+
+    /// Adds [`wgpu::TextureUsages::STORAGE_BINDING`] so the texture can be
+    /// bound as a storage texture.
+    pub fn with_storage(mut self) -> Result<Self, TextureStorageError> {
+        if !Self::supports_storage(self.format) {
+            return Err(TextureStorageError::UnsupportedFormat(self.format));
+        }
+
+        self.usage |= wgpu::TextureUsages::STORAGE_BINDING;
+        Ok(self)
+    }
+
+    /// Whether `format` can be used as a storage texture without any
+    /// optional device features.
+    pub fn supports_storage(format: wgpu::TextureFormat) -> bool {
+        format
+            .guaranteed_format_features(wgpu::Features::empty())
+            .allowed_usages
+            .contains(wgpu::TextureUsages::STORAGE_BINDING)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum TextureStorageError {
+    #[error("Texture format {0:?} does not support storage usage")]
+    UnsupportedFormat(TextureFormat),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum TextureUploadError {
+    #[error("Texture format {0:?} needs device features that are not enabled")]
+    UnsupportedFormat(TextureFormat),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TextureArrayError {
+    #[error("Texture array has no layers")]
+    Empty,
+
+    #[error("Layer {index} is {found:?}, expected {expected:?}")]
+    MismatchedSize {
+        index: usize,
+        expected: (u32, u32),
+        found: (u32, u32),
+    },
+
+    #[error("Layer {index} has format {found:?}, expected {expected:?}")]
+    MismatchedFormat {
+        index: usize,
+        expected: TextureFormat,
+        found: TextureFormat,
+    },
+
+    #[error("Layer {index} has {found} mip levels, expected {expected}")]
+    MismatchedMipLevels {
+        index: usize,
+        expected: u32,
+        found: u32,
+    },
 }
 
 pub struct GpuTexture {
@@ -249,18 +389,30 @@ impl GpuTexture {
     }
 
     pub fn create(device: &RenderDevice, texture: &Texture, sampler: Sampler) -> Self {
+        // Mipmapped textures without stored levels get them generated here.
+        // Formats that can't be filtered on the cpu only upload their base
+        // level instead of leaving the other levels uninitialized.
+        let generated;
+        let texture = match texture.mipmaps && texture.mip_levels <= 1 {
+            true => {
+                let mut mipmapped = texture.clone();
+                if mipmapped.generate_mipmaps().is_err() {
+                    mipmapped.mipmaps = false;
+                }
+
+                generated = mipmapped;
+                &generated
+            }
+            false => texture,
+        };
+
         let size = wgpu::Extent3d {
             width: texture.width,
             height: texture.height,
             depth_or_array_layers: texture.depth_or_layers,
         };
 
-        let mip_level_count = if texture.mipmaps {
-            let dimension = texture.dimension.into();
-            size.max_mips(dimension)
-        } else {
-            1
-        };
+        let mip_level_count = texture.mip_levels.max(1);
 
         let format = texture.format;
 
@@ -279,34 +431,42 @@ impl GpuTexture {
             view_formats: &[format],
         });
 
+        let (block_width, block_height) = format.block_dimensions();
         let block_size = format.block_copy_size(None).unwrap_or(0);
+        let mut offset = 0;
         for layer in 0..texture.depth_or_layers {
-            device.queue.write_texture(
-                wgpu::TexelCopyTextureInfo {
-                    texture: &created,
-                    mip_level: 0,
-                    origin: wgpu::Origin3d {
-                        x: 0,
-                        y: 0,
-                        z: layer,
+            for level in 0..texture.mip_levels.max(1) {
+                let width = (size.width >> level).max(1);
+                let height = (size.height >> level).max(1);
+                let len = Texture::image_size(format, width, height);
+
+                device.queue.write_texture(
+                    wgpu::TexelCopyTextureInfo {
+                        texture: &created,
+                        mip_level: level,
+                        origin: wgpu::Origin3d {
+                            x: 0,
+                            y: 0,
+                            z: layer,
+                        },
+                        aspect: TextureAspect::All,
                     },
-                    aspect: TextureAspect::All,
-                },
-                &texture.pixels[(layer as usize
-                    * texture.width as usize
-                    * texture.height as usize
-                    * block_size as usize)..],
-                wgpu::TexelCopyBufferLayout {
-                    bytes_per_row: Some(block_size * size.width),
-                    rows_per_image: Some(size.height),
-                    offset: 0,
-                },
-                wgpu::Extent3d {
-                    width: size.width,
-                    height: size.height,
-                    depth_or_array_layers: 1,
-                },
-            );
+                    &texture.pixels[offset..offset + len],
+                    wgpu::TexelCopyBufferLayout {
+                        bytes_per_row: Some(width.div_ceil(block_width) * block_size),
+                        rows_per_image: Some(height.div_ceil(block_height)),
+                        offset: 0,
+                    },
+                    wgpu::Extent3d {
+                        width,
+                        height,
+                        depth_or_array_layers: 1,
+                    }
+                    .physical_size(format),
+                );
+
+                offset += len;
+            }
         }
 
         let view = created.create_view(&wgpu::TextureViewDescriptor::default());
@@ -368,14 +528,83 @@ impl AsRef<wgpu::TextureView> for GpuTexture {
 impl RenderAsset for GpuTexture {
     type Source = Texture;
 
-    type Arg = Read<RenderDevice>;
+    type Arg = (Read<RenderDevice>, Read<RenderFeatures>);
 
     fn extract(
         _: AssetId<Self::Source>,
         texture: Self::Source,
-        device: &mut ecs::system::ArgItem<Self::Arg>,
+        (device, features): &mut ecs::system::ArgItem<Self::Arg>,
     ) -> Result<Self, super::ExtractError<Self::Source>> {
+        if !features.supports_format(texture.format) {
+            let error = TextureUploadError::UnsupportedFormat(texture.format);
+            return Err(super::ExtractError::from_error(error));
+        }
+
         let sampler = Sampler::from_texture(device, &texture);
         Ok(GpuTexture::create(device, &texture, sampler))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Texture, TextureArrayError, TextureDimension};
+
+    fn layer(size: u32, value: u8) -> Texture {
+        Texture::new(
+            wgpu::Extent3d {
+                width: size,
+                height: size,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            wgpu::TextureFormat::Rgba8Unorm,
+            vec![value; (size * size * 4) as usize],
+        )
+    }
+
+    #[test]
+    fn stack_layers() {
+        let texture = Texture::stack(&[layer(8, 0), layer(8, 1), layer(8, 2)]).unwrap();
+
+        assert_eq!(texture.dimension, TextureDimension::D2Array);
+        assert_eq!(texture.depth_or_layers, 3);
+        assert_eq!((texture.width, texture.height), (8, 8));
+
+        let layer_size = 8 * 8 * 4;
+        assert_eq!(texture.pixels.len(), layer_size * 3);
+        for (index, pixels) in texture.pixels.chunks_exact(layer_size).enumerate() {
+            assert!(pixels.iter().all(|value| *value == index as u8));
+        }
+    }
+
+    #[test]
+    fn stack_mismatched_layers() {
+        let error = Texture::stack(&[layer(8, 0), layer(4, 1)]).err();
+
+        assert_eq!(
+            error,
+            Some(TextureArrayError::MismatchedSize {
+                index: 1,
+                expected: (8, 8),
+                found: (4, 4),
+            })
+        );
+    }
+
+    #[test]
+    fn stack_mismatched_mip_levels() {
+        let mut mipmapped = layer(8, 1);
+        mipmapped.generate_mipmaps().unwrap();
+
+        let error = Texture::stack(&[layer(8, 0), mipmapped]).err();
+
+        assert_eq!(
+            error,
+            Some(TextureArrayError::MismatchedMipLevels {
+                index: 1,
+                expected: 1,
+                found: 4,
+            })
+        );
+    }
+}
@@ -30,6 +30,18 @@ impl Default for SamplerDesc {
     }
 }
 
+impl SamplerDesc {
+    /// The binding type a layout must declare to bind a sampler created
+    /// from this descriptor.
+    pub fn binding_type(&self) -> wgpu::SamplerBindingType {
+        match (self.compare, self.filter_mode) {
+            (Some(_), _) => wgpu::SamplerBindingType::Comparison,
+            (None, FilterMode::Linear) => wgpu::SamplerBindingType::Filtering,
+            (None, FilterMode::Nearest) => wgpu::SamplerBindingType::NonFiltering,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Sampler(Arc<wgpu::Sampler>);
 
@@ -39,7 +51,7 @@ impl Sampler {
         let filter_mode = desc.filter_mode.into();
 
         let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
-            label: None,
+            label: desc.label.as_deref(),
             address_mode_u: address_mode,
             address_mode_v: address_mode,
             address_mode_w: address_mode,
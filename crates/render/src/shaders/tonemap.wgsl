@@ -0,0 +1,33 @@
+@group(0) @binding(0) var input_texture: texture_2d<f32>;
+@group(0) @binding(1) var input_sampler: sampler;
+
+fn reinhard(color: vec3<f32>) -> vec3<f32> {
+    return color / (color + vec3<f32>(1.0));
+}
+
+// Narkowicz 2015, "ACES Filmic Tone Mapping Curve"
+fn aces(color: vec3<f32>) -> vec3<f32> {
+    let a = 2.51;
+    let b = 0.03;
+    let c = 2.43;
+    let d = 0.59;
+    let e = 0.14;
+    return clamp((color * (a * color + b)) / (color * (c * color + d) + e), vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+@fragment
+fn fs_none(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+    return textureSample(input_texture, input_sampler, uv);
+}
+
+@fragment
+fn fs_reinhard(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+    let color = textureSample(input_texture, input_sampler, uv);
+    return vec4<f32>(reinhard(color.rgb), color.a);
+}
+
+@fragment
+fn fs_aces(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+    let color = textureSample(input_texture, input_sampler, uv);
+    return vec4<f32>(aces(color.rgb), color.a);
+}
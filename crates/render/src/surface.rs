@@ -37,10 +37,37 @@ impl From<HandleError> for RenderSurfaceError {
 
 impl std::error::Error for RenderSurfaceError {}
 
+/// Selects what the [`RenderPlugin`](crate::plugins::RenderPlugin) renders
+/// to. Add it to the app before the plugin builds to render without a window.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SurfaceMode {
+    /// Present to the app's [`Window`].
+    #[default]
+    Window,
+    /// Render into offscreen textures, see [`RenderSurface::headless`].
+    Headless {
+        size: Size<u32>,
+        format: wgpu::TextureFormat,
+    },
+}
+
+/// Where a [`RenderSurface`] presents its frames.
+pub enum SurfaceTarget {
+    /// A swapchain for a window.
+    Window {
+        window: Window,
+        surface: wgpu::Surface<'static>,
+    },
+    /// Offscreen textures, for tests and rendering without a window.
+    Headless {
+        color: wgpu::Texture,
+        depth: wgpu::Texture,
+    },
+}
+
 #[derive(Resource)]
 pub struct RenderSurface {
-    window: Window,
-    surface: wgpu::Surface<'static>,
+    target: SurfaceTarget,
     config: SurfaceConfiguration,
     depth_format: wgpu::TextureFormat,
 }
@@ -80,8 +107,10 @@ impl RenderSurface {
         };
 
         let surface = Self {
-            window: window.clone(),
-            surface,
+            target: SurfaceTarget::Window {
+                window: window.clone(),
+                surface,
+            },
             config,
             depth_format: Self::DEPTH_FORMAT,
         };
@@ -89,12 +118,58 @@ impl RenderSurface {
         Ok((surface, adapter))
     }
 
-    pub fn surface(&self) -> &wgpu::Surface<'static> {
-        &self.surface
+    /// Creates a surface that renders into offscreen color and depth
+    /// textures instead of a window's swapchain. The color texture can be
+    /// copied from to read the rendered frame back.
+    pub fn headless(device: &RenderDevice, size: Size<u32>, format: wgpu::TextureFormat) -> Self {
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            format,
+            width: size.width,
+            height: size.height,
+            present_mode: PresentMode::Fifo,
+            alpha_mode: wgpu::CompositeAlphaMode::Auto,
+            view_formats: vec![],
+            desired_maximum_frame_latency: 2,
+        };
+
+        let (color, depth) = Self::create_headless_textures(device, &config, Self::DEPTH_FORMAT);
+
+        Self {
+            target: SurfaceTarget::Headless { color, depth },
+            config,
+            depth_format: Self::DEPTH_FORMAT,
+        }
+    }
+
+    pub fn target(&self) -> &SurfaceTarget {
+        &self.target
     }
 
-    pub fn window(&self) -> &Window {
-        &self.window
+    pub fn is_headless(&self) -> bool {
+        matches!(self.target, SurfaceTarget::Headless { .. })
+    }
+
+    pub fn surface(&self) -> Option<&wgpu::Surface<'static>> {
+        match &self.target {
+            SurfaceTarget::Window { surface, .. } => Some(surface),
+            SurfaceTarget::Headless { .. } => None,
+        }
+    }
+
+    pub fn window(&self) -> Option<&Window> {
+        match &self.target {
+            SurfaceTarget::Window { window, .. } => Some(window),
+            SurfaceTarget::Headless { .. } => None,
+        }
+    }
+
+    /// The offscreen depth texture of a headless surface.
+    pub fn depth_texture(&self) -> Option<&wgpu::Texture> {
+        match &self.target {
+            SurfaceTarget::Headless { depth, .. } => Some(depth),
+            SurfaceTarget::Window { .. } => None,
+        }
     }
 
     pub fn size(&self) -> Size<u32> {
@@ -125,24 +200,83 @@ impl RenderSurface {
     }
 
     pub fn configure(&self, device: &RenderDevice) {
-        self.surface.configure(device, &self.config);
+        if let SurfaceTarget::Window { surface, .. } = &self.target {
+            surface.configure(device, &self.config);
+        }
     }
 
-    pub fn texture(&self) -> Result<wgpu::SurfaceTexture, wgpu::SurfaceError> {
-        self.surface.get_current_texture()
+    /// The texture to render the next frame into.
+    pub fn texture(&self) -> Result<SurfaceTexture, wgpu::SurfaceError> {
+        match &self.target {
+            SurfaceTarget::Window { surface, .. } => {
+                surface.get_current_texture().map(SurfaceTexture::Window)
+            }
+            SurfaceTarget::Headless { color, .. } => Ok(SurfaceTexture::Headless(color.clone())),
+        }
     }
 
     pub fn resize(&mut self, device: &RenderDevice, width: u32, height: u32) {
         self.config.width = width;
         self.config.height = height;
-        self.surface.configure(device, &self.config);
+
+        match &mut self.target {
+            SurfaceTarget::Window { surface, .. } => surface.configure(device, &self.config),
+            SurfaceTarget::Headless { color, depth } => {
+                (*color, *depth) =
+                    Self::create_headless_textures(device, &self.config, self.depth_format);
+            }
+        }
+    }
+
+    fn create_headless_textures(
+        device: &RenderDevice,
+        config: &SurfaceConfiguration,
+        depth_format: wgpu::TextureFormat,
+    ) -> (wgpu::Texture, wgpu::Texture) {
+        let size = wgpu::Extent3d {
+            width: config.width,
+            height: config.height,
+            depth_or_array_layers: 1,
+        };
+
+        let color = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("HeadlessColor"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: config.format,
+            usage: config.usage,
+            view_formats: &[],
+        });
+
+        let depth = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("HeadlessDepth"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: depth_format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+
+        (color, depth)
     }
 
     pub(crate) fn resize_surface(
-        window: Main<&Window>,
+        window: Main<Option<&Window>>,
         device: &RenderDevice,
         surface: &mut RenderSurface,
     ) {
+        if surface.is_headless() {
+            return;
+        }
+
+        let Some(window) = *window else {
+            return;
+        };
+
         let size = window.size();
         if size.width != surface.width() || size.height != surface.height() {
             surface.resize(device, size.width, size.height);
@@ -155,7 +289,7 @@ impl RenderSurface {
         surface_texture: &mut RenderSurfaceTexture,
     ) {
         match surface.texture() {
-            Ok(texture) if texture.suboptimal => {
+            Ok(SurfaceTexture::Window(texture)) if texture.suboptimal => {
                 drop(texture);
                 surface.configure(device);
             }
@@ -172,25 +306,158 @@ impl RenderSurface {
     }
 }
 
+/// A frame's color texture, from either a window's swapchain or a headless
+/// surface.
+pub enum SurfaceTexture {
+    Window(wgpu::SurfaceTexture),
+    Headless(wgpu::Texture),
+}
+
+impl SurfaceTexture {
+    pub fn texture(&self) -> &wgpu::Texture {
+        match self {
+            SurfaceTexture::Window(surface) => &surface.texture,
+            SurfaceTexture::Headless(texture) => texture,
+        }
+    }
+}
+
 #[derive(Resource)]
-pub struct RenderSurfaceTexture(Option<wgpu::SurfaceTexture>);
+pub struct RenderSurfaceTexture(Option<SurfaceTexture>);
 
 impl RenderSurfaceTexture {
     pub fn new() -> Self {
         Self(None)
     }
 
-    pub fn set(&mut self, surface: wgpu::SurfaceTexture) {
+    pub fn set(&mut self, surface: SurfaceTexture) {
         self.0 = Some(surface);
     }
 
-    pub fn get(&self) -> Option<&wgpu::SurfaceTexture> {
-        self.0.as_ref()
+    pub fn get(&self) -> Option<&wgpu::Texture> {
+        self.0.as_ref().map(SurfaceTexture::texture)
     }
 
     fn present(&mut self) {
-        if let Some(surface) = self.0.take() {
+        if let Some(SurfaceTexture::Window(surface)) = self.0.take() {
             surface.present();
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::RenderSurface;
+    use crate::device::tests::device;
+    use math::Size;
+
+    const SHADER: &str = r#"
+        @vertex
+        fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+            let x = f32(i32(index) - 1) * 2.0;
+            let y = f32(i32(index & 1u) * 2 - 1) * 2.0;
+            return vec4<f32>(x, y, 0.0, 1.0);
+        }
+
+        @fragment
+        fn fs_main() -> @location(0) vec4<f32> {
+            return vec4<f32>(1.0, 0.0, 0.0, 1.0);
+        }
+    "#;
+
+    #[test]
+    #[ignore = "requires a GPU adapter"]
+    fn headless_triangle() {
+        let device = device();
+
+        let size = Size::new(64, 64);
+        let format = wgpu::TextureFormat::Rgba8Unorm;
+        let surface = RenderSurface::headless(&device, size, format);
+        assert_eq!(surface.size(), size);
+        assert_eq!(surface.format(), format);
+        assert_eq!(surface.depth_format(), RenderSurface::DEPTH_FORMAT);
+        assert!(surface.window().is_none());
+
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: None,
+            source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: None,
+            layout: None,
+            vertex: wgpu::VertexState {
+                module: &module,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &module,
+                entry_point: Some("fs_main"),
+                targets: &[Some(format.into())],
+                compilation_options: Default::default(),
+            }),
+            primitive: Default::default(),
+            depth_stencil: None,
+            multisample: Default::default(),
+            cache: None,
+            multiview: None,
+        });
+
+        let texture = surface.texture().unwrap();
+        let texture = texture.texture();
+        let view = texture.create_view(&Default::default());
+        let bytes_per_row = size.width * 4;
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: (bytes_per_row * size.height) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = device.create_command_encoder(&Default::default());
+        {
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: None,
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+
+            pass.set_pipeline(&pipeline);
+            pass.draw(0..3, 0..1);
+        }
+
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::TexelCopyBufferInfo {
+                buffer: &readback,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(bytes_per_row),
+                    rows_per_image: None,
+                },
+            },
+            texture.size(),
+        );
+
+        device.queue.submit(std::iter::once(encoder.finish()));
+
+        let slice = readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |result| result.unwrap());
+        device.poll(wgpu::PollType::Wait).unwrap();
+
+        let data = slice.get_mapped_range();
+        let center = ((size.height / 2) * bytes_per_row + (size.width / 2) * 4) as usize;
+        assert_eq!(&data[center..center + 4], &[255, 0, 0, 255]);
+    }
+}
@@ -0,0 +1,89 @@
+//! World space basis vectors and the matrix helpers that depend on them.
+//!
+//! The default convention is right handed with +Y up and -Z forward, which
+//! matches glam's `_rh` functions. Two crate features change it:
+//!
+//! - `z-up`: +Z is up and forward lies along the Y axis.
+//! - `left-handed`: forward is flipped so that `RIGHT x UP == FORWARD`, and
+//!   the `_lh` projection and view functions are used.
+//! - `view-forward`: view matrices look along `FORWARD` instead of `BACK`.
+//!
+//! In every convention `RIGHT` is +X.
+
+use math::{Mat4, Vec3};
+
+pub const RIGHT_HANDED: bool = !cfg!(feature = "left-handed");
+
+pub const RIGHT: Vec3 = Vec3::X;
+
+#[cfg(not(feature = "z-up"))]
+pub const UP: Vec3 = Vec3::Y;
+#[cfg(feature = "z-up")]
+pub const UP: Vec3 = Vec3::Z;
+
+#[cfg(all(not(feature = "z-up"), not(feature = "left-handed")))]
+pub const FORWARD: Vec3 = Vec3::NEG_Z;
+#[cfg(all(not(feature = "z-up"), feature = "left-handed"))]
+pub const FORWARD: Vec3 = Vec3::Z;
+#[cfg(all(feature = "z-up", not(feature = "left-handed")))]
+pub const FORWARD: Vec3 = Vec3::Y;
+#[cfg(all(feature = "z-up", feature = "left-handed"))]
+pub const FORWARD: Vec3 = Vec3::NEG_Y;
+
+pub const LEFT: Vec3 = Vec3::new(-RIGHT.x, -RIGHT.y, -RIGHT.z);
+pub const DOWN: Vec3 = Vec3::new(-UP.x, -UP.y, -UP.z);
+pub const BACK: Vec3 = Vec3::new(-FORWARD.x, -FORWARD.y, -FORWARD.z);
+
+/// The direction a camera looks in view space. View space is always +Y up,
+/// so this only depends on handedness.
+pub const VIEW_FORWARD: Vec3 = if RIGHT_HANDED { Vec3::NEG_Z } else { Vec3::Z };
+
+/// The local direction a transform's view matrix looks along. Views look
+/// along `BACK` (+Z by default) unless the `view-forward` feature is enabled.
+#[cfg(not(feature = "view-forward"))]
+pub const VIEW_DIRECTION: Vec3 = BACK;
+#[cfg(feature = "view-forward")]
+pub const VIEW_DIRECTION: Vec3 = FORWARD;
+
+/// A view matrix for a camera at `eye` looking in `direction`.
+pub fn look_to(eye: Vec3, direction: Vec3, up: Vec3) -> Mat4 {
+    if RIGHT_HANDED {
+        Mat4::look_to_rh(eye, direction, up)
+    } else {
+        Mat4::look_to_lh(eye, direction, up)
+    }
+}
+
+/// A view matrix for a camera at `eye` looking at `center`.
+pub fn look_at(eye: Vec3, center: Vec3, up: Vec3) -> Mat4 {
+    look_to(eye, center - eye, up)
+}
+
+/// An infinite reverse z perspective projection. The near plane maps to a
+/// depth of one and infinity to zero.
+pub fn perspective_infinite_reverse(fov: f32, aspect_ratio: f32, near: f32) -> Mat4 {
+    if RIGHT_HANDED {
+        Mat4::perspective_infinite_reverse_rh(fov, aspect_ratio, near)
+    } else {
+        Mat4::perspective_infinite_reverse_lh(fov, aspect_ratio, near)
+    }
+}
+
+/// A reverse z perspective projection with a finite far plane. The near
+/// plane maps to a depth of one and the far plane to zero.
+pub fn perspective_reverse(fov: f32, aspect_ratio: f32, near: f32, far: f32) -> Mat4 {
+    if RIGHT_HANDED {
+        Mat4::perspective_rh(fov, aspect_ratio, far, near)
+    } else {
+        Mat4::perspective_lh(fov, aspect_ratio, far, near)
+    }
+}
+
+/// An orthographic projection that maps the near plane to a depth of zero.
+pub fn orthographic(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> Mat4 {
+    if RIGHT_HANDED {
+        Mat4::orthographic_rh(left, right, bottom, top, near, far)
+    } else {
+        Mat4::orthographic_lh(left, right, bottom, top, near, far)
+    }
+}
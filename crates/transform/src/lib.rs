@@ -1,9 +1,12 @@
 use ecs::{
-    Component, ComponentKit, Entity, HierarchyExt, Parent, Query,
+    AddChild, Command, Component, ComponentKit, Entity, EntityCommands, HierarchyExt, Parent,
+    Query, World,
+    core::task::{CpuTaskPool, TaskPool},
     query::{With, Without},
 };
-use math::{Affine3A, Mat4, Quat, Vec2, Vec3, Vec3A};
+use math::{Affine3A, Mat3, Mat4, Quat, Vec2, Vec3, Vec3A};
 
+pub mod basis;
 pub mod plugin;
 
 #[derive(Component, Clone, Copy)]
@@ -28,6 +31,45 @@ impl Transform {
         self.rotation = rotation;
         self
     }
+
+    /// Decomposes `affine` into a transform. Shear can't be represented and
+    /// is lost.
+    pub fn from_affine(affine: Affine3A) -> Self {
+        let (scale, rotation, translation) = affine.to_scale_rotation_translation();
+        let (x, y, z) = rotation.to_euler(math::EulerRot::XYZ);
+
+        Self {
+            translation,
+            scale,
+            rotation: Vec3::new(x, y, z),
+        }
+    }
+
+    /// Rotates the transform so that its forward axis points from
+    /// `translation` at `target`. When the look direction is parallel to
+    /// `up`, an arbitrary axis perpendicular to it is used as up instead.
+    pub fn look_at(&mut self, target: Vec3, up: Vec3) {
+        let Some(forward) = (target - self.translation).try_normalize() else {
+            return;
+        };
+
+        let up = match up.cross(forward).length_squared() < 1e-6 {
+            true => forward.any_orthonormal_vector(),
+            false => up,
+        };
+
+        // `RIGHT x UP` is either forward or back depending on handedness.
+        let cross = basis::RIGHT.cross(basis::UP);
+        let cross_world = forward * basis::FORWARD.dot(cross);
+        let right = up.cross(cross_world).normalize();
+        let up = cross_world.cross(right);
+
+        let local = Mat3::from_cols(basis::RIGHT, basis::UP, cross);
+        let world = Mat3::from_cols(right, up, cross_world);
+        let rotation = Quat::from_mat3(&(world * local.transpose()));
+        let (x, y, z) = rotation.to_euler(math::EulerRot::XYZ);
+        self.rotation = Vec3::new(x, y, z);
+    }
 }
 
 impl Default for Transform {
@@ -57,6 +99,72 @@ impl Default for Transform2d {
     }
 }
 
+/// A local transform that stores its rotation as a quaternion, which avoids
+/// the gimbal lock and rotation order ambiguity of [`Transform`]'s euler
+/// angles.
+#[derive(Component, Clone, Copy)]
+pub struct TransformQuat {
+    pub translation: Vec3,
+    pub scale: Vec3,
+    pub rotation: Quat,
+}
+
+impl TransformQuat {
+    pub fn with_translation(mut self, translation: Vec3) -> Self {
+        self.translation = translation;
+        self
+    }
+
+    pub fn with_scale(mut self, scale: Vec3) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn with_rotation(mut self, rotation: Quat) -> Self {
+        self.rotation = rotation;
+        self
+    }
+}
+
+impl Default for TransformQuat {
+    fn default() -> Self {
+        Self {
+            translation: Vec3::ZERO,
+            scale: Vec3::ONE,
+            rotation: Quat::IDENTITY,
+        }
+    }
+}
+
+impl From<Transform> for TransformQuat {
+    fn from(value: Transform) -> Self {
+        let rotation = Quat::from_euler(
+            math::EulerRot::XYZ,
+            value.rotation.x,
+            value.rotation.y,
+            value.rotation.z,
+        );
+
+        Self {
+            translation: value.translation,
+            scale: value.scale,
+            rotation,
+        }
+    }
+}
+
+impl From<TransformQuat> for Transform {
+    fn from(value: TransformQuat) -> Self {
+        let (x, y, z) = value.rotation.to_euler(math::EulerRot::XYZ);
+
+        Self {
+            translation: value.translation,
+            scale: value.scale,
+            rotation: Vec3::new(x, y, z),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Component)]
 pub struct GlobalTransform(Affine3A);
 impl Default for GlobalTransform {
@@ -112,24 +220,62 @@ impl GlobalTransform {
         self.0.transform_point3(point)
     }
 
+    /// Transforms a direction or offset. Translation is ignored, but rotation
+    /// and scale are applied, so normalize the result for unit directions.
+    pub fn transform_vector(&self, vector: Vec3) -> Vec3 {
+        self.0.transform_vector3(vector)
+    }
+
+    /// Moves a world space point into the local space of this transform.
+    pub fn inverse_transform_point(&self, point: Vec3) -> Vec3 {
+        self.0.inverse().transform_point3(point)
+    }
+
+    /// Moves a world space direction or offset into the local space of this
+    /// transform.
+    pub fn inverse_transform_vector(&self, vector: Vec3) -> Vec3 {
+        Vec3::from(self.0.matrix3.inverse() * Vec3A::from(vector))
+    }
+
     pub fn to_scale_rotation_translation(&self) -> (Vec3, Quat, Vec3) {
         self.0.to_scale_rotation_translation()
     }
 
+    /// Interpolates between two transforms. Scale and translation are lerped
+    /// and rotation is slerped along the shorter arc. `t` is clamped to
+    /// `[0, 1]`.
+    pub fn lerp(&self, other: &GlobalTransform, t: f32) -> GlobalTransform {
+        let t = t.clamp(0.0, 1.0);
+        let (scale, rotation, translation) = self.to_scale_rotation_translation();
+        let (other_scale, other_rotation, other_translation) =
+            other.to_scale_rotation_translation();
+
+        let other_rotation = match rotation.dot(other_rotation) < 0.0 {
+            true => -other_rotation,
+            false => other_rotation,
+        };
+
+        GlobalTransform::new(
+            translation.lerp(other_translation, t),
+            rotation.slerp(other_rotation, t).normalize(),
+            scale.lerp(other_scale, t),
+        )
+    }
+
     pub fn back(&self) -> Vec3 {
-        (self.0.matrix3 * Vec3::Z).normalize()
+        (self.0.matrix3 * basis::BACK).normalize()
     }
 
     pub fn forward(&self) -> Vec3 {
-        (self.0.matrix3 * Vec3::NEG_Z).normalize()
+        (self.0.matrix3 * basis::FORWARD).normalize()
     }
 
     pub fn right(&self) -> Vec3 {
-        (self.0.matrix3 * Vec3::X).normalize()
+        (self.0.matrix3 * basis::RIGHT).normalize()
     }
 
     pub fn up(&self) -> Vec3 {
-        (self.0.matrix3 * Vec3::Y).normalize()
+        (self.0.matrix3 * basis::UP).normalize()
     }
 
     pub fn inverse(&self) -> Self {
@@ -138,15 +284,15 @@ impl GlobalTransform {
 
     pub fn look_at(&self, target: Vec3, up: Vec3) -> Mat4 {
         let translation = self.translation();
-        Mat4::look_to_rh(translation, translation + target, up)
+        basis::look_to(translation, translation + target, up)
     }
 
     pub fn view_matrix(&self) -> Mat4 {
         let (_, rotation, translation) = self.to_scale_rotation_translation();
-        Mat4::look_at_rh(
+        basis::look_to(
             translation,
-            translation + rotation * Vec3::Z,
-            rotation * Vec3::Y,
+            rotation * basis::VIEW_DIRECTION,
+            rotation * basis::UP,
         )
     }
 }
@@ -183,6 +329,61 @@ pub struct Transform2dKit {
     pub global: GlobalTransform,
 }
 
+#[derive(ComponentKit)]
+pub struct TransformQuatKit {
+    /// The local transform is the transform relative to the parent entity.
+    pub local: TransformQuat,
+    /// The global transform is the transform relative to the world origin.
+    pub global: GlobalTransform,
+}
+
+/// Moves `child` under `parent` and rewrites the child's [`Transform`] so
+/// that its [`GlobalTransform`] stays the same. Both global transforms are
+/// read as they were last propagated.
+pub struct SetParentKeepTransform {
+    pub parent: Entity,
+    pub child: Entity,
+}
+
+impl SetParentKeepTransform {
+    pub fn new(parent: Entity, child: Entity) -> Self {
+        Self { parent, child }
+    }
+}
+
+impl Command for SetParentKeepTransform {
+    fn execute(self, world: &mut World) {
+        let child = world.get_component::<GlobalTransform>(self.child).copied();
+        let parent = world
+            .get_component::<GlobalTransform>(self.parent)
+            .copied()
+            .unwrap_or_default();
+
+        AddChild::new(self.parent, self.child).execute(world);
+
+        let Some(child) = child else {
+            return;
+        };
+
+        if let Some(transform) = world.get_component_mut::<Transform>(self.child) {
+            *transform = Transform::from_affine(parent.0.inverse() * child.0);
+        }
+    }
+}
+
+pub trait TransformCommandsExt {
+    /// Queues a [`SetParentKeepTransform`] for this entity.
+    fn set_parent_keep_transform(&mut self, parent: Entity);
+}
+
+impl TransformCommandsExt for EntityCommands<'_> {
+    fn set_parent_keep_transform(&mut self, parent: Entity) {
+        self.add(move |entity, world: &mut World| {
+            SetParentKeepTransform::new(parent, entity).execute(world);
+        });
+    }
+}
+
 pub trait LocalTransform: Component + Copy {
     fn local(&self) -> Affine3A;
 }
@@ -199,6 +400,12 @@ impl LocalTransform for Transform {
     }
 }
 
+impl LocalTransform for TransformQuat {
+    fn local(&self) -> Affine3A {
+        Affine3A::from_scale_rotation_translation(self.scale, self.rotation, self.translation)
+    }
+}
+
 impl LocalTransform for Transform2d {
     fn local(&self) -> Affine3A {
         let rotation = math::Quat::from_rotation_z(self.rotation);
@@ -214,21 +421,454 @@ pub fn update_global_transforms<L: LocalTransform>(
     root_query: Query<(Entity, &L, &mut GlobalTransform), Without<Parent>>,
     transform_query: Query<(Entity, &L, &mut GlobalTransform), With<Parent>>,
 ) {
-    for (entity, local_transform, global_transform) in root_query.iter() {
-        // Calculate the global transform based on the local transform
-        *global_transform = GlobalTransform(local_transform.local());
-
-        update_child_transforms(entity, global_transform, &transform_query);
+    match CpuTaskPool::try_get() {
+        Some(pool) => par_update_global_transforms(pool, root_query, transform_query),
+        None => {
+            for (entity, local_transform, global_transform) in root_query.iter() {
+                update_root_transform(entity, local_transform, global_transform, &transform_query);
+            }
+        }
     }
 }
 
+/// Splits the roots into one chunk per thread and updates each chunk's
+/// subtrees on a separate task. Roots have no parent, so no two subtrees
+/// share an entity and each task writes to a disjoint set of
+/// [`GlobalTransform`]s.
+fn par_update_global_transforms<L: LocalTransform>(
+    pool: &TaskPool,
+    root_query: Query<(Entity, &L, &mut GlobalTransform), Without<Parent>>,
+    transform_query: Query<(Entity, &L, &mut GlobalTransform), With<Parent>>,
+) {
+    let mut roots = root_query.iter().collect::<Vec<_>>();
+    let size = roots.len().div_ceil(pool.size().max(1)).max(1);
+    let transform_query = &transform_query;
+
+    pool.scope(|scope| {
+        for chunk in roots.chunks_mut(size) {
+            scope.spawn(async move {
+                for (entity, local_transform, global_transform) in chunk {
+                    update_root_transform::<L>(
+                        *entity,
+                        local_transform,
+                        global_transform,
+                        transform_query,
+                    );
+                }
+            });
+        }
+    });
+}
+
+fn update_root_transform<L: LocalTransform>(
+    entity: Entity,
+    local_transform: &L,
+    global_transform: &mut GlobalTransform,
+    query: &Query<(Entity, &L, &mut GlobalTransform), With<Parent>>,
+) {
+    // Calculate the global transform based on the local transform
+    *global_transform = GlobalTransform(local_transform.local());
+
+    update_child_transforms(entity, global_transform, query);
+}
+
+/// Walks the subtree below `entity` with an explicit stack rather than
+/// recursion, so deep hierarchies are bounded by the heap instead of the
+/// thread's stack.
 fn update_child_transforms<L: LocalTransform>(
     entity: Entity,
     global_transform: &GlobalTransform,
     query: &Query<(Entity, &L, &mut GlobalTransform), With<Parent>>,
 ) {
-    for (child, child_transform, child_global_transform) in query.children(entity) {
-        *child_global_transform = global_transform * child_transform;
-        update_child_transforms(child, &child_global_transform, query);
+    let mut stack = vec![(entity, *global_transform)];
+    while let Some((parent, global_transform)) = stack.pop() {
+        for (child, child_transform, child_global_transform) in query.children(parent) {
+            *child_global_transform = &global_transform * child_transform;
+            stack.push((child, *child_global_transform));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        GlobalTransform, LocalTransform, SetParentKeepTransform, Transform, TransformKit,
+        par_update_global_transforms, update_root_transform,
+    };
+    use ecs::{
+        AddChild, ArchetypeAccess, Children, Command, CommandBuffer, Commands, Entity, Parent,
+        Query, QueryState, World,
+        core::task::TaskPool,
+        query::{With, Without},
+    };
+    use math::Vec3;
+
+    type Roots =
+        QueryState<(Entity, &'static Transform, &'static mut GlobalTransform), Without<Parent>>;
+    type Nodes =
+        QueryState<(Entity, &'static Transform, &'static mut GlobalTransform), With<Parent>>;
+
+    fn spawn(world: &mut World, transform: Transform) -> Entity {
+        let entity = world.spawn();
+        world.add_component(entity, transform);
+        world.add_component(entity, GlobalTransform::default());
+        entity
+    }
+
+    fn globals(world: &World, entities: &[Entity]) -> Vec<GlobalTransform> {
+        entities
+            .iter()
+            .map(|entity| *world.get_component::<GlobalTransform>(*entity).unwrap())
+            .collect()
+    }
+
+    fn reset(world: &mut World, entities: &[Entity]) {
+        for entity in entities {
+            *world.get_component_mut::<GlobalTransform>(*entity).unwrap() =
+                GlobalTransform::default();
+        }
+    }
+
+    #[test]
+    fn parallel_matches_serial() {
+        let mut world = World::new();
+        world.register::<Parent>();
+        world.register::<Children>();
+        world.register::<Transform>();
+        world.register::<GlobalTransform>();
+
+        let mut entities = Vec::new();
+        for root in 0..64 {
+            let offset = Vec3::new(root as f32, 0.0, 0.0);
+            let root = spawn(&mut world, Transform::default().with_translation(offset));
+            entities.push(root);
+
+            let mut parent = root;
+            for depth in 1..5 {
+                let transform = Transform::default()
+                    .with_translation(Vec3::new(0.0, depth as f32, 0.0))
+                    .with_rotation(Vec3::new(0.0, 0.0, depth as f32 * 0.25))
+                    .with_scale(Vec3::splat(0.5 + depth as f32 * 0.25));
+
+                let child = spawn(&mut world, transform);
+                let sibling = spawn(&mut world, transform.with_translation(Vec3::Z));
+                AddChild::new(parent, child).execute(&mut world);
+                AddChild::new(parent, sibling).execute(&mut world);
+                entities.extend([child, sibling]);
+                parent = child;
+            }
+        }
+
+        let mut roots = Roots::new(&mut world, &mut ArchetypeAccess::new());
+        let mut nodes = Nodes::new(&mut world, &mut ArchetypeAccess::new());
+
+        {
+            let roots = Query::new(unsafe { world.cell() }, &mut roots);
+            let nodes = Query::new(unsafe { world.cell() }, &mut nodes);
+            for (entity, local, global) in roots.iter() {
+                update_root_transform(entity, local, global, &nodes);
+            }
+        }
+
+        let serial = globals(&world, &entities);
+        reset(&mut world, &entities);
+
+        {
+            let pool = TaskPool::builder().size(4).build();
+            let roots = Query::new(unsafe { world.cell() }, &mut roots);
+            let nodes = Query::new(unsafe { world.cell() }, &mut nodes);
+            par_update_global_transforms(&pool, roots, nodes);
+        }
+
+        let parallel = globals(&world, &entities);
+        for (serial, parallel) in serial.iter().zip(&parallel) {
+            assert_eq!(**serial, **parallel);
+        }
+    }
+
+    #[test]
+    fn spawn_transform_kit() {
+        let mut world = World::new();
+        let mut buffer = CommandBuffer::new();
+        let translation = Vec3::new(1.0, 2.0, 3.0);
+
+        let mut commands = Commands::new(world.entities(), &mut buffer);
+        let entity = commands
+            .spawn(TransformKit {
+                local: Transform::default().with_translation(translation),
+                global: GlobalTransform::default(),
+            })
+            .finish();
+        buffer.execute(&mut world);
+
+        let local = world.get_component::<Transform>(entity).unwrap();
+        assert_eq!(local.translation, translation);
+        assert!(world.get_component::<GlobalTransform>(entity).is_some());
+    }
+
+    #[test]
+    fn reparent_keeps_global_transform() {
+        let mut world = World::new();
+        world.register::<Parent>();
+        world.register::<Children>();
+
+        let parent = Transform::default()
+            .with_translation(Vec3::new(5.0, 0.0, -2.0))
+            .with_rotation(Vec3::new(0.0, 0.0, std::f32::consts::FRAC_PI_2))
+            .with_scale(Vec3::splat(2.0));
+        let child = Transform::default()
+            .with_translation(Vec3::new(1.0, 2.0, 3.0))
+            .with_rotation(Vec3::new(0.3, 0.0, 0.0));
+
+        let parent_entity = world.spawn();
+        world.add_component(parent_entity, parent);
+        world.add_component(parent_entity, GlobalTransform::from_affine(parent.local()));
+
+        let child_entity = world.spawn();
+        world.add_component(child_entity, child);
+        world.add_component(child_entity, GlobalTransform::from_affine(child.local()));
+
+        SetParentKeepTransform::new(parent_entity, child_entity).execute(&mut world);
+
+        let local = world.get_component::<Transform>(child_entity).unwrap();
+        let global = parent.local() * local.local();
+        let expected = child.local();
+
+        assert_eq!(
+            world.get_component::<Parent>(child_entity).map(|p| p.get()),
+            Some(parent_entity)
+        );
+        assert!(global.translation.abs_diff_eq(expected.translation, 1e-5));
+        assert!(global.matrix3.abs_diff_eq(expected.matrix3, 1e-5));
+    }
+
+    #[test]
+    fn default_basis() {
+        use crate::basis;
+
+        if cfg!(any(
+            feature = "z-up",
+            feature = "left-handed",
+            feature = "view-forward"
+        )) {
+            return;
+        }
+
+        assert_eq!(basis::FORWARD, Vec3::NEG_Z);
+        assert_eq!(basis::UP, Vec3::Y);
+        assert_eq!(basis::RIGHT, Vec3::X);
+        assert_eq!(basis::VIEW_FORWARD, Vec3::NEG_Z);
+        assert_eq!(basis::VIEW_DIRECTION, Vec3::Z);
+        assert_eq!(GlobalTransform::ORIGIN.forward(), Vec3::NEG_Z);
+    }
+
+    #[test]
+    fn forward_matches_view_matrix() {
+        use crate::basis;
+        use math::Quat;
+
+        let handedness = basis::RIGHT.cross(basis::UP);
+        let expected = if basis::RIGHT_HANDED {
+            basis::BACK
+        } else {
+            basis::FORWARD
+        };
+        assert_eq!(handedness, expected);
+
+        let rotation = Quat::from_euler(math::EulerRot::XYZ, 0.3, 1.2, -0.7);
+        let transform = GlobalTransform::new(Vec3::new(1.0, 2.0, 3.0), rotation, Vec3::ONE);
+        let view = transform.view_matrix();
+
+        // The view looks along forward() only with the `view-forward` feature.
+        let expected = basis::VIEW_FORWARD * basis::VIEW_DIRECTION.dot(basis::FORWARD);
+        let forward = view.transform_vector3(transform.forward());
+        let up = view.transform_vector3(transform.up());
+        assert!(forward.abs_diff_eq(expected, 1e-5));
+        assert!(up.abs_diff_eq(Vec3::Y, 1e-5));
+
+        let origin = view.transform_point3(transform.translation());
+        assert!(origin.abs_diff_eq(Vec3::ZERO, 1e-5));
+    }
+
+    #[test]
+    fn transform_vector_ignores_translation() {
+        use math::Quat;
+
+        let transform = GlobalTransform::new(
+            Vec3::new(5.0, -2.0, 1.0),
+            Quat::from_rotation_y(std::f32::consts::FRAC_PI_2),
+            Vec3::splat(2.0),
+        );
+
+        let vector = transform.transform_vector(Vec3::X);
+        assert!(vector.abs_diff_eq(Vec3::new(0.0, 0.0, -2.0), 1e-5));
+
+        let point = transform.transform_point(Vec3::X);
+        assert!(point.abs_diff_eq(Vec3::new(5.0, -2.0, -1.0), 1e-5));
+    }
+
+    #[test]
+    fn inverse_round_trip() {
+        use math::Quat;
+
+        let rotation = Quat::from_euler(math::EulerRot::XYZ, 0.3, 1.2, -0.7);
+        let transform = GlobalTransform::new(Vec3::new(1.0, 2.0, 3.0), rotation, Vec3::splat(3.0));
+
+        let point = Vec3::new(-4.0, 0.5, 7.0);
+        let local = transform.inverse_transform_point(point);
+        assert!(transform.transform_point(local).abs_diff_eq(point, 1e-4));
+
+        let vector = Vec3::new(0.0, 1.0, -2.0);
+        let local = transform.inverse_transform_vector(vector);
+        assert!(transform.transform_vector(local).abs_diff_eq(vector, 1e-4));
+    }
+
+    #[test]
+    fn transform2d_hierarchy() {
+        use super::{LocalTransform, Transform2d};
+        use math::Vec2;
+
+        let mut world = World::new();
+        world.register::<Parent>();
+        world.register::<Children>();
+        world.register::<Transform2d>();
+        world.register::<GlobalTransform>();
+
+        let parent_local = Transform2d {
+            translation: Vec2::new(2.0, 1.0),
+            scale: Vec2::splat(2.0),
+            rotation: std::f32::consts::FRAC_PI_2,
+        };
+        let child_local = Transform2d {
+            translation: Vec2::new(1.0, 0.0),
+            ..Default::default()
+        };
+
+        let parent = world.spawn();
+        world.add_component(parent, parent_local);
+        world.add_component(parent, GlobalTransform::default());
+
+        let child = world.spawn();
+        world.add_component(child, child_local);
+        world.add_component(child, GlobalTransform::default());
+        AddChild::new(parent, child).execute(&mut world);
+
+        type Roots2d = QueryState<
+            (Entity, &'static Transform2d, &'static mut GlobalTransform),
+            Without<Parent>,
+        >;
+        type Nodes2d =
+            QueryState<(Entity, &'static Transform2d, &'static mut GlobalTransform), With<Parent>>;
+
+        let mut roots = Roots2d::new(&mut world, &mut ArchetypeAccess::new());
+        let mut nodes = Nodes2d::new(&mut world, &mut ArchetypeAccess::new());
+
+        {
+            let roots = Query::new(unsafe { world.cell() }, &mut roots);
+            let nodes = Query::new(unsafe { world.cell() }, &mut nodes);
+            for (entity, local, global) in roots.iter() {
+                update_root_transform(entity, local, global, &nodes);
+            }
+        }
+
+        let expected = parent_local.local() * child_local.local();
+        let translation = world
+            .get_component::<GlobalTransform>(child)
+            .unwrap()
+            .translation();
+        assert!(translation.abs_diff_eq(expected.translation.into(), 1e-5));
+        assert!(translation.abs_diff_eq(Vec3::new(2.0, 3.0, 0.0), 1e-5));
+    }
+
+    #[test]
+    fn deep_hierarchy() {
+        const DEPTH: usize = 100_000;
+
+        let mut world = World::new();
+        world.register::<Parent>();
+        world.register::<Children>();
+        world.register::<Transform>();
+        world.register::<GlobalTransform>();
+
+        let root = spawn(&mut world, Transform::default().with_translation(Vec3::X));
+        let mut leaf = root;
+        for _ in 1..DEPTH {
+            let child = spawn(&mut world, Transform::default().with_translation(Vec3::X));
+            AddChild::new(leaf, child).execute(&mut world);
+            leaf = child;
+        }
+
+        let mut roots = Roots::new(&mut world, &mut ArchetypeAccess::new());
+        let mut nodes = Nodes::new(&mut world, &mut ArchetypeAccess::new());
+
+        {
+            let roots = Query::new(unsafe { world.cell() }, &mut roots);
+            let nodes = Query::new(unsafe { world.cell() }, &mut nodes);
+            for (entity, local, global) in roots.iter() {
+                update_root_transform(entity, local, global, &nodes);
+            }
+        }
+
+        let global = world.get_component::<GlobalTransform>(leaf).unwrap();
+        let expected = Vec3::new(DEPTH as f32, 0.0, 0.0);
+        assert_eq!(global.transform_point(Vec3::ZERO), expected);
+    }
+
+    #[test]
+    fn lerp_endpoints() {
+        use math::Quat;
+
+        let a = GlobalTransform::new(
+            Vec3::new(1.0, 2.0, 3.0),
+            Quat::from_euler(math::EulerRot::XYZ, 0.3, 1.2, -0.7),
+            Vec3::splat(2.0),
+        );
+        let b = GlobalTransform::new(
+            Vec3::new(-4.0, 0.5, 7.0),
+            Quat::from_euler(math::EulerRot::XYZ, -2.5, 0.1, 2.9),
+            Vec3::new(1.0, 3.0, 0.5),
+        );
+
+        assert!(a.lerp(&b, 0.0).abs_diff_eq(*a, 1e-5));
+        assert!(a.lerp(&b, 1.0).abs_diff_eq(*b, 1e-5));
+        assert!(a.lerp(&b, -1.0).abs_diff_eq(*a, 1e-5));
+        assert!(a.lerp(&b, 2.0).abs_diff_eq(*b, 1e-5));
+    }
+
+    #[test]
+    fn look_at_faces_target() {
+        use super::LocalTransform;
+
+        let mut transform = Transform::default();
+        transform.look_at(Vec3::Z, Vec3::Y);
+        let forward = GlobalTransform::from_affine(transform.local()).forward();
+        assert!(forward.abs_diff_eq(Vec3::Z, 1e-5));
+
+        transform.look_at(Vec3::Y, Vec3::Y);
+        let forward = GlobalTransform::from_affine(transform.local()).forward();
+        assert!(forward.abs_diff_eq(Vec3::Y, 1e-5));
+    }
+
+    #[test]
+    fn quat_matches_euler() {
+        use super::{LocalTransform, TransformQuat};
+        use math::Quat;
+
+        let yaw = std::f32::consts::FRAC_PI_2;
+        let euler = Transform::default()
+            .with_translation(Vec3::new(1.0, 2.0, 3.0))
+            .with_rotation(Vec3::new(0.0, yaw, 0.0));
+        let quat = TransformQuat::default()
+            .with_translation(Vec3::new(1.0, 2.0, 3.0))
+            .with_rotation(Quat::from_rotation_y(yaw));
+
+        let expected = GlobalTransform::from_affine(euler.local()).matrix();
+        let actual = GlobalTransform::from_affine(quat.local()).matrix();
+        assert!(actual.abs_diff_eq(expected, 1e-6));
+
+        let converted = TransformQuat::from(euler);
+        assert!(converted.local().abs_diff_eq(quat.local(), 1e-6));
+        let round_trip = Transform::from(converted);
+        assert!(round_trip.local().abs_diff_eq(euler.local(), 1e-6));
     }
 }
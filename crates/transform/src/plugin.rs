@@ -1,5 +1,21 @@
-use crate::{GlobalTransform, LocalTransform, update_global_transforms};
-use ecs::{Children, Parent, Plugin, app::PostUpdate};
+use crate::{GlobalTransform, LocalTransform, Transform, Transform2d, update_global_transforms};
+use ecs::{Children, IntoSystemConfigs, Parent, Plugin, app::PostUpdate, system::SystemSet};
+
+/// The sets transform propagation runs in. Every [`TransformPlugin`] runs in
+/// [`TransformSet::Propagate`] and the [`Transform2dPlugin`] runs after it in
+/// [`TransformSet::Propagate2d`], so 2D transforms always see the 3D
+/// transforms of the same frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TransformSet {
+    Propagate,
+    Propagate2d,
+}
+
+impl SystemSet for TransformSet {
+    fn dyn_clone(&self) -> Box<dyn SystemSet> {
+        Box::new(*self)
+    }
+}
 
 pub struct TransformPlugin<T: LocalTransform>(std::marker::PhantomData<T>);
 impl<T: LocalTransform> TransformPlugin<T> {
@@ -14,6 +30,33 @@ impl<T: LocalTransform> Plugin for TransformPlugin<T> {
             .register::<GlobalTransform>()
             .register::<Parent>()
             .register::<Children>()
-            .add_systems(PostUpdate, update_global_transforms::<T>);
+            .add_systems(
+                PostUpdate,
+                update_global_transforms::<T>.in_set(TransformSet::Propagate),
+            );
+    }
+}
+
+/// Propagates [`Transform2d`]s to [`GlobalTransform`]s after every 3D
+/// transform has been propagated.
+///
+/// An entity can only be positioned by one local transform, so adding a
+/// [`Transform2d`] to an entity with a [`Transform`], or the other way
+/// around, panics.
+pub struct Transform2dPlugin;
+
+impl Plugin for Transform2dPlugin {
+    fn setup(&mut self, app: &mut ecs::AppBuilder) {
+        app.register::<Transform2d>()
+            .register::<GlobalTransform>()
+            .register::<Parent>()
+            .register::<Children>()
+            .register_exclusive::<Transform, Transform2d>()
+            .add_systems(
+                PostUpdate,
+                update_global_transforms::<Transform2d>
+                    .in_set(TransformSet::Propagate2d)
+                    .after_set(TransformSet::Propagate),
+            );
     }
 }
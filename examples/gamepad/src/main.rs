@@ -0,0 +1,21 @@
+use ecs::{App, EventWriter, Update};
+use hydra_gilrs::GilrsPlugin;
+use input::{GamepadButton, GamepadRumbleRequest, Gamepads};
+use std::time::Duration;
+use window::plugin::WindowPlugin;
+
+fn rumble_on_press(gamepads: &Gamepads, mut rumbles: EventWriter<GamepadRumbleRequest>) {
+    for (id, gamepad) in gamepads.connected() {
+        if gamepad.just_pressed(GamepadButton::South) {
+            let duration = Duration::from_millis(200);
+            rumbles.send(GamepadRumbleRequest::new(*id, 1.0, 0.5, duration));
+        }
+    }
+}
+
+fn main() {
+    App::new()
+        .add_plugins((WindowPlugin, GilrsPlugin))
+        .add_systems(Update, rumble_on_press)
+        .run();
+}
@@ -44,24 +44,29 @@ use ecs::{
     AddComponent, Commands, Component, Entity, Init, Plugin, Query, Resource, Spawner, Update,
     app::{sync::SyncComponentPlugin, time::Time},
     query::{Single, With},
-    unlifetime::Read,
+    unlifetime::{Read, Write},
 };
 use math::{Mat4, Size, Vec2, Vec3};
 use render::{
-    ActiveCamera, AsBinding, BindGroup, BindGroupBuilder, BindGroupLayout, BindGroupLayoutBuilder,
-    Camera, CameraAttachments, CameraPhase, FragmentState, Mesh, MeshAttribute,
+    ActiveCamera, ArrayBuffer, AsBinding, BindGroup, BindGroupBuilder, BindGroupLayout,
+    BindGroupLayoutBuilder, BlendMode, Camera, CameraAttachments, CameraPhase, DrawBatch,
+    DrawSorting, FragmentState, FrameRing, FramesInFlight, Mesh, MeshAttribute,
     MeshAttributeValues, MeshLayout, PipelineCache, PipelineId, Projection, Queue, RenderApp,
-    RenderAsset, RenderAssets, RenderCommandEncoder, RenderDevice, RenderPipelineDesc, RenderState,
-    RenderSurface, Shader, ShaderSettings, ShaderType, Texture, VertexState,
+    RenderAsset, RenderAssets, RenderCommandEncoder, RenderDevice, RenderFeatures,
+    RenderPipelineDesc, RenderSurface, Shader, ShaderSettings, ShaderType, Texture, VertexState,
+    ZDistance,
     allocator::MeshAllocator,
+    batch_draws,
     plugins::{CameraPlugin, MeshPlugin, RenderAppExt, Texture2dPlugin, Texture2dSettings},
+    sort_draws,
     storage::StorageBufferArray,
     uniform::UniformBufferArray,
     wgpu::{
-        BlendState, ColorTargetState, ColorWrites, CompareFunction, DepthStencilState, Face,
-        LoadOp, Operations, PrimitiveState, RenderPassColorAttachment,
+        BufferUsages, ColorTargetState, ColorWrites, CompareFunction, DepthBiasState,
+        DepthStencilState, Face, LoadOp, Operations, PrimitiveState, RenderPassColorAttachment,
         RenderPassDepthStencilAttachment, RenderPassDescriptor, SamplerBindingType, ShaderStages,
-        StoreOp, TextureSampleType, TextureViewDimension, VertexFormat, VertexStepMode,
+        StoreOp, TextureFormat, TextureSampleType, TextureViewDimension, VertexFormat,
+        VertexStepMode, util::DrawIndirectArgs,
     },
 };
 use transform::{GlobalTransform, Transform, plugin::TransformPlugin};
@@ -109,7 +114,14 @@ impl Plugin for ExamplePlugin {
         .load_asset::<Texture>(GENGAR_ID)
         .load_asset::<Shader>(DRAW_SPRITE_SHADER)
         .add_asset::<Mesh>(QUAD, quad)
-        .add_asset::<Material>(MATERIAL_ID, Material { texture: GENGAR_ID })
+        .add_asset::<Material>(
+            MATERIAL_ID,
+            Material {
+                texture: GENGAR_ID,
+                blend_mode: BlendMode::Transparent,
+                depth_bias: DepthBiasState::default(),
+            },
+        )
         .add_systems(Init, |mut spawner: Spawner| {
             spawner
                 .spawn()
@@ -123,7 +135,9 @@ impl Plugin for ExamplePlugin {
                 .spawn()
                 .with_component(Transform::default())
                 .with_component(GlobalTransform::default())
-                .with_component(DrawSprite)
+                .with_component(DrawSprite {
+                    material: MATERIAL_ID,
+                })
                 .finish();
         })
         .add_systems(
@@ -145,9 +159,9 @@ impl Plugin for ExamplePlugin {
         let pipeline = {
             let device = app.resource::<RenderDevice>();
             let surface = app.resource::<RenderSurface>();
-            let pipelines = unsafe { app.world().cell().get_mut() }.resource_mut::<PipelineCache>();
+            let frames = app.resource::<FramesInFlight>();
 
-            DrawPipeline::new(device, surface, pipelines)
+            DrawPipeline::new(device, surface, frames)
         };
 
         app.add_resource(pipeline);
@@ -193,49 +207,74 @@ impl ViewData {
     }
 }
 
+/// A sprite queued for a view.
+struct SpriteDraw {
+    material: AssetId<Material>,
+    model: Mat4,
+    distance: ZDistance,
+}
+
 #[derive(Component)]
-pub struct ViewInstance(u32);
+pub struct ViewInstance {
+    view: u32,
+    /// The first of the view's commands, one per batch with the opaque
+    /// batches first.
+    first_command: u32,
+    /// Instances of the objects buffer drawn with each material.
+    opaque: Vec<DrawBatch<AssetId<Material>>>,
+    transparent: Vec<DrawBatch<AssetId<Material>>>,
+}
 
 #[derive(Resource)]
 pub struct DrawPipeline {
     views: UniformBufferArray<ViewData>,
     objects: StorageBufferArray<Mat4>,
+    commands: ArrayBuffer<DrawIndirectArgs>,
 
-    view_binding: BindGroup,
+    view_bindings: FrameRing<BindGroup>,
     view_bind_group_layout: BindGroupLayout,
 
-    object_binding: BindGroup,
+    object_bindings: FrameRing<BindGroup>,
     object_bind_group_layout: BindGroupLayout,
 
     material_bind_group_layout: BindGroupLayout,
 
-    id: PipelineId,
+    format: TextureFormat,
+    depth_format: TextureFormat,
 }
 
 impl DrawPipeline {
-    pub fn new(
-        device: &RenderDevice,
-        surface: &RenderSurface,
-        pipelines: &mut PipelineCache,
-    ) -> Self {
-        let views = UniformBufferArray::new(device, None, None);
-        let objects = StorageBufferArray::new(device, None, None);
+    pub fn new(device: &RenderDevice, surface: &RenderSurface, frames: &FramesInFlight) -> Self {
+        let views = UniformBufferArray::new(device, frames, None, None);
+        let objects = StorageBufferArray::new(device, frames, None, None);
+        let commands = ArrayBuffer::new(
+            device,
+            0,
+            BufferUsages::INDIRECT | BufferUsages::COPY_DST,
+            None,
+        );
 
         let view_bind_group_layout = BindGroupLayoutBuilder::new()
             .with_uniform(0, ShaderStages::VERTEX, true, None, None)
             .build(device);
 
-        let view_binding = BindGroupBuilder::new(&view_bind_group_layout)
-            .with_uniform(0, views.as_ref(), 0, None)
-            .build(device);
+        let view_bindings = FrameRing::new(frames, |slot| {
+            BindGroupBuilder::new(&view_bind_group_layout)
+                .with_uniform(0, views.slot_buffer(slot).unwrap(), 0, None)
+                .build(device)
+                .unwrap()
+        });
 
         let object_bind_group_layout = BindGroupLayoutBuilder::new()
             .with_storage(0, ShaderStages::VERTEX, false, true, None, None)
             .build(device);
 
-        let object_binding = BindGroupBuilder::new(&object_bind_group_layout)
-            .with_storage(0, objects.as_ref(), 0, None)
-            .build(device);
+        let object_bindings = FrameRing::new(frames, |slot| {
+            BindGroupBuilder::new(&object_bind_group_layout)
+                .with_storage(0, objects.slot_buffer(slot).unwrap(), 0, None)
+                .build(device)
+                .unwrap()
+        });
 
         let material_bind_group_layout = BindGroupLayoutBuilder::new()
             .with_texture(
@@ -247,18 +286,34 @@ impl DrawPipeline {
             .with_sampler(1, ShaderStages::FRAGMENT, SamplerBindingType::NonFiltering)
             .build(device);
 
+        Self {
+            views,
+            objects,
+            commands,
+            view_bindings,
+            view_bind_group_layout,
+            object_bindings,
+            object_bind_group_layout,
+            material_bind_group_layout,
+            format: surface.format(),
+            depth_format: surface.depth_format(),
+        }
+    }
+
+    /// The pipeline that draws sprites with `material`.
+    fn descriptor(&self, material: &Material) -> RenderPipelineDesc {
         let layout = MeshLayout::into_vertex_buffer_layout(
             0,
             &[VertexFormat::Float32x2, VertexFormat::Float32x2],
             VertexStepMode::Vertex,
         );
 
-        let id = pipelines.queue_render_pipeline(RenderPipelineDesc {
+        RenderPipelineDesc {
             label: None,
             layout: vec![
-                view_bind_group_layout.clone(),
-                object_bind_group_layout.clone(),
-                material_bind_group_layout.clone(),
+                self.view_bind_group_layout.clone(),
+                self.object_bind_group_layout.clone(),
+                self.material_bind_group_layout.clone(),
             ],
             vertex: VertexState {
                 shader: *DRAW_SPRITE_SHADER.as_ref(),
@@ -269,8 +324,8 @@ impl DrawPipeline {
                 shader: *DRAW_SPRITE_SHADER.as_ref(),
                 entry: "fs_main".into(),
                 targets: vec![Some(ColorTargetState {
-                    format: surface.format(),
-                    blend: Some(BlendState::ALPHA_BLENDING),
+                    format: self.format,
+                    blend: Some(material.blend_mode.into()),
                     write_mask: ColorWrites::ALL,
                 })],
             }),
@@ -278,65 +333,132 @@ impl DrawPipeline {
                 cull_mode: Some(Face::Back),
                 ..Default::default()
             },
-            depth_stencil: Some(DepthStencilState {
-                format: surface.depth_format(),
-                depth_write_enabled: true,
-                depth_compare: CompareFunction::LessEqual,
-                stencil: Default::default(),
-                bias: Default::default(),
-            }),
+            depth_stencil: Some(material.depth_stencil(self.depth_format)),
             multisample: Default::default(),
             push_constants: vec![],
-        });
+        }
+    }
 
-        Self {
-            views,
-            objects,
-            view_binding,
-            view_bind_group_layout,
-            object_binding,
-            object_bind_group_layout,
-            material_bind_group_layout,
-            id,
+    /// Batches sorted `draws` by material and writes their transforms to the
+    /// objects buffer in batch order.
+    fn push_draws(
+        objects: &mut StorageBufferArray<Mat4>,
+        draws: &mut [SpriteDraw],
+        mode: BlendMode,
+    ) -> Vec<DrawBatch<AssetId<Material>>> {
+        let first = objects.len() as u32;
+        let mut batches = batch_draws(draws, mode, |draw| draw.material);
+        for draw in draws.iter() {
+            objects.push(&draw.model);
         }
+
+        for batch in &mut batches {
+            batch.instances = first + batch.instances.start..first + batch.instances.end;
+        }
+
+        batches
     }
 
     fn queue(
         pipeline: &mut Self,
         views: Query<(Entity, &GlobalTransform, &View2d, Option<&mut ViewInstance>), With<Camera>>,
-        objects: Query<&GlobalTransform, With<DrawSprite>>,
+        objects: Query<(&GlobalTransform, &DrawSprite)>,
+        materials: &RenderAssets<MaterialBinding>,
+        meshes: &MeshAllocator,
+        sorting: &DrawSorting,
         device: &RenderDevice,
         surface: &RenderSurface,
+        frames: &FramesInFlight,
         mut commands: Commands,
     ) {
-        pipeline.views.clear();
-        pipeline.objects.clear();
+        pipeline.views.set_frame(frames);
+        pipeline.objects.set_frame(frames);
+        pipeline.view_bindings.set_frame(frames);
+        pipeline.object_bindings.set_frame(frames);
+
+        let views_buffer = &mut pipeline.views;
+        let objects_buffer = &mut pipeline.objects;
+        views_buffer.clear();
+        objects_buffer.clear();
+        pipeline.commands.clear();
+
+        let Some(mesh) = meshes.vertex_slice(&QUAD) else {
+            return;
+        };
 
         let Size { width, height } = surface.size();
         for (entity, transform, view, instance) in views.iter() {
             let data = ViewData::new(transform, &view, width as f32, height as f32);
+            let view_from_world = transform.view_matrix();
+
+            let mut opaque = Vec::new();
+            let mut transparent = Vec::new();
+            for (object, sprite) in objects.iter() {
+                let Some(material) = materials.get(&sprite.material) else {
+                    continue;
+                };
+
+                let draw = SpriteDraw {
+                    material: sprite.material,
+                    model: object.matrix(),
+                    distance: ZDistance::new(&view_from_world, object.translation()),
+                };
+
+                if material.blend_mode.is_transparent() {
+                    transparent.push(draw);
+                } else {
+                    opaque.push(draw);
+                }
+            }
+
+            sort_draws(&mut opaque, BlendMode::Opaque, *sorting, |d| d.distance);
+            sort_draws(&mut transparent, BlendMode::Transparent, *sorting, |d| {
+                d.distance
+            });
+
+            let queued = ViewInstance {
+                view: views_buffer.push(&data),
+                first_command: pipeline.commands.len() as u32,
+                opaque: Self::push_draws(objects_buffer, &mut opaque, BlendMode::Opaque),
+                transparent: Self::push_draws(
+                    objects_buffer,
+                    &mut transparent,
+                    BlendMode::Transparent,
+                ),
+            };
+
+            for batch in queued.opaque.iter().chain(&queued.transparent) {
+                pipeline.commands.push(DrawIndirectArgs {
+                    vertex_count: mesh.range.len() as u32,
+                    instance_count: batch.instances.len() as u32,
+                    first_vertex: mesh.range.start,
+                    first_instance: batch.instances.start,
+                });
+            }
+
             if let Some(instance) = instance {
-                instance.0 = pipeline.views.push(&data);
+                *instance = queued;
             } else {
-                let instance = ViewInstance(pipeline.views.push(&data));
-                commands.add(AddComponent::new(entity, instance));
+                commands.add(AddComponent::new(entity, queued));
             }
         }
 
-        for transform in objects.iter() {
-            pipeline.objects.push(&transform.matrix());
-        }
+        pipeline.commands.update(device);
 
-        if pipeline.views.update(device).is_some() {
-            pipeline.view_binding = BindGroupBuilder::new(&pipeline.view_bind_group_layout)
-                .with_uniform(0, pipeline.views.as_ref(), 0, None)
-                .build(device);
+        if views_buffer.update(device).is_some() {
+            *pipeline.view_bindings.current_mut() =
+                BindGroupBuilder::new(&pipeline.view_bind_group_layout)
+                    .with_uniform(0, views_buffer.inner(), 0, None)
+                    .build(device)
+                    .unwrap();
         }
 
-        if pipeline.objects.update(device).is_some() {
-            pipeline.object_binding = BindGroupBuilder::new(&pipeline.object_bind_group_layout)
-                .with_uniform(0, pipeline.objects.as_ref(), 0, None)
-                .build(device);
+        if objects_buffer.update(device).is_some() {
+            *pipeline.object_bindings.current_mut() =
+                BindGroupBuilder::new(&pipeline.object_bind_group_layout)
+                    .with_storage(0, objects_buffer.inner(), 0, None)
+                    .build(device)
+                    .unwrap();
         }
     }
 
@@ -345,17 +467,10 @@ impl DrawPipeline {
         pipelines: &PipelineCache,
         meshes: &MeshAllocator,
         materials: &RenderAssets<MaterialBinding>,
+        features: &RenderFeatures,
         pipeline_data: &mut DrawPipeline,
         mut encoder: RenderCommandEncoder,
     ) {
-        let Some(material) = materials.get(&MATERIAL_ID) else {
-            return;
-        };
-
-        let Some(pipeline) = pipelines.get_render_pipeline(&pipeline_data.id) else {
-            return;
-        };
-
         let (attachments, instance) = *camera;
 
         let Some(color) = attachments.color.as_ref() else {
@@ -366,7 +481,7 @@ impl DrawPipeline {
             return;
         };
 
-        let pass = encoder.begin_render_pass(&RenderPassDescriptor {
+        let desc = RenderPassDescriptor {
             label: None,
             color_attachments: &vec![Some(RenderPassColorAttachment {
                 view: color,
@@ -386,23 +501,43 @@ impl DrawPipeline {
             }),
             timestamp_writes: None,
             occlusion_query_set: None,
-        });
+        };
 
-        let mut state = RenderState::new(pass);
+        // Recording the reads catches a compute pass that writes these
+        // buffers after this pass has already read them.
+        let reads = [
+            pipeline_data.objects.inner().id(),
+            pipeline_data.commands.as_ref().id(),
+        ];
+        let mut state = encoder.begin_tracked_render_pass(&desc, &reads);
 
-        state.set_pipeline(pipeline);
         state.set_vertex_buffer(0, mesh.buffer.slice(..));
-        state.set_bind_group(0, &pipeline_data.view_binding, &[instance.0]);
-        state.set_bind_group(1, &pipeline_data.object_binding, &[]);
-        state.set_bind_group(2, &material.0, &[]);
-
-        let instances = 0..pipeline_data.objects.len() as u32;
-        state.draw(mesh.range, instances);
+        state.set_bind_group(0, pipeline_data.view_bindings.current(), &[instance.view]);
+        state.set_bind_group(1, pipeline_data.object_bindings.current(), &[]);
+
+        // Transparent sprites blend over the opaque ones, so they go last.
+        let path = features.draw_path();
+        let batches = instance.opaque.iter().chain(&instance.transparent);
+        for (command, batch) in (instance.first_command..).zip(batches) {
+            let Some(material) = materials.get(&batch.key) else {
+                continue;
+            };
+
+            let Some(pipeline) = pipelines.get_render_pipeline(&material.pipeline) else {
+                continue;
+            };
+
+            state.set_pipeline(pipeline);
+            state.set_bind_group(2, &material.bind_group, &[]);
+            state.draw_commands(path, &pipeline_data.commands, command..command + 1);
+        }
     }
 }
 
 #[derive(Clone, Component)]
-pub struct DrawSprite;
+pub struct DrawSprite {
+    material: AssetId<Material>,
+}
 
 #[derive(Asset, Clone, AsBinding)]
 pub struct Material {
@@ -410,9 +545,31 @@ pub struct Material {
     #[sampler(1)]
     #[dependency]
     texture: AssetId<Texture>,
+    blend_mode: BlendMode,
+    /// Offsets the material's depth toward the camera, e.g. so decals don't
+    /// z-fight with the surface they are layered on.
+    depth_bias: DepthBiasState,
+}
+
+impl Material {
+    /// Transparent sprites are sorted back to front instead of relying on the
+    /// depth buffer, so they don't write depth.
+    fn depth_stencil(&self, format: TextureFormat) -> DepthStencilState {
+        DepthStencilState {
+            format,
+            depth_write_enabled: !self.blend_mode.is_transparent(),
+            depth_compare: CompareFunction::LessEqual,
+            stencil: Default::default(),
+            bias: self.depth_bias,
+        }
+    }
 }
 
-pub struct MaterialBinding(BindGroup);
+pub struct MaterialBinding {
+    bind_group: BindGroup,
+    blend_mode: BlendMode,
+    pipeline: PipelineId,
+}
 
 impl RenderAsset for MaterialBinding {
     type Source = Material;
@@ -420,21 +577,71 @@ impl RenderAsset for MaterialBinding {
     type Arg = (
         Read<RenderDevice>,
         Read<DrawPipeline>,
+        Write<PipelineCache>,
         <Material as AsBinding>::Arg,
     );
 
     fn extract(
         _: AssetId<Self::Source>,
         asset: Self::Source,
-        (device, pipeline_data, arg): &mut ecs::ArgItem<Self::Arg>,
+        (device, pipeline_data, pipelines, arg): &mut ecs::ArgItem<Self::Arg>,
     ) -> Result<Self, render::ExtractError<Self::Source>> {
+        let blend_mode = asset.blend_mode;
+        let desc = pipeline_data.descriptor(&asset);
         let bind_group = asset
             .create_bind_group(device, &pipeline_data.material_bind_group_layout, arg)
             .map_err(|_| render::ExtractError::Retry(asset))?;
-        Ok(Self(bind_group))
+        let pipeline = pipelines.queue_render_pipeline(desc);
+
+        Ok(Self {
+            bind_group,
+            blend_mode,
+            pipeline,
+        })
+    }
+
+    fn removed(
+        _: &AssetId<Self::Source>,
+        asset: &Self,
+        (_, _, pipelines, _): &mut ecs::ArgItem<Self::Arg>,
+    ) {
+        pipelines.remove_render_pipeline(asset.pipeline);
     }
 
     fn usage(_: &Self::Source) -> render::AssetUsage {
         render::AssetUsage::Keep
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{GENGAR_ID, Material};
+    use render::{
+        BlendMode,
+        wgpu::{DepthBiasState, TextureFormat},
+    };
+
+    #[test]
+    fn decal_depth_bias() {
+        let decal = Material {
+            texture: GENGAR_ID,
+            blend_mode: BlendMode::Transparent,
+            depth_bias: DepthBiasState {
+                constant: -2,
+                slope_scale: -1.0,
+                clamp: 0.0,
+            },
+        };
+
+        let state = decal.depth_stencil(TextureFormat::Depth32Float);
+        assert!(state.bias.is_enabled());
+        assert_eq!(state.bias.constant, -2);
+
+        let sprite = Material {
+            depth_bias: DepthBiasState::default(),
+            ..decal
+        };
+        let state = sprite.depth_stencil(TextureFormat::Depth32Float);
+        assert!(!state.bias.is_enabled());
+    }
+}